@@ -1,18 +1,24 @@
 use std::{
     fs::File,
     io::BufWriter,
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::{Instant, SystemTime},
 };
 
 use chrono::{DateTime, Utc};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use ocpq_shared::{
     binding_box::{evaluate_box_tree, BindingBoxTree},
-    preprocessing::linked_ocel::IndexLinkedOCEL,
+    cel::custom_functions::{load_cel_plugins_from_dir, load_default_cel_plugins},
+    ocel_profile::get_ocel_profile,
+    preprocessing::{
+        snapshot::load_ocel_with_snapshot_cache,
+        validation::{validate_and_link_ocel, ValidationAction},
+    },
+    translation::neo4j_export::export_ocel_to_neo4j_csv,
 };
 use process_mining::{
-    import_ocel_json_from_path, import_ocel_sqlite_from_path, import_ocel_xml_file,
+    import_ocel_json_from_path, import_ocel_sqlite_from_path, import_ocel_xml_file, OCEL,
 };
 
 /// Simple program to greet a person
@@ -24,32 +30,135 @@ struct Args {
     ocel: PathBuf,
 
     /// File path where the input BindingBoxTree Serialization is located
-    #[arg(short, long)]
-    bbox_tree: PathBuf,
+    #[arg(short, long, required_unless_present_any = ["profile", "validation_report", "neo4j_export"])]
+    bbox_tree: Option<PathBuf>,
+
+    /// Write a profiling report (types, attributes, relationship cardinalities, ...) of the OCEL as JSON to this file
+    #[arg(long)]
+    profile: Option<PathBuf>,
+
+    /// Export the OCEL as CSV files for the Neo4j bulk import (neo4j-admin database import) into this directory
+    #[arg(long)]
+    neo4j_export: Option<PathBuf>,
+
+    /// What to do if the OCEL has data-quality issues (e.g., dangling references or duplicate IDs)
+    #[arg(long, value_enum, default_value_t = OnIssues::Keep)]
+    on_issues: OnIssues,
+
+    /// Write the data-quality validation report of the OCEL as JSON to this file
+    #[arg(long)]
+    validation_report: Option<PathBuf>,
+
+    /// Do not use (or write) cached snapshots of the input OCEL
+    #[arg(long)]
+    no_snapshot_cache: bool,
+
+    /// Directory containing CEL function plugins (defaults to the OCPQ_CEL_PLUGIN_DIR environment variable or the OCPQ data directory)
+    #[arg(long)]
+    cel_plugins: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OnIssues {
+    /// Only report issues
+    Keep,
+    /// Repair issues before evaluating
+    Repair,
+    /// Abort if there are any issues
+    Reject,
+}
+
+impl From<OnIssues> for ValidationAction {
+    fn from(value: OnIssues) -> Self {
+        match value {
+            OnIssues::Keep => ValidationAction::Keep,
+            OnIssues::Repair => ValidationAction::Repair,
+            OnIssues::Reject => ValidationAction::Reject,
+        }
+    }
+}
+
+fn import_ocel(path: &Path) -> Result<OCEL, anyhow::Error> {
+    Ok(match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => import_ocel_json_from_path(path)?,
+        Some("sqlite") => import_ocel_sqlite_from_path(path)?,
+        Some("xml") => import_ocel_xml_file(path),
+        x => anyhow::bail!("Unknown OCEL 2.0 file extension: {:?}", x),
+    })
 }
 
 fn main() {
     let args = Args::parse();
 
-    let bbox_reader = File::open(args.bbox_tree).expect("Could not find input bbox tree file");
-    let bbox_tree: BindingBoxTree =
-        serde_json::from_reader(bbox_reader).expect("Could not parse bbox_tree JSON");
-    let now = Instant::now();
-    let ocel = match args.ocel.extension().and_then(|e| e.to_str()) {
-        Some("json") => {
-            import_ocel_json_from_path(args.ocel).expect("Could not parse JSON OCEL2.0")
+    match args.cel_plugins {
+        Some(dir) => {
+            let names = load_cel_plugins_from_dir(dir).expect("Could not load CEL plugins");
+            println!("Loaded CEL plugin functions: {:?}", names);
         }
-        Some("sqlite") => {
-            import_ocel_sqlite_from_path(args.ocel).expect("Could not parse SQLite OCEL2.0")
+        None => {
+            load_default_cel_plugins();
         }
-        Some("xml") => import_ocel_xml_file(args.ocel),
-        x => panic!("Could not import OCEL 2.0 file. Unknown extension: {:?}", x),
-    };
-    println!("Imported OCEL 2.0 in {:?}", now.elapsed());
+    }
+
+    let bbox_tree: Option<BindingBoxTree> = args.bbox_tree.map(|path| {
+        let bbox_reader = File::open(path).expect("Could not find input bbox tree file");
+        serde_json::from_reader(bbox_reader).expect("Could not parse bbox_tree JSON")
+    });
     let now = Instant::now();
-    let index_linked_ocel = IndexLinkedOCEL::new(ocel);
-    println!("Linked OCEL 2.0 in {:?}", now.elapsed());
-    let res = evaluate_box_tree(bbox_tree, &index_linked_ocel, true);
+    let validation_res = if args.no_snapshot_cache {
+        let ocel = import_ocel(&args.ocel).expect("Could not import OCEL 2.0");
+        println!("Imported OCEL 2.0 in {:?}", now.elapsed());
+        let now = Instant::now();
+        let validation_res = validate_and_link_ocel(ocel, args.on_issues.into());
+        println!("Validated and linked OCEL 2.0 in {:?}", now.elapsed());
+        validation_res
+    } else {
+        let validation_res =
+            load_ocel_with_snapshot_cache(&args.ocel, import_ocel, args.on_issues.into())
+                .expect("Could not import OCEL 2.0");
+        println!("Loaded OCEL 2.0 in {:?}", now.elapsed());
+        validation_res
+    };
+    let report = match &validation_res {
+        Ok((_, report)) | Err(report) => report,
+    };
+    if !report.is_valid() {
+        eprintln!("OCEL 2.0 has {}", report.summary());
+    }
+    if let Some(report_path) = args.validation_report {
+        let report_writer =
+            File::create(report_path).expect("Could not create validation report output file!");
+        serde_json::to_writer_pretty(BufWriter::new(report_writer), report).unwrap();
+    }
+    let Ok((index_linked_ocel, _)) = validation_res else {
+        eprintln!("Rejected OCEL 2.0 because of data-quality issues");
+        std::process::exit(1);
+    };
+
+    if let Some(profile_path) = args.profile {
+        let now = Instant::now();
+        let profile = get_ocel_profile(&index_linked_ocel);
+        let profile_writer =
+            File::create(profile_path).expect("Could not create profile output file!");
+        serde_json::to_writer_pretty(BufWriter::new(profile_writer), &profile).unwrap();
+        println!("Profiled OCEL 2.0 in {:?}", now.elapsed());
+    }
+
+    if let Some(export_dir) = args.neo4j_export {
+        let now = Instant::now();
+        let files = export_ocel_to_neo4j_csv(&index_linked_ocel, export_dir)
+            .expect("Could not export OCEL 2.0 for Neo4j");
+        println!("Exported OCEL 2.0 for Neo4j in {:?}", now.elapsed());
+        println!(
+            "Import with: neo4j-admin database import full {}",
+            files.import_args().join(" ")
+        );
+    }
+
+    let Some(bbox_tree) = bbox_tree else {
+        return;
+    };
+    let res = evaluate_box_tree(bbox_tree, &index_linked_ocel, true, true);
 
     let now = Instant::now();
     let res_writer = File::create(format!(
@@ -0,0 +1,400 @@
+use std::collections::{HashMap, HashSet};
+
+use process_mining::ocel::ocel_struct::OCELType;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::preprocessing::linked_ocel::IndexLinkedOCEL;
+
+use super::{structs::BindingBoxTreeNode, BindingBoxTree};
+
+/// Situation and violation counts of one node of a [BindingBoxTree] on one OCEL
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeEvaluationCounts {
+    pub situation_count: usize,
+    pub situation_violated_count: usize,
+    /// Fraction of violated situations (`None` if there are no situations)
+    pub violation_rate: Option<f64>,
+}
+
+impl NodeEvaluationCounts {
+    fn add(&mut self, violated: bool) {
+        self.situation_count += 1;
+        if violated {
+            self.situation_violated_count += 1;
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        if self.situation_count > 0 {
+            self.violation_rate =
+                Some(self.situation_violated_count as f64 / self.situation_count as f64);
+        }
+        self
+    }
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeComparison {
+    pub node_index: usize,
+    pub a: NodeEvaluationCounts,
+    pub b: NodeEvaluationCounts,
+    /// Violation rate on OCEL B minus violation rate on OCEL A (`None` if one of them is undefined)
+    pub violation_rate_delta: Option<f64>,
+}
+
+/// Which of the two compared OCELs something refers to
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComparedOCEL {
+    A,
+    B,
+}
+
+/// A difference between the event/object types or attributes of two OCELs
+///
+/// `used_in_tree` is set if the (event/object) type is bound by a variable of the compared tree,
+/// i.e., the difference may explain missing matches
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SchemaDifference {
+    #[serde(rename_all = "camelCase")]
+    EventTypeMissing {
+        event_type: String,
+        missing_in: ComparedOCEL,
+        used_in_tree: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    ObjectTypeMissing {
+        object_type: String,
+        missing_in: ComparedOCEL,
+        used_in_tree: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    AttributeMissing {
+        ocel_type: String,
+        is_event_type: bool,
+        attribute: String,
+        missing_in: ComparedOCEL,
+        used_in_tree: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    AttributeValueTypeDiffers {
+        ocel_type: String,
+        is_event_type: bool,
+        attribute: String,
+        value_type_a: String,
+        value_type_b: String,
+        used_in_tree: bool,
+    },
+}
+
+impl SchemaDifference {
+    pub fn used_in_tree(&self) -> bool {
+        match self {
+            SchemaDifference::EventTypeMissing { used_in_tree, .. }
+            | SchemaDifference::ObjectTypeMissing { used_in_tree, .. }
+            | SchemaDifference::AttributeMissing { used_in_tree, .. }
+            | SchemaDifference::AttributeValueTypeDiffers { used_in_tree, .. } => *used_in_tree,
+        }
+    }
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxTreeComparison {
+    pub nodes: Vec<NodeComparison>,
+    /// Schema differences, where differences involving types used in the tree come first
+    pub schema_differences: Vec<SchemaDifference>,
+    pub bindings_skipped_a: bool,
+    pub bindings_skipped_b: bool,
+}
+
+fn evaluate_counts(
+    tree: &BindingBoxTree,
+    ocel: &IndexLinkedOCEL,
+) -> (Vec<NodeEvaluationCounts>, bool) {
+    let (evaluation_results_flat, bindings_skipped) = tree.evaluate(ocel);
+    let mut counts = vec![NodeEvaluationCounts::default(); tree.nodes.len()];
+    for (index, _binding, viol) in evaluation_results_flat {
+        counts[index].add(viol.is_some());
+    }
+    (
+        counts.into_iter().map(|c| c.finish()).collect(),
+        bindings_skipped,
+    )
+}
+
+/// Evaluate a [BindingBoxTree] on two OCELs (e.g., two periods or two plants) and compare the results
+///
+/// Besides side-by-side counts for all nodes, differences in the event/object types and attributes of both OCELs are reported.
+pub fn compare_box_tree(
+    tree: &BindingBoxTree,
+    ocel_a: &IndexLinkedOCEL,
+    ocel_b: &IndexLinkedOCEL,
+) -> BoxTreeComparison {
+    let ((counts_a, bindings_skipped_a), (counts_b, bindings_skipped_b)) = rayon::join(
+        || evaluate_counts(tree, ocel_a),
+        || evaluate_counts(tree, ocel_b),
+    );
+    let nodes = counts_a
+        .into_iter()
+        .zip(counts_b)
+        .enumerate()
+        .map(|(node_index, (a, b))| NodeComparison {
+            node_index,
+            violation_rate_delta: a
+                .violation_rate
+                .zip(b.violation_rate)
+                .map(|(rate_a, rate_b)| rate_b - rate_a),
+            a,
+            b,
+        })
+        .collect();
+    BoxTreeComparison {
+        nodes,
+        schema_differences: get_schema_differences(tree, ocel_a, ocel_b),
+        bindings_skipped_a,
+        bindings_skipped_b,
+    }
+}
+
+/// Event and object types which are bound by some variable of the tree
+fn get_types_used_in_tree(tree: &BindingBoxTree) -> (HashSet<&String>, HashSet<&String>) {
+    let mut event_types = HashSet::new();
+    let mut object_types = HashSet::new();
+    for node in &tree.nodes {
+        if let BindingBoxTreeNode::Box(b, _) = node {
+            event_types.extend(b.new_event_vars.values().flatten());
+            object_types.extend(b.new_object_vars.values().flatten());
+        }
+    }
+    (event_types, object_types)
+}
+
+fn compare_types(
+    types_a: &[OCELType],
+    types_b: &[OCELType],
+    is_event_type: bool,
+    used_in_tree: &HashSet<&String>,
+    differences: &mut Vec<SchemaDifference>,
+) {
+    let types_a: HashMap<&String, &OCELType> = types_a.iter().map(|t| (&t.name, t)).collect();
+    let types_b: HashMap<&String, &OCELType> = types_b.iter().map(|t| (&t.name, t)).collect();
+    let mut names: Vec<&String> = types_a.keys().chain(types_b.keys()).copied().collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let used_in_tree = used_in_tree.contains(name);
+        let (t_a, t_b) = match (types_a.get(name), types_b.get(name)) {
+            (Some(t_a), Some(t_b)) => (t_a, t_b),
+            (a, _) => {
+                let missing_in = if a.is_some() {
+                    ComparedOCEL::B
+                } else {
+                    ComparedOCEL::A
+                };
+                differences.push(if is_event_type {
+                    SchemaDifference::EventTypeMissing {
+                        event_type: name.clone(),
+                        missing_in,
+                        used_in_tree,
+                    }
+                } else {
+                    SchemaDifference::ObjectTypeMissing {
+                        object_type: name.clone(),
+                        missing_in,
+                        used_in_tree,
+                    }
+                });
+                continue;
+            }
+        };
+        let attrs_a: HashMap<&String, &String> =
+            t_a.attributes.iter().map(|a| (&a.name, &a.value_type)).collect();
+        let attrs_b: HashMap<&String, &String> =
+            t_b.attributes.iter().map(|a| (&a.name, &a.value_type)).collect();
+        let mut attr_names: Vec<&String> =
+            attrs_a.keys().chain(attrs_b.keys()).copied().collect();
+        attr_names.sort();
+        attr_names.dedup();
+        for attr in attr_names {
+            match (attrs_a.get(attr), attrs_b.get(attr)) {
+                (Some(value_type_a), Some(value_type_b)) => {
+                    if value_type_a != value_type_b {
+                        differences.push(SchemaDifference::AttributeValueTypeDiffers {
+                            ocel_type: name.clone(),
+                            is_event_type,
+                            attribute: attr.clone(),
+                            value_type_a: value_type_a.to_string(),
+                            value_type_b: value_type_b.to_string(),
+                            used_in_tree,
+                        })
+                    }
+                }
+                (a, _) => differences.push(SchemaDifference::AttributeMissing {
+                    ocel_type: name.clone(),
+                    is_event_type,
+                    attribute: attr.clone(),
+                    missing_in: if a.is_some() {
+                        ComparedOCEL::B
+                    } else {
+                        ComparedOCEL::A
+                    },
+                    used_in_tree,
+                }),
+            }
+        }
+    }
+}
+
+pub fn get_schema_differences(
+    tree: &BindingBoxTree,
+    ocel_a: &IndexLinkedOCEL,
+    ocel_b: &IndexLinkedOCEL,
+) -> Vec<SchemaDifference> {
+    let (used_event_types, used_object_types) = get_types_used_in_tree(tree);
+    let mut differences = Vec::new();
+    compare_types(
+        &ocel_a.ocel.event_types,
+        &ocel_b.ocel.event_types,
+        true,
+        &used_event_types,
+        &mut differences,
+    );
+    compare_types(
+        &ocel_a.ocel.object_types,
+        &ocel_b.ocel.object_types,
+        false,
+        &used_object_types,
+        &mut differences,
+    );
+    // Stable sort: Keep order by type/attribute name otherwise
+    differences.sort_by_key(|d| !d.used_in_tree());
+    differences
+}
+
+#[test]
+fn compares_violation_rates_and_schemas() {
+    use process_mining::ocel::ocel_struct::OCELTypeAttribute;
+
+    use super::{
+        structs::{Constraint, EventVariable, Filter, ObjectVariable},
+        BindingBox,
+    };
+    use crate::preprocessing::tests::test_ocel;
+
+    // Orders which should be paid
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(
+        ObjectVariable(0),
+        vec!["order".to_string()].into_iter().collect(),
+    );
+    root.constraints.push(Constraint::ANY {
+        child_names: vec!["A".to_string()],
+    });
+    let mut child = BindingBox::default();
+    child.new_event_vars.insert(
+        EventVariable(1),
+        vec!["pay order".to_string()].into_iter().collect(),
+    );
+    child.filters.push(Filter::O2E {
+        object: ObjectVariable(0),
+        event: EventVariable(1),
+        qualifier: None,
+        filter_label: None,
+    });
+    let tree = BindingBoxTree {
+        nodes: vec![
+            BindingBoxTreeNode::Box(root, vec![1]),
+            BindingBoxTreeNode::Box(child, vec![]),
+        ],
+        edge_names: vec![((0, 1), "A".to_string())].into_iter().collect(),
+    };
+
+    // All orders are paid in A, half of them in B; 'pay order' has an amount attribute only in A
+    let mut ocel_a = test_ocel(
+        vec![
+            ("e1", "cancel order", 0, vec![("o1", "order")]),
+            ("e2", "pay order", 10, vec![("o1", "order")]),
+            ("e3", "pay order", 20, vec![("o2", "order")]),
+        ],
+        vec![("o1", "order", vec![]), ("o2", "order", vec![])],
+    );
+    ocel_a.event_types[1].attributes.push(OCELTypeAttribute {
+        name: "amount".to_string(),
+        value_type: "float".to_string(),
+    });
+    let ocel_b = test_ocel(
+        vec![
+            ("e1", "pay order", 10, vec![("o1", "order")]),
+            ("e2", "pay order", 20, vec![("o2", "order")]),
+        ],
+        vec![
+            ("o1", "order", vec![]),
+            ("o2", "order", vec![]),
+            ("o3", "order", vec![]),
+            ("o4", "order", vec![]),
+        ],
+    );
+    let comparison = compare_box_tree(
+        &tree,
+        &IndexLinkedOCEL::new(ocel_a),
+        &IndexLinkedOCEL::new(ocel_b),
+    );
+
+    let counts = comparison
+        .nodes
+        .iter()
+        .map(|n| {
+            (
+                n.node_index,
+                (n.a.situation_count, n.a.situation_violated_count),
+                (n.b.situation_count, n.b.situation_violated_count),
+                n.violation_rate_delta,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        counts,
+        vec![
+            (0, (2, 0), (4, 2), Some(0.5)),
+            (1, (2, 0), (2, 0), Some(0.0)),
+        ]
+    );
+    assert_eq!(comparison.nodes[0].b.violation_rate, Some(0.5));
+    assert!(!comparison.bindings_skipped_a && !comparison.bindings_skipped_b);
+
+    // The missing attribute of the used 'pay order' type comes before the (unused) missing 'cancel order' type
+    let differences = &comparison.schema_differences;
+    assert_eq!(differences.len(), 2);
+    assert!(matches!(
+        &differences[0],
+        SchemaDifference::AttributeMissing {
+            ocel_type,
+            is_event_type: true,
+            attribute,
+            missing_in: ComparedOCEL::B,
+            used_in_tree: true,
+        } if ocel_type == "pay order" && attribute == "amount"
+    ));
+    assert!(matches!(
+        &differences[1],
+        SchemaDifference::EventTypeMissing {
+            event_type,
+            missing_in: ComparedOCEL::B,
+            used_in_tree: false,
+        } if event_type == "cancel order"
+    ));
+}
@@ -1,8 +1,13 @@
+use std::sync::Mutex;
+
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL};
+use crate::{
+    cel::get_indices_from_cel,
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
 
-use super::structs::{Binding, BindingBox, BindingStep};
+use super::structs::{Binding, BindingBox, BindingStep, Filter, Variable};
 const MAX_NUM_BINDINGS: usize = 2_000_000;
 /// This can slightly reduce memory usage by filtering out unfitting bindings before collecting into a vec
 /// However, the filters may be checked multiple times
@@ -12,10 +17,11 @@ fn check_next_filters(
     next_step: usize,
     steps: &[BindingStep],
     ocel: &IndexLinkedOCEL,
+    cel_failed: &Mutex<Vec<Binding>>,
 ) -> Option<Binding> {
     for step in steps.iter().skip(next_step) {
         if let BindingStep::Filter(f) = &step {
-            if f.check_binding(&b, ocel) {
+            if check_filter(f, &b, ocel, cel_failed) {
                 continue;
             } else {
                 return None;
@@ -27,8 +33,33 @@ fn check_next_filters(
     Some(b)
 }
 
+/// Check a filter, recording the binding in `cel_failed` if the CEL expression of a [Filter::BindingCEL] cannot be evaluated
+fn check_filter(
+    f: &Filter,
+    b: &Binding,
+    ocel: &IndexLinkedOCEL,
+    cel_failed: &Mutex<Vec<Binding>>,
+) -> bool {
+    if let Filter::BindingCEL { variable, cel } = f {
+        if let Some(index) = b.get_any_index(variable) {
+            return match get_indices_from_cel(cel, b, ocel) {
+                Ok(indices) => indices.contains(&index),
+                Err(_) => {
+                    cel_failed.lock().unwrap().push(b.clone());
+                    false
+                }
+            };
+        }
+    }
+    f.check_binding(b, ocel)
+}
+
+/// Result of expanding a binding box: The expanded bindings, whether bindings were skipped (too many bindings),
+/// and the (partially expanded) bindings for which a binding CEL expression could not be evaluated
+pub type ExpansionResult = (Vec<Binding>, bool, Vec<Binding>);
+
 impl BindingBox {
-    pub fn expand_empty(&self, ocel: &IndexLinkedOCEL) -> (Vec<Binding>,bool) {
+    pub fn expand_empty(&self, ocel: &IndexLinkedOCEL) -> ExpansionResult {
         self.expand(Binding::default(), ocel)
     }
 
@@ -36,11 +67,11 @@ impl BindingBox {
         &self,
         ocel: &IndexLinkedOCEL,
         steps: &[BindingStep],
-    ) -> (Vec<Binding>, bool) {
+    ) -> ExpansionResult {
         self.expand_with_steps(Binding::default(), ocel, steps)
     }
 
-    pub fn expand(&self, parent_binding: Binding, ocel: &IndexLinkedOCEL) -> (Vec<Binding>, bool) {
+    pub fn expand(&self, parent_binding: Binding, ocel: &IndexLinkedOCEL) -> ExpansionResult {
         let order = BindingStep::get_binding_order(self, Some(&parent_binding), Some(ocel));
         self.expand_with_steps(parent_binding, ocel, &order)
     }
@@ -50,9 +81,11 @@ impl BindingBox {
         parent_binding: Binding,
         ocel: &IndexLinkedOCEL,
         steps: &[BindingStep],
-    ) -> (Vec<Binding>, bool) {
+    ) -> ExpansionResult {
         let mut ret = vec![parent_binding];
         let mut bindings_skipped = false;
+        let cel_failed_bindings = Mutex::new(Vec::new());
+        let cel_failed = &cel_failed_bindings;
         // let mut sizes_per_step: Vec<usize> = Vec::with_capacity(steps.len());
         for step_index in 0..steps.len() {
             let step = &steps[step_index];
@@ -89,6 +122,7 @@ impl BindingBox {
                                             step_index + 1,
                                             steps,
                                             ocel,
+                                            cel_failed,
                                         )
                                     } else {
                                         None
@@ -112,6 +146,7 @@ impl BindingBox {
                                         step_index + 1,
                                         steps,
                                         ocel,
+                                        cel_failed,
                                     )
                                 })
                         })
@@ -122,25 +157,22 @@ impl BindingBox {
                     ret = ret
                         .into_par_iter()
                         .flat_map_iter(|b| {
-                            let e = b.get_ev(from_ev_var, ocel).unwrap();
+                            let e = *b.get_ev_index(from_ev_var).unwrap();
                             let obj_types = self.new_object_vars.get(ob_var).unwrap();
-                            e.relationships
-                                .iter()
-                                .filter(|rel| {
-                                    obj_types.contains(
-                                        &ocel.ob_by_id(&rel.object_id).unwrap().object_type,
-                                    ) && (qualifier.is_none()
-                                        || qualifier.as_ref().unwrap() == &rel.qualifier)
+                            ocel.get_rels(&EventOrObjectIndex::Event(e))
+                                .filter(|(rel_ob, rel_qualifier)| {
+                                    obj_types
+                                        .contains(&ocel.ob_by_index(rel_ob).unwrap().object_type)
+                                        && (qualifier.is_none()
+                                            || qualifier.as_ref().unwrap() == rel_qualifier)
                                 })
-                                .filter_map(move |rel| {
+                                .filter_map(move |(rel_ob, _)| {
                                     check_next_filters(
-                                        b.clone().expand_with_ob(
-                                            *ob_var,
-                                            *ocel.object_index_map.get(&rel.object_id).unwrap(),
-                                        ),
+                                        b.clone().expand_with_ob(*ob_var, rel_ob),
                                         step_index + 1,
                                         steps,
                                         ocel,
+                                        cel_failed,
                                     )
                                 })
                         })
@@ -153,24 +185,23 @@ impl BindingBox {
                         .flat_map_iter(|b| {
                             let ob_index = b.get_ob_index(from_ob_var_name).unwrap();
                             ocel.get_symmetric_rels_ob(ob_index)
-                                .unwrap()
-                                .iter()
                                 .filter_map(move |(to_index, rev, qual)| {
                                     if let EventOrObjectIndex::Object(to_ob_index) = to_index {
-                                        if rev == reversed
+                                        if rev == *reversed
                                             && (qualifier.is_none()
                                                 || qual == qualifier.as_ref().unwrap())
                                         {
                                             let allowed_types =
                                                 self.new_object_vars.get(ob_var_name)?;
-                                            let o = ocel.ob_by_index(to_ob_index)?;
+                                            let o = ocel.ob_by_index(&to_ob_index)?;
                                             if allowed_types.contains(&o.object_type) {
                                                 check_next_filters(
                                                     b.clone()
-                                                        .expand_with_ob(*ob_var_name, *to_ob_index),
+                                                        .expand_with_ob(*ob_var_name, to_ob_index),
                                                     step_index + 1,
                                                     steps,
                                                     ocel,
+                                                    cel_failed,
                                                 )
                                             } else {
                                                 None
@@ -194,21 +225,20 @@ impl BindingBox {
                             // let ob = ocel.ob_by_index(ob_index).unwrap();
                             let ev_types = self.new_event_vars.get(ev_var_name).unwrap();
                             ocel.get_symmetric_rels_ob(ob_index)
-                                .unwrap()
-                                .iter()
                                 .filter_map(move |(rel_to, _reversed, q)| {
                                     if qualifier.is_none()
                                         || qualifier.as_ref().unwrap().contains(q)
                                     {
                                         if let EventOrObjectIndex::Event(rel_to_ev) = rel_to {
-                                            let to_ev = ocel.ev_by_index(rel_to_ev)?;
+                                            let to_ev = ocel.ev_by_index(&rel_to_ev)?;
                                             if ev_types.contains(&to_ev.event_type) {
                                                 check_next_filters(
                                                     b.clone()
-                                                        .expand_with_ev(*ev_var_name, *rel_to_ev),
+                                                        .expand_with_ev(*ev_var_name, rel_to_ev),
                                                     step_index + 1,
                                                     steps,
                                                     ocel,
+                                                    cel_failed,
                                                 )
                                             } else {
                                                 None
@@ -224,11 +254,62 @@ impl BindingBox {
                         .take_any(MAX_NUM_BINDINGS + 1)
                         .collect();
                 }
+                BindingStep::BindFromCEL(var, cel) => {
+                    ret = ret
+                        .into_par_iter()
+                        .flat_map_iter(|b| {
+                            let indices = match get_indices_from_cel(cel, &b, ocel) {
+                                Ok(indices) => indices,
+                                Err(_) => {
+                                    cel_failed.lock().unwrap().push(b.clone());
+                                    Vec::new()
+                                }
+                            };
+                            indices
+                                .into_iter()
+                                .filter_map(move |index| {
+                                    let expanded_b = match (var, index) {
+                                        (
+                                            Variable::Event(ev_var),
+                                            EventOrObjectIndex::Event(e_index),
+                                        ) => {
+                                            let ev_types = self.new_event_vars.get(ev_var)?;
+                                            let e = ocel.ev_by_index(&e_index)?;
+                                            if !ev_types.contains(&e.event_type) {
+                                                return None;
+                                            }
+                                            b.clone().expand_with_ev(*ev_var, e_index)
+                                        }
+                                        (
+                                            Variable::Object(ob_var),
+                                            EventOrObjectIndex::Object(o_index),
+                                        ) => {
+                                            let ob_types = self.new_object_vars.get(ob_var)?;
+                                            let o = ocel.ob_by_index(&o_index)?;
+                                            if !ob_types.contains(&o.object_type) {
+                                                return None;
+                                            }
+                                            b.clone().expand_with_ob(*ob_var, o_index)
+                                        }
+                                        _ => return None,
+                                    };
+                                    check_next_filters(
+                                        expanded_b,
+                                        step_index + 1,
+                                        steps,
+                                        ocel,
+                                        cel_failed,
+                                    )
+                                })
+                        })
+                        .take_any(MAX_NUM_BINDINGS + 1)
+                        .collect();
+                }
                 // _ => {}
                 BindingStep::Filter(f) => {
                     ret = ret
                         .into_par_iter()
-                        .filter(|b| f.check_binding(b, ocel))
+                        .filter(|b| check_filter(f, b, ocel, cel_failed))
                         .collect()
                 }
             }
@@ -255,6 +336,6 @@ impl BindingBox {
         //     println!("Steps: {:?}", steps);
         // println!("Set sizes: {:?}", sizes_per_step);
         // }
-        (ret, bindings_skipped)
+        (ret, bindings_skipped, cel_failed_bindings.into_inner().unwrap())
     }
 }
@@ -4,6 +4,8 @@ pub mod step_order;
 
 pub mod expand_step;
 
+pub mod compare;
+
 #[cfg(test)]
 pub mod test;
 
@@ -24,7 +26,9 @@ use crate::preprocessing::linked_ocel::{EventIndex, IndexLinkedOCEL, ObjectIndex
 #[serde(rename_all = "camelCase")]
 pub struct EvaluateBoxTreeResult {
     pub evaluation_results: Vec<EvaluationResultWithCount>,
+    /// All object IDs, to resolve the object indices of bindings (empty, unless requested)
     pub object_ids: Vec<String>,
+    /// All event IDs, to resolve the event indices of bindings (empty, unless requested)
     pub event_ids: Vec<String>,
     pub bindings_skipped: bool,
 }
@@ -58,6 +62,9 @@ impl EvaluateBoxTreeResult {
 pub struct CheckWithBoxTreeRequest {
     pub tree: BindingBoxTree,
     pub measure_performance: Option<bool>,
+    /// Include all event and object IDs in the result
+    #[serde(default)]
+    pub include_ids: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -99,6 +106,7 @@ pub fn evaluate_box_tree(
     tree: BindingBoxTree,
     ocel: &IndexLinkedOCEL,
     measure_performance: bool,
+    include_ids: bool,
 ) -> EvaluateBoxTreeResult {
     if measure_performance {
         let n = 10;
@@ -180,8 +188,16 @@ pub fn evaluate_box_tree(
     );
     EvaluateBoxTreeResult {
         evaluation_results,
-        object_ids: ocel.ocel.objects.iter().map(|o| o.id.clone()).collect(),
-        event_ids: ocel.ocel.events.iter().map(|o| o.id.clone()).collect(),
+        object_ids: if include_ids {
+            ocel.ocel.objects.iter().map(|o| o.id.clone()).collect()
+        } else {
+            Vec::new()
+        },
+        event_ids: if include_ids {
+            ocel.ocel.events.iter().map(|o| o.id.clone()).collect()
+        } else {
+            Vec::new()
+        },
         bindings_skipped
     }
 }
@@ -349,7 +365,7 @@ pub fn filter_ocel_box_tree(tree: BindingBoxTree, ocel: &IndexLinkedOCEL) -> Opt
                 }
                 added_ob_types.insert(ob.object_type.clone());
             }
-            let mut ob = ob.clone();
+            let mut ob = ocel.full_object(ob_index).unwrap();
             ob.relationships.retain(|rel| {
                 check_o2o_inclusion(
                     **ob_index,
@@ -377,7 +393,7 @@ pub fn filter_ocel_box_tree(tree: BindingBoxTree, ocel: &IndexLinkedOCEL) -> Opt
                 }
                 added_ev_types.insert(ev.event_type.clone());
             }
-            let mut ev = ev.clone();
+            let mut ev = ocel.full_event(ev_index).unwrap();
             ev.relationships.retain(|rel| {
                 check_e2o_inclusion(
                     **ev_index,
@@ -2,7 +2,10 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::{discovery::advanced::EventOrObjectType, preprocessing::linked_ocel::IndexLinkedOCEL};
+use crate::{
+    cel::get_vars_in_cel_program, discovery::advanced::EventOrObjectType,
+    preprocessing::linked_ocel::IndexLinkedOCEL,
+};
 
 use super::{
     structs::{BindingBox, BindingStep, Filter, Qualifier, Variable},
@@ -185,6 +188,34 @@ impl BindingStep {
                 _ => {}
             }
         }
+        // Variables which can be bound using a CEL expression, once all variables referenced in the expression are bound
+        // (filter index, variable, CEL expression, referenced variables)
+        let cel_bindings: Vec<(usize, Variable, &String, HashSet<Variable>)> = bbox
+            .filters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| match f {
+                Filter::BindingCEL { variable, cel } if new_vars.contains(variable) => {
+                    let mut referenced_vars = get_vars_in_cel_program(cel);
+                    referenced_vars.remove(variable);
+                    Some((i, variable.clone(), cel, referenced_vars))
+                }
+                _ => None,
+            })
+            .collect();
+        // 1 if `var` can be bound using a CEL expression, -1 if it could be bound by a CEL expression later, 0 otherwise
+        let cel_bind_priority = |var: &Variable, bound_vars: &HashSet<Variable>| -> i32 {
+            let mut ret = 0;
+            for (_, v, _, referenced_vars) in &cel_bindings {
+                if v == var {
+                    if referenced_vars.is_subset(bound_vars) {
+                        return 1;
+                    }
+                    ret = -1;
+                }
+            }
+            ret
+        };
         let mut filter_indices_incoporated = HashSet::new();
 
         fn add_supported_filters(
@@ -225,6 +256,7 @@ impl BindingStep {
                     .any(|bv| var_can_bind.get(bv).unwrap().contains(v));
                 (vs.len() as i32) * 10
                     + if can_be_bound { 100 } else { 0 }
+                    + cel_bind_priority(v, &bound_vars) * 100
                     + if let Variable::Object(_) = v { 0 } else { 1 }
             })
             .map(|(k, _)| k)
@@ -234,7 +266,15 @@ impl BindingStep {
                 if bound_vars.contains(&var) {
                     continue;
                 }
-                if let Some((v, (_var, qualifier, filter_index, reversed))) = bound_vars
+                if let Some((filter_index, _var, cel, _referenced_vars)) =
+                    cel_bindings.iter().find(|(_, v, _, referenced_vars)| {
+                        v == &var && referenced_vars.is_subset(&bound_vars)
+                    })
+                {
+                    // `var` can be bound using a CEL expression
+                    filter_indices_incoporated.insert(*filter_index);
+                    ret.push(BindingStep::BindFromCEL(var.clone(), cel.to_string()));
+                } else if let Some((v, (_var, qualifier, filter_index, reversed))) = bound_vars
                     .iter()
                     .flat_map(|v| {
                         var_can_bind_with_qualifier
@@ -307,11 +347,7 @@ impl BindingStep {
                 let can_be_bound = bound_vars
                     .iter()
                     .any(|bv| var_can_bind.get(bv).unwrap().contains(var));
-                if can_be_bound {
-                    100
-                } else {
-                    0
-                }
+                (if can_be_bound { 100 } else { 0 }) + cel_bind_priority(var, &bound_vars) * 100
             })
         }
 
@@ -12,7 +12,10 @@ use serde_with::serde_as;
 use ts_rs::TS;
 
 use crate::{
-    cel::{add_cel_label, check_cel_predicate, get_vars_in_cel_program},
+    cel::{
+        add_cel_label, check_cel_predicate, get_indices_from_cel,
+        get_referenced_names_in_cel_program, get_vars_in_cel_program,
+    },
     preprocessing::linked_ocel::{EventIndex, EventOrObjectIndex, IndexLinkedOCEL, ObjectIndex},
 };
 #[derive(TS)]
@@ -73,6 +76,10 @@ pub enum LabelValue {
     Int(i64),
     Float(#[ts(as = "f64")] OrderedFloat<f64>),
     Bool(bool),
+    Time(chrono::DateTime<chrono::Utc>),
+    /// Duration in seconds
+    Duration(#[ts(as = "f64")] OrderedFloat<f64>),
+    List(Vec<LabelValue>),
     Null,
 }
 
@@ -83,6 +90,9 @@ impl LabelValue {
             LabelValue::Int(i) => i.to_string(),
             LabelValue::Float(f) => f.to_string(),
             LabelValue::Bool(b) => b.to_string(),
+            LabelValue::Time(t) => t.to_rfc3339(),
+            LabelValue::Duration(d) => format!("{d}s"),
+            LabelValue::List(l) => format!("[{}]", l.iter().map(|v| v.to_string()).join(", ")),
             LabelValue::Null => "null".to_string(),
         }
     }
@@ -306,6 +316,81 @@ pub enum ViolationReason {
 
     ConstraintNotSatisfied(usize),
     UnknownChildSet,
+    /// The CEL expression of a binding filter (`BindingCEL`) could not be evaluated
+    BindingCELFailed,
+    /// A label function of the box redefines a (read-only) label of a parent box
+    LabelAlreadyDefined,
+}
+
+/// Split the label functions of a box into those computed before and after evaluating the children
+///
+/// Labels which do not depend on child results (directly or through other labels) are computed before evaluating children,
+/// so that they are visible to child boxes.
+/// Within both parts, labels are ordered so that labels are computed after the labels they reference
+/// (otherwise, and for cyclic references, the declaration order is kept).
+///
+/// Labels already set by a parent box are read-only: If a box redefines them, the names of these labels are returned as error.
+pub fn split_label_functions<'a>(
+    labels: &'a [LabelFunction],
+    parent_labels: &BTreeMap<String, LabelValue>,
+    child_names: &HashSet<String>,
+) -> Result<(Vec<&'a LabelFunction>, Vec<&'a LabelFunction>), Vec<String>> {
+    let redefined: Vec<String> = labels
+        .iter()
+        .filter(|l| parent_labels.contains_key(&l.label))
+        .map(|l| l.label.clone())
+        .collect();
+    if !redefined.is_empty() {
+        return Err(redefined);
+    }
+    let referenced: Vec<HashSet<String>> = labels
+        .iter()
+        .map(|l| get_referenced_names_in_cel_program(&l.cel))
+        .collect();
+    // Propagate child dependencies until nothing changes (independent of the declaration order)
+    let mut post_child_label_names: HashSet<&str> = HashSet::new();
+    loop {
+        let num_post = post_child_label_names.len();
+        for (label_fun, refs) in labels.iter().zip(&referenced) {
+            if refs
+                .iter()
+                .any(|n| child_names.contains(n) || post_child_label_names.contains(n.as_str()))
+            {
+                post_child_label_names.insert(label_fun.label.as_str());
+            }
+        }
+        if post_child_label_names.len() == num_post {
+            break;
+        }
+    }
+    let (pre, post): (Vec<_>, Vec<_>) = labels
+        .iter()
+        .zip(&referenced)
+        .partition(|(l, _)| !post_child_label_names.contains(l.label.as_str()));
+    Ok((
+        order_by_label_references(pre),
+        order_by_label_references(post),
+    ))
+}
+
+/// Order label functions such that referenced labels (of the same list) are computed first
+fn order_by_label_references<'a>(
+    mut remaining: Vec<(&'a LabelFunction, &HashSet<String>)>,
+) -> Vec<&'a LabelFunction> {
+    let mut ordered: Vec<&'a LabelFunction> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let ready = remaining
+            .iter()
+            .position(|(label_fun, refs)| {
+                !refs.iter().any(|n| {
+                    n != &label_fun.label && remaining.iter().any(|(other, _)| &other.label == n)
+                })
+            })
+            // Cyclic references: Keep declaration order
+            .unwrap_or(0);
+        ordered.push(remaining.remove(ready).0);
+    }
+    ordered
 }
 
 pub type EvaluationResult = (usize, Binding, Option<ViolationReason>);
@@ -326,16 +411,30 @@ impl BindingBoxTreeNode {
         };
         // match self {
         //     BindingBoxTreeNode::Box(bbox, children) => {
-        let (expanded, expanding_skipped_bindings): (Vec<Binding>,bool) = bbox.expand(parent_binding.clone(), ocel);
+        let (expanded, expanding_skipped_bindings, cel_failed): (Vec<Binding>,bool,Vec<Binding>) = bbox.expand(parent_binding.clone(), ocel);
         enum BindingResult {
             FilteredOutBySizeFilter(Binding, EvaluationResults),
             Sat(Binding, EvaluationResults),
             Viol(Binding, ViolationReason, EvaluationResults),
         }
         let expanded_len = expanded.len();
+        let child_names: HashSet<String> = children
+            .iter()
+            .map(|c| {
+                tree.edge_names
+                    .get(&(own_index, *c))
+                    .cloned()
+                    .unwrap_or(format!("{UNNAMED}{c}"))
+            })
+            .collect();
+        let (pre_child_labels, post_child_labels, labels_redefined) =
+            match split_label_functions(&bbox.labels, &parent_binding.label_map, &child_names) {
+                Ok((pre, post)) => (pre, post, false),
+                Err(_) => (Vec::new(), Vec::new(), true),
+            };
         let it = rayon_cancel::CancelAdapter::new(expanded.into_par_iter());
         let x = it.canceller();
-        let re: Vec<_> = it
+        let mut re: Vec<_> = it
             .map(|mut b| {
                 let _passed_size_filter = true;
                 // let mut all_res: EvaluationResults = Vec::new();
@@ -343,6 +442,18 @@ impl BindingBoxTreeNode {
                 //     HashMap::new();
                 let mut all_res = Vec::new();
                 let mut child_res = HashMap::with_capacity(children.len());
+                // Bindings of a box redefining (read-only) labels of a parent box are reported as violated
+                if labels_redefined {
+                    let r = vec![(
+                        own_index,
+                        b.clone(),
+                        Some(ViolationReason::LabelAlreadyDefined),
+                    )];
+                    return BindingResult::Viol(b, ViolationReason::LabelAlreadyDefined, r);
+                }
+                for label_fun in &pre_child_labels {
+                    add_cel_label(&mut b, None, ocel, label_fun);
+                }
                 for c in &children {
                     let c_name = tree
                         .edge_names
@@ -367,7 +478,7 @@ impl BindingBoxTreeNode {
 
                     all_res.extend(c_res);
                 }
-                for label_fun in &bbox.labels {
+                for label_fun in &post_child_labels {
                     add_cel_label(&mut b, Some(&child_res), ocel, label_fun);
                 }
                 for sf in &bbox.size_filters {
@@ -380,6 +491,16 @@ impl BindingBoxTreeNode {
 
                 for (constr_index, constr) in bbox.constraints.iter().enumerate() {
                     let viol = match constr {
+                        Constraint::Filter {
+                            filter: Filter::BindingCEL { variable, cel },
+                        } => match b.get_any_index(variable).map(|index| {
+                            get_indices_from_cel(cel, &b, ocel)
+                                .map(|indices| indices.contains(&index))
+                        }) {
+                            Some(Ok(true)) => None,
+                            Some(Err(_)) => Some(ViolationReason::BindingCELFailed),
+                            _ => Some(ViolationReason::ConstraintNotSatisfied(constr_index)),
+                        },
                         Constraint::Filter { filter } => {
                             if filter.check_binding(&b, ocel) {
                                 None
@@ -478,6 +599,11 @@ impl BindingBoxTreeNode {
                 BindingResult::Sat(b, all_res)
             })
             .collect();
+        // Bindings for which a binding CEL expression failed are reported as violated (instead of silently being dropped)
+        re.extend(cel_failed.into_iter().map(|b| {
+            let r = vec![(own_index, b.clone(), Some(ViolationReason::BindingCELFailed))];
+            BindingResult::Viol(b, ViolationReason::BindingCELFailed, r)
+        }));
         let recursive_calls_cancelled = x.is_cancelled();
         (re.into_par_iter()
             .fold(
@@ -654,9 +780,20 @@ pub enum Filter {
     BasicFilterCEL {
         cel: String,
     },
+    /// Variable is bound to one of the events/objects returned by the CEL expression
+    ///
+    /// The expression should evaluate to a list of event/object indices (e.g., `ob_0`) or IDs.
+    /// Other variables referenced in the expression are bound before this variable.
+    BindingCEL {
+        variable: Variable,
+        cel: String,
+    },
 }
 
 impl Filter {
+    /// Check if a binding satisfies the filter
+    ///
+    /// Filters referencing unbound variables are not satisfied.
     pub fn check_binding(&self, b: &Binding, ocel: &IndexLinkedOCEL) -> bool {
         match self {
             Filter::O2E {
@@ -665,13 +802,14 @@ impl Filter {
                 qualifier,
                 filter_label: _,
             } => {
-                let ob = b.get_ob(object, ocel).unwrap();
-                let ev = b.get_ev(event, ocel).unwrap();
+                let (Some(ob), Some(ev)) = (b.get_ob_index(object), b.get_ev_index(event)) else {
+                    return false;
+                };
 
-                ev.relationships.iter().any(|rel| {
-                    rel.object_id == ob.id
+                ocel.get_rels(&EventOrObjectIndex::Event(*ev)).any(|(rel_ob, rel_qualifier)| {
+                    rel_ob == *ob
                         && if let Some(q) = qualifier {
-                            &rel.qualifier == q
+                            rel_qualifier == q.as_str()
                         } else {
                             true
                         }
@@ -683,12 +821,14 @@ impl Filter {
                 qualifier,
                 filter_label: _,
             } => {
-                let ob1 = b.get_ob(object, ocel).unwrap();
-                let ob2 = b.get_ob(other_object, ocel).unwrap();
-                ob1.relationships.iter().any(|rel| {
-                    rel.object_id == ob2.id
+                let (Some(ob1), Some(ob2)) = (b.get_ob_index(object), b.get_ob_index(other_object))
+                else {
+                    return false;
+                };
+                ocel.get_rels(&EventOrObjectIndex::Object(*ob1)).any(|(rel_ob, rel_qualifier)| {
+                    rel_ob == *ob2
                         && if let Some(q) = qualifier {
-                            &rel.qualifier == q
+                            rel_qualifier == q.as_str()
                         } else {
                             true
                         }
@@ -700,8 +840,9 @@ impl Filter {
                 min_seconds: min_sec,
                 max_seconds: max_sec,
             } => {
-                let e1 = b.get_ev(ev_var_1, ocel).unwrap();
-                let e2 = b.get_ev(ev_var_2, ocel).unwrap();
+                let (Some(e1), Some(e2)) = (b.get_ev(ev_var_1, ocel), b.get_ev(ev_var_2, ocel)) else {
+                    return false;
+                };
                 let duration_diff = (e2.time - e1.time).num_milliseconds() as f64 / 1000.0;
                 !min_sec.is_some_and(|min_sec| duration_diff < min_sec)
                     && !max_sec.is_some_and(|max_sec| duration_diff > max_sec)
@@ -716,10 +857,10 @@ impl Filter {
                 attribute_name,
                 value_filter,
             } => {
-                let e_opt = b.get_ev(event, ocel);
+                let e_opt = b.get_ev_index(event);
                 if let Some(e) = e_opt {
-                    if let Some(attr) = e.attributes.iter().find(|at| &at.name == attribute_name) {
-                        value_filter.check_value(&attr.value)
+                    if let Some(attr_value) = ocel.ev_attribute(e, attribute_name) {
+                        value_filter.check_value(attr_value)
                     } else {
                         false
                     }
@@ -733,30 +874,25 @@ impl Filter {
                 at_time,
                 value_filter,
             } => {
-                let o_opt = b.get_ob(object, ocel);
+                let o_opt = b.get_ob_index(object).filter(|o| ocel.ob_by_index(o).is_some());
                 if let Some(o) = o_opt {
                     match at_time {
-                        ObjectValueFilterTimepoint::Always => o
-                            .attributes
-                            .iter()
-                            .filter(|at| &at.name == attribute_name)
-                            .all(|at| value_filter.check_value(&at.value)),
-                        ObjectValueFilterTimepoint::Sometime => o
-                            .attributes
-                            .iter()
-                            .filter(|at| &at.name == attribute_name)
-                            .any(|at| value_filter.check_value(&at.value)),
+                        ObjectValueFilterTimepoint::Always => ocel
+                            .ob_attribute_values(o, attribute_name)
+                            .all(|(value, _)| value_filter.check_value(value)),
+                        ObjectValueFilterTimepoint::Sometime => ocel
+                            .ob_attribute_values(o, attribute_name)
+                            .any(|(value, _)| value_filter.check_value(value)),
                         ObjectValueFilterTimepoint::AtEvent { event } => {
                             if let Some(ev) = b.get_ev(event, ocel) {
                                 // Find last attribute value update _before_ the event occured (or at the same time)
-                                if let Some(last_val_before) = o
-                                    .attributes
-                                    .iter()
-                                    .filter(|at| &at.name == attribute_name && at.time <= ev.time)
-                                    .sorted_by_key(|x| x.time)
+                                if let Some((last_val_before, _)) = ocel
+                                    .ob_attribute_values(o, attribute_name)
+                                    .filter(|(_, time)| **time <= ev.time)
+                                    .sorted_by_key(|(_, time)| **time)
                                     .last()
                                 {
-                                    value_filter.check_value(&last_val_before.value)
+                                    value_filter.check_value(last_val_before)
                                 } else {
                                     false
                                 }
@@ -775,6 +911,11 @@ impl Filter {
                 // println!("Took {:?}",now.elapsed());
                 check_cel_predicate(cel, b, None, ocel)
             }
+            Filter::BindingCEL { variable, cel } => match b.get_any_index(variable) {
+                Some(index) => get_indices_from_cel(cel, b, ocel)
+                    .is_ok_and(|indices| indices.contains(&index)),
+                None => false,
+            },
         }
     }
 }
@@ -1060,6 +1201,11 @@ impl Filter {
                 ret
             }
             Filter::BasicFilterCEL { cel } => get_vars_in_cel_program(cel),
+            Filter::BindingCEL { variable, cel } => {
+                let mut ret = get_vars_in_cel_program(cel);
+                ret.insert(variable.clone());
+                ret
+            }
         }
     }
 }
@@ -1078,6 +1224,8 @@ pub enum BindingStep {
     // bool: reversed?
     BindObFromOb(ObjectVariable, ObjectVariable, Qualifier, bool),
     BindEvFromOb(EventVariable, ObjectVariable, Qualifier),
+    /// Bind variable to the events/objects returned by the CEL expression
+    BindFromCEL(Variable, String),
     Filter(Filter),
 }
 
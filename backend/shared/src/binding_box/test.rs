@@ -315,3 +315,171 @@
 //         )
 //     }
 // }
+
+#[test]
+fn label_functions_are_split_by_child_dependencies() {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::structs::{split_label_functions, LabelFunction, LabelValue};
+
+    let label = |label: &str, cel: &str| LabelFunction {
+        label: label.to_string(),
+        cel: cel.to_string(),
+    };
+    // Labels are declared before the labels they depend on
+    let labels = vec![
+        label("twice", "numA * 2"),
+        label("numA", "size(A)"),
+        label("two", "one + 1"),
+        label("one", "1"),
+    ];
+    let child_names: HashSet<String> = vec!["A".to_string()].into_iter().collect();
+
+    let (pre, post) = split_label_functions(&labels, &BTreeMap::new(), &child_names).unwrap();
+    let names = |ls: Vec<&LabelFunction>| ls.iter().map(|l| l.label.clone()).collect::<Vec<_>>();
+    assert_eq!(names(pre), vec!["one", "two"]);
+    // Labels depending on post-child labels are also computed after the children
+    assert_eq!(names(post), vec!["numA", "twice"]);
+
+    // Labels of parent boxes are read-only
+    let parent_labels: BTreeMap<String, LabelValue> = vec![("one".to_string(), LabelValue::Int(1))]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        split_label_functions(&labels, &parent_labels, &child_names).err(),
+        Some(vec!["one".to_string()])
+    );
+}
+
+#[test]
+fn redefined_parent_labels_are_reported() {
+    use super::{
+        structs::{BindingBoxTreeNode, LabelFunction, ObjectVariable},
+        BindingBox, BindingBoxTree, ViolationReason,
+    };
+    use crate::preprocessing::{linked_ocel::IndexLinkedOCEL, tests::test_ocel};
+
+    let ocel = IndexLinkedOCEL::new(test_ocel(
+        vec![],
+        vec![("o1", "order", vec![]), ("o2", "order", vec![])],
+    ));
+    let with_label = |label: &str| BindingBox {
+        labels: vec![LabelFunction {
+            label: label.to_string(),
+            cel: "1".to_string(),
+        }],
+        ..Default::default()
+    };
+    let tree = |child_label: &str| BindingBoxTree {
+        nodes: vec![
+            BindingBoxTreeNode::Box(
+                BindingBox {
+                    new_object_vars: vec![(
+                        ObjectVariable(0),
+                        vec!["order".to_string()].into_iter().collect(),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    ..with_label("x")
+                },
+                vec![1],
+            ),
+            BindingBoxTreeNode::Box(with_label(child_label), vec![]),
+        ],
+        edge_names: Default::default(),
+    };
+
+    let (res, _) = tree("x").evaluate(&ocel);
+    let child_res: Vec<_> = res.iter().filter(|(index, _, _)| *index == 1).collect();
+    assert_eq!(child_res.len(), 2);
+    assert!(child_res
+        .iter()
+        .all(|(_, _, v)| matches!(v, Some(ViolationReason::LabelAlreadyDefined))));
+
+    let (res, _) = tree("y").evaluate(&ocel);
+    assert!(res.iter().all(|(_, _, v)| v.is_none()));
+}
+
+#[test]
+fn failing_binding_cel_expressions_are_reported() {
+    use super::{
+        structs::{BindingBoxTreeNode, Constraint, Filter, ObjectVariable, Variable},
+        BindingBox, BindingBoxTree, ViolationReason,
+    };
+    use crate::{
+        cel::get_indices_from_cel,
+        preprocessing::{linked_ocel::IndexLinkedOCEL, tests::test_ocel},
+    };
+
+    let ocel = IndexLinkedOCEL::new(test_ocel(
+        vec![("e1", "place order", 0, vec![("o1", "order")])],
+        vec![("o1", "order", vec![]), ("o2", "order", vec![])],
+    ));
+    let empty = super::Binding::default();
+    assert!(get_indices_from_cel("[]", &empty, &ocel).is_ok_and(|i| i.is_empty()));
+    assert_eq!(
+        get_indices_from_cel("['o1']", &empty, &ocel).unwrap().len(),
+        1
+    );
+    assert!(get_indices_from_cel("1 + 1", &empty, &ocel).is_err());
+    assert!(get_indices_from_cel("[", &empty, &ocel).is_err());
+
+    let tree = |filters: Vec<Filter>, constraints: Vec<Constraint>| BindingBoxTree {
+        nodes: vec![BindingBoxTreeNode::Box(
+            BindingBox {
+                new_object_vars: vec![(
+                    ObjectVariable(0),
+                    vec!["order".to_string()].into_iter().collect(),
+                )]
+                .into_iter()
+                .collect(),
+                filters,
+                constraints,
+                ..Default::default()
+            },
+            vec![],
+        )],
+        edge_names: Default::default(),
+    };
+    let binding_cel = |cel: &str| Filter::BindingCEL {
+        variable: Variable::Object(ObjectVariable(0)),
+        cel: cel.to_string(),
+    };
+
+    // As a constraint, a failing expression is reported for every binding
+    let (res, _) = tree(
+        vec![],
+        vec![Constraint::Filter {
+            filter: binding_cel("1 + 1"),
+        }],
+    )
+    .evaluate(&ocel);
+    assert_eq!(res.len(), 2);
+    assert!(res
+        .iter()
+        .all(|(_, _, v)| matches!(v, Some(ViolationReason::BindingCELFailed))));
+
+    // ...while a non-matching expression is a regular violation
+    let (res, _) = tree(
+        vec![],
+        vec![Constraint::Filter {
+            filter: binding_cel("['o1']"),
+        }],
+    )
+    .evaluate(&ocel);
+    assert_eq!(res.iter().filter(|(_, _, v)| v.is_none()).count(), 1);
+    assert_eq!(
+        res.iter()
+            .filter(|(_, _, v)| matches!(v, Some(ViolationReason::ConstraintNotSatisfied(0))))
+            .count(),
+        1
+    );
+
+    // When binding from a failing expression, the (partial) binding is reported instead of silently dropped
+    let (res, _) = tree(vec![binding_cel("1 + 1")], vec![]).evaluate(&ocel);
+    assert!(res
+        .iter()
+        .any(|(_, _, v)| matches!(v, Some(ViolationReason::BindingCELFailed))));
+    let (res, _) = tree(vec![binding_cel("[]")], vec![]).evaluate(&ocel);
+    assert!(res.is_empty());
+}
@@ -0,0 +1,335 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, Error};
+use cel_interpreter::{Program, Value};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{binding_box::Binding, preprocessing::linked_ocel::IndexLinkedOCEL};
+
+use super::{execute_cel_program, BUILTIN_CEL_FUNCTIONS};
+
+/// A user-defined CEL function
+///
+/// Receives all arguments (including the target of a method call, e.g., `o1` in `o1.f(x)`, as first argument)
+/// and the OCEL the expression is evaluated on.
+pub type CustomCELFunction =
+    Arc<dyn Fn(&[Value], &IndexLinkedOCEL) -> Result<Value, String> + Send + Sync>;
+
+static CUSTOM_CEL_FUNCTIONS: Lazy<RwLock<HashMap<String, CustomCELFunction>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Environment variable which can be used to override the CEL plugin directory
+pub const CEL_PLUGIN_DIR_ENV: &str = "OCPQ_CEL_PLUGIN_DIR";
+
+/// Maximal nesting depth of calls to plugin-defined CEL functions
+///
+/// Plugin functions can call each other, so (possibly indirectly) recursive definitions
+/// fail with an error at this depth instead of overflowing the stack.
+pub const MAX_CEL_PLUGIN_CALL_DEPTH: usize = 16;
+
+thread_local! {
+    // Nested plugin function calls are executed on the same thread as the outer call
+    static CEL_PLUGIN_CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Register a custom function, which is then available in all CEL expressions
+///
+/// Functions with the same name as a previously registered custom function replace it.
+/// Names of built-in functions (e.g., `attr`) can not be used.
+pub fn register_cel_function<F>(name: impl Into<String>, f: F) -> Result<(), Error>
+where
+    F: Fn(&[Value], &IndexLinkedOCEL) -> Result<Value, String> + Send + Sync + 'static,
+{
+    let name = name.into();
+    if BUILTIN_CEL_FUNCTIONS.contains(&name.as_str()) {
+        return Err(anyhow!("{name} is a built-in CEL function"));
+    }
+    CUSTOM_CEL_FUNCTIONS
+        .write()
+        .unwrap()
+        .insert(name, Arc::new(f));
+    Ok(())
+}
+
+pub fn unregister_cel_function(name: &str) -> bool {
+    CUSTOM_CEL_FUNCTIONS.write().unwrap().remove(name).is_some()
+}
+
+pub fn get_custom_cel_function_names() -> Vec<String> {
+    let mut names: Vec<String> = CUSTOM_CEL_FUNCTIONS
+        .read()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+pub(crate) fn get_custom_cel_functions() -> Vec<(String, CustomCELFunction)> {
+    CUSTOM_CEL_FUNCTIONS
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(name, f)| (name.clone(), Arc::clone(f)))
+        .collect()
+}
+
+/// A CEL function defined in a plugin file
+///
+/// The function body is a CEL expression, in which the parameters are available as variables.
+/// All other CEL functions (built-in or custom) can be used in the body.
+///
+/// Plugin files are JSON files containing either a single definition or a list of definitions, e.g.,
+/// `{"name": "isHeavy", "params": ["o"], "cel": "o.attr('weight') > 100.0"}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CELFunctionDefinition {
+    pub name: String,
+    pub params: Vec<String>,
+    pub cel: String,
+}
+
+impl CELFunctionDefinition {
+    pub fn register(&self) -> Result<(), Error> {
+        let program = Program::compile(&self.cel)
+            .map_err(|e| anyhow!("Could not compile CEL function {}: {e:?}", self.name))?;
+        let name = self.name.clone();
+        let params = self.params.clone();
+        register_cel_function(self.name.clone(), move |args, ocel| {
+            if args.len() != params.len() {
+                return Err(format!(
+                    "{name} expects {} arguments, got {}",
+                    params.len(),
+                    args.len()
+                ));
+            }
+            let depth = CEL_PLUGIN_CALL_DEPTH.get();
+            if depth >= MAX_CEL_PLUGIN_CALL_DEPTH {
+                return Err(format!(
+                    "{name} exceeds the maximal call depth of CEL plugin functions ({MAX_CEL_PLUGIN_CALL_DEPTH}), is it defined recursively?"
+                ));
+            }
+            let variables = params
+                .iter()
+                .cloned()
+                .zip(args.iter().cloned())
+                .collect::<Vec<_>>();
+            CEL_PLUGIN_CALL_DEPTH.set(depth + 1);
+            let res = execute_cel_program(&program, &Binding::default(), None, &variables, ocel)
+                .map_err(|e| format!("{e:?}"));
+            CEL_PLUGIN_CALL_DEPTH.set(depth);
+            res
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CELPluginFile {
+    Single(CELFunctionDefinition),
+    Multiple(Vec<CELFunctionDefinition>),
+}
+
+/// Directory from which CEL plugins are loaded at startup
+///
+/// Uses the `OCPQ_CEL_PLUGIN_DIR` environment variable if set, and `<data dir>/ocpq/cel-plugins` otherwise
+pub fn get_default_cel_plugin_dir() -> Option<PathBuf> {
+    match std::env::var_os(CEL_PLUGIN_DIR_ENV) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs_next::data_dir().map(|p| p.join("ocpq").join("cel-plugins")),
+    }
+}
+
+/// Load and register all CEL function definitions from `.json` files in the given directory
+///
+/// Returns the names of all registered functions. Invalid files are skipped (with an error message).
+pub fn load_cel_plugins_from_dir(dir: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    let mut registered = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.extension().is_some_and(|ext| ext == "json") {
+            continue;
+        }
+        let defs = match serde_json::from_reader(BufReader::new(File::open(&path)?)) {
+            Ok(CELPluginFile::Single(def)) => vec![def],
+            Ok(CELPluginFile::Multiple(defs)) => defs,
+            Err(e) => {
+                eprintln!("Could not parse CEL plugin file {path:?}: {e:?}");
+                continue;
+            }
+        };
+        for def in defs {
+            match def.register() {
+                Ok(()) => registered.push(def.name),
+                Err(e) => eprintln!("Could not register CEL function from {path:?}: {e:?}"),
+            }
+        }
+    }
+    Ok(registered)
+}
+
+/// Load CEL plugins from the default plugin directory (if it exists)
+pub fn load_default_cel_plugins() -> Vec<String> {
+    match get_default_cel_plugin_dir() {
+        Some(dir) if dir.is_dir() => match load_cel_plugins_from_dir(&dir) {
+            Ok(names) => {
+                println!("Loaded {} CEL plugin function(s) from {dir:?}", names.len());
+                names
+            }
+            Err(e) => {
+                eprintln!("Could not load CEL plugins from {dir:?}: {e:?}");
+                Vec::new()
+            }
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn builtin_functions_can_not_be_replaced() {
+    for name in [
+        "size",
+        "max",
+        "contains",
+        "startsWith",
+        "duration",
+        "timestamp",
+        "attr",
+    ] {
+        assert!(register_cel_function(name, |_, _| Ok(Value::Null)).is_err());
+    }
+    assert!(!get_custom_cel_function_names().contains(&"size".to_string()));
+
+    register_cel_function("testRegistryAnswer", |_, _| Ok(Value::Int(42))).unwrap();
+    assert!(get_custom_cel_function_names().contains(&"testRegistryAnswer".to_string()));
+    assert!(unregister_cel_function("testRegistryAnswer"));
+    assert!(!unregister_cel_function("testRegistryAnswer"));
+}
+
+#[test]
+fn cel_plugins_are_loaded_from_dir() {
+    use crate::{cel::evaluate_cel, preprocessing::tests::test_ocel};
+
+    let dir = std::env::temp_dir().join(format!("ocpq-cel-plugins-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("single.json"),
+        r#"{"name": "testPluginDouble", "params": ["x"], "cel": "x * 2"}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("multiple.json"),
+        r#"[
+            {"name": "testPluginInc", "params": ["x"], "cel": "x + 1"},
+            {"name": "size", "params": ["x"], "cel": "0"},
+            {"name": "testPluginInvalid", "params": [], "cel": "1 +"}
+        ]"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("invalid.json"), "{").unwrap();
+    std::fs::write(dir.join("ignored.txt"), "not a plugin").unwrap();
+
+    let mut names = load_cel_plugins_from_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    names.sort();
+    assert_eq!(names, vec!["testPluginDouble", "testPluginInc"]);
+
+    let ocel = IndexLinkedOCEL::new(test_ocel(vec![], vec![]));
+    let binding = Binding::default();
+    assert_eq!(
+        evaluate_cel("testPluginInc(testPluginDouble(20))", &binding, None, &ocel).unwrap(),
+        Value::Int(41)
+    );
+    // Built-in functions are not shadowed by plugins
+    assert_eq!(
+        evaluate_cel("size([1, 2, 3])", &binding, None, &ocel).unwrap(),
+        Value::Int(3)
+    );
+    assert!(load_cel_plugins_from_dir(dir.join("missing")).is_err());
+}
+
+#[test]
+fn recursive_cel_plugins_fail_instead_of_overflowing() {
+    use crate::{cel::evaluate_cel, preprocessing::tests::test_ocel};
+
+    let def = |name: &str, cel: &str| CELFunctionDefinition {
+        name: name.to_string(),
+        params: vec!["x".to_string()],
+        cel: cel.to_string(),
+    };
+    def("testPluginPing", "testPluginPong(x + 1)")
+        .register()
+        .unwrap();
+    def("testPluginPong", "testPluginPing(x + 1)")
+        .register()
+        .unwrap();
+    def(
+        "testPluginCountdown",
+        "x <= 0 ? 0 : testPluginCountdown(x - 1) + 1",
+    )
+    .register()
+    .unwrap();
+
+    let ocel = IndexLinkedOCEL::new(test_ocel(vec![], vec![]));
+    let binding = Binding::default();
+    assert!(evaluate_cel("testPluginPing(0)", &binding, None, &ocel).is_err());
+    // Bounded recursion below the maximal depth is fine
+    assert_eq!(
+        evaluate_cel("testPluginCountdown(5)", &binding, None, &ocel).unwrap(),
+        Value::Int(5)
+    );
+    assert!(evaluate_cel("testPluginCountdown(100)", &binding, None, &ocel).is_err());
+    for name in ["testPluginPing", "testPluginPong", "testPluginCountdown"] {
+        unregister_cel_function(name);
+    }
+}
+
+#[test]
+fn builtin_function_list_matches_registered_functions() {
+    use cel_interpreter::ExecutionError;
+
+    use crate::{
+        cel::{evaluate_cel, CELEvalError},
+        preprocessing::tests::test_ocel,
+    };
+
+    let ocel = IndexLinkedOCEL::new(test_ocel(vec![], vec![]));
+    let is_undeclared = |cel: &str| {
+        matches!(
+            evaluate_cel(cel, &Binding::default(), None, &ocel),
+            Err(CELEvalError::ExecError(
+                ExecutionError::UndeclaredReference(_)
+            ))
+        )
+    };
+    assert!(is_undeclared("testNotAFunction()"));
+    // All listed functions are available (calls without arguments fail, but not as undeclared references)...
+    for name in BUILTIN_CEL_FUNCTIONS {
+        assert!(
+            !is_undeclared(&format!("{name}()")),
+            "{name} is not available"
+        );
+    }
+    // ...and all OCEL functions added in `execute_cel_program` are listed
+    let registered_names: Vec<&str> = include_str!("mod.rs")
+        .split("context.add_function(")
+        .skip(1)
+        .filter_map(|registration| registration.trim_start().strip_prefix('"'))
+        .filter_map(|registration| registration.split('"').next())
+        .collect();
+    assert!(registered_names.contains(&"attr"));
+    for name in registered_names {
+        assert!(
+            BUILTIN_CEL_FUNCTIONS.contains(&name),
+            "{name} is missing in BUILTIN_CEL_FUNCTIONS"
+        );
+    }
+}
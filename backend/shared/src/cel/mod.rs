@@ -5,10 +5,11 @@ use std::{
 };
 
 use cel_interpreter::{
-    extractors::This, objects::Map, Context, ExecutionError, FunctionContext, Program,
+    extractors::{Arguments, This},
+    objects::Map, Context, ExecutionError, FunctionContext, Program,
     ResolveResult, Value,
 };
-use chrono::{DateTime, FixedOffset, Local};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use process_mining::ocel::ocel_struct::OCELAttributeValue;
@@ -23,19 +24,101 @@ use crate::{
     },
 };
 
+pub mod custom_functions;
+
+use custom_functions::get_custom_cel_functions;
+
+/// Names of all functions which are available in CEL expressions by default
+///
+/// This includes the standard functions of the CEL interpreter (see `Context::default`) and the OCEL functions added by OCPQ.
+/// A test in `custom_functions` checks this list against the functions registered in `execute_cel_program`.
+pub const BUILTIN_CEL_FUNCTIONS: &[&str] = &[
+    // Standard functions of cel-interpreter
+    "contains",
+    "size",
+    "has",
+    "map",
+    "filter",
+    "all",
+    "max",
+    "startsWith",
+    "endsWith",
+    "matches",
+    "string",
+    "bytes",
+    "double",
+    "int",
+    "uint",
+    "exists",
+    "exists_one",
+    "duration",
+    "timestamp",
+    "getFullYear",
+    "getMonth",
+    "getDayOfYear",
+    "getDayOfMonth",
+    "getDate",
+    "getDayOfWeek",
+    "getHours",
+    "getMinutes",
+    "getSeconds",
+    "getMilliseconds",
+    // OCEL functions
+    "type",
+    "min",
+    "attr",
+    "attrAt",
+    "id",
+    "attrs",
+    "time",
+    "numEvents",
+    "numObjects",
+    "events",
+    "objects",
+    "objects_related_via",
+    "events_related_via",
+    "sum",
+    "avg",
+];
+
 fn string_to_index(s: &str) -> Option<EventOrObjectIndex> {
     // ob_ and ev_ are the prefixes we reserve
-    let (typ, num) = s.split_at(3);
-    let num = num.parse::<usize>().ok()?;
-    if typ == "ob_" {
-        Some(EventOrObjectIndex::Object(ObjectIndex(num)))
-    } else if typ == "ev_" {
-        Some(EventOrObjectIndex::Event(EventIndex(num)))
+    if let Some(num) = s.strip_prefix("ob_") {
+        Some(EventOrObjectIndex::Object(ObjectIndex(num.parse::<usize>().ok()?)))
+    } else if let Some(num) = s.strip_prefix("ev_") {
+        Some(EventOrObjectIndex::Event(EventIndex(num.parse::<usize>().ok()?)))
     } else {
         None
     }
 }
 
+/// Resolve a CEL string value (either an index name, e.g. `ob_0`, or an event/object ID) to indices
+fn string_to_indices(s: &str, ocel: &IndexLinkedOCEL) -> Vec<EventOrObjectIndex> {
+    if let Some(index) = string_to_index(s) {
+        return vec![index];
+    }
+    let mut ret = Vec::new();
+    if let Some(ob_index) = ocel.object_index_map.get(s) {
+        ret.push(EventOrObjectIndex::Object(*ob_index));
+    }
+    if let Some(ev_index) = ocel.event_index_map.get(s) {
+        ret.push(EventOrObjectIndex::Event(*ev_index));
+    }
+    ret
+}
+
+fn related_via<'a>(
+    variable: &str,
+    qualifier: &'a str,
+    ocel: &'a IndexLinkedOCEL,
+) -> impl Iterator<Item = EventOrObjectIndex> + 'a {
+    string_to_index(variable)
+        .into_iter()
+        .flat_map(move |index| ocel.get_symmetric_rels(&index))
+        .filter(move |(_, _, q)| *q == qualifier)
+        .map(|(rel_index, _, _)| rel_index)
+}
+
 struct RawBindingContextPtr<'a, T>(*mut &'a T);
 
 unsafe impl<T> Send for RawBindingContextPtr<'_, T> {}
@@ -48,6 +131,11 @@ impl<T> Clone for RawBindingContextPtr<'_, T> {
 
 impl<T> Copy for RawBindingContextPtr<'_, T> {}
 
+/// Index of an event/object given as index string (e.g., `ob_0`), if it exists in the OCEL
+fn string_to_existing_index(s: &str, ocel: &IndexLinkedOCEL) -> Option<EventOrObjectIndex> {
+    string_to_index(s).filter(|index| ocel.ob_or_ev_by_index(*index).is_some())
+}
+
 fn index_string_to_val<'a>(s: &str, ocel: &'a IndexLinkedOCEL) -> Option<OCELNodeRef<'a>> {
     let index = string_to_index(s)?;
     ocel.ob_or_ev_by_index(index)
@@ -72,9 +160,11 @@ pub static CEL_PROGRAM_CACHE: Lazy<RwLock<HashMap<String, Program>>> = Lazy::new
 pub fn lazy_compile_and_insert_into_cache(cel: &str) {
     let already_in_cache = CEL_PROGRAM_CACHE.read().unwrap().contains_key(cel);
     if !already_in_cache {
-        let program = Program::compile(cel).unwrap();
-        let mut w_lock = CEL_PROGRAM_CACHE.write().unwrap();
-        w_lock.insert(cel.to_string(), program);
+        // Invalid expressions are not cached (evaluating them fails with a parse error)
+        if let Ok(program) = Program::compile(cel) {
+            let mut w_lock = CEL_PROGRAM_CACHE.write().unwrap();
+            w_lock.insert(cel.to_string(), program);
+        }
     }
 }
 
@@ -102,310 +192,387 @@ pub fn evaluate_cel<'a>(
     lazy_compile_and_insert_into_cache(cel);
     let cache_read = CEL_PROGRAM_CACHE.read().unwrap();
     if let Some(p) = cache_read.get(cel) {
-        // println!("Program compiled: {:?}", now.elapsed());
-
-        let mut context: Context<'a> = Context::default();
-        for (e_var, e_index) in binding.event_map.iter() {
-            let name = ev_var_to_name(e_var);
-            let value = ev_index_to_name(e_index);
-            context.add_variable(name, value).unwrap();
-        }
-        for (o_var, o_index) in binding.object_map.iter() {
-            let name = ob_var_to_name(o_var);
-            let value = ob_index_to_name(o_index);
-            context.add_variable(name, value).unwrap();
-        }
+        execute_cel_program(p, binding, child_res, &[], ocel)
+    } else {
+        Err(CELEvalError::ParseError)
+    }
+}
 
-        for (label, value) in binding.label_map.iter() {
-            context
-                .add_variable(label, Into::<cel_interpreter::Value>::into(value.clone()))
-                .unwrap();
-        }
+/// Execute a compiled CEL program
+///
+/// Additional `variables` are added to the context (e.g., parameters of custom CEL functions)
+pub(crate) fn execute_cel_program<'a>(
+    p: &Program,
+    binding: &'a Binding,
+    child_res: Option<&HashMap<String, Vec<(Binding, Option<ViolationReason>)>>>,
+    variables: &[(String, Value)],
+    ocel: &'a IndexLinkedOCEL,
+) -> Result<Value, CELEvalError> {
+    let mut context: Context<'a> = Context::default();
+    for (e_var, e_index) in binding.event_map.iter() {
+        let name = ev_var_to_name(e_var);
+        let value = ev_index_to_name(e_index);
+        context.add_variable(name, value).unwrap();
+    }
+    for (o_var, o_index) in binding.object_map.iter() {
+        let name = ob_var_to_name(o_var);
+        let value = ob_index_to_name(o_index);
+        context.add_variable(name, value).unwrap();
+    }
 
+    for (label, value) in binding.label_map.iter() {
         context
-            .add_variable("now", Value::Timestamp(Local::now().into()))
+            .add_variable(label, Into::<cel_interpreter::Value>::into(value.clone()))
             .unwrap();
+    }
 
-        if let Some(child_res) = child_res {
-            for (child_name, child_out) in child_res {
-                let value: Vec<Value> = child_out
-                    .iter()
-                    .map(|(b, violated)| {
-                        let mut b_map = HashMap::new();
-                        b_map.extend(b.event_map.iter().map(|(ev_v, ev_i)| {
-                            (ev_var_to_name(ev_v).into(), ev_index_to_name(ev_i).into())
-                        }));
-                        b_map.extend(b.object_map.iter().map(|(ob_v, ob_i)| {
-                            (ob_var_to_name(ob_v).into(), ob_index_to_name(ob_i).into())
-                        }));
-                        b_map.extend(b.label_map.iter().map(|(label, value)| {
-                            (label.clone().into(), Into::<cel_interpreter::Value>::into(value.clone()))
-                        }));
-                        b_map.insert("satisfied".into(), violated.is_none().into());
-                        Value::Map(Map {
-                            map: Arc::new(b_map),
-                        })
+    context
+        .add_variable("now", Value::Timestamp(Local::now().into()))
+        .unwrap();
+
+    if let Some(child_res) = child_res {
+        for (child_name, child_out) in child_res {
+            let value: Vec<Value> = child_out
+                .iter()
+                .map(|(b, violated)| {
+                    let mut b_map = HashMap::new();
+                    b_map.extend(b.event_map.iter().map(|(ev_v, ev_i)| {
+                        (ev_var_to_name(ev_v).into(), ev_index_to_name(ev_i).into())
+                    }));
+                    b_map.extend(b.object_map.iter().map(|(ob_v, ob_i)| {
+                        (ob_var_to_name(ob_v).into(), ob_index_to_name(ob_i).into())
+                    }));
+                    b_map.extend(b.label_map.iter().map(|(label, value)| {
+                        (label.clone().into(), Into::<cel_interpreter::Value>::into(value.clone()))
+                    }));
+                    b_map.insert("satisfied".into(), violated.is_none().into());
+                    Value::Map(Map {
+                        map: Arc::new(b_map),
                     })
-                    .collect_vec();
-                context.add_variable_from_value(child_name.clone(), value)
-            }
+                })
+                .collect_vec();
+            context.add_variable_from_value(child_name.clone(), value)
         }
+    }
 
-        // println!("Context added: {:?}", now.elapsed());
-
-        let ocel_raw = RawBindingContextPtr(unsafe {
-            std::mem::transmute::<*mut &'a IndexLinkedOCEL, *mut &'static IndexLinkedOCEL>(
-                Box::into_raw(Box::new(ocel)),
-            )
-        });
-        // let binding_raw = RawBindingContextPtr(unsafe {
-        //     std::mem::transmute::<*mut &'a Binding, *mut &'static Binding>(Box::into_raw(Box::new(
-        //         binding,
-        //     )))
-        // });
+    for (name, value) in variables {
+        context.add_variable_from_value(name.clone(), value.clone());
+    }
 
+    // println!("Context added: {:?}", now.elapsed());
+
+    let ocel_raw = RawBindingContextPtr(unsafe {
+        std::mem::transmute::<*mut &'a IndexLinkedOCEL, *mut &'static IndexLinkedOCEL>(
+            Box::into_raw(Box::new(ocel)),
+        )
+    });
+    // let binding_raw = RawBindingContextPtr(unsafe {
+    //     std::mem::transmute::<*mut &'a Binding, *mut &'static Binding>(Box::into_raw(Box::new(
+    //         binding,
+    //     )))
+    // });
+
+    // Custom functions are added before the OCEL functions, so that they can not replace them
+    // (registering custom functions with the name of a standard function of the interpreter is not allowed)
+    for (name, f) in get_custom_cel_functions() {
         context.add_function(
-            "type",
-            move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-
-                match val {
-                    Some(val_ref) => {
-                        let ocel_type = match val_ref {
-                            OCELNodeRef::Event(ev) => &ev.event_type,
-                            OCELNodeRef::Object(ob) => &ob.object_type,
-                        };
-                        Ok(ocel_type.clone().into())
-                    }
-
-                    None => ftx.error("Event or Object not found.").into(),
+            name,
+            move |ftx: &FunctionContext, Arguments(args): Arguments| -> ResolveResult {
+                let mut all_args = Vec::with_capacity(args.len() + 1);
+                if let Some(this) = &ftx.this {
+                    all_args.push(this.clone());
                 }
+                all_args.extend(args.iter().cloned());
+                let ocel = unsafe { get_ocel_raw(ocel_raw) };
+                f(&all_args, ocel).map_err(|e| ftx.error(e))
             },
         );
+    }
 
-        context.add_function("min", |cel_interpreter::extractors::Arguments(args): cel_interpreter::extractors::Arguments| -> Result<Value,ExecutionError> {
-            // If items is a list of values, then operate on the list
-            let items = if args.len() == 1 {
-                match &args[0] {
-                    Value::List(values) => values,
-                    _ => return Ok(args[0].clone()),
+    context.add_function(
+        "type",
+        move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
+            let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
+
+            match val {
+                Some(val_ref) => {
+                    let ocel_type = match val_ref {
+                        OCELNodeRef::Event(ev) => &ev.event_type,
+                        OCELNodeRef::Object(ob) => &ob.object_type,
+                    };
+                    Ok(ocel_type.clone().into())
                 }
-            } else {
-                &args
-            };
-            items
-                .iter()
-                .skip(1)
-                .try_fold(items.first().unwrap_or(&Value::Null), |acc, x| {
-                    match acc.partial_cmp(x) {
-                        Some(std::cmp::Ordering::Less) => Ok(acc),
-                        Some(_) => Ok(x),
-                        None => Err(ExecutionError::ValuesNotComparable(acc.clone(), x.clone())),
-                    }
-                })
-                .cloned()
-        });
 
-        context.add_function(
-            "attr",
-            move |ftx: &FunctionContext,
-                  This(variable): This<Arc<String>>,
-                  attr_name: Arc<String>|
-                  -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-                let res = match val {
-                    Some(val_ref) => {
-                        let attr_val = match val_ref {
-                            OCELNodeRef::Event(ev) => ev
-                                .attributes
-                                .iter()
-                                .find(|a| &a.name == attr_name.as_ref())
-                                .map(|a| &a.value),
-                            OCELNodeRef::Object(ob) => ob
-                                .attributes
-                                .iter()
-                                .find(|a| &a.name == attr_name.as_ref())
-                                .map(|a| &a.value),
+                None => ftx.error("Event or Object not found.").into(),
+            }
+        },
+    );
+
+    context.add_function("min", |Arguments(args): Arguments| -> Result<Value,ExecutionError> {
+        // If items is a list of values, then operate on the list
+        let items = if args.len() == 1 {
+            match &args[0] {
+                Value::List(values) => values,
+                _ => return Ok(args[0].clone()),
+            }
+        } else {
+            &args
+        };
+        items
+            .iter()
+            .skip(1)
+            .try_fold(items.first().unwrap_or(&Value::Null), |acc, x| {
+                match acc.partial_cmp(x) {
+                    Some(std::cmp::Ordering::Less) => Ok(acc),
+                    Some(_) => Ok(x),
+                    None => Err(ExecutionError::ValuesNotComparable(acc.clone(), x.clone())),
+                }
+            })
+            .cloned()
+    });
+
+    context.add_function(
+        "attr",
+        move |ftx: &FunctionContext,
+              This(variable): This<Arc<String>>,
+              attr_name: Arc<String>|
+              -> ResolveResult {
+            let ocel = unsafe { get_ocel_raw(ocel_raw) };
+            let res = match string_to_existing_index(&variable, ocel) {
+                Some(index) => {
+                    let attr_val = match index {
+                        EventOrObjectIndex::Event(ev_index) => {
+                            ocel.ev_attribute(&ev_index, &attr_name)
+                        }
+                        EventOrObjectIndex::Object(ob_index) => {
+                            ocel.ob_attribute(&ob_index, &attr_name)
                         }
-                        .unwrap_or(&OCELAttributeValue::Null);
-                        let cel_val = match attr_val {
-                            OCELAttributeValue::Float(f) => (*f).into(),
-                            OCELAttributeValue::Integer(i) => (*i).into(),
-                            OCELAttributeValue::String(s) => s.clone().into(),
-                            OCELAttributeValue::Time(t) => t.fixed_offset().into(),
-                            OCELAttributeValue::Boolean(b) => (*b).into(),
-                            OCELAttributeValue::Null => Value::Null,
-                        };
-                        Ok(cel_val)
                     }
+                    .unwrap_or(&OCELAttributeValue::Null);
+                    let cel_val = match attr_val {
+                        OCELAttributeValue::Float(f) => (*f).into(),
+                        OCELAttributeValue::Integer(i) => (*i).into(),
+                        OCELAttributeValue::String(s) => s.clone().into(),
+                        OCELAttributeValue::Time(t) => t.fixed_offset().into(),
+                        OCELAttributeValue::Boolean(b) => (*b).into(),
+                        OCELAttributeValue::Null => Value::Null,
+                    };
+                    Ok(cel_val)
+                }
 
-                    None => ftx.error("Event or Object not found.").into(),
-                };
-                res
-            },
-        );
-
-        context.add_function(
-            "attrAt",
-            move |ftx: &FunctionContext,
-                  This(variable): This<Arc<String>>,
-                  attr_name: Arc<String>,
-                  at: DateTime<FixedOffset>|
-                  -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-                let res = match val {
-                    Some(val_ref) => {
-                        let attr_val = match val_ref {
-                            OCELNodeRef::Event(ev) => ev
-                                .attributes
-                                .iter()
-                                .find(|a| &a.name == attr_name.as_ref())
-                                .map(|a| &a.value),
-                            OCELNodeRef::Object(ob) => ob
-                                .attributes
-                                .iter()
-                                .filter(|a| &a.name == attr_name.as_ref())
-                                .sorted_by_key(|a| a.time)
-                                .filter(|a| a.time <= at)
-                                .last()
-                                .map(|a| &a.value),
+                None => ftx.error("Event or Object not found.").into(),
+            };
+            res
+        },
+    );
+
+    context.add_function(
+        "attrAt",
+        move |ftx: &FunctionContext,
+              This(variable): This<Arc<String>>,
+              attr_name: Arc<String>,
+              at: DateTime<FixedOffset>|
+              -> ResolveResult {
+            let ocel = unsafe { get_ocel_raw(ocel_raw) };
+            let res = match string_to_existing_index(&variable, ocel) {
+                Some(index) => {
+                    let attr_val = match index {
+                        EventOrObjectIndex::Event(ev_index) => {
+                            ocel.ev_attribute(&ev_index, &attr_name)
                         }
-                        .unwrap_or(&OCELAttributeValue::Null);
-                        Ok(ocel_val_to_cel_val(attr_val))
+                        EventOrObjectIndex::Object(ob_index) => ocel
+                            .ob_attribute_values(&ob_index, &attr_name)
+                            .sorted_by_key(|(_, time)| **time)
+                            .filter(|(_, time)| **time <= at)
+                            .last()
+                            .map(|(value, _)| value),
                     }
+                    .unwrap_or(&OCELAttributeValue::Null);
+                    Ok(ocel_val_to_cel_val(attr_val))
+                }
 
-                    None => ftx.error("Event or Object not found.").into(),
-                };
-                res
-            },
-        );
-
-        context.add_function(
-            "id",
-            move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-
-                match val {
-                    Some(val_ref) => {
-                        let attr_val = match val_ref {
-                            OCELNodeRef::Event(ev) => &ev.id,
-                            OCELNodeRef::Object(ob) => &ob.id,
-                        };
-                        Ok(attr_val.clone().into())
-                    }
+                None => ftx.error("Event or Object not found.").into(),
+            };
+            res
+        },
+    );
+
+    context.add_function(
+        "id",
+        move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
+            let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
+
+            match val {
+                Some(val_ref) => {
+                    let attr_val = match val_ref {
+                        OCELNodeRef::Event(ev) => &ev.id,
+                        OCELNodeRef::Object(ob) => &ob.id,
+                    };
+                    Ok(attr_val.clone().into())
+                }
 
-                    None => ftx.error("Event or Object not found.").into(),
+                None => ftx.error("Event or Object not found.").into(),
+            }
+        },
+    );
+
+    context.add_function(
+        "attrs",
+        move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
+            let ocel = unsafe { get_ocel_raw(ocel_raw) };
+            let res = match string_to_existing_index(&variable, ocel) {
+                Some(index) => {
+                    let attr_val: Vec<Vec<Value>> = match index {
+                        EventOrObjectIndex::Event(ev_index) => ocel
+                            .ev_attributes(&ev_index)
+                            .map(|(name, value)| {
+                                vec![
+                                    name.to_string().into(),
+                                    ocel_val_to_cel_val(value),
+                                    Value::Null,
+                                ]
+                            })
+                            .collect(),
+                        EventOrObjectIndex::Object(ob_index) => ocel
+                            .ob_attributes(&ob_index)
+                            .map(|(name, value, time)| {
+                                vec![
+                                    name.to_string().into(),
+                                    ocel_val_to_cel_val(value),
+                                    time.fixed_offset().into(),
+                                ]
+                            })
+                            .collect(),
+                    };
+                    Ok(attr_val.into())
                 }
-            },
-        );
 
-        context.add_function(
-            "attrs",
-            move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-                let res = match val {
-                    Some(val_ref) => {
-                        let attr_val: Vec<Vec<Value>> = match val_ref {
-                            OCELNodeRef::Event(ev) => ev
-                                .attributes
-                                .iter()
-                                .map(|a| {
-                                    vec![
-                                        a.name.clone().into(),
-                                        ocel_val_to_cel_val(&a.value),
-                                        Value::Null,
-                                    ]
-                                })
-                                .collect(),
-                            OCELNodeRef::Object(ob) => ob
-                                .attributes
-                                .iter()
-                                .map(|a| {
-                                    vec![
-                                        a.name.clone().into(),
-                                        ocel_val_to_cel_val(&a.value),
-                                        a.time.fixed_offset().into(),
-                                    ]
-                                })
-                                .collect(),
-                        };
-                        Ok(attr_val.into())
-                    }
+                None => ftx.error("Event or Object not found.").into(),
+            };
+            res
+        },
+    );
+
+    context.add_function(
+        "time",
+        move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
+            let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
+            match val {
+                Some(OCELNodeRef::Event(ev)) => Ok(ev.time.fixed_offset().into()),
+                _ => ftx.error("Event not found.").into(),
+            }
+        },
+    );
 
-                    None => ftx.error("Event or Object not found.").into(),
-                };
-                res
-            },
-        );
+    context.add_function("numEvents", move || -> ResolveResult {
+        unsafe { Ok((get_ocel_raw(ocel_raw).ocel.events.len() as u64).into()) }
+    });
+    context.add_function("numObjects", move || -> ResolveResult {
+        unsafe { Ok((get_ocel_raw(ocel_raw).ocel.objects.len() as u64).into()) }
+    });
 
-        context.add_function(
-            "time",
-            move |ftx: &FunctionContext, This(variable): This<Arc<String>>| -> ResolveResult {
-                let val = unsafe { index_string_to_val_raw(&variable, ocel_raw) };
-                match val {
-                    Some(OCELNodeRef::Event(ev)) => Ok(ev.time.fixed_offset().into()),
-                    _ => ftx.error("Event not found.").into(),
-                }
-            },
-        );
+    context.add_function("events", move || -> ResolveResult {
+        unsafe {
+            Ok((get_ocel_raw(ocel_raw)
+                .ocel
+                .events
+                .iter()
+                .enumerate()
+                .map(|(i, _)| ev_index_to_name(&EventIndex(i))))
+            .collect_vec()
+            .into())
+        }
+    });
 
-        context.add_function("numEvents", move || -> ResolveResult {
-            unsafe { Ok((get_ocel_raw(ocel_raw).ocel.events.len() as u64).into()) }
-        });
-        context.add_function("numObjects", move || -> ResolveResult {
-            unsafe { Ok((get_ocel_raw(ocel_raw).ocel.objects.len() as u64).into()) }
-        });
-
-        context.add_function("events", move || -> ResolveResult {
-            unsafe {
-                Ok((get_ocel_raw(ocel_raw)
-                    .ocel
-                    .events
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| ev_index_to_name(&EventIndex(i))))
+    context.add_function("objects", move || -> ResolveResult {
+        unsafe {
+            Ok((get_ocel_raw(ocel_raw)
+                .ocel
+                .objects
+                .iter()
+                .enumerate()
+                .map(|(i, _)| ob_index_to_name(&ObjectIndex(i))))
+            .collect_vec()
+            .into())
+        }
+    });
+
+    context.add_function(
+        "objects_related_via",
+        move |This(variable): This<Arc<String>>, qualifier: Arc<String>| -> ResolveResult {
+            let ocel = unsafe { get_ocel_raw(ocel_raw) };
+            Ok(related_via(&variable, &qualifier, ocel)
+                .filter_map(|rel_index| match rel_index {
+                    EventOrObjectIndex::Object(o) => Some(ob_index_to_name(&o)),
+                    EventOrObjectIndex::Event(_) => None,
+                })
+                .unique()
                 .collect_vec()
                 .into())
-            }
-        });
-
-        context.add_function("objects", move || -> ResolveResult {
-            unsafe {
-                Ok((get_ocel_raw(ocel_raw)
-                    .ocel
-                    .objects
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| ob_index_to_name(&ObjectIndex(i))))
+        },
+    );
+
+    context.add_function(
+        "events_related_via",
+        move |This(variable): This<Arc<String>>, qualifier: Arc<String>| -> ResolveResult {
+            let ocel = unsafe { get_ocel_raw(ocel_raw) };
+            Ok(related_via(&variable, &qualifier, ocel)
+                .filter_map(|rel_index| match rel_index {
+                    EventOrObjectIndex::Event(e) => Some(ev_index_to_name(&e)),
+                    EventOrObjectIndex::Object(_) => None,
+                })
+                .unique()
                 .collect_vec()
                 .into())
-            }
-        });
-
-        context.add_function(
-            "sum",
-            move |_ftx: &FunctionContext, This(variable): This<Arc<Vec<Value>>>| -> ResolveResult {
-                Ok(variable.iter().map(value_to_float).sum::<f64>().into())
-            },
-        );
+        },
+    );
+
+    context.add_function(
+        "sum",
+        move |_ftx: &FunctionContext, This(variable): This<Arc<Vec<Value>>>| -> ResolveResult {
+            Ok(variable.iter().map(value_to_float).sum::<f64>().into())
+        },
+    );
+
+    context.add_function(
+        "avg",
+        move |_ftx: &FunctionContext, This(variable): This<Arc<Vec<Value>>>| -> ResolveResult {
+            let (count, sum) = variable
+                .iter()
+                .map(value_to_float)
+                .fold((0_usize, 0.0), |(count, sum), f| (count + 1, sum + f));
+            Ok((sum / count as f64).into())
+        },
+    );
+    let res = p.execute(&context);
+    unsafe {
+        let _ocel_box = Box::from_raw(ocel_raw.0);
+    }
+    Ok(res?)
+}
 
-        context.add_function(
-            "avg",
-            move |_ftx: &FunctionContext, This(variable): This<Arc<Vec<Value>>>| -> ResolveResult {
-                let (count, sum) = variable
-                    .iter()
-                    .map(value_to_float)
-                    .fold((0_usize, 0.0), |(count, sum), f| (count + 1, sum + f));
-                Ok((sum / count as f64).into())
-            },
-        );
-        let res = p.execute(&context);
-        unsafe {
-            let _ocel_box = Box::from_raw(ocel_raw.0);
-        }
-        Ok(res?)
-    } else {
-        Err(CELEvalError::ParseError)
+/// Evaluate a CEL expression returning a list of event/object indices or IDs
+///
+/// A single string value is treated as a list with one element.
+/// Fails if the expression cannot be evaluated or returns neither a list nor a string
+/// (so that errors can be distinguished from expressions not matching any event/object).
+pub fn get_indices_from_cel<'a>(
+    cel: &str,
+    binding: &'a Binding,
+    ocel: &'a IndexLinkedOCEL,
+) -> Result<Vec<EventOrObjectIndex>, CELEvalError> {
+    match evaluate_cel(cel, binding, None, ocel)? {
+        Value::List(values) => Ok(values
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(string_to_indices(s, ocel)),
+                _ => None,
+            })
+            .flatten()
+            .unique()
+            .collect()),
+        Value::String(s) => Ok(string_to_indices(&s, ocel)),
+        v => Err(CELEvalError::UnexpectedResult(format!("{v:?}"))),
     }
 }
 
@@ -413,6 +580,8 @@ pub fn evaluate_cel<'a>(
 pub enum CELEvalError {
     ExecError(ExecutionError),
     ParseError,
+    /// The expression returned an unexpected value (e.g., not a list of events/objects)
+    UnexpectedResult(String),
 }
 
 impl From<ExecutionError> for CELEvalError {
@@ -475,24 +644,40 @@ fn ocel_val_to_cel_val(val: &OCELAttributeValue) -> Value {
         OCELAttributeValue::Null => Value::Null,
     }
 }
-fn string_to_var(s: &str) -> Variable {
+pub(crate) fn string_to_var(s: &str) -> Option<Variable> {
+    if s.len() < 2 {
+        return None;
+    }
     let (typ, num) = s.split_at(1);
-    let num = num.parse::<usize>().map(|v| v - 1).unwrap_or_default();
-    if typ == "o" {
-        Variable::Object(ObjectVariable(num))
-    } else {
-        Variable::Event(EventVariable(num))
+    let num = num.parse::<usize>().ok()?.checked_sub(1)?;
+    match typ {
+        "o" => Some(Variable::Object(ObjectVariable(num))),
+        "e" => Some(Variable::Event(EventVariable(num))),
+        _ => None,
     }
 }
 
-pub fn get_vars_in_cel_program(cel: &str) -> HashSet<Variable> {
+/// Get all variable names referenced in a CEL program
+///
+/// This also includes names which are not event/object variables (e.g., labels or child set names)
+pub fn get_referenced_names_in_cel_program(cel: &str) -> HashSet<String> {
     lazy_compile_and_insert_into_cache(cel);
     let r_lock = CEL_PROGRAM_CACHE.read().unwrap();
-    let p = r_lock.get(cel).unwrap();
-    p.references()
-        .variables()
-        .into_iter()
-        .map(string_to_var)
+    match r_lock.get(cel) {
+        Some(p) => p
+            .references()
+            .variables()
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect(),
+        None => HashSet::new(),
+    }
+}
+
+pub fn get_vars_in_cel_program(cel: &str) -> HashSet<Variable> {
+    get_referenced_names_in_cel_program(cel)
+        .iter()
+        .filter_map(|s| string_to_var(s))
         .collect()
 }
 
@@ -504,8 +689,11 @@ impl From<cel_interpreter::Value> for LabelValue {
             Value::Float(f) => LabelValue::Float(f.into()),
             Value::String(arc) => LabelValue::String(arc),
             Value::Bool(b) => LabelValue::Bool(b),
-            Value::Duration(time_delta) => LabelValue::String(Arc::new(time_delta.to_string())),
-            Value::Timestamp(date_time) => LabelValue::String(Arc::new(date_time.to_rfc3339())),
+            Value::Duration(time_delta) => {
+                LabelValue::Duration((time_delta.num_milliseconds() as f64 / 1000.0).into())
+            }
+            Value::Timestamp(date_time) => LabelValue::Time(date_time.with_timezone(&Utc)),
+            Value::List(l) => LabelValue::List(l.iter().cloned().map(LabelValue::from).collect()),
             _ => LabelValue::Null,
         }
     }
@@ -518,6 +706,11 @@ impl From<LabelValue> for cel_interpreter::Value {
             LabelValue::Int(i) => Value::Int(i),
             LabelValue::Float(f) => Value::Float(f.into()),
             LabelValue::Bool(b) => Value::Bool(b),
+            LabelValue::Time(t) => Value::Timestamp(t.fixed_offset()),
+            LabelValue::Duration(d) => {
+                Value::Duration(chrono::Duration::milliseconds((d.0 * 1000.0).round() as i64))
+            }
+            LabelValue::List(l) => Value::List(Arc::new(l.into_iter().map(Value::from).collect())),
             LabelValue::Null => Value::Null,
         }
     }
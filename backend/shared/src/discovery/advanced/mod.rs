@@ -6,7 +6,6 @@ use std::{cmp::max, collections::HashMap};
 
 use itertools::Itertools;
 
-use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
@@ -18,55 +17,31 @@ use crate::{
 };
 
 use super::{
-    graph_discovery::discover_count_constraints_for_supporting_instances, RNG_SEED, SAMPLE_FRAC,
-    SAMPLE_MIN_NUM_INSTANCES,
+    graph_discovery::{discover_count_constraints_for_supporting_instances, get_instances},
+    SamplingOptions,
 };
 
 // 1st Step: Allow building of  (simple) sampled bindings based on object/event type
+// Each type is sampled separately (i.e., the sample is stratified by type)
 pub fn generate_sample_bindings(
     ocel: &IndexLinkedOCEL,
     ocel_types: &Vec<EventOrObjectType>,
     target_variable: Variable,
+    sampling: &SamplingOptions,
 ) -> Vec<Binding> {
-    let mut rng = StdRng::seed_from_u64(RNG_SEED);
-    match target_variable {
-        Variable::Event(ev) => {
-            let instances: Vec<_> = ocel_types
-                .iter()
-                .flat_map(|t| ocel.events_of_type.get(t.inner()))
-                .flatten()
-                .collect();
-            let sample_count = if instances.len() >= SAMPLE_MIN_NUM_INSTANCES {
-                (instances.len() as f32 * SAMPLE_FRAC).ceil() as usize
-            } else {
-                instances.len()
-            };
-            instances
-                .iter()
-                .choose_multiple(&mut rng, sample_count)
-                .iter()
-                .map(|i| Binding::default().expand_with_ev(ev, ***i))
-                .collect()
-        }
-        Variable::Object(ov) => {
-            let instances: Vec<_> = ocel_types
-                .iter()
-                .flat_map(|t| ocel.objects_of_type.get(t.inner()))
-                .flatten()
-                .collect();
-            let sample_count = if instances.len() >= SAMPLE_MIN_NUM_INSTANCES {
-                (instances.len() as f32 * SAMPLE_FRAC).ceil() as usize
-            } else {
-                instances.len()
-            };
-            instances
-                .iter()
-                .choose_multiple(&mut rng, sample_count)
-                .iter()
-                .map(|i| Binding::default().expand_with_ob(ov, ***i))
-                .collect()
-        }
-    }
+    ocel_types
+        .iter()
+        .flat_map(|t| get_instances(ocel, t, sampling))
+        .filter_map(|i| match (&target_variable, i) {
+            (Variable::Event(ev), EventOrObjectIndex::Event(ei)) => {
+                Some(Binding::default().expand_with_ev(*ev, ei))
+            }
+            (Variable::Object(ov), EventOrObjectIndex::Object(oi)) => {
+                Some(Binding::default().expand_with_ob(*ov, oi))
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 pub fn binding_to_instances(
@@ -101,12 +76,14 @@ pub fn get_labeled_instances(
     ocel: &IndexLinkedOCEL,
     ocel_type: &EventOrObjectType,
     subtree: BindingBoxTree,
+    sampling: &SamplingOptions,
 ) -> Vec<(EventOrObjectIndex, bool)> {
     let variable = match ocel_type {
         EventOrObjectType::Event(_) => Variable::Event(EventVariable(0)),
         EventOrObjectType::Object(_) => Variable::Object(ObjectVariable(0)),
     };
-    let bindings = generate_sample_bindings(ocel, &vec![ocel_type.clone()], variable.clone());
+    let bindings =
+        generate_sample_bindings(ocel, &vec![ocel_type.clone()], variable.clone(), sampling);
 
     let violated_instances = bindings
         .iter()
@@ -247,8 +224,14 @@ pub fn discover_or_constraints_old(
     ocel_type: &EventOrObjectType,
     input_variable: Variable,
     subtrees: Vec<BindingBoxTree>,
+    sampling: &SamplingOptions,
 ) -> Vec<BindingBoxTree> {
-    let bindings = generate_sample_bindings(ocel, &vec![ocel_type.clone()], input_variable.clone());
+    let bindings = generate_sample_bindings(
+        ocel,
+        &vec![ocel_type.clone()],
+        input_variable.clone(),
+        sampling,
+    );
     let mut all_subtrees = subtrees.clone();
     for st in &subtrees {
         let violated_instances = bindings
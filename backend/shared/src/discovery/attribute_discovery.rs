@@ -0,0 +1,505 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use process_mining::ocel::ocel_struct::OCELAttributeValue;
+
+use crate::{
+    binding_box::{
+        structs::{
+            BindingBoxTreeNode, Constraint, EventVariable, Filter, ObjectValueFilterTimepoint,
+            ObjectVariable, ValueFilter,
+        },
+        BindingBox, BindingBoxTree,
+    },
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
+
+use super::{
+    advanced::EventOrObjectType,
+    graph_discovery::{
+        discover_count_constraints_for_supporting_instances, get_instances, CountConstraint,
+    },
+    SamplingOptions,
+};
+
+/// Maximal number of values in a discovered value set (String attributes)
+const MAX_VALUE_SET_SIZE: usize = 10;
+/// Maximal number of distinct values of an attribute to condition count constraints on
+const MAX_CONDITION_VALUES: usize = 10;
+/// Minimal number of (sampled) instances with a value to condition count constraints on
+const MIN_CONDITION_SUPPORT: usize = 10;
+
+/// A typical value range or value set of an event or object attribute
+#[derive(Debug, Clone)]
+pub struct AttributeConstraint {
+    pub ocel_type: EventOrObjectType,
+    pub attribute_name: String,
+    pub value_filter: ValueFilter,
+    /// Fraction of (sampled) events/objects of the type satisfying the value filter
+    pub coverage: f32,
+}
+
+impl AttributeConstraint {
+    pub fn get_constraint_name(&self) -> String {
+        let values = match &self.value_filter {
+            ValueFilter::Float { min, max } => format_range(min, max),
+            ValueFilter::Integer { min, max } => format_range(min, max),
+            ValueFilter::Boolean { is_true } => format!("= {is_true}"),
+            ValueFilter::String { is_in } => {
+                format!(
+                    "in {{{}}}",
+                    is_in.iter().map(|v| format!("'{v}'")).join(", ")
+                )
+            }
+            ValueFilter::Time { from, to } => format_range(from, to),
+        };
+        format!(
+            "'{}' {} for '{}' ({:.1}%)",
+            self.attribute_name,
+            values,
+            self.ocel_type.inner(),
+            self.coverage * 100.0
+        )
+    }
+
+    pub fn get_full_tree(&self) -> BindingBoxTree {
+        let mut tree = self.to_subtree();
+        if let BindingBoxTreeNode::Box(bbox, _) = &mut tree.nodes[0] {
+            add_type_var(bbox, &self.ocel_type);
+        }
+        tree
+    }
+
+    /// Tree checking the value filter for an already bound event/object (variable 0)
+    pub fn to_subtree(&self) -> BindingBoxTree {
+        let mut bbox = BindingBox::default();
+        bbox.constraints.push(Constraint::Filter {
+            filter: get_attribute_filter(
+                &self.ocel_type,
+                &self.attribute_name,
+                self.value_filter.clone(),
+                ObjectValueFilterTimepoint::Always,
+            ),
+        });
+        BindingBoxTree {
+            nodes: vec![BindingBoxTreeNode::Box(bbox, vec![])],
+            edge_names: HashMap::default(),
+        }
+    }
+}
+
+/// A count constraint which only applies to events/objects with a specific attribute value
+#[derive(Debug, Clone)]
+pub struct ConditionedCountConstraint {
+    pub attribute_name: String,
+    pub value_filter: ValueFilter,
+    pub count_constraint: CountConstraint,
+}
+
+impl ConditionedCountConstraint {
+    pub fn get_constraint_name(&self) -> String {
+        let value = match &self.value_filter {
+            ValueFilter::Boolean { is_true } => is_true.to_string(),
+            ValueFilter::String { is_in } => format!("'{}'", is_in.join("', '")),
+            v => format!("{v:?}"),
+        };
+        format!(
+            "{} if '{}' = {}",
+            self.count_constraint.get_constraint_name(),
+            self.attribute_name,
+            value
+        )
+    }
+
+    pub fn get_full_tree(&self) -> BindingBoxTree {
+        let mut tree = self.count_constraint.get_full_tree();
+        if let BindingBoxTreeNode::Box(bbox, _) = &mut tree.nodes[0] {
+            bbox.filters.push(get_attribute_filter(
+                &self.count_constraint.root_type,
+                &self.attribute_name,
+                self.value_filter.clone(),
+                ObjectValueFilterTimepoint::Sometime,
+            ));
+        }
+        tree
+    }
+}
+
+fn format_range<T: std::fmt::Display>(min: &Option<T>, max: &Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("in [{min}, {max}]"),
+        (Some(min), None) => format!("≥ {min}"),
+        (None, Some(max)) => format!("≤ {max}"),
+        (None, None) => "any value".to_string(),
+    }
+}
+
+fn add_type_var(bbox: &mut BindingBox, ocel_type: &EventOrObjectType) {
+    match ocel_type {
+        EventOrObjectType::Event(et) => {
+            bbox.new_event_vars
+                .insert(EventVariable(0), vec![et.clone()].into_iter().collect());
+        }
+        EventOrObjectType::Object(ot) => {
+            bbox.new_object_vars
+                .insert(ObjectVariable(0), vec![ot.clone()].into_iter().collect());
+        }
+    }
+}
+
+fn get_attribute_filter(
+    ocel_type: &EventOrObjectType,
+    attribute_name: &str,
+    value_filter: ValueFilter,
+    at_time: ObjectValueFilterTimepoint,
+) -> Filter {
+    match ocel_type {
+        EventOrObjectType::Event(_) => Filter::EventAttributeValueFilter {
+            event: EventVariable(0),
+            attribute_name: attribute_name.to_string(),
+            value_filter,
+        },
+        EventOrObjectType::Object(_) => Filter::ObjectAttributeValueFilter {
+            object: ObjectVariable(0),
+            attribute_name: attribute_name.to_string(),
+            at_time,
+            value_filter,
+        },
+    }
+}
+
+/// All values of the attributes of an event/object, by attribute name
+fn get_attribute_values<'a>(
+    ocel: &'a IndexLinkedOCEL,
+    index: &EventOrObjectIndex,
+) -> HashMap<&'a str, Vec<&'a OCELAttributeValue>> {
+    let mut ret: HashMap<&str, Vec<&OCELAttributeValue>> = HashMap::new();
+    for (name, value) in ocel.attributes_of(index) {
+        ret.entry(name).or_default().push(value);
+    }
+    ret
+}
+
+/// Value of a sorted list at the given quantile
+fn quantile<T: Copy>(sorted: &[T], q: f32) -> Option<T> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let i = ((sorted.len() - 1) as f32 * q).round() as usize;
+    sorted.get(i).copied()
+}
+
+/// Find a value filter satisfied by (at least) a `coverage` fraction of instances
+///
+/// `values` contains the (non-null) values of each instance; An instance satisfies a filter if all its values do
+fn get_value_filter_with_coverage(
+    values: &[Vec<&OCELAttributeValue>],
+    coverage: f32,
+) -> Option<ValueFilter> {
+    let all_values = values.iter().flatten().collect_vec();
+    if all_values.is_empty() {
+        return None;
+    }
+    let lower_q = (1.0 - coverage) / 2.0;
+    let upper_q = 1.0 - lower_q;
+    if all_values
+        .iter()
+        .all(|v| matches!(v, OCELAttributeValue::Integer(_)))
+    {
+        let as_int = |v: &&OCELAttributeValue| match v {
+            OCELAttributeValue::Integer(i) => *i,
+            _ => 0,
+        };
+        let mins = values
+            .iter()
+            .filter_map(|vs| vs.iter().map(as_int).min())
+            .sorted()
+            .collect_vec();
+        let maxs = values
+            .iter()
+            .filter_map(|vs| vs.iter().map(as_int).max())
+            .sorted()
+            .collect_vec();
+        return Some(ValueFilter::Integer {
+            min: quantile(&mins, lower_q),
+            max: quantile(&maxs, upper_q),
+        });
+    }
+    if all_values.iter().all(|v| {
+        matches!(
+            v,
+            OCELAttributeValue::Integer(_) | OCELAttributeValue::Float(_)
+        )
+    }) {
+        let as_float = |v: &&OCELAttributeValue| match v {
+            OCELAttributeValue::Integer(i) => *i as f64,
+            OCELAttributeValue::Float(f) => *f,
+            _ => 0.0,
+        };
+        let mins = values
+            .iter()
+            .filter_map(|vs| vs.iter().map(as_float).min_by(f64::total_cmp))
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+        let maxs = values
+            .iter()
+            .filter_map(|vs| vs.iter().map(as_float).max_by(f64::total_cmp))
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+        return Some(ValueFilter::Float {
+            min: quantile(&mins, lower_q),
+            max: quantile(&maxs, upper_q),
+        });
+    }
+    if all_values
+        .iter()
+        .all(|v| matches!(v, OCELAttributeValue::Boolean(_)))
+    {
+        let num_all_true = values
+            .iter()
+            .filter(|vs| {
+                vs.iter()
+                    .all(|v| matches!(v, OCELAttributeValue::Boolean(true)))
+            })
+            .count();
+        let num_all_false = values
+            .iter()
+            .filter(|vs| {
+                vs.iter()
+                    .all(|v| matches!(v, OCELAttributeValue::Boolean(false)))
+            })
+            .count();
+        return Some(ValueFilter::Boolean {
+            is_true: num_all_true >= num_all_false,
+        });
+    }
+    if all_values
+        .iter()
+        .all(|v| matches!(v, OCELAttributeValue::String(_)))
+    {
+        let mut counts: HashMap<&String, usize> = HashMap::new();
+        for v in &all_values {
+            if let OCELAttributeValue::String(s) = v {
+                *counts.entry(s).or_default() += 1;
+            }
+        }
+        let target_count = (coverage * all_values.len() as f32).ceil() as usize;
+        let mut is_in = Vec::new();
+        let mut covered = 0;
+        for (s, count) in counts
+            .into_iter()
+            .sorted_by(|(a, count_a), (b, count_b)| count_b.cmp(count_a).then(a.cmp(b)))
+        {
+            if covered >= target_count {
+                break;
+            }
+            is_in.push(s.clone());
+            covered += count;
+        }
+        if is_in.len() > MAX_VALUE_SET_SIZE {
+            return None;
+        }
+        is_in.sort();
+        return Some(ValueFilter::String { is_in });
+    }
+    // Time values or mixed value types are not considered
+    None
+}
+
+/// Discover typical value ranges (numeric attributes) or value sets (boolean and string attributes) of the attributes of an event/object type
+///
+/// Only constraints satisfied by at least a `coverage` fraction of the (sampled) instances are returned.
+/// For objects, all values of an attribute (over time) need to be in the range/set.
+pub fn discover_attribute_constraints(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    ocel_type: &EventOrObjectType,
+    sampling: &SamplingOptions,
+) -> Vec<AttributeConstraint> {
+    let instances = get_instances(ocel, ocel_type, sampling);
+    discover_attribute_constraints_for_supporting_instances(ocel, coverage, &instances, ocel_type)
+}
+
+/// Discover typical value ranges or value sets of the attributes of the given events/objects (all of type `ocel_type`)
+pub fn discover_attribute_constraints_for_supporting_instances(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    instances: &[EventOrObjectIndex],
+    ocel_type: &EventOrObjectType,
+) -> Vec<AttributeConstraint> {
+    let mut values_per_attr: HashMap<&str, Vec<Vec<&OCELAttributeValue>>> = HashMap::new();
+    for (i, index) in instances.iter().enumerate() {
+        for (name, values) in get_attribute_values(ocel, index) {
+            let attr_values = values_per_attr
+                .entry(name)
+                .or_insert_with(|| vec![Vec::new(); instances.len()]);
+            attr_values[i] = values
+                .into_iter()
+                .filter(|v| !matches!(v, OCELAttributeValue::Null))
+                .collect();
+        }
+    }
+    let mut ret = Vec::new();
+    for (attribute_name, values) in values_per_attr.into_iter().sorted_by_key(|(name, _)| *name) {
+        let Some(value_filter) = get_value_filter_with_coverage(&values, coverage) else {
+            continue;
+        };
+        // Events without a value do not satisfy the filter, objects without a value always satisfy it
+        let num_sat = values
+            .iter()
+            .filter(|vs| match ocel_type {
+                EventOrObjectType::Event(_) => {
+                    !vs.is_empty() && vs.iter().all(|v| value_filter.check_value(v))
+                }
+                EventOrObjectType::Object(_) => vs.iter().all(|v| value_filter.check_value(v)),
+            })
+            .count();
+        let actual_coverage = num_sat as f32 / values.len().max(1) as f32;
+        if actual_coverage >= coverage {
+            ret.push(AttributeConstraint {
+                ocel_type: ocel_type.clone(),
+                attribute_name: attribute_name.to_string(),
+                value_filter,
+                coverage: actual_coverage,
+            });
+        }
+    }
+    ret
+}
+
+/// Discover count constraints which only hold for events/objects with a specific value of a categorical (boolean or string) attribute
+///
+/// E.g., "if payment method = credit, then exactly one check event".
+/// Count constraints which also hold for all events/objects of the type are not returned.
+pub fn discover_conditioned_count_constraints(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    ocel_type: &EventOrObjectType,
+    sampling: &SamplingOptions,
+) -> Vec<ConditionedCountConstraint> {
+    let instances = get_instances(ocel, ocel_type, sampling);
+    let unconditioned: HashSet<CountConstraint> =
+        discover_count_constraints_for_supporting_instances(
+            ocel,
+            coverage,
+            instances.iter(),
+            ocel_type,
+        )
+        .into_iter()
+        .collect();
+    let mut instances_per_value: HashMap<(&str, ValueKey), Vec<EventOrObjectIndex>> =
+        HashMap::new();
+    for index in &instances {
+        for (name, values) in get_attribute_values(ocel, index) {
+            let keys: HashSet<ValueKey> = values.into_iter().filter_map(ValueKey::new).collect();
+            for key in keys {
+                instances_per_value
+                    .entry((name, key))
+                    .or_default()
+                    .push(*index);
+            }
+        }
+    }
+    let num_values_per_attr = instances_per_value.keys().counts_by(|(name, _)| *name);
+    let mut ret = Vec::new();
+    for ((attribute_name, key), value_instances) in instances_per_value
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+    {
+        if num_values_per_attr[attribute_name] > MAX_CONDITION_VALUES
+            || num_values_per_attr[attribute_name] < 2
+            || value_instances.len() < MIN_CONDITION_SUPPORT
+        {
+            continue;
+        }
+        for count_constraint in discover_count_constraints_for_supporting_instances(
+            ocel,
+            coverage,
+            value_instances.iter(),
+            ocel_type,
+        ) {
+            if !unconditioned.contains(&count_constraint) {
+                ret.push(ConditionedCountConstraint {
+                    attribute_name: attribute_name.to_string(),
+                    value_filter: key.to_value_filter(),
+                    count_constraint,
+                });
+            }
+        }
+    }
+    ret
+}
+
+/// Categorical attribute value
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum ValueKey {
+    Boolean(bool),
+    String(String),
+}
+
+impl ValueKey {
+    fn new(v: &OCELAttributeValue) -> Option<Self> {
+        match v {
+            OCELAttributeValue::Boolean(b) => Some(ValueKey::Boolean(*b)),
+            OCELAttributeValue::String(s) => Some(ValueKey::String(s.clone())),
+            _ => None,
+        }
+    }
+
+    fn to_value_filter(&self) -> ValueFilter {
+        match self {
+            ValueKey::Boolean(b) => ValueFilter::Boolean { is_true: *b },
+            ValueKey::String(s) => ValueFilter::String {
+                is_in: vec![s.clone()],
+            },
+        }
+    }
+}
+
+#[test]
+fn discovers_value_range_and_value_set() {
+    use process_mining::ocel::ocel_struct::OCELEventAttribute;
+
+    let ids = (0..10).map(|i| format!("pay{i}")).collect_vec();
+    let mut ocel = crate::preprocessing::tests::test_ocel(
+        ids.iter()
+            .map(|id| (id.as_str(), "pay order", 0, vec![]))
+            .collect(),
+        vec![],
+    );
+    for (i, ev) in ocel.events.iter_mut().enumerate() {
+        ev.attributes = vec![
+            OCELEventAttribute {
+                name: "amount".to_string(),
+                value: OCELAttributeValue::Integer(10 + i as i64),
+            },
+            OCELEventAttribute {
+                name: "method".to_string(),
+                value: OCELAttributeValue::String(if i < 8 { "card" } else { "cash" }.to_string()),
+            },
+        ];
+    }
+    let ocel = IndexLinkedOCEL::new(ocel);
+
+    let constraints = discover_attribute_constraints(
+        &ocel,
+        0.7,
+        &EventOrObjectType::Event("pay order".to_string()),
+        &SamplingOptions::default(),
+    );
+    assert_eq!(constraints.len(), 2);
+    assert_eq!(constraints[0].attribute_name, "amount");
+    assert!(matches!(
+        constraints[0].value_filter,
+        ValueFilter::Integer {
+            min: Some(11),
+            max: Some(18)
+        }
+    ));
+    assert_eq!(constraints[0].coverage, 0.8);
+    assert_eq!(constraints[1].attribute_name, "method");
+    assert!(
+        matches!(&constraints[1].value_filter, ValueFilter::String { is_in } if is_in == &["card"])
+    );
+    assert_eq!(constraints[1].coverage, 0.8);
+}
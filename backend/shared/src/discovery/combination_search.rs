@@ -0,0 +1,515 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binding_box::{
+        structs::{BindingBoxTreeNode, Constraint, EventVariable, ObjectVariable, Variable},
+        Binding, BindingBox, BindingBoxTree,
+    },
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
+
+use super::{
+    advanced::{generate_sample_bindings, label_bindings, EventOrObjectType},
+    graph_discovery::{
+        discover_count_constraints_for_supporting_instances,
+        discover_ef_constraints_for_supporting_instances, get_instances,
+    },
+    SamplingOptions,
+};
+
+fn default_max_subtrees() -> usize {
+    4
+}
+fn default_max_depth() -> usize {
+    2
+}
+fn default_beam_width() -> usize {
+    10
+}
+fn default_min_coverage() -> f32 {
+    0.8
+}
+fn default_max_coverage() -> f32 {
+    1.0
+}
+fn default_min_coverage_gain() -> f32 {
+    0.1
+}
+
+/// Options for the beam search over OR/AND combinations of subtrees
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CombinationSearchOptions {
+    /// Maximal number of subtrees in a combination (at least 4 for ANDs of ORs)
+    #[serde(default = "default_max_subtrees")]
+    pub max_subtrees: usize,
+    /// Maximal nesting depth of OR/AND gates (1: only a single OR or AND gate)
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    /// Number of combinations kept after each search step
+    #[serde(default = "default_beam_width")]
+    pub beam_width: usize,
+    /// Minimal fraction of sampled bindings satisfying a returned combination
+    #[serde(default = "default_min_coverage")]
+    pub min_coverage: f32,
+    /// Maximal fraction of sampled bindings satisfying a returned combination
+    #[serde(default = "default_max_coverage")]
+    pub max_coverage: f32,
+    /// Minimal coverage gain of a returned combination (see [`CombinationCandidate::coverage_gain`])
+    #[serde(default = "default_min_coverage_gain")]
+    pub min_coverage_gain: f32,
+}
+
+impl Default for CombinationSearchOptions {
+    fn default() -> Self {
+        Self {
+            max_subtrees: default_max_subtrees(),
+            max_depth: default_max_depth(),
+            beam_width: default_beam_width(),
+            min_coverage: default_min_coverage(),
+            max_coverage: default_max_coverage(),
+            min_coverage_gain: default_min_coverage_gain(),
+        }
+    }
+}
+
+/// A (nested) OR/AND combination of subtrees (referenced by their index)
+///
+/// Children of gates are sorted and gates of the same kind are flattened, so equal combinations are also structurally equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TreeCombination {
+    Subtree(usize),
+    OR(Vec<TreeCombination>),
+    AND(Vec<TreeCombination>),
+}
+
+impl TreeCombination {
+    /// Indices of all subtrees in the combination
+    pub fn subtrees(&self) -> Vec<usize> {
+        match self {
+            TreeCombination::Subtree(i) => vec![*i],
+            TreeCombination::OR(cs) | TreeCombination::AND(cs) => {
+                cs.iter().flat_map(|c| c.subtrees()).collect()
+            }
+        }
+    }
+
+    /// Nesting depth of gates
+    pub fn depth(&self) -> usize {
+        match self {
+            TreeCombination::Subtree(_) => 0,
+            TreeCombination::OR(cs) | TreeCombination::AND(cs) => {
+                1 + cs.iter().map(|c| c.depth()).max().unwrap_or_default()
+            }
+        }
+    }
+
+    fn combine(self, other: Self, is_or: bool) -> Self {
+        let mut children = Vec::new();
+        for c in [self, other] {
+            match (c, is_or) {
+                (TreeCombination::OR(cs), true) | (TreeCombination::AND(cs), false) => {
+                    children.extend(cs)
+                }
+                (c, _) => children.push(c),
+            }
+        }
+        children.sort();
+        if is_or {
+            TreeCombination::OR(children)
+        } else {
+            TreeCombination::AND(children)
+        }
+    }
+
+    pub fn get_name(&self, subtree_names: &[String]) -> String {
+        let child_name = |c: &TreeCombination| match c {
+            TreeCombination::Subtree(_) => c.get_name(subtree_names),
+            _ => format!("({})", c.get_name(subtree_names)),
+        };
+        match self {
+            TreeCombination::Subtree(i) => subtree_names[*i].clone(),
+            TreeCombination::OR(cs) => cs.iter().map(child_name).join(" OR "),
+            TreeCombination::AND(cs) => cs.iter().map(child_name).join(" AND "),
+        }
+    }
+
+    /// Build the combined tree, binding `input_variable` (of type `ocel_type`) in the root box
+    ///
+    /// The subtrees are expected to use `input_variable` as their input and to have unique edge names.
+    pub fn to_tree(
+        &self,
+        subtrees: &[BindingBoxTree],
+        ocel_type: &EventOrObjectType,
+        input_variable: &Variable,
+    ) -> BindingBoxTree {
+        let mut root = BindingBox::default();
+        match ocel_type {
+            EventOrObjectType::Event(et) => root.new_event_vars.insert(
+                EventVariable(input_variable.to_inner()),
+                vec![et.clone()].into_iter().collect(),
+            ),
+            EventOrObjectType::Object(ot) => root.new_object_vars.insert(
+                ObjectVariable(input_variable.to_inner()),
+                vec![ot.clone()].into_iter().collect(),
+            ),
+        };
+        let mut tree = BindingBoxTree {
+            nodes: vec![BindingBoxTreeNode::Box(root, vec![])],
+            edge_names: Default::default(),
+        };
+        match self {
+            TreeCombination::Subtree(_) => {
+                let name = add_to_tree(&mut tree, 0, self, subtrees);
+                set_constraint(
+                    &mut tree,
+                    0,
+                    Constraint::SAT {
+                        child_names: vec![name],
+                    },
+                );
+            }
+            TreeCombination::OR(cs) | TreeCombination::AND(cs) => {
+                let child_names = cs
+                    .iter()
+                    .map(|c| add_to_tree(&mut tree, 0, c, subtrees))
+                    .collect();
+                set_constraint(&mut tree, 0, gate_constraint(self, child_names));
+            }
+        }
+        tree
+    }
+}
+
+fn gate_constraint(combination: &TreeCombination, child_names: Vec<String>) -> Constraint {
+    match combination {
+        TreeCombination::AND(_) => Constraint::AND { child_names },
+        _ => Constraint::OR { child_names },
+    }
+}
+
+fn set_constraint(tree: &mut BindingBoxTree, node: usize, constraint: Constraint) {
+    if let BindingBoxTreeNode::Box(bbox, _) = &mut tree.nodes[node] {
+        bbox.constraints.push(constraint);
+    }
+}
+
+/// Add a combination as a child of `parent`, returning the name of the new edge
+fn add_to_tree(
+    tree: &mut BindingBoxTree,
+    parent: usize,
+    combination: &TreeCombination,
+    subtrees: &[BindingBoxTree],
+) -> String {
+    let index = tree.nodes.len();
+    match combination {
+        TreeCombination::Subtree(i) => {
+            let subtree = &subtrees[*i];
+            tree.nodes.extend(subtree.nodes.iter().map(|n| match n {
+                BindingBoxTreeNode::Box(bbox, children) => BindingBoxTreeNode::Box(
+                    bbox.clone(),
+                    children.iter().map(|c| c + index).collect(),
+                ),
+                BindingBoxTreeNode::OR(c1, c2) => BindingBoxTreeNode::OR(c1 + index, c2 + index),
+                BindingBoxTreeNode::AND(c1, c2) => BindingBoxTreeNode::AND(c1 + index, c2 + index),
+                BindingBoxTreeNode::NOT(c) => BindingBoxTreeNode::NOT(c + index),
+            }));
+            tree.edge_names.extend(
+                subtree
+                    .edge_names
+                    .iter()
+                    .map(|((from, to), name)| ((from + index, to + index), name.clone())),
+            );
+        }
+        TreeCombination::OR(cs) | TreeCombination::AND(cs) => {
+            tree.nodes
+                .push(BindingBoxTreeNode::Box(BindingBox::default(), vec![]));
+            let child_names = cs
+                .iter()
+                .map(|c| add_to_tree(tree, index, c, subtrees))
+                .collect();
+            set_constraint(tree, index, gate_constraint(combination, child_names));
+        }
+    }
+    if let BindingBoxTreeNode::Box(_, children) = &mut tree.nodes[parent] {
+        children.push(index);
+    }
+    let name = format!("G{index}");
+    tree.edge_names.insert((parent, index), name.clone());
+    name
+}
+
+#[derive(Debug, Clone)]
+pub struct CombinationCandidate {
+    pub combination: TreeCombination,
+    /// Fraction of sampled bindings satisfying the combination
+    pub coverage: f32,
+    /// Coverage of an OR combination minus the coverage of its best single subtree,
+    /// or coverage of an AND combination minus the coverage of the conjunction of all its subtrees
+    ///
+    /// For ANDs, this is the reduction of the violation rate compared to requiring each subtree.
+    /// Flat ANDs of subtrees thus never have a positive gain: They are equivalent to the single subtrees as separate constraints.
+    pub coverage_gain: f32,
+}
+
+/// Beam search over OR/AND combinations (including ANDs of ORs) of the given subtrees
+///
+/// In each step, the combinations in the beam are combined with each other and with single subtrees.
+/// Only the `beam_width` combinations with the highest coverage gain are expanded further.
+/// Returned combinations are sorted by coverage gain (descending).
+pub fn search_tree_combinations(
+    ocel: &IndexLinkedOCEL,
+    subtrees: &[BindingBoxTree],
+    input_bindings: &Vec<Binding>,
+    options: &CombinationSearchOptions,
+) -> Vec<CombinationCandidate> {
+    let n = input_bindings.len().max(1) as f32;
+    let coverage_of = |sat: &[bool]| sat.iter().filter(|s| **s).count() as f32 / n;
+    let singles: Vec<(TreeCombination, Vec<bool>)> = subtrees
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            (
+                TreeCombination::Subtree(i),
+                label_bindings(ocel, input_bindings, t),
+            )
+        })
+        .collect();
+    let subtree_coverage = singles
+        .iter()
+        .map(|(_, sat)| coverage_of(sat))
+        .collect_vec();
+    let mut beam = singles.clone();
+    let mut seen: HashSet<TreeCombination> = HashSet::new();
+    let mut ret = Vec::new();
+    for _ in 1..options.max_subtrees {
+        let mut next: Vec<(CombinationCandidate, Vec<bool>)> = Vec::new();
+        for (c1, sat1) in &beam {
+            let subtrees1 = c1.subtrees();
+            for (c2, sat2) in beam.iter().chain(singles.iter()) {
+                let subtrees2 = c2.subtrees();
+                if subtrees1.len() + subtrees2.len() > options.max_subtrees
+                    || subtrees1.iter().any(|i| subtrees2.contains(i))
+                {
+                    continue;
+                }
+                for is_or in [true, false] {
+                    let combination = c1.clone().combine(c2.clone(), is_or);
+                    if combination.depth() > options.max_depth || !seen.insert(combination.clone())
+                    {
+                        continue;
+                    }
+                    let sat = sat1
+                        .iter()
+                        .zip(sat2)
+                        .map(|(a, b)| if is_or { *a || *b } else { *a && *b })
+                        .collect_vec();
+                    let coverage = coverage_of(&sat);
+                    let baseline_coverage = if is_or {
+                        combination
+                            .subtrees()
+                            .iter()
+                            .map(|i| subtree_coverage[*i])
+                            .fold(0.0, f32::max)
+                    } else {
+                        let subtrees = combination.subtrees();
+                        coverage_of(
+                            &(0..input_bindings.len())
+                                .map(|b| subtrees.iter().all(|i| singles[*i].1[b]))
+                                .collect_vec(),
+                        )
+                    };
+                    next.push((
+                        CombinationCandidate {
+                            combination,
+                            coverage,
+                            coverage_gain: coverage - baseline_coverage,
+                        },
+                        sat,
+                    ));
+                }
+            }
+        }
+        next.sort_by(|(a, _), (b, _)| b.coverage_gain.total_cmp(&a.coverage_gain));
+        ret.extend(
+            next.iter()
+                .filter(|(c, _)| {
+                    c.coverage >= options.min_coverage
+                        && c.coverage <= options.max_coverage
+                        && c.coverage_gain >= options.min_coverage_gain
+                })
+                .map(|(c, _)| c.clone()),
+        );
+        beam = next
+            .into_iter()
+            .take(options.beam_width)
+            .map(|(c, sat)| (c.combination, sat))
+            .collect();
+        if beam.is_empty() {
+            break;
+        }
+    }
+    ret.sort_by(|a, b| b.coverage_gain.total_cmp(&a.coverage_gain));
+    ret
+}
+
+/// Discover OR/AND combinations of count and EF constraints for an event/object type
+///
+/// Candidate subtrees are count and EF constraints (EF only for object types) holding for at least a `candidate_coverage` fraction of the (sampled) instances.
+pub fn discover_combined_constraints(
+    ocel: &IndexLinkedOCEL,
+    ocel_type: &EventOrObjectType,
+    candidate_coverage: f32,
+    max_candidates: usize,
+    options: &CombinationSearchOptions,
+    sampling: &SamplingOptions,
+) -> Vec<(String, BindingBoxTree)> {
+    let variable = match ocel_type {
+        EventOrObjectType::Event(_) => Variable::Event(EventVariable(0)),
+        EventOrObjectType::Object(_) => Variable::Object(ObjectVariable(0)),
+    };
+    let instances = get_instances(ocel, ocel_type, sampling);
+    let mut candidates: Vec<(String, BindingBoxTree)> = Vec::new();
+    for cc in discover_count_constraints_for_supporting_instances(
+        ocel,
+        candidate_coverage,
+        instances.iter(),
+        ocel_type,
+    ) {
+        let child_name = format!("L{}", candidates.len());
+        candidates.push((
+            cc.get_constraint_name(),
+            cc.to_subtree(child_name, variable.to_inner(), 2),
+        ));
+    }
+    if let EventOrObjectType::Object(object_type) = ocel_type {
+        for ef in discover_ef_constraints_for_supporting_instances(
+            ocel,
+            candidate_coverage,
+            instances.iter().flat_map(|i| match i {
+                EventOrObjectIndex::Object(oi) => Some(oi),
+                EventOrObjectIndex::Event(_) => None,
+            }),
+            object_type,
+            sampling,
+        ) {
+            let child_name = format!("L{}", candidates.len());
+            candidates.push((
+                ef.get_constraint_name(),
+                ef.to_subtree(
+                    child_name,
+                    variable.to_inner(),
+                    2,
+                    3,
+                    variable.to_inner() + 1,
+                ),
+            ));
+        }
+    }
+    candidates.truncate(max_candidates);
+    let (names, subtrees): (Vec<_>, Vec<_>) = candidates.into_iter().unzip();
+    let bindings =
+        generate_sample_bindings(ocel, &vec![ocel_type.clone()], variable.clone(), sampling);
+    search_tree_combinations(ocel, &subtrees, &bindings, options)
+        .into_iter()
+        .map(|c| {
+            (
+                c.combination.get_name(&names),
+                c.combination.to_tree(&subtrees, ocel_type, &variable),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn search_returns_and_of_ors() {
+    use process_mining::ocel::ocel_struct::{OCELAttributeValue, OCELEventAttribute};
+
+    use super::attribute_discovery::AttributeConstraint;
+    use crate::binding_box::structs::ValueFilter;
+
+    // Each of the four boolean attributes is true for the events in its range
+    let ranges = [("a", 3..11), ("b", 11..20), ("c", 3..12), ("d", 12..20)];
+    let ids = (0..20).map(|i| format!("e{i}")).collect_vec();
+    let mut ocel = crate::preprocessing::tests::test_ocel(
+        ids.iter()
+            .map(|id| (id.as_str(), "check", 0, vec![]))
+            .collect(),
+        vec![],
+    );
+    for (i, ev) in ocel.events.iter_mut().enumerate() {
+        ev.attributes = ranges
+            .iter()
+            .map(|(name, range)| OCELEventAttribute {
+                name: name.to_string(),
+                value: OCELAttributeValue::Boolean(range.contains(&i)),
+            })
+            .collect();
+    }
+    let ocel = IndexLinkedOCEL::new(ocel);
+    let ocel_type = EventOrObjectType::Event("check".to_string());
+    let subtrees = ranges
+        .iter()
+        .map(|(name, _)| {
+            AttributeConstraint {
+                ocel_type: ocel_type.clone(),
+                attribute_name: name.to_string(),
+                value_filter: ValueFilter::Boolean { is_true: true },
+                coverage: 0.0,
+            }
+            .to_subtree()
+        })
+        .collect_vec();
+    let variable = Variable::Event(EventVariable(0));
+    let bindings = generate_sample_bindings(
+        &ocel,
+        &vec![ocel_type.clone()],
+        variable.clone(),
+        &SamplingOptions::default(),
+    );
+
+    let res = search_tree_combinations(
+        &ocel,
+        &subtrees,
+        &bindings,
+        &CombinationSearchOptions::default(),
+    );
+    let expected = TreeCombination::AND(vec![
+        TreeCombination::OR(vec![
+            TreeCombination::Subtree(0),
+            TreeCombination::Subtree(1),
+        ]),
+        TreeCombination::OR(vec![
+            TreeCombination::Subtree(2),
+            TreeCombination::Subtree(3),
+        ]),
+    ]);
+    assert_eq!(res[0].combination, expected);
+    assert_eq!(res[0].coverage, 0.85);
+    // None of the events satisfies all four subtrees
+    assert_eq!(res[0].coverage_gain, 0.85);
+    // Flat ANDs are never returned
+    assert!(res.iter().all(|c| match &c.combination {
+        TreeCombination::AND(cs) => cs.iter().any(|c| !matches!(c, TreeCombination::Subtree(_))),
+        _ => true,
+    }));
+
+    let (results, _skipped) = expected
+        .to_tree(&subtrees, &ocel_type, &variable)
+        .evaluate(&ocel);
+    let root_results = results
+        .iter()
+        .filter(|(node, _, _)| *node == 0)
+        .collect_vec();
+    assert_eq!(root_results.len(), 20);
+    assert_eq!(
+        root_results
+            .iter()
+            .filter(|(_, _, violation)| violation.is_some())
+            .count(),
+        3
+    );
+}
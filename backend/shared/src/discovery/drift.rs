@@ -0,0 +1,537 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binding_box::BindingBoxTree,
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
+
+use super::{
+    advanced::EventOrObjectType,
+    get_instance_time,
+    graph_discovery::{
+        discover_count_constraints_for_supporting_instances,
+        discover_ef_constraints_for_supporting_instances,
+        discover_o2o_path_ef_constraints_for_supporting_instances, get_instances,
+        get_o2o_path_description, CountConstraint, EFConstraint,
+    },
+    CountConstraintOptions, EventuallyFollowsConstraintOptions, SamplingOptions,
+};
+
+/// Splitting of the log into consecutive time windows
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TimeWindowing {
+    /// Windows of equal duration between the first and last event
+    #[serde(rename_all = "camelCase")]
+    Fixed { num_windows: usize },
+    /// Windows containing (roughly) the same number of events
+    #[serde(rename_all = "camelCase")]
+    Adaptive { num_windows: usize },
+}
+
+impl Default for TimeWindowing {
+    fn default() -> Self {
+        TimeWindowing::Adaptive { num_windows: 5 }
+    }
+}
+
+fn default_min_instances_per_window() -> usize {
+    10
+}
+
+fn default_min_relative_change() -> f64 {
+    0.1
+}
+
+/// Options for discovering count and EF constraints per time window
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftDiscoveryRequest {
+    pub count_constraints: Option<CountConstraintOptions>,
+    pub eventually_follows_constraints: Option<EventuallyFollowsConstraintOptions>,
+    #[serde(default)]
+    pub windowing: TimeWindowing,
+    /// Windows with fewer (sampled) instances of a type are not used for constraints on this type
+    #[serde(default = "default_min_instances_per_window")]
+    pub min_instances_per_window: usize,
+    /// Minimal relative change of a bound to be reported as drift
+    #[serde(default = "default_min_relative_change")]
+    pub min_relative_change: f64,
+    #[serde(default)]
+    pub sampling: SamplingOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftTimeWindow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Number of events with a timestamp in this window
+    pub num_events: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DriftConstraintKind {
+    Count,
+    EventuallyFollows,
+}
+
+/// Bounds of a constraint discovered in one time window
+///
+/// Counts for count constraints, durations in seconds for EF constraints.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftBounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// A count or EF constraint whose bounds differ between time windows
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftingConstraint {
+    /// Name of the constraint (without bounds)
+    pub name: String,
+    pub kind: DriftConstraintKind,
+    /// Number of (sampled) instances of the root type per window
+    pub num_instances: Vec<usize>,
+    /// Discovered bounds per window
+    ///
+    /// `None` if the constraint was not discovered in this window,
+    /// or if the window has too few instances (see `num_instances`)
+    pub bounds: Vec<Option<DriftBounds>>,
+    /// Windows in which the bounds changed compared to the previous window with enough instances
+    pub change_windows: Vec<usize>,
+    /// Constraint as discovered in the last window (with enough instances) it was discovered in
+    pub latest_tree: Option<BindingBoxTree>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftDiscoveryResponse {
+    pub windows: Vec<DriftTimeWindow>,
+    pub constraints: Vec<DriftingConstraint>,
+}
+
+/// Split the time range of all events of the OCEL into windows
+fn get_time_windows(ocel: &IndexLinkedOCEL, windowing: &TimeWindowing) -> Vec<DriftTimeWindow> {
+    let times = ocel
+        .ocel
+        .events
+        .iter()
+        .map(|ev| ev.time.with_timezone(&Utc))
+        .sorted()
+        .collect_vec();
+    let (Some(first), Some(last)) = (times.first(), times.last()) else {
+        return Vec::new();
+    };
+    let starts = match windowing {
+        TimeWindowing::Fixed { num_windows } => {
+            let num_windows = (*num_windows).max(1) as i32;
+            let duration = (*last - *first) / num_windows;
+            (0..num_windows)
+                .map(|i| *first + duration * i)
+                .dedup()
+                .collect_vec()
+        }
+        TimeWindowing::Adaptive { num_windows } => {
+            let num_windows = (*num_windows).max(1);
+            (0..num_windows)
+                .map(|i| times[i * times.len() / num_windows])
+                .dedup()
+                .collect_vec()
+        }
+    };
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, from)| {
+            let to = starts.get(i + 1).copied().unwrap_or(*last);
+            let is_last = i + 1 == starts.len();
+            let num_events = times
+                .iter()
+                .filter(|t| **t >= *from && (**t < to || is_last))
+                .count();
+            DriftTimeWindow {
+                from: *from,
+                to,
+                num_events,
+            }
+        })
+        .collect()
+}
+
+/// Index of the window the instance (by its event timestamp, or the timestamp of the first event for objects) belongs to
+fn get_window_index(
+    ocel: &IndexLinkedOCEL,
+    windows: &[DriftTimeWindow],
+    index: &EventOrObjectIndex,
+) -> Option<usize> {
+    let time = get_instance_time(ocel, index)?.with_timezone(&Utc);
+    windows.partition_point(|w| w.from <= time).checked_sub(1)
+}
+
+/// (Sampled) instances of the type, grouped by window
+fn get_instances_per_window(
+    ocel: &IndexLinkedOCEL,
+    windows: &[DriftTimeWindow],
+    ocel_type: &EventOrObjectType,
+    sampling: &SamplingOptions,
+) -> Vec<Vec<EventOrObjectIndex>> {
+    let mut ret = vec![Vec::new(); windows.len()];
+    for index in get_instances(ocel, ocel_type, sampling) {
+        if let Some(w) = get_window_index(ocel, windows, &index) {
+            ret[w].push(index);
+        }
+    }
+    ret
+}
+
+fn bounds_differ(a: &DriftBounds, b: &DriftBounds, min_relative_change: f64) -> bool {
+    let differ = |x: Option<f64>, y: Option<f64>| match (x, y) {
+        (None, None) => false,
+        (Some(x), Some(y)) => (x - y).abs() > min_relative_change * x.abs().max(y.abs()),
+        _ => true,
+    };
+    differ(a.min, b.min) || differ(a.max, b.max)
+}
+
+/// Constraints (by name without bounds) discovered per window
+struct WindowedConstraints {
+    kind: DriftConstraintKind,
+    per_window: Vec<Option<(DriftBounds, BindingBoxTree)>>,
+    num_instances: Vec<usize>,
+}
+
+impl WindowedConstraints {
+    /// Returns `None` if the constraint does not drift
+    fn into_drifting_constraint(
+        self,
+        name: String,
+        min_instances_per_window: usize,
+        min_relative_change: f64,
+    ) -> Option<DriftingConstraint> {
+        let mut change_windows = Vec::new();
+        let mut prev: Option<Option<DriftBounds>> = None;
+        for (i, (bounds, n)) in self.per_window.iter().zip(&self.num_instances).enumerate() {
+            if *n < min_instances_per_window {
+                continue;
+            }
+            let bounds = bounds.as_ref().map(|(b, _)| *b);
+            if let Some(prev) = prev {
+                let changed = match (&prev, &bounds) {
+                    (Some(a), Some(b)) => bounds_differ(a, b, min_relative_change),
+                    (None, None) => false,
+                    _ => true,
+                };
+                if changed {
+                    change_windows.push(i);
+                }
+            }
+            prev = Some(bounds);
+        }
+        if change_windows.is_empty() {
+            return None;
+        }
+        let latest_tree = self
+            .per_window
+            .iter()
+            .zip(&self.num_instances)
+            .rev()
+            .filter(|(_, n)| **n >= min_instances_per_window)
+            .find_map(|(c, _)| c.as_ref().map(|(_, tree)| tree.clone()));
+        Some(DriftingConstraint {
+            name,
+            kind: self.kind,
+            bounds: self
+                .per_window
+                .into_iter()
+                .zip(&self.num_instances)
+                .map(|(b, n)| b.filter(|_| *n >= min_instances_per_window).map(|(b, _)| b))
+                .collect(),
+            num_instances: self.num_instances,
+            change_windows,
+            latest_tree,
+        })
+    }
+}
+
+fn get_type_key(t: &EventOrObjectType) -> String {
+    match t {
+        EventOrObjectType::Event(et) => format!("event '{et}'"),
+        EventOrObjectType::Object(ot) => format!("object '{ot}'"),
+    }
+}
+
+fn get_count_constraint_key(c: &CountConstraint) -> String {
+    format!(
+        "{} per {}{}",
+        get_type_key(&c.related_type),
+        get_type_key(&c.root_type),
+        if c.ocel_relation_flipped {
+            " (reversed)"
+        } else {
+            ""
+        }
+    )
+}
+
+fn get_ef_constraint_key(c: &EFConstraint) -> String {
+    let mut key = format!(
+        "'{}' -> '{}' for '{}'",
+        c.from_ev_type, c.to_ev_type, c.for_object_type
+    );
+    if !c.to_object_path.is_empty() {
+        key.push_str(&format!(
+            " via {}",
+            get_o2o_path_description(&c.to_object_path)
+        ));
+    }
+    key
+}
+
+/// Discover count and EF constraints per time window, and return constraints whose bounds drift between windows
+///
+/// All windows share the same time boundaries (based on all events), so that bounds are comparable across types.
+/// Objects are assigned to the window of their first event.
+pub fn discover_constraint_drift(
+    ocel: &IndexLinkedOCEL,
+    options: DriftDiscoveryRequest,
+) -> DriftDiscoveryResponse {
+    let windows = get_time_windows(ocel, &options.windowing);
+    // BTreeMap, so that constraints are returned in a deterministic order
+    let mut windowed: BTreeMap<String, WindowedConstraints> = BTreeMap::new();
+    let mut insert = |key: String,
+                      kind: DriftConstraintKind,
+                      window: usize,
+                      num_instances: &Vec<usize>,
+                      bounds: DriftBounds,
+                      tree: BindingBoxTree| {
+        let entry = windowed.entry(key).or_insert_with(|| WindowedConstraints {
+            kind,
+            per_window: vec![None; windows.len()],
+            num_instances: num_instances.clone(),
+        });
+        entry.per_window[window] = Some((bounds, tree));
+    };
+
+    if let Some(count_opts) = &options.count_constraints {
+        let types = count_opts
+            .object_types
+            .iter()
+            .map(|ot| EventOrObjectType::Object(ot.clone()))
+            .chain(
+                count_opts
+                    .event_types
+                    .iter()
+                    .map(|et| EventOrObjectType::Event(et.clone())),
+            );
+        for t in types {
+            let instances = get_instances_per_window(ocel, &windows, &t, &options.sampling);
+            let num_instances = instances.iter().map(|i| i.len()).collect_vec();
+            for (w, window_instances) in instances.iter().enumerate() {
+                for c in discover_count_constraints_for_supporting_instances(
+                    ocel,
+                    count_opts.cover_fraction,
+                    window_instances.iter(),
+                    &t,
+                ) {
+                    let bounds = DriftBounds {
+                        min: c.min_count.map(|n| n as f64),
+                        max: c.max_count.map(|n| n as f64),
+                    };
+                    insert(
+                        get_count_constraint_key(&c),
+                        DriftConstraintKind::Count,
+                        w,
+                        &num_instances,
+                        bounds,
+                        c.get_full_tree(),
+                    );
+                }
+            }
+        }
+    }
+    if let Some(ef_opts) = &options.eventually_follows_constraints {
+        for ot in &ef_opts.object_types {
+            let instances = get_instances_per_window(
+                ocel,
+                &windows,
+                &EventOrObjectType::Object(ot.clone()),
+                &options.sampling,
+            );
+            let num_instances = instances.iter().map(|i| i.len()).collect_vec();
+            for (w, window_instances) in instances.iter().enumerate() {
+                let ob_indices = window_instances
+                    .iter()
+                    .flat_map(|i| match i {
+                        EventOrObjectIndex::Object(oi) => Some(*oi),
+                        _ => None,
+                    })
+                    .collect_vec();
+                let mut constraints = discover_ef_constraints_for_supporting_instances(
+                    ocel,
+                    ef_opts.cover_fraction,
+                    ob_indices.iter(),
+                    ot,
+                    &options.sampling,
+                );
+                if ef_opts.max_o2o_path_length > 0 {
+                    constraints.extend(discover_o2o_path_ef_constraints_for_supporting_instances(
+                        ocel,
+                        ef_opts.cover_fraction,
+                        ob_indices.iter(),
+                        ot,
+                        ef_opts.max_o2o_path_length,
+                        &options.sampling,
+                    ));
+                }
+                for c in constraints {
+                    let bounds = DriftBounds {
+                        min: c.min_duration_sec,
+                        max: c.max_duration_sec,
+                    };
+                    insert(
+                        get_ef_constraint_key(&c),
+                        DriftConstraintKind::EventuallyFollows,
+                        w,
+                        &num_instances,
+                        bounds,
+                        c.get_full_tree(),
+                    );
+                }
+            }
+        }
+    }
+
+    let constraints = windowed
+        .into_iter()
+        .flat_map(|(name, c)| {
+            c.into_drifting_constraint(
+                name,
+                options.min_instances_per_window,
+                options.min_relative_change,
+            )
+        })
+        .collect();
+    DriftDiscoveryResponse {
+        windows,
+        constraints,
+    }
+}
+
+#[test]
+fn detects_count_drift_between_two_windows() {
+    // Orders placed in the first window contain one item, orders placed in the second window contain three items
+    let orders = (0..20)
+        .map(|i| {
+            let num_items = if i < 10 { 1 } else { 3 };
+            let items = (0..num_items).map(|j| format!("i{i}-{j}")).collect_vec();
+            (format!("o{i}"), format!("e{i}"), items)
+        })
+        .collect_vec();
+    let events = orders
+        .iter()
+        .enumerate()
+        .map(|(i, (ob, ev, _))| {
+            let t = (if i < 10 { i } else { 1000 + i }) as i64;
+            (ev.as_str(), "place order", t, vec![(ob.as_str(), "order")])
+        })
+        .collect();
+    let objects = orders
+        .iter()
+        .flat_map(|(ob, _, items)| {
+            std::iter::once((
+                ob.as_str(),
+                "order",
+                items.iter().map(|it| (it.as_str(), "contains")).collect(),
+            ))
+            .chain(items.iter().map(|it| (it.as_str(), "item", vec![])))
+        })
+        .collect();
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(events, objects));
+
+    let res = discover_constraint_drift(
+        &ocel,
+        DriftDiscoveryRequest {
+            count_constraints: Some(CountConstraintOptions {
+                object_types: vec!["order".to_string()],
+                event_types: vec![],
+                cover_fraction: 1.0,
+            }),
+            eventually_follows_constraints: None,
+            windowing: TimeWindowing::Adaptive { num_windows: 2 },
+            min_instances_per_window: default_min_instances_per_window(),
+            min_relative_change: default_min_relative_change(),
+            sampling: SamplingOptions::default(),
+        },
+    );
+    assert_eq!(
+        res.windows.iter().map(|w| w.num_events).collect_vec(),
+        vec![10, 10]
+    );
+    // The number of 'place order' events per order does not drift
+    assert_eq!(res.constraints.len(), 1);
+    let c = &res.constraints[0];
+    assert_eq!(c.name, "object 'item' per object 'order'");
+    assert_eq!(c.kind, DriftConstraintKind::Count);
+    assert_eq!(c.num_instances, vec![10, 10]);
+    assert_eq!(
+        c.bounds,
+        vec![
+            Some(DriftBounds {
+                min: Some(1.0),
+                max: Some(1.0)
+            }),
+            Some(DriftBounds {
+                min: Some(3.0),
+                max: Some(3.0)
+            })
+        ]
+    );
+    assert_eq!(c.change_windows, vec![1]);
+}
+
+#[test]
+fn drift_keys_distinguish_directions_and_kinds() {
+    use super::graph_discovery::O2OPathStep;
+
+    let ef = |reversed: bool| EFConstraint {
+        from_ev_type: "place order".to_string(),
+        to_ev_type: "send package".to_string(),
+        min_duration_sec: Some(0.0),
+        max_duration_sec: Some(60.0),
+        for_object_type: "order".to_string(),
+        to_object_path: vec![O2OPathStep {
+            object_type: "package".to_string(),
+            reversed,
+            qualifier: "ships".to_string(),
+        }],
+    };
+    assert_eq!(
+        get_ef_constraint_key(&ef(false)),
+        "'place order' -> 'send package' for 'order' via -> 'package' (ships)"
+    );
+    assert_eq!(
+        get_ef_constraint_key(&ef(true)),
+        "'place order' -> 'send package' for 'order' via <- 'package' (ships)"
+    );
+
+    // Event and object types with the same name
+    let cc = |related_type: EventOrObjectType| CountConstraint {
+        min_count: Some(1),
+        max_count: Some(1),
+        root_type: EventOrObjectType::Object("order".to_string()),
+        related_type,
+        ocel_relation_flipped: false,
+    };
+    assert_ne!(
+        get_count_constraint_key(&cc(EventOrObjectType::Event("payment".to_string()))),
+        get_count_constraint_key(&cc(EventOrObjectType::Object("payment".to_string())))
+    );
+}
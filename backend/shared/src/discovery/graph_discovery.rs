@@ -14,12 +14,6 @@ use itertools::Itertools;
 //     layout::{Axis, ColorAxis},
 //     Layout, Plot, Scatter,
 // };
-use rand::{
-    // random,
-    rngs::StdRng,
-    seq::IteratorRandom,
-    SeedableRng,
-};
 
 use crate::{
     binding_box::{
@@ -31,7 +25,7 @@ use crate::{
     },
     discovery::{
         advanced::{binding_to_instances, generate_sample_bindings, label_bindings},
-        RNG_SEED, SAMPLE_FRAC, SAMPLE_MIN_NUM_INSTANCES,
+        SamplingOptions,
     },
     preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL, OCELNodeRef, ObjectIndex},
 };
@@ -46,60 +40,40 @@ pub enum RefType {
     EventReversed,
 }
 
+/// (Sampled) instances of an event/object type
 pub fn get_instances(
     ocel: &IndexLinkedOCEL,
     ocel_type: &EventOrObjectType,
+    sampling: &SamplingOptions,
 ) -> Vec<EventOrObjectIndex> {
-    let mut rng = StdRng::seed_from_u64(RNG_SEED);
-    let instances: Option<Vec<_>> = match &ocel_type {
-        EventOrObjectType::Event(et) => {
-            if let Some(o_indices) = ocel.events_of_type.get(et) {
-                let instances = if o_indices.len() >= SAMPLE_MIN_NUM_INSTANCES {
-                    let sample_count = (o_indices.len() as f32 * SAMPLE_FRAC).ceil() as usize;
-                    o_indices.iter().choose_multiple(&mut rng, sample_count)
-                } else {
-                    o_indices.iter().collect()
-                };
-                Some(
-                    instances
-                        .into_iter()
-                        .map(|i| EventOrObjectIndex::Event(*i))
-                        .collect(),
-                )
-            } else {
-                None
-            }
-        }
-        EventOrObjectType::Object(ot) => {
-            if let Some(o_indices) = ocel.objects_of_type.get(ot) {
-                let instances = if o_indices.len() >= SAMPLE_MIN_NUM_INSTANCES {
-                    let sample_count = (o_indices.len() as f32 * SAMPLE_FRAC).ceil() as usize;
-                    o_indices.iter().choose_multiple(&mut rng, sample_count)
-                } else {
-                    o_indices.iter().collect()
-                };
-                Some(
-                    instances
-                        .into_iter()
-                        .map(|i| EventOrObjectIndex::Object(*i))
-                        .collect(),
-                )
-            } else {
-                None
-            }
-        }
+    let instances = match &ocel_type {
+        EventOrObjectType::Event(et) => ocel
+            .events_of_type
+            .get(et)
+            .into_iter()
+            .flatten()
+            .map(|i| EventOrObjectIndex::Event(*i))
+            .collect_vec(),
+        EventOrObjectType::Object(ot) => ocel
+            .objects_of_type
+            .get(ot)
+            .into_iter()
+            .flatten()
+            .map(|i| EventOrObjectIndex::Object(*i))
+            .collect_vec(),
     };
-    instances.unwrap_or_default()
+    sampling.sample_instances(ocel, instances)
 }
 
 pub fn discover_count_constraints(
     ocel: &IndexLinkedOCEL,
     coverage: f32,
     ocel_type: EventOrObjectType,
+    sampling: &SamplingOptions,
 ) -> Vec<CountConstraint> {
     let now = Instant::now();
     let mut ret = Vec::new();
-    let instances: Vec<_> = get_instances(ocel, &ocel_type);
+    let instances: Vec<_> = get_instances(ocel, &ocel_type, sampling);
     ret.extend(discover_count_constraints_for_supporting_instances(
         ocel,
         coverage,
@@ -134,28 +108,26 @@ pub fn discover_count_constraints_for_supporting_instances<
                 ]
             }))
             .collect();
-        if let Some(rels) = ocel.symmetric_rels.get(o_index.borrow()) {
-            for (index, reversed, _qualifier) in rels {
-                let (ref_type, ocel_type) = match ocel.ob_or_ev_by_index(*index).unwrap() {
-                    OCELNodeRef::Event(e) => (
-                        if *reversed {
-                            RefType::EventReversed
-                        } else {
-                            RefType::Event
-                        },
-                        e.event_type.clone(),
-                    ),
-                    OCELNodeRef::Object(o) => (
-                        if *reversed {
-                            RefType::ObjectReversed
-                        } else {
-                            RefType::Object
-                        },
-                        o.object_type.clone(),
-                    ),
-                };
-                *map.entry((ref_type, ocel_type)).or_default() += 1;
-            }
+        for (index, reversed, _qualifier) in ocel.get_symmetric_rels(o_index.borrow()) {
+            let (ref_type, ocel_type) = match ocel.ob_or_ev_by_index(index).unwrap() {
+                OCELNodeRef::Event(e) => (
+                    if reversed {
+                        RefType::EventReversed
+                    } else {
+                        RefType::Event
+                    },
+                    e.event_type.clone(),
+                ),
+                OCELNodeRef::Object(o) => (
+                    if reversed {
+                        RefType::ObjectReversed
+                    } else {
+                        RefType::Object
+                    },
+                    o.object_type.clone(),
+                ),
+            };
+            *map.entry((ref_type, ocel_type)).or_default() += 1;
         }
         for (k, val) in map {
             total_map.entry(k).or_default().push(val);
@@ -520,6 +492,30 @@ impl CountConstraint {
     }
 }
 
+/// One step along an O2O relationship, from the previous object to a related object of `object_type`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct O2OPathStep {
+    pub object_type: String,
+    /// True if the O2O relationship points from the related object to the previous object
+    pub reversed: bool,
+    /// Qualifier of the O2O relationship (paths via differently qualified relationships are discovered separately)
+    pub qualifier: String,
+}
+
+/// Description of an O2O path, with the direction of each relationship (e.g., `-> 'item' (contains) <- 'package' (packs)`)
+pub fn get_o2o_path_description(path: &[O2OPathStep]) -> String {
+    path.iter()
+        .map(|step| {
+            format!(
+                "{} '{}' ({})",
+                if step.reversed { "<-" } else { "->" },
+                step.object_type,
+                step.qualifier
+            )
+        })
+        .join(" ")
+}
+
 #[derive(Debug, Clone)]
 pub struct EFConstraint {
     pub from_ev_type: String,
@@ -527,16 +523,25 @@ pub struct EFConstraint {
     pub min_duration_sec: Option<f64>,
     pub max_duration_sec: Option<f64>,
     pub for_object_type: String,
+    /// O2O path from the `for_object_type` object to the object the `to_ev_type` event relates to
+    ///
+    /// Empty if both events relate to the same object
+    pub to_object_path: Vec<O2OPathStep>,
 }
 
 pub fn discover_ef_constraints(
     ocel: &IndexLinkedOCEL,
     coverage: f32,
     object_type: &String,
+    sampling: &SamplingOptions,
 ) -> Vec<EFConstraint> {
     let _now = Instant::now();
     let mut ret = Vec::new();
-    let instances: Vec<_> = get_instances(ocel, &EventOrObjectType::Object(object_type.clone()));
+    let instances: Vec<_> = get_instances(
+        ocel,
+        &EventOrObjectType::Object(object_type.clone()),
+        sampling,
+    );
     ret.extend(discover_ef_constraints_for_supporting_instances(
         ocel,
         coverage,
@@ -545,6 +550,7 @@ pub fn discover_ef_constraints(
             _ => None,
         }),
         object_type,
+        sampling,
     ));
 
     // println!("Graph Count Discovery took {:?}", now.elapsed());
@@ -559,52 +565,45 @@ pub fn discover_ef_constraints_for_supporting_instances<
     coverage: f32,
     supporting_instances: I,
     supporting_object_type: &String,
+    sampling: &SamplingOptions,
 ) -> Vec<EFConstraint> {
     let _now = Instant::now();
     let mut ret = Vec::new();
-    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut rng = sampling.rng();
     let mut total_map: HashMap<(&String, &String), Vec<Option<f64>>> = HashMap::new();
     for o_index in supporting_instances {
-        if let Some(rels) = ocel.get_symmetric_rels_ob(o_index.borrow()) {
-            let evs = rels
+        let evs = ocel
+            .get_symmetric_rels_ob(o_index.borrow())
+            .flat_map(|(o_or_e_index, _reverse, _qualifier)| match o_or_e_index {
+                EventOrObjectIndex::Event(ei) => ocel.ev_by_index(&ei),
+                EventOrObjectIndex::Object(_) => None,
+            })
+            .collect_vec();
+        let evs = sampling.sample_object_events(&mut rng, evs);
+        // println!("Selected {} out of {} events",evs.len(), evs_num);
+        for i in 0..evs.len() {
+            let mut min_delay_to: HashMap<&String, Option<f64>> = ocel
+                .ocel
+                .event_types
                 .iter()
-                .flat_map(|(o_or_e_index, _reverse, _qualifier)| match o_or_e_index {
-                    EventOrObjectIndex::Event(ei) => ocel.ev_by_index(ei),
-                    EventOrObjectIndex::Object(_) => None,
-                })
-                .collect_vec();
-            let evs_num = evs.len();
-            let evs = if evs_num >= 1000 {
-                evs.into_iter()
-                    .choose_multiple(&mut rng, (SAMPLE_FRAC * evs_num as f32).ceil() as usize)
-            } else {
-                evs
-            };
-            // println!("Selected {} out of {} events",evs.len(), evs_num);
-            for i in 0..evs.len() {
-                let mut min_delay_to: HashMap<&String, Option<f64>> = ocel
-                    .ocel
-                    .event_types
-                    .iter()
-                    .map(|t| (&t.name, None))
-                    .collect();
-                for j in 0..evs.len() {
-                    if i != j && evs[i].time <= evs[j].time {
-                        let time_diff =
-                            (evs[j].time - evs[i].time).num_milliseconds() as f64 / 1000.0;
+                .map(|t| (&t.name, None))
+                .collect();
+            for j in 0..evs.len() {
+                if i != j && evs[i].time <= evs[j].time {
+                    let time_diff =
+                        (evs[j].time - evs[i].time).num_milliseconds() as f64 / 1000.0;
 
-                        let v = min_delay_to.entry(&evs[j].event_type).or_default();
-                        if v.is_none() || v.unwrap() > time_diff {
-                            *v = Some(time_diff);
-                        }
+                    let v = min_delay_to.entry(&evs[j].event_type).or_default();
+                    if v.is_none() || v.unwrap() > time_diff {
+                        *v = Some(time_diff);
                     }
                 }
-                for (to_ev_type, min_delay) in &min_delay_to {
-                    total_map
-                        .entry((&evs[i].event_type, *to_ev_type))
-                        .or_default()
-                        .push(*min_delay);
-                }
+            }
+            for (to_ev_type, min_delay) in &min_delay_to {
+                total_map
+                    .entry((&evs[i].event_type, *to_ev_type))
+                    .or_default()
+                    .push(*min_delay);
             }
         }
     }
@@ -645,6 +644,7 @@ pub fn discover_ef_constraints_for_supporting_instances<
                             min_duration_sec: Some(min),
                             max_duration_sec: Some(max),
                             for_object_type: supporting_object_type.clone(),
+                            to_object_path: Vec::new(),
                         });
                     }
                 }
@@ -654,18 +654,204 @@ pub fn discover_ef_constraints_for_supporting_instances<
     ret
 }
 
+/// Maximal number of objects reached from one supporting object when following O2O paths
+const MAX_O2O_PATH_OBJECTS: usize = 1000;
+
+/// Objects reachable from an object via O2O paths of length 1 to `max_path_length`, grouped by the path (object types, directions and qualifiers)
+///
+/// Paths do not revisit objects already on the path
+fn get_o2o_path_objects(
+    ocel: &IndexLinkedOCEL,
+    start: ObjectIndex,
+    max_path_length: usize,
+) -> HashMap<Vec<O2OPathStep>, HashSet<ObjectIndex>> {
+    let mut ret: HashMap<Vec<O2OPathStep>, HashSet<ObjectIndex>> = HashMap::new();
+    let mut frontier: Vec<(Vec<ObjectIndex>, Vec<O2OPathStep>)> = vec![(vec![start], Vec::new())];
+    let mut num_reached = 0;
+    for _ in 0..max_path_length {
+        let mut next_frontier = Vec::new();
+        for (visited, path) in &frontier {
+            let last = visited.last().unwrap();
+            for (to, reversed, qualifier) in ocel.get_symmetric_rels_ob(last) {
+                let EventOrObjectIndex::Object(to_ob) = to else {
+                    continue;
+                };
+                if visited.contains(&to_ob) || num_reached >= MAX_O2O_PATH_OBJECTS {
+                    continue;
+                }
+                let Some(ob) = ocel.ob_by_index(&to_ob) else {
+                    continue;
+                };
+                let mut new_path = path.clone();
+                new_path.push(O2OPathStep {
+                    object_type: ob.object_type.clone(),
+                    reversed,
+                    qualifier: qualifier.to_string(),
+                });
+                let mut new_visited = visited.clone();
+                new_visited.push(to_ob);
+                ret.entry(new_path.clone()).or_default().insert(to_ob);
+                num_reached += 1;
+                next_frontier.push((new_visited, new_path));
+            }
+        }
+        frontier = next_frontier;
+    }
+    ret
+}
+
+/// Discover EF constraints for an object type, where the target event relates to an object reachable via an O2O path
+pub fn discover_o2o_path_ef_constraints(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    object_type: &String,
+    max_path_length: usize,
+    sampling: &SamplingOptions,
+) -> Vec<EFConstraint> {
+    let instances: Vec<_> = get_instances(
+        ocel,
+        &EventOrObjectType::Object(object_type.clone()),
+        sampling,
+    );
+    discover_o2o_path_ef_constraints_for_supporting_instances(
+        ocel,
+        coverage,
+        instances.into_iter().flat_map(|i| match i {
+            EventOrObjectIndex::Object(oi) => Some(oi),
+            _ => None,
+        }),
+        object_type,
+        max_path_length,
+        sampling,
+    )
+}
+
+/// Discover EF constraints where the target event relates to an object reachable from the supporting object
+/// via an O2O path (e.g., an order and the packages of its items)
+///
+/// Only considers paths of length 1 to `max_path_length`; For EF constraints on the same object see
+/// [`discover_ef_constraints_for_supporting_instances`].
+pub fn discover_o2o_path_ef_constraints_for_supporting_instances<
+    It: Borrow<ObjectIndex>,
+    I: Iterator<Item = It>,
+>(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    supporting_instances: I,
+    supporting_object_type: &String,
+    max_path_length: usize,
+    sampling: &SamplingOptions,
+) -> Vec<EFConstraint> {
+    let mut ret = Vec::new();
+    let mut rng = sampling.rng();
+    // Number of considered source events per event type
+    let mut num_from_evs: HashMap<&String, usize> = HashMap::new();
+    // Minimal delays to target events (only for source events with some later target event)
+    let mut total_map: HashMap<(&String, &String, Vec<O2OPathStep>), Vec<f64>> = HashMap::new();
+    for o_index in supporting_instances {
+        let evs = ocel
+            .events_of_object(o_index.borrow())
+            .flat_map(|ei| ocel.ev_by_index(ei))
+            .collect_vec();
+        let evs = sampling.sample_object_events(&mut rng, evs);
+        for e in &evs {
+            *num_from_evs.entry(&e.event_type).or_default() += 1;
+        }
+        for (path, obs) in get_o2o_path_objects(ocel, *o_index.borrow(), max_path_length) {
+            let path_evs: HashSet<_> = obs
+                .iter()
+                .flat_map(|o| ocel.events_of_object(o))
+                .collect();
+            let path_evs = path_evs
+                .into_iter()
+                .flat_map(|ei| ocel.ev_by_index(ei))
+                .collect_vec();
+            for from_ev in &evs {
+                let mut min_delay_to: HashMap<&String, f64> = HashMap::new();
+                for to_ev in &path_evs {
+                    if from_ev.id != to_ev.id && from_ev.time <= to_ev.time {
+                        let time_diff =
+                            (to_ev.time - from_ev.time).num_milliseconds() as f64 / 1000.0;
+                        let v = min_delay_to.entry(&to_ev.event_type).or_insert(time_diff);
+                        if *v > time_diff {
+                            *v = time_diff;
+                        }
+                    }
+                }
+                for (to_ev_type, min_delay) in min_delay_to {
+                    total_map
+                        .entry((&from_ev.event_type, to_ev_type, path.clone()))
+                        .or_default()
+                        .push(min_delay);
+                }
+            }
+        }
+    }
+    for ((from_ev_type, to_ev_type, path), delays) in total_map {
+        let n = num_from_evs.get(from_ev_type).copied().unwrap_or_default();
+        if n == 0 || (delays.len() as f32 / n as f32) < coverage {
+            continue;
+        }
+        let mean = delays.iter().sum::<f64>() / delays.len() as f64;
+        let std_deviation = (delays
+            .iter()
+            .map(|c| {
+                let diff = mean - *c;
+                diff * diff
+            })
+            .sum::<f64>()
+            / delays.len() as f64)
+            .sqrt();
+        // Source events without any later target event count as not covered
+        let mut seconds = delays.into_iter().map(Some).collect_vec();
+        seconds.resize(n.max(seconds.len()), None);
+        if let Some((min, max)) = get_seconds_range_with_coverage(
+            &seconds,
+            coverage,
+            0.0,
+            std_deviation,
+            Direction::Increase,
+        ) {
+            ret.push(EFConstraint {
+                from_ev_type: from_ev_type.clone(),
+                to_ev_type: to_ev_type.clone(),
+                min_duration_sec: Some(min),
+                max_duration_sec: Some(max),
+                for_object_type: supporting_object_type.clone(),
+                to_object_path: path,
+            });
+        }
+    }
+    ret
+}
+
 impl EFConstraint {
     pub fn get_constraint_name(&self) -> String {
+        if self.to_object_path.is_empty() {
+            return format!(
+                "Quick '{}' -> '{}' for '{}'",
+                self.from_ev_type, self.to_ev_type, self.for_object_type,
+            );
+        }
         format!(
-            "Quick '{}' -> '{}' for '{}'",
-            self.from_ev_type, self.to_ev_type, self.for_object_type,
+            "Quick '{}' -> '{}' for '{}' via {}",
+            self.from_ev_type,
+            self.to_ev_type,
+            self.for_object_type,
+            get_o2o_path_description(&self.to_object_path),
         )
     }
     pub fn get_full_tree(&self) -> BindingBoxTree {
         // let child_name = "A".to_string();
         let inner_child_name = "A".to_string();
         let inner_variable = 0;
-        let mut subtree = self.to_subtree(inner_child_name.clone(), inner_variable, 1, 2);
+        let mut subtree = self.to_subtree(
+            inner_child_name.clone(),
+            inner_variable,
+            1,
+            2,
+            inner_variable + 1,
+        );
         match &mut subtree.nodes[0] {
             BindingBoxTreeNode::Box(bbox, _) => {
                 bbox.new_object_vars.insert(
@@ -677,12 +863,18 @@ impl EFConstraint {
         }
         subtree
     }
+    /// Construct the subtree for this constraint, where `inner_variable` is the object variable of the `for_object_type` object
+    ///
+    /// If the constraint has an O2O path, the objects along the path are bound in an intermediate box
+    /// (using the object variables `first_path_ob_var`, `first_path_ob_var + 1`, ...), which is required to have any satisfied child binding.
+    /// The path variables must not be used elsewhere in the tree.
     pub fn to_subtree(
         &self,
         child_name: String,
         inner_variable: usize,
         new_from_ev_var: usize,
         new_to_ev_var: usize,
+        first_path_ob_var: usize,
     ) -> BindingBoxTree {
         let bbox0 = BindingBoxTreeNode::Box(
             BindingBox {
@@ -700,17 +892,33 @@ impl EFConstraint {
                     filter_label: None,
                 }],
                 size_filters: vec![],
-                constraints: vec![Constraint::SizeFilter {
-                    filter: SizeFilter::NumChilds {
-                        child_name: child_name.clone(),
-                        min: Some(1),
-                        max: None,
-                    },
+                constraints: vec![if self.to_object_path.is_empty() {
+                    Constraint::SizeFilter {
+                        filter: SizeFilter::NumChilds {
+                            child_name: child_name.clone(),
+                            min: Some(1),
+                            max: None,
+                        },
+                    }
+                } else {
+                    Constraint::ANY {
+                        child_names: vec![child_name.clone()],
+                    }
                 }],
                 ..Default::default()
             },
             vec![1],
         );
+        if !self.to_object_path.is_empty() {
+            return self.to_path_subtree(
+                bbox0,
+                child_name,
+                inner_variable,
+                new_from_ev_var,
+                new_to_ev_var,
+                first_path_ob_var,
+            );
+        }
 
         let bbox1 = BindingBoxTreeNode::Box(
             BindingBox {
@@ -746,42 +954,123 @@ impl EFConstraint {
             edge_names: vec![((0, 1), child_name)].into_iter().collect(),
         }
     }
+
+    fn to_path_subtree(
+        &self,
+        bbox0: BindingBoxTreeNode,
+        child_name: String,
+        inner_variable: usize,
+        new_from_ev_var: usize,
+        new_to_ev_var: usize,
+        first_path_ob_var: usize,
+    ) -> BindingBoxTree {
+        let path_child_name = format!("{child_name}_path");
+        let path_vars = (0..self.to_object_path.len())
+            .map(|i| ObjectVariable(first_path_ob_var + i))
+            .collect_vec();
+        let mut prev_var = ObjectVariable(inner_variable);
+        let mut path_filters = Vec::new();
+        for (step, var) in self.to_object_path.iter().zip(path_vars.iter()) {
+            let (object, other_object) = if step.reversed {
+                (*var, prev_var)
+            } else {
+                (prev_var, *var)
+            };
+            path_filters.push(Filter::O2O {
+                object,
+                other_object,
+                qualifier: Some(step.qualifier.clone()),
+                filter_label: None,
+            });
+            prev_var = *var;
+        }
+        let bbox1 = BindingBoxTreeNode::Box(
+            BindingBox {
+                new_object_vars: self
+                    .to_object_path
+                    .iter()
+                    .zip(path_vars.iter())
+                    .map(|(step, var)| {
+                        (*var, vec![step.object_type.clone()].into_iter().collect())
+                    })
+                    .collect(),
+                filters: path_filters,
+                constraints: vec![Constraint::SizeFilter {
+                    filter: SizeFilter::NumChilds {
+                        child_name: path_child_name.clone(),
+                        min: Some(1),
+                        max: None,
+                    },
+                }],
+                ..Default::default()
+            },
+            vec![2],
+        );
+        let bbox2 = BindingBoxTreeNode::Box(
+            BindingBox {
+                new_event_vars: vec![(
+                    EventVariable(new_to_ev_var),
+                    vec![self.to_ev_type.clone()].into_iter().collect(),
+                )]
+                .into_iter()
+                .collect(),
+                filters: vec![
+                    Filter::O2E {
+                        object: prev_var,
+                        event: EventVariable(new_to_ev_var),
+                        qualifier: None,
+                        filter_label: None,
+                    },
+                    Filter::TimeBetweenEvents {
+                        from_event: EventVariable(new_from_ev_var),
+                        to_event: EventVariable(new_to_ev_var),
+                        min_seconds: self.min_duration_sec,
+                        max_seconds: self.max_duration_sec,
+                    },
+                ],
+                ..Default::default()
+            },
+            vec![],
+        );
+        BindingBoxTree {
+            nodes: vec![bbox0, bbox1, bbox2],
+            edge_names: vec![((0, 1), child_name), ((1, 2), path_child_name)]
+                .into_iter()
+                .collect(),
+        }
+    }
 }
 
+/// Discover OR constraints (of count and EF constraints) for an event/object type
+///
+/// Candidate count constraints are discovered for each of the `count_cover_multipliers` (multiplied with `coverage`)
 pub fn discover_or_constraints_new(
     ocel: &IndexLinkedOCEL,
     ocel_type: &EventOrObjectType,
     coverage: f32,
+    count_cover_multipliers: &[f32],
+    sampling: &SamplingOptions,
 ) -> Vec<(String, BindingBoxTree)> {
     let mut now = Instant::now();
     let mut ret = Vec::new();
-    let instances: Vec<_> = get_instances(ocel, ocel_type);
-    let mut count_constraints: HashSet<CountConstraint> =
-        discover_count_constraints_for_supporting_instances(
-            ocel,
-            0.11 * coverage,
-            instances.iter(),
-            ocel_type,
-        )
-        .into_iter()
+    let instances: Vec<_> = get_instances(ocel, ocel_type, sampling);
+    let count_constraints: HashSet<CountConstraint> = count_cover_multipliers
+        .iter()
+        .flat_map(|multiplier| {
+            discover_count_constraints_for_supporting_instances(
+                ocel,
+                multiplier * coverage,
+                instances.iter(),
+                ocel_type,
+            )
+        })
         .collect();
-    count_constraints.extend(discover_count_constraints_for_supporting_instances(
-        ocel,
-        0.7 * coverage,
-        instances.iter(),
-        ocel_type,
-    ));
-    count_constraints.extend(discover_count_constraints_for_supporting_instances(
-        ocel,
-        coverage,
-        instances.iter(),
-        ocel_type,
-    ));
     let variable = match ocel_type {
         EventOrObjectType::Event(_) => Variable::Event(EventVariable(0)),
         EventOrObjectType::Object(_) => Variable::Object(ObjectVariable(0)),
     };
-    let bindings = generate_sample_bindings(ocel, &vec![ocel_type.clone()], variable.clone());
+    let bindings =
+        generate_sample_bindings(ocel, &vec![ocel_type.clone()], variable.clone(), sampling);
     let max_sat_count: usize = (1.1 * coverage * bindings.len() as f32).ceil() as usize;
     let b_instances = binding_to_instances(&bindings, variable.clone());
     count_constraints.into_iter().for_each(|cc| {
@@ -806,11 +1095,18 @@ pub fn discover_or_constraints_new(
                             EventOrObjectIndex::Event(_) => None,
                         }),
                     object_type,
+                    sampling,
                 );
             ef_constraints.into_iter().take(20).for_each(|ef_c| {
                 // Check if cc OR ef_c is a good candidate
                 // for that, first get labeled results for ef_c
-                let ef_c_subtree = ef_c.to_subtree("X".to_string(), variable.to_inner(), 2, 3);
+                let ef_c_subtree = ef_c.to_subtree(
+                    "X".to_string(),
+                    variable.to_inner(),
+                    2,
+                    3,
+                    variable.to_inner() + 1,
+                );
                 if let Some(or_tree) = check_or_compat(
                     ocel,
                     &bindings,
@@ -890,9 +1186,16 @@ pub fn discover_or_constraints_new(
                 EventOrObjectIndex::Event(_) => None,
             }),
             object_type,
+            sampling,
         );
         ef_constraints.into_iter().take(20).for_each(|ef_1| {
-            let ef1_subtree = ef_1.to_subtree("Y".to_string(), variable.to_inner(), 2, 3);
+            let ef1_subtree = ef_1.to_subtree(
+                "Y".to_string(),
+                variable.to_inner(),
+                2,
+                3,
+                variable.to_inner() + 1,
+            );
             let ef1_labeled_bindings = label_bindings(ocel, &bindings, &ef1_subtree);
             let ef1_sat_count: usize = ef1_labeled_bindings.iter().filter(|x| **x).count();
             if ef1_sat_count < max_sat_count {
@@ -911,13 +1214,20 @@ pub fn discover_or_constraints_new(
                             })
                             .cloned(),
                         object_type,
+                        sampling,
                     );
                 // println!("\t{} ef2_constraints",ef2_constraints.len());
                 ef2_constraints.into_iter().take(20).for_each(|ef_2| {
                     // println!("Next EF combination");
                     // Check if cc OR ef_c is a good candidate
                     // for that, first get labeled results for ef_c
-                    let ef2_subtree = ef_2.to_subtree("X".to_string(), variable.to_inner(), 2, 3);
+                    let ef2_subtree = ef_2.to_subtree(
+                        "X".to_string(),
+                        variable.to_inner(),
+                        2,
+                        3,
+                        variable.to_inner() + 1,
+                    );
                     if let Some(or_tree) = check_or_compat(
                         ocel,
                         &bindings,
@@ -1066,3 +1376,73 @@ pub fn merge_or_tree(
         }));
     or_tree
 }
+
+#[test]
+fn discovers_ef_constraint_via_o2o_path() {
+    // Orders contain items, which are packed in packages (O2O from the package to the item)
+    let events = vec![
+        ("place1", "place order", 0, vec![("o1", "order")]),
+        ("send1", "send package", 3600, vec![("p1", "package")]),
+        ("place2", "place order", 10_000, vec![("o2", "order")]),
+        ("send2", "send package", 17_200, vec![("p2", "package")]),
+    ];
+    let objects = vec![
+        ("o1", "order", vec![("i1", "contains")]),
+        ("o2", "order", vec![("i2", "contains")]),
+        ("i1", "item", vec![]),
+        ("i2", "item", vec![]),
+        ("p1", "package", vec![("i1", "packs")]),
+        ("p2", "package", vec![("i2", "packs")]),
+    ];
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(events, objects));
+
+    let constraints = discover_o2o_path_ef_constraints(
+        &ocel,
+        1.0,
+        &"order".to_string(),
+        2,
+        &SamplingOptions::default(),
+    );
+    assert_eq!(constraints.len(), 1);
+    let c = &constraints[0];
+    assert_eq!(
+        (c.from_ev_type.as_str(), c.to_ev_type.as_str()),
+        ("place order", "send package")
+    );
+    assert_eq!(
+        c.to_object_path,
+        vec![
+            O2OPathStep {
+                object_type: "item".to_string(),
+                reversed: false,
+                qualifier: "contains".to_string(),
+            },
+            O2OPathStep {
+                object_type: "package".to_string(),
+                reversed: true,
+                qualifier: "packs".to_string(),
+            },
+        ]
+    );
+    // Delays of 1h and 2h (mean 1.5h, standard deviation 0.5h)
+    assert_eq!(c.min_duration_sec, Some(0.0));
+    assert_eq!(c.max_duration_sec, Some(7200.0));
+
+    let tree = c.get_full_tree();
+    // The reversed step binds the package as the source of the O2O relationship
+    let BindingBoxTreeNode::Box(path_box, _) = &tree.nodes[1] else {
+        panic!("Expected a BindingBox");
+    };
+    assert!(matches!(
+        &path_box.filters[1],
+        Filter::O2O {
+            object: ObjectVariable(2),
+            other_object: ObjectVariable(1),
+            qualifier: Some(q),
+            ..
+        } if q == "packs"
+    ));
+    let (results, _skipped) = tree.evaluate(&ocel);
+    assert_eq!(results.iter().filter(|(node, _, _)| *node == 0).count(), 2);
+    assert!(results.iter().all(|(_, _, violation)| violation.is_none()));
+}
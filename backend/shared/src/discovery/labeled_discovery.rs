@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binding_box::{
+        structs::{EventVariable, ObjectVariable, Variable},
+        Binding, BindingBoxTree,
+    },
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
+
+use super::{
+    advanced::{label_bindings, EventOrObjectType},
+    attribute_discovery::discover_attribute_constraints_for_supporting_instances,
+    combination_search::TreeCombination,
+    default_max_combination_candidates,
+    graph_discovery::{
+        discover_count_constraints_for_supporting_instances,
+        discover_ef_constraints_for_supporting_instances,
+    },
+    SamplingOptions,
+};
+
+fn default_true() -> bool {
+    true
+}
+fn default_cover_fraction() -> f32 {
+    0.9
+}
+fn default_max_results() -> usize {
+    50
+}
+
+/// Options for discovering constraints which separate known-good from known-bad events/objects
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LabeledDiscoveryRequest {
+    /// IDs of events/objects which should satisfy the constraint
+    pub good_ids: Vec<String>,
+    /// IDs of events/objects which should violate the constraint
+    pub bad_ids: Vec<String>,
+    /// Cover fraction (of the good instances) used for discovering candidate constraints
+    #[serde(default = "default_cover_fraction")]
+    pub cover_fraction: f32,
+    #[serde(default = "default_true")]
+    pub count_constraints: bool,
+    #[serde(default = "default_true")]
+    pub eventually_follows_constraints: bool,
+    #[serde(default = "default_true")]
+    pub attribute_constraints: bool,
+    /// Also consider OR/AND combinations of two candidate constraints
+    #[serde(default = "default_true")]
+    pub or_constraints: bool,
+    /// Number of best single candidates (per type) used for OR/AND combinations
+    #[serde(default = "default_max_combination_candidates")]
+    pub max_combination_candidates: usize,
+    /// Maximal number of returned candidates
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Only used for sampling events of objects with many events (EF constraints)
+    #[serde(default)]
+    pub sampling: SamplingOptions,
+}
+
+/// A candidate constraint with its classification quality on the labeled events/objects
+///
+/// Satisfying the constraint is interpreted as classifying an event/object as good.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LabeledConstraintCandidate {
+    pub name: String,
+    pub tree: BindingBoxTree,
+    /// Fraction of the events/objects satisfying the constraint which are labeled as good
+    pub precision: f32,
+    /// Fraction of the good events/objects satisfying the constraint
+    pub recall: f32,
+    pub f1_score: f32,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub true_negatives: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LabeledDiscoveryResponse {
+    /// Candidates, sorted by F1 score (descending)
+    pub candidates: Vec<LabeledConstraintCandidate>,
+    /// Labeled IDs which are neither an event nor an object ID of the OCEL
+    pub unknown_ids: Vec<String>,
+}
+
+fn get_index_and_type(
+    ocel: &IndexLinkedOCEL,
+    id: &String,
+) -> Option<(EventOrObjectIndex, EventOrObjectType)> {
+    if let (Some(index), Some(ob)) = (ocel.index_of_ob(id), ocel.ob_by_id(id)) {
+        return Some((
+            EventOrObjectIndex::Object(*index),
+            EventOrObjectType::Object(ob.object_type.clone()),
+        ));
+    }
+    if let (Some(index), Some(ev)) = (ocel.index_of_ev(id), ocel.ev_by_id(id)) {
+        return Some((
+            EventOrObjectIndex::Event(*index),
+            EventOrObjectType::Event(ev.event_type.clone()),
+        ));
+    }
+    None
+}
+
+fn to_binding(index: &EventOrObjectIndex) -> Binding {
+    match index {
+        EventOrObjectIndex::Event(ei) => Binding::default().expand_with_ev(EventVariable(0), *ei),
+        EventOrObjectIndex::Object(oi) => Binding::default().expand_with_ob(ObjectVariable(0), *oi),
+    }
+}
+
+/// Labeled instances of one event/object type
+#[derive(Default)]
+struct LabeledInstances {
+    good: Vec<EventOrObjectIndex>,
+    bad: Vec<EventOrObjectIndex>,
+}
+
+/// A candidate constraint for one type, with the results on the good and bad instances of this type
+struct Candidate {
+    name: String,
+    /// Tree using variable 0 as input (without binding it)
+    subtree: BindingBoxTree,
+    /// Tree binding variable 0 in the root box
+    tree: BindingBoxTree,
+    sat_good: Vec<bool>,
+    sat_bad: Vec<bool>,
+}
+
+impl Candidate {
+    /// `num_good` and `num_bad` are the total number of labeled instances (of all types)
+    fn to_result(&self, num_good: usize, num_bad: usize) -> LabeledConstraintCandidate {
+        get_result(
+            self.name.clone(),
+            self.tree.clone(),
+            &self.sat_good,
+            &self.sat_bad,
+            num_good,
+            num_bad,
+        )
+    }
+}
+
+/// Labeled instances of other types never satisfy the constraint, as they are not bound by its root box
+fn get_result(
+    name: String,
+    tree: BindingBoxTree,
+    sat_good: &[bool],
+    sat_bad: &[bool],
+    num_good: usize,
+    num_bad: usize,
+) -> LabeledConstraintCandidate {
+    let true_positives = sat_good.iter().filter(|s| **s).count();
+    let false_positives = sat_bad.iter().filter(|s| **s).count();
+    let precision = if true_positives + false_positives > 0 {
+        true_positives as f32 / (true_positives + false_positives) as f32
+    } else {
+        0.0
+    };
+    let recall = if num_good > 0 {
+        true_positives as f32 / num_good as f32
+    } else {
+        0.0
+    };
+    let f1_score = if precision + recall > 0.0 {
+        2.0 * precision * recall / (precision + recall)
+    } else {
+        0.0
+    };
+    LabeledConstraintCandidate {
+        name,
+        tree,
+        precision,
+        recall,
+        f1_score,
+        true_positives,
+        false_positives,
+        false_negatives: num_good - true_positives,
+        true_negatives: num_bad - false_positives,
+    }
+}
+
+fn get_candidates_for_type(
+    ocel: &IndexLinkedOCEL,
+    ocel_type: &EventOrObjectType,
+    labeled: &LabeledInstances,
+    options: &LabeledDiscoveryRequest,
+) -> Vec<Candidate> {
+    let variable = match ocel_type {
+        EventOrObjectType::Event(_) => Variable::Event(EventVariable(0)),
+        EventOrObjectType::Object(_) => Variable::Object(ObjectVariable(0)),
+    };
+    // Name, subtree and full tree
+    let mut trees: Vec<(String, BindingBoxTree, BindingBoxTree)> = Vec::new();
+    if options.count_constraints {
+        for cc in discover_count_constraints_for_supporting_instances(
+            ocel,
+            options.cover_fraction,
+            labeled.good.iter(),
+            ocel_type,
+        ) {
+            let child_name = format!("L{}", trees.len());
+            trees.push((
+                cc.get_constraint_name(),
+                cc.to_subtree(child_name, variable.to_inner(), 2),
+                cc.get_full_tree(),
+            ));
+        }
+    }
+    if let (true, EventOrObjectType::Object(object_type)) =
+        (options.eventually_follows_constraints, ocel_type)
+    {
+        for ef in discover_ef_constraints_for_supporting_instances(
+            ocel,
+            options.cover_fraction,
+            labeled.good.iter().flat_map(|i| match i {
+                EventOrObjectIndex::Object(oi) => Some(oi),
+                EventOrObjectIndex::Event(_) => None,
+            }),
+            object_type,
+            &options.sampling,
+        ) {
+            let child_name = format!("L{}", trees.len());
+            trees.push((
+                ef.get_constraint_name(),
+                ef.to_subtree(
+                    child_name,
+                    variable.to_inner(),
+                    2,
+                    3,
+                    variable.to_inner() + 1,
+                ),
+                ef.get_full_tree(),
+            ));
+        }
+    }
+    if options.attribute_constraints {
+        for ac in discover_attribute_constraints_for_supporting_instances(
+            ocel,
+            options.cover_fraction,
+            &labeled.good,
+            ocel_type,
+        ) {
+            trees.push((
+                ac.get_constraint_name(),
+                ac.to_subtree(),
+                ac.get_full_tree(),
+            ));
+        }
+    }
+    let good_bindings = labeled.good.iter().map(to_binding).collect_vec();
+    let bad_bindings = labeled.bad.iter().map(to_binding).collect_vec();
+    trees
+        .into_iter()
+        .map(|(name, subtree, tree)| Candidate {
+            sat_good: label_bindings(ocel, &good_bindings, &subtree),
+            sat_bad: label_bindings(ocel, &bad_bindings, &subtree),
+            name,
+            subtree,
+            tree,
+        })
+        .collect()
+}
+
+/// OR and AND combinations of two of the best `max_candidates` candidates (by F1 score)
+///
+/// `single_results` are the results of the single candidates (in the same order).
+fn get_combinations(
+    candidates: &[Candidate],
+    single_results: &[LabeledConstraintCandidate],
+    ocel_type: &EventOrObjectType,
+    max_candidates: usize,
+    num_good: usize,
+    num_bad: usize,
+) -> Vec<LabeledConstraintCandidate> {
+    let variable = match ocel_type {
+        EventOrObjectType::Event(_) => Variable::Event(EventVariable(0)),
+        EventOrObjectType::Object(_) => Variable::Object(ObjectVariable(0)),
+    };
+    let best = single_results
+        .iter()
+        .enumerate()
+        .sorted_by(|(_, a), (_, b)| b.f1_score.total_cmp(&a.f1_score))
+        .take(max_candidates)
+        .map(|(i, _)| i)
+        .sorted()
+        .collect_vec();
+    let subtrees = candidates.iter().map(|c| c.subtree.clone()).collect_vec();
+    let names = candidates.iter().map(|c| c.name.clone()).collect_vec();
+    let mut ret = Vec::new();
+    for (i, j) in best.iter().tuple_combinations() {
+        let (a, b) = (&candidates[*i], &candidates[*j]);
+        for is_or in [true, false] {
+            let children = vec![TreeCombination::Subtree(*i), TreeCombination::Subtree(*j)];
+            let combination = if is_or {
+                TreeCombination::OR(children)
+            } else {
+                TreeCombination::AND(children)
+            };
+            let combine = |x: &[bool], y: &[bool]| {
+                x.iter()
+                    .zip(y)
+                    .map(|(x, y)| if is_or { *x || *y } else { *x && *y })
+                    .collect_vec()
+            };
+            ret.push(get_result(
+                combination.get_name(&names),
+                combination.to_tree(&subtrees, ocel_type, &variable),
+                &combine(&a.sat_good, &b.sat_good),
+                &combine(&a.sat_bad, &b.sat_bad),
+                num_good,
+                num_bad,
+            ));
+        }
+    }
+    ret
+}
+
+/// Discover constraints (count, EF, attribute and OR/AND combinations) separating the given good and bad events/objects
+///
+/// Candidates are discovered on the good instances of each labeled type, and then evaluated on all labeled instances.
+pub fn discover_constraints_from_labels(
+    ocel: &IndexLinkedOCEL,
+    options: LabeledDiscoveryRequest,
+) -> LabeledDiscoveryResponse {
+    let mut unknown_ids = Vec::new();
+    let mut labeled_per_type: HashMap<EventOrObjectType, LabeledInstances> = HashMap::new();
+    for (id, is_good) in options
+        .good_ids
+        .iter()
+        .map(|id| (id, true))
+        .chain(options.bad_ids.iter().map(|id| (id, false)))
+    {
+        match get_index_and_type(ocel, id) {
+            Some((index, ocel_type)) => {
+                let labeled = labeled_per_type.entry(ocel_type).or_default();
+                if is_good {
+                    labeled.good.push(index);
+                } else {
+                    labeled.bad.push(index);
+                }
+            }
+            None => unknown_ids.push(id.clone()),
+        }
+    }
+    let num_good = labeled_per_type.values().map(|l| l.good.len()).sum();
+    let num_bad = labeled_per_type.values().map(|l| l.bad.len()).sum();
+
+    let mut results = Vec::new();
+    for (ocel_type, labeled) in labeled_per_type
+        .iter()
+        .sorted_by_key(|(t, _)| t.inner().clone())
+    {
+        if labeled.good.is_empty() {
+            continue;
+        }
+        let candidates = get_candidates_for_type(ocel, ocel_type, labeled, &options);
+        let single_results = candidates
+            .iter()
+            .map(|c| c.to_result(num_good, num_bad))
+            .collect_vec();
+        let combinations = if options.or_constraints {
+            get_combinations(
+                &candidates,
+                &single_results,
+                ocel_type,
+                options.max_combination_candidates,
+                num_good,
+                num_bad,
+            )
+        } else {
+            Vec::new()
+        };
+        results.extend(single_results);
+        results.extend(combinations);
+    }
+    // Stable sort: Ties are resolved by recall, then by order of discovery (single constraints first)
+    results.sort_by(|a, b| {
+        b.f1_score
+            .total_cmp(&a.f1_score)
+            .then(b.recall.total_cmp(&a.recall))
+    });
+    results.truncate(options.max_results);
+    LabeledDiscoveryResponse {
+        candidates: results,
+        unknown_ids,
+    }
+}
+
+#[test]
+fn separates_good_and_bad_objects() {
+    // Good orders are paid, bad orders are not
+    let orders = (0..10)
+        .map(|i| (format!("o{i}"), format!("place{i}"), format!("pay{i}")))
+        .collect_vec();
+    let mut events = Vec::new();
+    for (i, (ob, place, pay)) in orders.iter().enumerate() {
+        let t = 100 * i as i64;
+        events.push((
+            place.as_str(),
+            "place order",
+            t,
+            vec![(ob.as_str(), "order")],
+        ));
+        if i < 5 {
+            events.push((
+                pay.as_str(),
+                "pay order",
+                t + 10,
+                vec![(ob.as_str(), "order")],
+            ));
+        }
+    }
+    let objects = orders
+        .iter()
+        .map(|(ob, _, _)| (ob.as_str(), "order", vec![]))
+        .collect_vec();
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(events, objects));
+
+    let options: LabeledDiscoveryRequest = serde_json::from_value(serde_json::json!({
+        "goodIds": ["o0", "o1", "o2", "o3", "o4"],
+        "badIds": ["o5", "o6", "o7", "o8", "o9", "unknown"],
+    }))
+    .unwrap();
+    let res = discover_constraints_from_labels(&ocel, options);
+    assert_eq!(res.unknown_ids, vec!["unknown".to_string()]);
+    assert_eq!(res.candidates[0].f1_score, 1.0);
+    let paid = res
+        .candidates
+        .iter()
+        .find(|c| c.name == "=1 'pay order' per 'order'")
+        .unwrap();
+    assert_eq!(paid.f1_score, 1.0);
+    assert_eq!(
+        (
+            paid.true_positives,
+            paid.false_positives,
+            paid.false_negatives,
+            paid.true_negatives
+        ),
+        (5, 0, 0, 5)
+    );
+    // Satisfied by all labeled orders
+    let placed = res
+        .candidates
+        .iter()
+        .find(|c| c.name == "=1 'place order' per 'order'")
+        .unwrap();
+    assert_eq!((placed.precision, placed.recall), (0.5, 1.0));
+    // The results are sorted by F1 score
+    assert!(res
+        .candidates
+        .windows(2)
+        .all(|w| w[0].f1_score >= w[1].f1_score));
+}
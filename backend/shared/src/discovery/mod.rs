@@ -1,25 +1,182 @@
 use core::f32;
 use std::collections::HashMap;
 
+use chrono::{DateTime, FixedOffset};
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+
 use advanced::EventOrObjectType;
+use attribute_discovery::{discover_attribute_constraints, discover_conditioned_count_constraints};
+use combination_search::{discover_combined_constraints, CombinationSearchOptions};
 use graph_discovery::{
-    discover_count_constraints, discover_ef_constraints, discover_or_constraints_new,
+    discover_count_constraints, discover_ef_constraints, discover_o2o_path_ef_constraints,
+    discover_or_constraints_new,
 };
 use itertools::Itertools;
+use negative_discovery::{
+    discover_negative_object_constraints, discover_negative_qualifier_constraints,
+};
+use quality::{rank_discovered_constraints, ConstraintRankingOptions, DiscoveredConstraintMetrics};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{binding_box::BindingBoxTree, preprocessing::linked_ocel::IndexLinkedOCEL};
+use crate::{
+    binding_box::BindingBoxTree,
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
 
 // use self::evaluation::{get_count_constraint_fraction, get_ef_constraint_fraction};
 
 pub mod advanced;
+pub mod attribute_discovery;
+pub mod combination_search;
+pub mod drift;
 pub mod evaluation;
 pub mod graph_discovery;
+pub mod labeled_discovery;
+pub mod negative_discovery;
+pub mod quality;
 
 pub static SAMPLE_MIN_NUM_INSTANCES: usize = 3000;
 pub static SAMPLE_FRAC: f32 = 0.1;
 pub static RNG_SEED: u64 = 13375050;
+pub static NUM_TIME_STRATA: usize = 10;
+pub static SAMPLE_MIN_NUM_OBJECT_EVENTS: usize = 1000;
+/// Default number of best single constraints (per type) used as candidates for OR/AND combinations
+pub static MAX_COMBINATION_CANDIDATES: usize = 30;
+
+fn default_sample_min_num_instances() -> usize {
+    SAMPLE_MIN_NUM_INSTANCES
+}
+fn default_sample_frac() -> f32 {
+    SAMPLE_FRAC
+}
+fn default_rng_seed() -> u64 {
+    RNG_SEED
+}
+fn default_num_time_strata() -> usize {
+    NUM_TIME_STRATA
+}
+fn default_sample_min_num_object_events() -> usize {
+    SAMPLE_MIN_NUM_OBJECT_EVENTS
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SamplingStrategy {
+    /// Uniformly sample from all instances of a type
+    #[default]
+    Random,
+    /// Sample the same fraction from each of `num_time_strata` consecutive time windows
+    /// (by event timestamp, or timestamp of the first event for objects)
+    StratifiedByTime,
+}
+
+/// Sampling of events/objects during constraint discovery
+///
+/// Instances are always sampled separately per event/object type.
+/// Discovery with the same options (including the seed) on the same OCEL yields the same results.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingOptions {
+    /// Only types with at least this many instances are sampled (otherwise, all instances are used)
+    #[serde(default = "default_sample_min_num_instances")]
+    pub min_num_instances: usize,
+    /// Fraction of instances to sample
+    #[serde(default = "default_sample_frac")]
+    pub sample_fraction: f32,
+    #[serde(default = "default_rng_seed")]
+    pub seed: u64,
+    #[serde(default)]
+    pub strategy: SamplingStrategy,
+    /// Number of time windows for [`SamplingStrategy::StratifiedByTime`]
+    #[serde(default = "default_num_time_strata")]
+    pub num_time_strata: usize,
+    /// Only the events of objects with at least this many events are sampled for EF constraints
+    /// (using `sample_fraction`; otherwise, all events of an object are used)
+    #[serde(default = "default_sample_min_num_object_events")]
+    pub min_num_object_events: usize,
+}
+
+impl Default for SamplingOptions {
+    fn default() -> Self {
+        Self {
+            min_num_instances: SAMPLE_MIN_NUM_INSTANCES,
+            sample_fraction: SAMPLE_FRAC,
+            seed: RNG_SEED,
+            strategy: SamplingStrategy::default(),
+            num_time_strata: NUM_TIME_STRATA,
+            min_num_object_events: SAMPLE_MIN_NUM_OBJECT_EVENTS,
+        }
+    }
+}
+
+impl SamplingOptions {
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// Sample from the instances of one event/object type
+    pub fn sample_instances(
+        &self,
+        ocel: &IndexLinkedOCEL,
+        instances: Vec<EventOrObjectIndex>,
+    ) -> Vec<EventOrObjectIndex> {
+        if instances.len() < self.min_num_instances {
+            return instances;
+        }
+        let mut rng = self.rng();
+        match self.strategy {
+            SamplingStrategy::Random => {
+                let sample_count = (instances.len() as f32 * self.sample_fraction).ceil() as usize;
+                instances
+                    .into_iter()
+                    .choose_multiple(&mut rng, sample_count)
+            }
+            SamplingStrategy::StratifiedByTime => {
+                let sorted = instances
+                    .into_iter()
+                    .sorted_by_cached_key(|i| get_instance_time(ocel, i))
+                    .collect_vec();
+                let stratum_size = sorted.len().div_ceil(self.num_time_strata.max(1)).max(1);
+                sorted
+                    .chunks(stratum_size)
+                    .flat_map(|stratum| {
+                        let sample_count =
+                            (stratum.len() as f32 * self.sample_fraction).ceil() as usize;
+                        stratum
+                            .iter()
+                            .copied()
+                            .choose_multiple(&mut rng, sample_count)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Sample from the events of one object (for EF constraints)
+    pub fn sample_object_events<T>(&self, rng: &mut StdRng, evs: Vec<T>) -> Vec<T> {
+        if evs.len() < self.min_num_object_events {
+            return evs;
+        }
+        let sample_count = (evs.len() as f32 * self.sample_fraction).ceil() as usize;
+        evs.into_iter().choose_multiple(rng, sample_count)
+    }
+}
+
+/// Timestamp of an event, or of the first event of an object
+pub(crate) fn get_instance_time(
+    ocel: &IndexLinkedOCEL,
+    index: &EventOrObjectIndex,
+) -> Option<DateTime<FixedOffset>> {
+    match index {
+        EventOrObjectIndex::Event(ev_index) => ocel.ev_by_index(ev_index).map(|ev| ev.time),
+        EventOrObjectIndex::Object(ob_index) => ocel
+            .events_of_object(ob_index)
+            .flat_map(|ev_index| ocel.ev_by_index(ev_index))
+            .map(|ev| ev.time)
+            .min(),
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +191,9 @@ pub struct CountConstraintOptions {
 pub struct EventuallyFollowsConstraintOptions {
     pub object_types: Vec<String>,
     pub cover_fraction: f32,
+    /// Also discover EF constraints to events of objects related through O2O paths of up to this length (0: disabled)
+    #[serde(default)]
+    pub max_o2o_path_length: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,6 +202,55 @@ pub struct ORConstraintOptions {
     pub object_types: Vec<String>,
     pub event_types: Vec<String>,
     pub cover_fraction: f32,
+    /// Multipliers of `cover_fraction` used for discovering candidate count constraints
+    #[serde(default = "default_or_count_cover_multipliers")]
+    pub count_cover_multipliers: Vec<f32>,
+}
+
+fn default_or_count_cover_multipliers() -> Vec<f32> {
+    vec![0.11, 0.7, 1.0]
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeConstraintOptions {
+    pub object_types: Vec<String>,
+    pub event_types: Vec<String>,
+    pub cover_fraction: f32,
+    /// Also discover count constraints which only hold for a specific value of an attribute
+    #[serde(default)]
+    pub conditioned_count_constraints: bool,
+}
+
+/// Options for discovering prohibitions
+///
+/// For object types: event types never co-occurring and event types never occurring after another.
+/// For event types: qualifiers never used for related objects.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NegativeConstraintOptions {
+    pub object_types: Vec<String>,
+    pub event_types: Vec<String>,
+    pub cover_fraction: f32,
+}
+
+pub(crate) fn default_max_combination_candidates() -> usize {
+    MAX_COMBINATION_CANDIDATES
+}
+
+/// Options for discovering (nested) OR/AND combinations of count and EF constraints
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CombinationConstraintOptions {
+    pub object_types: Vec<String>,
+    pub event_types: Vec<String>,
+    /// Cover fraction of the single count/EF constraints used as candidates for combinations
+    pub cover_fraction: f32,
+    /// Maximal number of candidate constraints per type
+    #[serde(default = "default_max_combination_candidates")]
+    pub max_candidates: usize,
+    #[serde(default)]
+    pub search: CombinationSearchOptions,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -50,11 +259,25 @@ pub struct AutoDiscoverConstraintsRequest {
     pub count_constraints: Option<CountConstraintOptions>,
     pub eventually_follows_constraints: Option<EventuallyFollowsConstraintOptions>,
     pub or_constraints: Option<ORConstraintOptions>,
+    #[serde(default)]
+    pub attribute_constraints: Option<AttributeConstraintOptions>,
+    #[serde(default)]
+    pub negative_constraints: Option<NegativeConstraintOptions>,
+    #[serde(default)]
+    pub combination_constraints: Option<CombinationConstraintOptions>,
+    /// Ranking and deduplication of the discovered constraints (default: order of discovery, no deduplication and no metrics)
+    #[serde(default)]
+    pub ranking: Option<ConstraintRankingOptions>,
+    #[serde(default)]
+    pub sampling: SamplingOptions,
 }
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AutoDiscoverConstraintsResponse {
-    pub constraints: Vec<(String, BindingBoxTree)>,
+    /// Name, tree and quality metrics (on the full OCEL) of each discovered constraint
+    ///
+    /// Metrics are only computed if `ranking` is set in the request (as this evaluates all constraints).
+    pub constraints: Vec<(String, BindingBoxTree, Option<DiscoveredConstraintMetrics>)>,
 }
 
 pub fn auto_discover_constraints_with_options(
@@ -62,18 +285,31 @@ pub fn auto_discover_constraints_with_options(
     options: AutoDiscoverConstraintsRequest,
 ) -> AutoDiscoverConstraintsResponse {
     let mut trees_per_type: HashMap<EventOrObjectType, Vec<BindingBoxTree>> = HashMap::new();
-    let mut ret = AutoDiscoverConstraintsResponse {
-        constraints: Vec::new(),
-    };
+    let mut constraints: Vec<(String, BindingBoxTree)> = Vec::new();
     if let Some(eventually_follows_options) = options.eventually_follows_constraints {
         for ot in &eventually_follows_options.object_types {
-            for c in discover_ef_constraints(ocel, eventually_follows_options.cover_fraction, ot) {
-                ret.constraints
-                    .push((c.get_constraint_name(), c.get_full_tree()));
+            for c in discover_ef_constraints(
+                ocel,
+                eventually_follows_options.cover_fraction,
+                ot,
+                &options.sampling,
+            ) {
+                constraints.push((c.get_constraint_name(), c.get_full_tree()));
                 trees_per_type
                     .entry(EventOrObjectType::Object(ot.clone()))
                     .or_default()
-                    .push(c.to_subtree("X".to_string(), 0, 2, 3))
+                    .push(c.to_subtree("X".to_string(), 0, 2, 3, 1))
+            }
+            if eventually_follows_options.max_o2o_path_length > 0 {
+                for c in discover_o2o_path_ef_constraints(
+                    ocel,
+                    eventually_follows_options.cover_fraction,
+                    ot,
+                    eventually_follows_options.max_o2o_path_length,
+                    &options.sampling,
+                ) {
+                    constraints.push((c.get_constraint_name(), c.get_full_tree()));
+                }
             }
         }
     };
@@ -91,9 +327,13 @@ pub fn auto_discover_constraints_with_options(
                 .map(|et| EventOrObjectType::Event(et.clone())),
         );
         for t in types {
-            for cc in discover_count_constraints(ocel, count_opts.cover_fraction, t.clone()) {
-                ret.constraints
-                    .push((cc.get_constraint_name(), cc.get_full_tree()));
+            for cc in discover_count_constraints(
+                ocel,
+                count_opts.cover_fraction,
+                t.clone(),
+                &options.sampling,
+            ) {
+                constraints.push((cc.get_constraint_name(), cc.get_full_tree()));
 
                 trees_per_type
                     .entry(t.clone())
@@ -102,24 +342,177 @@ pub fn auto_discover_constraints_with_options(
             }
         }
     }
+    if let Some(attr_opts) = &options.attribute_constraints {
+        let types = attr_opts
+            .object_types
+            .iter()
+            .map(|ot| EventOrObjectType::Object(ot.clone()))
+            .chain(
+                attr_opts
+                    .event_types
+                    .iter()
+                    .map(|et| EventOrObjectType::Event(et.clone())),
+            );
+        for t in types {
+            for c in discover_attribute_constraints(
+                ocel,
+                attr_opts.cover_fraction,
+                &t,
+                &options.sampling,
+            ) {
+                constraints.push((c.get_constraint_name(), c.get_full_tree()));
+            }
+            if attr_opts.conditioned_count_constraints {
+                for c in discover_conditioned_count_constraints(
+                    ocel,
+                    attr_opts.cover_fraction,
+                    &t,
+                    &options.sampling,
+                ) {
+                    constraints.push((c.get_constraint_name(), c.get_full_tree()));
+                }
+            }
+        }
+    }
+    if let Some(negative_opts) = &options.negative_constraints {
+        for ot in &negative_opts.object_types {
+            for c in discover_negative_object_constraints(
+                ocel,
+                negative_opts.cover_fraction,
+                ot,
+                &options.sampling,
+            ) {
+                constraints.push((c.get_constraint_name(), c.get_full_tree()));
+            }
+        }
+        for et in &negative_opts.event_types {
+            for c in discover_negative_qualifier_constraints(
+                ocel,
+                negative_opts.cover_fraction,
+                et,
+                &options.sampling,
+            ) {
+                constraints.push((c.get_constraint_name(), c.get_full_tree()));
+            }
+        }
+    }
+    if let Some(combination_opts) = &options.combination_constraints {
+        let types = combination_opts
+            .object_types
+            .iter()
+            .map(|ot| EventOrObjectType::Object(ot.clone()))
+            .chain(
+                combination_opts
+                    .event_types
+                    .iter()
+                    .map(|et| EventOrObjectType::Event(et.clone())),
+            );
+        for t in types {
+            constraints.extend(discover_combined_constraints(
+                ocel,
+                &t,
+                combination_opts.cover_fraction,
+                combination_opts.max_candidates,
+                &combination_opts.search,
+                &options.sampling,
+            ));
+        }
+    }
     if let Some(or_constraint_option) = options.or_constraints {
         for ot in &or_constraint_option.object_types {
             let ocel_type = EventOrObjectType::Object(ot.clone());
-            ret.constraints.extend(discover_or_constraints_new(
+            constraints.extend(discover_or_constraints_new(
                 ocel,
                 &ocel_type,
                 or_constraint_option.cover_fraction,
+                &or_constraint_option.count_cover_multipliers,
+                &options.sampling,
             ));
         }
         for et in &or_constraint_option.event_types {
             let ocel_type = EventOrObjectType::Event(et.clone());
-            ret.constraints.extend(discover_or_constraints_new(
+            constraints.extend(discover_or_constraints_new(
                 ocel,
                 &ocel_type,
                 or_constraint_option.cover_fraction,
+                &or_constraint_option.count_cover_multipliers,
+                &options.sampling,
             ));
         }
     }
 
-    ret
+    let constraints = match options.ranking {
+        Some(ranking) => rank_discovered_constraints(ocel, constraints, &ranking)
+            .into_iter()
+            .map(|(name, tree, metrics)| (name, tree, Some(metrics)))
+            .collect(),
+        None => constraints
+            .into_iter()
+            .map(|(name, tree)| (name, tree, None))
+            .collect(),
+    };
+    AutoDiscoverConstraintsResponse { constraints }
+}
+
+#[test]
+fn discovery_with_the_same_seed_is_deterministic() {
+    // Orders with many events, so that both the orders and their events are sampled
+    let ids = (0..20)
+        .map(|i| {
+            let evs = (0..40).map(|j| format!("e{i}-{j}")).collect_vec();
+            (format!("o{i}"), evs)
+        })
+        .collect_vec();
+    let events = ids
+        .iter()
+        .flat_map(|(ob, evs)| {
+            evs.iter().enumerate().map(move |(j, ev)| {
+                let ev_type = if j % 2 == 0 {
+                    "place order"
+                } else {
+                    "pay order"
+                };
+                (
+                    ev.as_str(),
+                    ev_type,
+                    (j * j % 97) as i64,
+                    vec![(ob.as_str(), "order")],
+                )
+            })
+        })
+        .collect();
+    let objects = ids
+        .iter()
+        .map(|(ob, _)| (ob.as_str(), "order", vec![]))
+        .collect();
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(events, objects));
+
+    for strategy in [SamplingStrategy::Random, SamplingStrategy::StratifiedByTime] {
+        let sampling = SamplingOptions {
+            min_num_instances: 10,
+            sample_fraction: 0.5,
+            strategy,
+            num_time_strata: 2,
+            min_num_object_events: 10,
+            ..Default::default()
+        };
+        let ot = EventOrObjectType::Object("order".to_string());
+        let instances = graph_discovery::get_instances(&ocel, &ot, &sampling);
+        assert_eq!(instances.len(), 10);
+        assert_eq!(
+            instances,
+            graph_discovery::get_instances(&ocel, &ot, &sampling)
+        );
+
+        let discover = || {
+            discover_ef_constraints(&ocel, 0.5, &"order".to_string(), &sampling)
+                .iter()
+                .map(|c| c.get_constraint_name())
+                .sorted()
+                .collect_vec()
+        };
+        let names = discover();
+        assert!(!names.is_empty());
+        assert_eq!(names, discover());
+    }
 }
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset};
+use itertools::Itertools;
+
+use crate::{
+    binding_box::{
+        structs::{BindingBoxTreeNode, Constraint, EventVariable, Filter, ObjectVariable},
+        BindingBox, BindingBoxTree,
+    },
+    preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL},
+};
+
+use super::{advanced::EventOrObjectType, graph_discovery::get_instances, SamplingOptions};
+
+/// Minimal number of (sampled) instances a negative constraint needs to apply to
+const MIN_NEGATIVE_SUPPORT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NegativeConstraintKind {
+    /// No object of `object_type` is related to events of both event types
+    NeverCoOccur {
+        object_type: String,
+        ev_type_a: String,
+        ev_type_b: String,
+    },
+    /// No event of `event_type` is related to an object of `object_type` with `qualifier`
+    /// (although other event types use this qualifier for objects of `object_type`)
+    NeverQualifier {
+        event_type: String,
+        object_type: String,
+        qualifier: String,
+    },
+    /// No event of `ev_type` occurs (at the same time or) after an event of `after_ev_type` of the same object
+    NeverAfter {
+        object_type: String,
+        ev_type: String,
+        after_ev_type: String,
+    },
+}
+
+/// A prohibition, i.e., a pattern which (almost) never occurs
+#[derive(Debug, Clone)]
+pub struct NegativeConstraint {
+    pub kind: NegativeConstraintKind,
+    /// Number of (sampled) instances the prohibition applies to
+    /// (e.g., objects with at least one of the two event types for [`NegativeConstraintKind::NeverCoOccur`])
+    pub support: usize,
+    /// Fraction of the supporting instances satisfying the prohibition
+    pub confidence: f32,
+}
+
+impl NegativeConstraint {
+    pub fn get_constraint_name(&self) -> String {
+        let name = match &self.kind {
+            NegativeConstraintKind::NeverCoOccur {
+                object_type,
+                ev_type_a,
+                ev_type_b,
+            } => format!("No '{ev_type_a}' together with '{ev_type_b}' per '{object_type}'"),
+            NegativeConstraintKind::NeverQualifier {
+                event_type,
+                object_type,
+                qualifier,
+            } => format!("No '{object_type}' as '{qualifier}' per '{event_type}'"),
+            NegativeConstraintKind::NeverAfter {
+                object_type,
+                ev_type,
+                after_ev_type,
+            } => format!("No '{ev_type}' after '{after_ev_type}' per '{object_type}'"),
+        };
+        format!("{name} (support: {})", self.support)
+    }
+
+    pub fn get_full_tree(&self) -> BindingBoxTree {
+        let mut root = BindingBox::default();
+        let mut child = BindingBox::default();
+        match &self.kind {
+            NegativeConstraintKind::NeverCoOccur {
+                object_type,
+                ev_type_a,
+                ev_type_b,
+            } => {
+                root.new_object_vars.insert(
+                    ObjectVariable(0),
+                    vec![object_type.clone()].into_iter().collect(),
+                );
+                child.new_event_vars.insert(
+                    EventVariable(0),
+                    vec![ev_type_a.clone()].into_iter().collect(),
+                );
+                child.new_event_vars.insert(
+                    EventVariable(1),
+                    vec![ev_type_b.clone()].into_iter().collect(),
+                );
+                child.filters.push(o2e_filter(0, 0, None));
+                child.filters.push(o2e_filter(0, 1, None));
+            }
+            NegativeConstraintKind::NeverQualifier {
+                event_type,
+                object_type,
+                qualifier,
+            } => {
+                root.new_event_vars.insert(
+                    EventVariable(0),
+                    vec![event_type.clone()].into_iter().collect(),
+                );
+                child.new_object_vars.insert(
+                    ObjectVariable(0),
+                    vec![object_type.clone()].into_iter().collect(),
+                );
+                child
+                    .filters
+                    .push(o2e_filter(0, 0, Some(qualifier.clone())));
+            }
+            NegativeConstraintKind::NeverAfter {
+                object_type,
+                ev_type,
+                after_ev_type,
+            } => {
+                root.new_object_vars.insert(
+                    ObjectVariable(0),
+                    vec![object_type.clone()].into_iter().collect(),
+                );
+                child.new_event_vars.insert(
+                    EventVariable(0),
+                    vec![after_ev_type.clone()].into_iter().collect(),
+                );
+                child.new_event_vars.insert(
+                    EventVariable(1),
+                    vec![ev_type.clone()].into_iter().collect(),
+                );
+                child.filters.push(o2e_filter(0, 0, None));
+                child.filters.push(o2e_filter(0, 1, None));
+                child.filters.push(Filter::TimeBetweenEvents {
+                    from_event: EventVariable(0),
+                    to_event: EventVariable(1),
+                    min_seconds: Some(0.0),
+                    max_seconds: None,
+                });
+            }
+        }
+        let child_name = "A".to_string();
+        root.constraints.push(Constraint::NOT {
+            child_names: vec![child_name.clone()],
+        });
+        BindingBoxTree {
+            nodes: vec![
+                BindingBoxTreeNode::Box(root, vec![1]),
+                BindingBoxTreeNode::Box(child, vec![]),
+            ],
+            edge_names: vec![((0, 1), child_name)].into_iter().collect(),
+        }
+    }
+}
+
+fn o2e_filter(object: usize, event: usize, qualifier: Option<String>) -> Filter {
+    Filter::O2E {
+        object: ObjectVariable(object),
+        event: EventVariable(event),
+        qualifier,
+        filter_label: None,
+    }
+}
+
+/// Discover event types which never co-occur for an object, and event types which never occur after another one
+///
+/// Only prohibitions satisfied by at least a `coverage` fraction of the (sampled) objects they apply to are returned.
+/// For co-occurrences, this needs to hold for the objects of each of the two event types.
+pub fn discover_negative_object_constraints(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    object_type: &String,
+    sampling: &SamplingOptions,
+) -> Vec<NegativeConstraint> {
+    let instances = get_instances(
+        ocel,
+        &EventOrObjectType::Object(object_type.clone()),
+        sampling,
+    );
+    // Number of objects with events of the type
+    let mut num_with_type: HashMap<&String, usize> = HashMap::new();
+    // Number of objects with events of both types (a < b)
+    let mut num_with_both: HashMap<(&String, &String), usize> = HashMap::new();
+    // Number of objects with an event of type a at or after an event of type b
+    let mut num_with_after: HashMap<(&String, &String), usize> = HashMap::new();
+    for index in &instances {
+        let EventOrObjectIndex::Object(ob_index) = index else {
+            continue;
+        };
+        let mut time_range_per_type: HashMap<
+            &String,
+            (DateTime<FixedOffset>, DateTime<FixedOffset>),
+        > = HashMap::new();
+        for ev in ocel
+            .events_of_object(ob_index)
+            .flat_map(|ev_index| ocel.ev_by_index(ev_index))
+        {
+            time_range_per_type
+                .entry(&ev.event_type)
+                .and_modify(|(min, max)| {
+                    *min = (*min).min(ev.time);
+                    *max = (*max).max(ev.time);
+                })
+                .or_insert((ev.time, ev.time));
+        }
+        for (a, (_, a_max)) in &time_range_per_type {
+            *num_with_type.entry(*a).or_default() += 1;
+            for (b, (b_min, _)) in &time_range_per_type {
+                if a < b {
+                    *num_with_both.entry((*a, *b)).or_default() += 1;
+                }
+                if a != b && a_max >= b_min {
+                    *num_with_after.entry((*a, *b)).or_default() += 1;
+                }
+            }
+        }
+    }
+    let ev_types = num_with_type
+        .iter()
+        .filter(|(_, n)| **n >= MIN_NEGATIVE_SUPPORT)
+        .map(|(t, _)| *t)
+        .sorted()
+        .collect_vec();
+    let mut ret = Vec::new();
+    for (a, b) in ev_types.iter().tuple_combinations() {
+        let num_a = num_with_type[a];
+        let num_b = num_with_type[b];
+        let num_both = num_with_both.get(&(*a, *b)).copied().unwrap_or_default();
+        let conf_a = 1.0 - num_both as f32 / num_a as f32;
+        let conf_b = 1.0 - num_both as f32 / num_b as f32;
+        if conf_a >= coverage && conf_b >= coverage {
+            let support = num_a + num_b - num_both;
+            ret.push(NegativeConstraint {
+                kind: NegativeConstraintKind::NeverCoOccur {
+                    object_type: object_type.clone(),
+                    ev_type_a: (*a).clone(),
+                    ev_type_b: (*b).clone(),
+                },
+                support,
+                confidence: 1.0 - num_both as f32 / support as f32,
+            });
+        }
+    }
+    for (a, b) in ev_types.iter().cartesian_product(ev_types.iter()) {
+        if a == b {
+            continue;
+        }
+        let (first, second) = if a < b { (*a, *b) } else { (*b, *a) };
+        let num_both = num_with_both
+            .get(&(first, second))
+            .copied()
+            .unwrap_or_default();
+        if num_both < MIN_NEGATIVE_SUPPORT {
+            continue;
+        }
+        let num_after = num_with_after.get(&(*a, *b)).copied().unwrap_or_default();
+        let confidence = 1.0 - num_after as f32 / num_both as f32;
+        if confidence >= coverage {
+            ret.push(NegativeConstraint {
+                kind: NegativeConstraintKind::NeverAfter {
+                    object_type: object_type.clone(),
+                    ev_type: (*a).clone(),
+                    after_ev_type: (*b).clone(),
+                },
+                support: num_both,
+                confidence,
+            });
+        }
+    }
+    ret
+}
+
+/// Discover E2O qualifiers which are used for an object type, but never by events of the given type
+///
+/// Only prohibitions satisfied by at least a `coverage` fraction of the (sampled) events related to objects of the object type are returned.
+pub fn discover_negative_qualifier_constraints(
+    ocel: &IndexLinkedOCEL,
+    coverage: f32,
+    event_type: &String,
+    sampling: &SamplingOptions,
+) -> Vec<NegativeConstraint> {
+    // Qualifiers used (by any event type) per object type
+    let mut qualifiers_per_ob_type: HashMap<&String, HashSet<&str>> = HashMap::new();
+    for ev_index in ocel.events_of_type.values().flatten() {
+        for (ob_index, qualifier) in ocel.get_rels(&EventOrObjectIndex::Event(*ev_index)) {
+            if let Some(ob) = ocel.ob_by_index(&ob_index) {
+                qualifiers_per_ob_type
+                    .entry(&ob.object_type)
+                    .or_default()
+                    .insert(qualifier);
+            }
+        }
+    }
+    let instances = get_instances(
+        ocel,
+        &EventOrObjectType::Event(event_type.clone()),
+        sampling,
+    );
+    // Number of events related to some object of the type
+    let mut num_related: HashMap<&String, usize> = HashMap::new();
+    // Number of events related to some object of the type with the qualifier
+    let mut num_with_qualifier: HashMap<(&String, &str), usize> = HashMap::new();
+    for index in &instances {
+        let used: HashSet<(&String, &str)> = ocel
+            .get_rels(index)
+            .flat_map(|(ob_index, qualifier)| {
+                ocel.ob_by_index(&ob_index)
+                    .map(|ob| (&ob.object_type, qualifier))
+            })
+            .collect();
+        for ot in used.iter().map(|(ot, _)| *ot).unique() {
+            *num_related.entry(ot).or_default() += 1;
+        }
+        for key in used {
+            *num_with_qualifier.entry(key).or_default() += 1;
+        }
+    }
+    let mut ret = Vec::new();
+    for (ot, num) in num_related.into_iter().sorted() {
+        if num < MIN_NEGATIVE_SUPPORT {
+            continue;
+        }
+        for q in qualifiers_per_ob_type
+            .get(ot)
+            .into_iter()
+            .flatten()
+            .sorted()
+        {
+            let num_q = num_with_qualifier
+                .get(&(ot, *q))
+                .copied()
+                .unwrap_or_default();
+            let confidence = 1.0 - num_q as f32 / num as f32;
+            if confidence >= coverage {
+                ret.push(NegativeConstraint {
+                    kind: NegativeConstraintKind::NeverQualifier {
+                        event_type: event_type.clone(),
+                        object_type: ot.clone(),
+                        qualifier: q.to_string(),
+                    },
+                    support: num,
+                    confidence,
+                });
+            }
+        }
+    }
+    ret
+}
+
+#[test]
+fn discovers_never_after_pair() {
+    let ids = (0..MIN_NEGATIVE_SUPPORT)
+        .map(|i| (format!("o{i}"), format!("place{i}"), format!("pay{i}")))
+        .collect_vec();
+    let mut events = Vec::new();
+    for (i, (ob, place, pay)) in ids.iter().enumerate() {
+        let t = 100 * i as i64;
+        events.push((
+            place.as_str(),
+            "place order",
+            t,
+            vec![(ob.as_str(), "order")],
+        ));
+        events.push((
+            pay.as_str(),
+            "pay order",
+            t + 10,
+            vec![(ob.as_str(), "order")],
+        ));
+    }
+    let objects = ids
+        .iter()
+        .map(|(ob, _, _)| (ob.as_str(), "order", vec![]))
+        .collect_vec();
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(events, objects));
+
+    let constraints = discover_negative_object_constraints(
+        &ocel,
+        1.0,
+        &"order".to_string(),
+        &SamplingOptions::default(),
+    );
+    let kinds = constraints.iter().map(|c| c.kind.clone()).collect_vec();
+    assert_eq!(
+        kinds,
+        vec![NegativeConstraintKind::NeverAfter {
+            object_type: "order".to_string(),
+            ev_type: "place order".to_string(),
+            after_ev_type: "pay order".to_string(),
+        }]
+    );
+    assert_eq!(constraints[0].support, MIN_NEGATIVE_SUPPORT);
+    assert_eq!(constraints[0].confidence, 1.0);
+    let (results, _skipped) = constraints[0].get_full_tree().evaluate(&ocel);
+    assert_eq!(results.iter().filter(|(node, _, _)| *node == 0).count(), 10);
+    assert!(results.iter().all(|(_, _, violation)| violation.is_none()));
+
+    // Both event types always co-occur, so no qualifier or co-occurrence prohibitions are found
+    assert!(discover_negative_qualifier_constraints(
+        &ocel,
+        1.0,
+        &"pay order".to_string(),
+        &SamplingOptions::default(),
+    )
+    .is_empty());
+}
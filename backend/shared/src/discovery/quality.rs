@@ -0,0 +1,293 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binding_box::{structs::BindingBoxTreeNode, Binding, BindingBoxTree},
+    preprocessing::linked_ocel::IndexLinkedOCEL,
+};
+
+/// Quality metrics of a discovered constraint
+///
+/// All metrics are computed on the full OCEL (not only on the sample used for discovery).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredConstraintMetrics {
+    /// Number of situations (i.e., bindings of the root box) satisfying the constraint
+    pub support: usize,
+    /// Total number of situations (i.e., bindings of the root box)
+    pub num_situations: usize,
+    /// Fraction of situations satisfying the constraint
+    pub confidence: f32,
+    /// Fraction of situations violating the constraint
+    pub violation_rate: f32,
+    /// Fraction of all events/objects of the root type(s) satisfying the constraint
+    ///
+    /// Lower than `confidence` if the root box filters its bindings (e.g., attribute-conditioned constraints)
+    pub coverage: f32,
+    /// Maximal overlap (Jaccard index) of the violated situations with any higher-ranked constraint on the same root type(s)
+    ///
+    /// 1.0 for constraints equivalent to a higher-ranked constraint.
+    /// Constraints without violations are not redundant to each other (0.0), as they agree on all situations.
+    pub redundancy: f32,
+    /// True if some bindings were skipped during evaluation (i.e., the metrics are incomplete)
+    pub bindings_skipped: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConstraintRankingKey {
+    /// Keep the order of discovery
+    #[default]
+    None,
+    Support,
+    Confidence,
+    Coverage,
+}
+
+fn default_max_redundancy() -> f32 {
+    0.95
+}
+
+/// Options for ranking and deduplicating discovered constraints
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintRankingOptions {
+    /// Metric to sort discovered constraints by (descending)
+    #[serde(default)]
+    pub rank_by: ConstraintRankingKey,
+    /// Remove constraints with a `redundancy` above `max_redundancy`
+    #[serde(default)]
+    pub deduplicate: bool,
+    #[serde(default = "default_max_redundancy")]
+    pub max_redundancy: f32,
+    /// Only keep constraints with at least this confidence
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
+    /// Only return the (top-ranked) first `max_constraints` constraints
+    #[serde(default)]
+    pub max_constraints: Option<usize>,
+}
+
+impl Default for ConstraintRankingOptions {
+    fn default() -> Self {
+        Self {
+            rank_by: ConstraintRankingKey::default(),
+            deduplicate: false,
+            max_redundancy: default_max_redundancy(),
+            min_confidence: None,
+            max_constraints: None,
+        }
+    }
+}
+
+/// Evaluation of a discovered constraint on the full OCEL
+struct ConstraintEvaluation {
+    name: String,
+    tree: BindingBoxTree,
+    metrics: DiscoveredConstraintMetrics,
+    /// Variables and types bound in the root box (constraints are only compared with others with the same root)
+    root_signature: Vec<String>,
+    violated: HashSet<Binding>,
+}
+
+fn get_root_signature(tree: &BindingBoxTree) -> Vec<String> {
+    let Some(BindingBoxTreeNode::Box(bbox, _)) = tree.nodes.first() else {
+        return Vec::new();
+    };
+    let ev_vars = bbox
+        .new_event_vars
+        .iter()
+        .map(|(v, types)| format!("{v:?}:{}", types.iter().sorted().join(",")));
+    let ob_vars = bbox
+        .new_object_vars
+        .iter()
+        .map(|(v, types)| format!("{v:?}:{}", types.iter().sorted().join(",")));
+    ev_vars.chain(ob_vars).sorted().collect()
+}
+
+/// Number of events/objects of the root variable's type(s), if the root box binds exactly one variable
+fn get_num_root_instances(ocel: &IndexLinkedOCEL, tree: &BindingBoxTree) -> Option<usize> {
+    let Some(BindingBoxTreeNode::Box(bbox, _)) = tree.nodes.first() else {
+        return None;
+    };
+    match (
+        bbox.new_event_vars.values().collect_vec().as_slice(),
+        bbox.new_object_vars.values().collect_vec().as_slice(),
+    ) {
+        ([ev_types], []) => Some(
+            ev_types
+                .iter()
+                .map(|et| ocel.events_of_type.get(et).map(|e| e.len()).unwrap_or(0))
+                .sum(),
+        ),
+        ([], [ob_types]) => Some(
+            ob_types
+                .iter()
+                .map(|ot| ocel.objects_of_type.get(ot).map(|o| o.len()).unwrap_or(0))
+                .sum(),
+        ),
+        _ => None,
+    }
+}
+
+fn evaluate_constraint(
+    ocel: &IndexLinkedOCEL,
+    name: String,
+    tree: BindingBoxTree,
+) -> ConstraintEvaluation {
+    let (results, bindings_skipped) = tree.evaluate(ocel);
+    let mut num_situations = 0;
+    let mut violated = HashSet::new();
+    for (node_index, binding, violation) in results {
+        if node_index == 0 {
+            num_situations += 1;
+            if violation.is_some() {
+                violated.insert(binding);
+            }
+        }
+    }
+    let support = num_situations - violated.len();
+    let confidence = if num_situations > 0 {
+        support as f32 / num_situations as f32
+    } else {
+        0.0
+    };
+    let coverage = match get_num_root_instances(ocel, &tree) {
+        Some(0) => 0.0,
+        Some(n) => support as f32 / n as f32,
+        None => confidence,
+    };
+    ConstraintEvaluation {
+        root_signature: get_root_signature(&tree),
+        name,
+        tree,
+        metrics: DiscoveredConstraintMetrics {
+            support,
+            num_situations,
+            confidence,
+            violation_rate: if num_situations > 0 {
+                1.0 - confidence
+            } else {
+                0.0
+            },
+            coverage,
+            redundancy: 0.0,
+            bindings_skipped,
+        },
+        violated,
+    }
+}
+
+fn get_redundancy(a: &ConstraintEvaluation, b: &ConstraintEvaluation) -> f32 {
+    if a.root_signature != b.root_signature {
+        return 0.0;
+    }
+    if a.name == b.name {
+        return 1.0;
+    }
+    jaccard_index(&a.violated, &b.violated)
+}
+
+/// Jaccard index of two sets (0.0 if both are empty)
+fn jaccard_index(a: &HashSet<Binding>, b: &HashSet<Binding>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// Compute quality metrics of discovered constraints on the full OCEL, and rank/deduplicate them
+pub fn rank_discovered_constraints(
+    ocel: &IndexLinkedOCEL,
+    constraints: Vec<(String, BindingBoxTree)>,
+    options: &ConstraintRankingOptions,
+) -> Vec<(String, BindingBoxTree, DiscoveredConstraintMetrics)> {
+    let mut evaluations: Vec<ConstraintEvaluation> = constraints
+        .into_par_iter()
+        .map(|(name, tree)| evaluate_constraint(ocel, name, tree))
+        .collect();
+    if let Some(min_confidence) = options.min_confidence {
+        evaluations.retain(|e| e.metrics.confidence >= min_confidence);
+    }
+    let metric_of = |e: &ConstraintEvaluation| match options.rank_by {
+        ConstraintRankingKey::None => 0.0,
+        ConstraintRankingKey::Support => e.metrics.support as f32,
+        ConstraintRankingKey::Confidence => e.metrics.confidence,
+        ConstraintRankingKey::Coverage => e.metrics.coverage,
+    };
+    // Stable sort, so the order of discovery is kept for ties
+    evaluations.sort_by(|a, b| metric_of(b).total_cmp(&metric_of(a)));
+
+    let mut ret: Vec<ConstraintEvaluation> = Vec::new();
+    for mut e in evaluations {
+        e.metrics.redundancy = ret
+            .iter()
+            .map(|prev| get_redundancy(&e, prev))
+            .fold(0.0, f32::max);
+        if options.deduplicate && e.metrics.redundancy > options.max_redundancy {
+            continue;
+        }
+        ret.push(e);
+        if options.max_constraints.is_some_and(|max| ret.len() >= max) {
+            break;
+        }
+    }
+    ret.into_iter()
+        .map(|e| (e.name, e.tree, e.metrics))
+        .collect()
+}
+
+#[test]
+fn constraints_without_violations_are_not_redundant() {
+    use crate::binding_box::{structs::ObjectVariable, BindingBox};
+
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(
+        vec![],
+        vec![("o1", "order", vec![]), ("o2", "order", vec![])],
+    ));
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(
+        ObjectVariable(0),
+        vec!["order".to_string()].into_iter().collect(),
+    );
+    // Two different constraints on the same root type, which both hold for all orders
+    let without_child = BindingBoxTree {
+        nodes: vec![BindingBoxTreeNode::Box(root.clone(), vec![])],
+        edge_names: Default::default(),
+    };
+    let with_child = BindingBoxTree {
+        nodes: vec![
+            BindingBoxTreeNode::Box(root, vec![1]),
+            BindingBoxTreeNode::Box(BindingBox::default(), vec![]),
+        ],
+        edge_names: vec![((0, 1), "A".to_string())].into_iter().collect(),
+    };
+    let constraints = vec![
+        ("A".to_string(), without_child),
+        ("B".to_string(), with_child),
+    ];
+    let ranked = rank_discovered_constraints(&ocel, constraints.clone(), &Default::default());
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked[0].2.support, 2);
+    assert_eq!(ranked[1].2.support, 2);
+    assert_eq!(ranked[0].2.redundancy, 0.0);
+    assert_eq!(ranked[1].2.redundancy, 0.0);
+    assert!(!ranked[1].2.bindings_skipped);
+
+    let deduplicated = rank_discovered_constraints(
+        &ocel,
+        constraints,
+        &ConstraintRankingOptions {
+            deduplicate: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        deduplicated.iter().map(|(name, _, _)| name).collect_vec(),
+        vec!["A", "B"]
+    );
+}
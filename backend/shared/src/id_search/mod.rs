@@ -0,0 +1,298 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::preprocessing::linked_ocel::IndexLinkedOCEL;
+
+/// Number of IDs returned per page if no limit is given
+const DEFAULT_LIMIT: usize = 100;
+/// Maximal number of IDs returned per page
+const MAX_LIMIT: usize = 10_000;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IDKind {
+    Event,
+    Object,
+}
+
+/// Search for event or object IDs
+///
+/// All filters are optional and combined conjunctively.
+/// Matching IDs are returned in index order, paged by `offset` and `limit`.
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIDsRequest {
+    pub kind: IDKind,
+    /// Only include IDs starting with this prefix
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Only include events/objects of this type
+    #[serde(default)]
+    pub ocel_type: Option<String>,
+    /// Only include events/objects with an index of at least `from_index`
+    #[serde(default)]
+    pub from_index: Option<usize>,
+    /// Only include events/objects with an index less than `to_index`
+    #[serde(default)]
+    pub to_index: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+    /// Maximal number of returned IDs (capped at 10000)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IDWithIndex {
+    pub index: usize,
+    pub id: String,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIDsResponse {
+    pub ids: Vec<IDWithIndex>,
+    /// Total number of matching IDs (across all pages)
+    pub total: usize,
+}
+
+/// Search event or object IDs by prefix, type and index range
+pub fn search_ids(ocel: &IndexLinkedOCEL, req: &SearchIDsRequest) -> SearchIDsResponse {
+    let num = match req.kind {
+        IDKind::Event => ocel.ocel.events.len(),
+        IDKind::Object => ocel.ocel.objects.len(),
+    };
+    let from = req.from_index.unwrap_or(0);
+    let to = req.to_index.unwrap_or(num).min(num);
+    let indices: Box<dyn Iterator<Item = usize>> = match (&req.ocel_type, req.kind) {
+        (None, _) => Box::new(from..to.max(from)),
+        (Some(t), IDKind::Event) => Box::new(
+            ocel.events_of_type
+                .get(t)
+                .into_iter()
+                .flatten()
+                .map(|e| e.0)
+                .filter(|i| (from..to).contains(i)),
+        ),
+        (Some(t), IDKind::Object) => Box::new(
+            ocel.objects_of_type
+                .get(t)
+                .into_iter()
+                .flatten()
+                .map(|o| o.0)
+                .filter(|i| (from..to).contains(i)),
+        ),
+    };
+    let id_of = |index: usize| match req.kind {
+        IDKind::Event => &ocel.ocel.events[index].id,
+        IDKind::Object => &ocel.ocel.objects[index].id,
+    };
+    let limit = req.limit.min(MAX_LIMIT);
+    let mut ids = Vec::new();
+    let mut total = 0;
+    for index in indices {
+        let id = id_of(index);
+        if req.prefix.as_ref().is_some_and(|p| !id.starts_with(p)) {
+            continue;
+        }
+        if total >= req.offset && ids.len() < limit {
+            ids.push(IDWithIndex {
+                index,
+                id: id.clone(),
+            });
+        }
+        total += 1;
+    }
+    SearchIDsResponse { ids, total }
+}
+
+#[cfg(test)]
+fn search_object_ids(ocel: &IndexLinkedOCEL, req: SearchIDsRequest) -> (Vec<String>, usize) {
+    let res = search_ids(ocel, &req);
+    (res.ids.into_iter().map(|id| id.id).collect(), res.total)
+}
+
+#[test]
+fn search_ids_filters_and_pages() {
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(
+        vec![
+            ("e1", "place order", 0, vec![]),
+            ("e2", "pay order", 10, vec![]),
+            ("e3", "place order", 20, vec![]),
+        ],
+        vec![
+            ("o1", "order", vec![]),
+            ("o2", "order", vec![]),
+            ("o10", "order", vec![]),
+            ("i1", "item", vec![]),
+            ("i2", "item", vec![]),
+        ],
+    ));
+    let all = SearchIDsRequest {
+        kind: IDKind::Object,
+        prefix: None,
+        ocel_type: None,
+        from_index: None,
+        to_index: None,
+        offset: 0,
+        limit: DEFAULT_LIMIT,
+    };
+    let ids = |v: &[&str]| v.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        search_object_ids(&ocel, all.clone()),
+        (ids(&["o1", "o2", "o10", "i1", "i2"]), 5)
+    );
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                prefix: Some("o1".to_string()),
+                ..all.clone()
+            }
+        ),
+        (ids(&["o1", "o10"]), 2)
+    );
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                ocel_type: Some("item".to_string()),
+                ..all.clone()
+            }
+        ),
+        (ids(&["i1", "i2"]), 2)
+    );
+    // Index ranges include `from_index` and exclude `to_index`
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                from_index: Some(1),
+                to_index: Some(3),
+                ..all.clone()
+            }
+        ),
+        (ids(&["o2", "o10"]), 2)
+    );
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                ocel_type: Some("order".to_string()),
+                from_index: Some(1),
+                to_index: Some(100),
+                ..all.clone()
+            }
+        ),
+        (ids(&["o2", "o10"]), 2)
+    );
+    // Unknown types and empty ranges match nothing
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                ocel_type: Some("customer".to_string()),
+                ..all.clone()
+            }
+        ),
+        (vec![], 0)
+    );
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                from_index: Some(4),
+                to_index: Some(2),
+                ..all.clone()
+            }
+        ),
+        (vec![], 0)
+    );
+    // Pages report the total number of matches
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                offset: 1,
+                limit: 2,
+                ..all.clone()
+            }
+        ),
+        (ids(&["o2", "o10"]), 5)
+    );
+    assert_eq!(
+        search_object_ids(
+            &ocel,
+            SearchIDsRequest {
+                offset: 5,
+                ..all.clone()
+            }
+        ),
+        (vec![], 5)
+    );
+
+    let res = search_ids(
+        &ocel,
+        &SearchIDsRequest {
+            kind: IDKind::Event,
+            ocel_type: Some("place order".to_string()),
+            ..all
+        },
+    );
+    assert_eq!(
+        res.ids
+            .iter()
+            .map(|id| (id.index, id.id.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(0, "e1"), (2, "e3")]
+    );
+}
+
+#[test]
+fn search_ids_caps_the_limit() {
+    let ids = (0..MAX_LIMIT + 2000)
+        .map(|i| format!("o{i}"))
+        .collect::<Vec<_>>();
+    let ocel = IndexLinkedOCEL::new(crate::preprocessing::tests::test_ocel(
+        vec![],
+        ids.iter()
+            .map(|id| (id.as_str(), "order", vec![]))
+            .collect(),
+    ));
+    let req = SearchIDsRequest {
+        kind: IDKind::Object,
+        prefix: None,
+        ocel_type: None,
+        from_index: None,
+        to_index: None,
+        offset: 0,
+        limit: MAX_LIMIT * 2,
+    };
+    let (found, total) = search_object_ids(&ocel, req.clone());
+    assert_eq!((found.len(), total), (MAX_LIMIT, MAX_LIMIT + 2000));
+    assert_eq!(found.last().map(|id| id.as_str()), Some("o9999"));
+
+    let (found, total) = search_object_ids(
+        &ocel,
+        SearchIDsRequest {
+            offset: MAX_LIMIT,
+            ..req
+        },
+    );
+    assert_eq!((found.len(), total), (2000, MAX_LIMIT + 2000));
+    assert_eq!(found.first().map(|id| id.as_str()), Some("o10000"));
+}
@@ -1,4 +1,4 @@
-use preprocessing::linked_ocel::IndexLinkedOCEL;
+use preprocessing::linked_ocel::{EventIndex, IndexLinkedOCEL, ObjectIndex};
 use process_mining::{
     ocel::ocel_struct::{OCELEvent, OCELObject, OCELType},
     OCEL,
@@ -11,12 +11,17 @@ pub mod ocel_qualifiers {
 pub mod binding_box;
 pub mod constraints_2;
 pub mod discovery;
+pub mod id_search;
 pub mod ocel_graph;
+pub mod ocel_profile;
 pub mod translation;
 pub mod preprocessing {
+    pub mod compact;
     pub mod linked_ocel;
     pub mod preprocess;
+    pub mod snapshot;
     pub mod tests;
+    pub mod validation;
 }
 pub mod cel;
 pub mod table_export;
@@ -29,19 +34,81 @@ pub struct OCELInfo {
     pub num_events: usize,
     pub object_types: Vec<OCELType>,
     pub event_types: Vec<OCELType>,
+    /// All object IDs (empty, unless explicitly requested)
+    #[serde(default)]
     pub object_ids: Vec<String>,
+    /// All event IDs (empty, unless explicitly requested)
+    #[serde(default)]
     pub event_ids: Vec<String>,
 }
 
+impl OCELInfo {
+    /// Construct the info of an OCEL
+    ///
+    /// The (potentially very large) lists of all event and object IDs are only included if `include_ids` is set.
+    /// Use [`id_search::search_ids`] to retrieve IDs page-wise instead.
+    pub fn from_ocel(ocel: &OCEL, include_ids: bool) -> Self {
+        OCELInfo {
+            num_objects: ocel.objects.len(),
+            num_events: ocel.events.len(),
+            object_types: ocel.object_types.clone(),
+            event_types: ocel.event_types.clone(),
+            event_ids: if include_ids {
+                ocel.events.iter().map(|ev| ev.id.clone()).collect()
+            } else {
+                Vec::new()
+            },
+            object_ids: if include_ids {
+                ocel.objects.iter().map(|ob| ob.id.clone()).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
 impl From<&OCEL> for OCELInfo {
     fn from(val: &OCEL) -> Self {
-        OCELInfo {
-            num_objects: val.objects.len(),
-            num_events: val.events.len(),
-            object_types: val.object_types.clone(),
-            event_types: val.event_types.clone(),
-            event_ids: val.events.iter().map(|ev| ev.id.clone()).collect(),
-            object_ids: val.objects.iter().map(|ob| ob.id.clone()).collect(),
+        OCELInfo::from_ocel(val, false)
+    }
+}
+
+/// Opt-in flag for including all event and object IDs in a response
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncludeIDsOptions {
+    #[serde(default)]
+    pub include_ids: bool,
+}
+
+/// New objects and events to append to the currently loaded OCEL
+///
+/// Objects are appended before events, so that new events can reference the new objects
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppendToOCELRequest {
+    #[serde(default)]
+    pub objects: Vec<OCELObject>,
+    #[serde(default)]
+    pub events: Vec<OCELEvent>,
+}
+
+/// Number of objects and events appended by an [AppendToOCELRequest]
+///
+/// Objects/events with an already present ID are skipped and not counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendToOCELCounts {
+    pub num_objects: usize,
+    pub num_events: usize,
+}
+
+impl AppendToOCELRequest {
+    pub fn apply(self, ocel: &mut IndexLinkedOCEL) -> AppendToOCELCounts {
+        let new_obs = ocel.append_objects(self.objects);
+        let new_evs = ocel.append_events(self.events);
+        AppendToOCELCounts {
+            num_objects: new_obs.len(),
+            num_events: new_evs.len(),
         }
     }
 }
@@ -71,10 +138,9 @@ pub struct EventWithIndex {
 pub fn get_event_info(ocel: &IndexLinkedOCEL, req: IndexOrID) -> Option<EventWithIndex> {
     let ev_with_index = match req {
         IndexOrID::ID(id) => ocel
-            .ev_by_id(&id)
-            .cloned()
-            .and_then(|ev| ocel.index_of_ev(&id).map(|ev_index| (ev, ev_index.0))),
-        IndexOrID::Index(index) => ocel.ocel.events.get(index).cloned().map(|ev| (ev, index)),
+            .index_of_ev(&id)
+            .and_then(|ev_index| ocel.full_event(ev_index).map(|ev| (ev, ev_index.0))),
+        IndexOrID::Index(index) => ocel.full_event(&EventIndex(index)).map(|ev| (ev, index)),
     };
     ev_with_index.map(|(event, index)| EventWithIndex { event, index })
 }
@@ -82,10 +148,9 @@ pub fn get_event_info(ocel: &IndexLinkedOCEL, req: IndexOrID) -> Option<EventWit
 pub fn get_object_info(ocel: &IndexLinkedOCEL, req: IndexOrID) -> Option<ObjectWithIndex> {
     let ob_with_index = match req {
         IndexOrID::ID(id) => ocel
-            .ob_by_id(&id)
-            .cloned()
-            .and_then(|ob| ocel.index_of_ob(&id).map(|ob_index| (ob, ob_index.0))),
-        IndexOrID::Index(index) => ocel.ocel.objects.get(index).cloned().map(|ev| (ev, index)),
+            .index_of_ob(&id)
+            .and_then(|ob_index| ocel.full_object(ob_index).map(|ob| (ob, ob_index.0))),
+        IndexOrID::Index(index) => ocel.full_object(&ObjectIndex(index)).map(|ob| (ob, index)),
     };
     ob_with_index.map(|(object, index)| ObjectWithIndex { object, index })
 }
@@ -2,7 +2,7 @@ use process_mining::ocel::ocel_struct::{OCELEvent, OCELObject};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL};
+use crate::preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL, OCELNodeRef};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
@@ -55,22 +55,22 @@ pub fn get_ocel_graph(ocel: &IndexLinkedOCEL, options: OCELGraphOptions) -> Opti
         let max_distance = options.max_distance;
         while let Some((index, distance)) = queue.pop() {
             if distance < max_distance {
-                if let Some(rels) = ocel.symmetric_rels.get(&index) {
-                    // Check for rels_size_ignore_threshold but also continue if at the root node (root node always gets expanded)
-                    if root_index == index || rels.len() < options.rels_size_ignore_threshold {
-                        for (r, reversed, qualifier) in rels {
-                            let arc = if !reversed {
-                                (index, *r, qualifier.clone())
-                            } else {
-                                (*r, index, qualifier.clone())
-                            };
-                            if !done_indices.contains(r) {
-                                expanded_arcs.push(arc);
-                                queue.push((*r, distance + 1));
-                                done_indices.push(*r);
-                            } else if !options.spanning_tree {
-                                expanded_arcs.push(arc);
-                            }
+                // Check for rels_size_ignore_threshold but also continue if at the root node (root node always gets expanded)
+                if root_index == index
+                    || ocel.num_symmetric_rels(&index) < options.rels_size_ignore_threshold
+                {
+                    for (r, reversed, qualifier) in ocel.get_symmetric_rels(&index) {
+                        let arc = if !reversed {
+                            (index, r, qualifier.to_string())
+                        } else {
+                            (r, index, qualifier.to_string())
+                        };
+                        if !done_indices.contains(&r) {
+                            expanded_arcs.push(arc);
+                            queue.push((r, distance + 1));
+                            done_indices.push(r);
+                        } else if !options.spanning_tree {
+                            expanded_arcs.push(arc);
                         }
                     }
                 }
@@ -80,24 +80,23 @@ pub fn get_ocel_graph(ocel: &IndexLinkedOCEL, options: OCELGraphOptions) -> Opti
             .iter()
             .map(|i| match i {
                 EventOrObjectIndex::Object(o_index) => {
-                    GraphNode::Object(ocel.ob_by_index(o_index).unwrap().clone())
+                    GraphNode::Object(ocel.full_object(o_index).unwrap())
                 }
                 EventOrObjectIndex::Event(e_index) => {
-                    GraphNode::Event(ocel.ev_by_index(e_index).unwrap().clone())
+                    GraphNode::Event(ocel.full_event(e_index).unwrap())
                 }
             })
             .collect();
+        let id_of = |index: &EventOrObjectIndex| match ocel.ob_or_ev_by_index(*index).unwrap() {
+            OCELNodeRef::Event(ev) => ev.id.clone(),
+            OCELNodeRef::Object(ob) => ob.id.clone(),
+        };
         let links = expanded_arcs
             .iter()
-            .map(|(from, to, qualifier)| {
-                let from = ocel.ob_or_ev_by_index(*from).unwrap().cloned();
-                let to = ocel.ob_or_ev_by_index(*to).unwrap().cloned();
-
-                GraphLink {
-                    source: from.get_id().clone(),
-                    target: to.get_id().clone(),
-                    qualifier: qualifier.clone(),
-                }
+            .map(|(from, to, qualifier)| GraphLink {
+                source: id_of(from),
+                target: id_of(to),
+                qualifier: qualifier.clone(),
             })
             .collect();
         Some(OCELGraph { nodes, links })
@@ -0,0 +1,452 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use process_mining::ocel::ocel_struct::{OCELAttributeValue, OCELType};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::preprocessing::linked_ocel::{
+    EventIndex, EventOrObjectIndex, IndexLinkedOCEL, ObjectIndex,
+};
+
+/// Number of most frequent values reported per attribute
+const NUM_TOP_VALUES: usize = 10;
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OCELProfile {
+    pub num_events: usize,
+    pub num_objects: usize,
+    pub event_types: Vec<TypeProfile>,
+    pub object_types: Vec<TypeProfile>,
+    /// Cardinalities of E2O relationships, per (event type, qualifier, object type)
+    pub e2o_cardinalities: Vec<RelationshipCardinality>,
+    /// Cardinalities of O2O relationships, per (object type, qualifier, object type)
+    pub o2o_cardinalities: Vec<RelationshipCardinality>,
+    pub events_without_objects: usize,
+    pub objects_without_events: usize,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeProfile {
+    pub name: String,
+    pub count: usize,
+    /// Earliest and latest event (of this type, or involving an object of this type)
+    pub time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    pub attributes: Vec<AttributeProfile>,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeProfile {
+    pub name: String,
+    /// Value type as declared in the OCEL type (if declared)
+    pub value_type: Option<String>,
+    /// Fraction of events/objects of the type with a (non-null) value for this attribute
+    pub fill_rate: f64,
+    pub num_distinct_values: usize,
+    /// Most frequent values and their number of occurrences
+    pub top_values: Vec<(String, usize)>,
+    /// Minimum, average and maximum of integer or float values (if any)
+    pub numeric_summary: Option<(f64, f64, f64)>,
+}
+
+/// Number of related objects per source event/object for one (source type, qualifier, target type) combination
+///
+/// Sources without any such relationship are counted with cardinality 0
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipCardinality {
+    pub from_type: String,
+    pub qualifier: String,
+    pub to_type: String,
+    pub min: usize,
+    pub avg: f64,
+    pub max: usize,
+    /// (Cardinality, Number of source events/objects with this cardinality), sorted by cardinality
+    pub histogram: Vec<(usize, usize)>,
+}
+
+fn attribute_value_to_string(v: &OCELAttributeValue) -> Option<String> {
+    match v {
+        OCELAttributeValue::Time(t) => Some(t.to_rfc3339()),
+        OCELAttributeValue::Integer(i) => Some(i.to_string()),
+        OCELAttributeValue::Float(f) => Some(f.to_string()),
+        OCELAttributeValue::Boolean(b) => Some(b.to_string()),
+        OCELAttributeValue::String(s) => Some(s.clone()),
+        OCELAttributeValue::Null => None,
+    }
+}
+
+#[derive(Default)]
+struct AttributeAccumulator {
+    filled: usize,
+    values: HashMap<String, usize>,
+    numeric: Option<(f64, f64, f64, usize)>,
+}
+
+impl AttributeAccumulator {
+    fn add(&mut self, v: &OCELAttributeValue) {
+        let Some(s) = attribute_value_to_string(v) else {
+            return;
+        };
+        *self.values.entry(s).or_default() += 1;
+        let num = match v {
+            OCELAttributeValue::Integer(i) => *i as f64,
+            OCELAttributeValue::Float(f) => *f,
+            _ => return,
+        };
+        self.numeric = Some(match self.numeric {
+            Some((min, max, sum, n)) => (min.min(num), max.max(num), sum + num, n + 1),
+            None => (num, num, num, 1),
+        });
+    }
+
+    fn into_profile(
+        self,
+        name: String,
+        value_type: Option<String>,
+        count: usize,
+    ) -> AttributeProfile {
+        AttributeProfile {
+            name,
+            value_type,
+            fill_rate: if count > 0 {
+                self.filled as f64 / count as f64
+            } else {
+                0.0
+            },
+            num_distinct_values: self.values.len(),
+            top_values: self
+                .values
+                .into_iter()
+                .sorted_by(|(a, count_a), (b, count_b)| count_b.cmp(count_a).then(a.cmp(b)))
+                .take(NUM_TOP_VALUES)
+                .collect(),
+            numeric_summary: self
+                .numeric
+                .map(|(min, max, sum, n)| (min, sum / n as f64, max)),
+        }
+    }
+}
+
+/// Profile the attributes of events/objects of one type
+///
+/// `instance_attributes` contains the attributes (name and value) of each event/object of the type
+fn get_type_profile<'a>(
+    ocel_type: &OCELType,
+    instance_attributes: impl Iterator<Item = Vec<(&'a str, &'a OCELAttributeValue)>>,
+    time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> TypeProfile {
+    let mut count = 0;
+    let mut acc: HashMap<&str, AttributeAccumulator> = HashMap::new();
+    for attrs in instance_attributes {
+        count += 1;
+        for (name, values) in &attrs.into_iter().group_by(|(name, _)| *name) {
+            let a = acc.entry(name).or_default();
+            let mut filled = false;
+            for (_, v) in values {
+                filled |= !matches!(v, OCELAttributeValue::Null);
+                a.add(v);
+            }
+            if filled {
+                a.filled += 1;
+            }
+        }
+    }
+    let declared: HashMap<&str, &String> = ocel_type
+        .attributes
+        .iter()
+        .map(|a| (a.name.as_str(), &a.value_type))
+        .collect();
+    for name in declared.keys() {
+        acc.entry(*name).or_default();
+    }
+    TypeProfile {
+        name: ocel_type.name.clone(),
+        count,
+        time_range,
+        attributes: acc
+            .into_iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(name, a)| {
+                a.into_profile(name.to_string(), declared.get(name).map(|t| t.to_string()), count)
+            })
+            .collect(),
+    }
+}
+
+fn get_cardinalities(
+    counts: HashMap<(&str, &str, &str), Vec<usize>>,
+    num_of_type: impl Fn(&str) -> usize,
+) -> Vec<RelationshipCardinality> {
+    counts
+        .into_iter()
+        .sorted_by_key(|(k, _)| *k)
+        .map(|((from_type, qualifier, to_type), mut cards)| {
+            // Add sources without any such relationship
+            let num_sources = num_of_type(from_type);
+            cards.resize(num_sources.max(cards.len()), 0);
+            let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+            for c in &cards {
+                *histogram.entry(*c).or_default() += 1;
+            }
+            RelationshipCardinality {
+                from_type: from_type.to_string(),
+                qualifier: qualifier.to_string(),
+                to_type: to_type.to_string(),
+                min: cards.iter().min().copied().unwrap_or_default(),
+                avg: cards.iter().sum::<usize>() as f64 / cards.len().max(1) as f64,
+                max: cards.iter().max().copied().unwrap_or_default(),
+                histogram: histogram.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+/// Count relationships of one event/object per (qualifier, target object type)
+fn count_rels<'a>(
+    ocel: &'a IndexLinkedOCEL,
+    index: &EventOrObjectIndex,
+) -> HashMap<(&'a str, &'a str), usize> {
+    let mut counts = HashMap::new();
+    for (ob_index, qualifier) in ocel.get_rels(index) {
+        if let Some(ob) = ocel.ob_by_index(&ob_index) {
+            *counts
+                .entry((qualifier, ob.object_type.as_str()))
+                .or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Compute a profiling report of an OCEL
+///
+/// Includes per-type counts, attribute fill rates and value distributions, relationship cardinalities and time ranges
+pub fn get_ocel_profile(ocel: &IndexLinkedOCEL) -> OCELProfile {
+    let event_types = ocel
+        .ocel
+        .event_types
+        .par_iter()
+        .map(|et| {
+            let evs = ocel
+                .events_of_type
+                .get(&et.name)
+                .map(|evs| evs.as_slice())
+                .unwrap_or_default();
+            let time_range = evs
+                .iter()
+                .filter_map(|e| ocel.ev_by_index(e))
+                .map(|e| e.time.with_timezone(&Utc))
+                .minmax()
+                .into_option();
+            get_type_profile(
+                et,
+                evs.iter().map(|e| {
+                    ocel.ev_attributes(e)
+                        .sorted_by_key(|(name, _)| *name)
+                        .collect()
+                }),
+                time_range,
+            )
+        })
+        .collect();
+    let object_types = ocel
+        .ocel
+        .object_types
+        .par_iter()
+        .map(|ot| {
+            let obs = ocel
+                .objects_of_type
+                .get(&ot.name)
+                .map(|obs| obs.as_slice())
+                .unwrap_or_default();
+            let time_range = obs
+                .iter()
+                .flat_map(|o| ocel.events_of_object(o))
+                .filter_map(|e| ocel.ev_by_index(e))
+                .map(|e| e.time.with_timezone(&Utc))
+                .minmax()
+                .into_option();
+            get_type_profile(
+                ot,
+                obs.iter().map(|o| {
+                    ocel.ob_attributes(o)
+                        .map(|(name, value, _)| (name, value))
+                        .sorted_by_key(|(name, _)| *name)
+                        .collect()
+                }),
+                time_range,
+            )
+        })
+        .collect();
+
+    let mut e2o_counts: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+    let mut events_without_objects = 0;
+    for (i, e) in ocel.ocel.events.iter().enumerate() {
+        let counts = count_rels(ocel, &EventOrObjectIndex::Event(EventIndex(i)));
+        if counts.is_empty() {
+            events_without_objects += 1;
+        }
+        for ((qualifier, object_type), c) in counts {
+            e2o_counts
+                .entry((e.event_type.as_str(), qualifier, object_type))
+                .or_default()
+                .push(c);
+        }
+    }
+    let mut o2o_counts: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+    let mut objects_without_events = 0;
+    for (i, o) in ocel.ocel.objects.iter().enumerate() {
+        if ocel.num_events_of_object(&ObjectIndex(i)) == 0 {
+            objects_without_events += 1;
+        }
+        for ((qualifier, object_type), c) in
+            count_rels(ocel, &EventOrObjectIndex::Object(ObjectIndex(i)))
+        {
+            o2o_counts
+                .entry((o.object_type.as_str(), qualifier, object_type))
+                .or_default()
+                .push(c);
+        }
+    }
+
+    OCELProfile {
+        num_events: ocel.ocel.events.len(),
+        num_objects: ocel.ocel.objects.len(),
+        event_types,
+        object_types,
+        e2o_cardinalities: get_cardinalities(e2o_counts, |t| {
+            ocel.events_of_type.get(t).map(|evs| evs.len()).unwrap_or_default()
+        }),
+        o2o_cardinalities: get_cardinalities(o2o_counts, |t| {
+            ocel.objects_of_type.get(t).map(|obs| obs.len()).unwrap_or_default()
+        }),
+        events_without_objects,
+        objects_without_events,
+    }
+}
+
+#[test]
+fn profiles_small_ocel() {
+    use chrono::FixedOffset;
+    use process_mining::ocel::ocel_struct::{
+        OCELEventAttribute, OCELObjectAttribute, OCELTypeAttribute,
+    };
+
+    let mut ocel = crate::preprocessing::tests::test_ocel(
+        vec![
+            (
+                "e1",
+                "place order",
+                0,
+                vec![("o1", "order"), ("i1", "item"), ("i2", "item")],
+            ),
+            (
+                "e2",
+                "place order",
+                10,
+                vec![("o2", "order"), ("i3", "item")],
+            ),
+            ("e3", "place order", 20, vec![("o3", "order")]),
+            ("e4", "note", 30, vec![]),
+        ],
+        vec![
+            ("o1", "order", vec![("i1", "contains"), ("i2", "contains")]),
+            ("o2", "order", vec![]),
+            ("o3", "order", vec![]),
+            ("i1", "item", vec![]),
+            ("i2", "item", vec![]),
+            ("i3", "item", vec![]),
+            ("c1", "customer", vec![]),
+        ],
+    );
+    ocel.event_types[0].attributes.push(OCELTypeAttribute {
+        name: "price".to_string(),
+        value_type: "float".to_string(),
+    });
+    for (i, price) in [(0, 10.0), (1, 20.0)] {
+        ocel.events[i].attributes.push(OCELEventAttribute {
+            name: "price".to_string(),
+            value: OCELAttributeValue::Float(price),
+        });
+    }
+    ocel.object_types[0].attributes.push(OCELTypeAttribute {
+        name: "status".to_string(),
+        value_type: "string".to_string(),
+    });
+    ocel.objects[0].attributes.push(OCELObjectAttribute {
+        name: "status".to_string(),
+        value: OCELAttributeValue::String("new".to_string()),
+        time: DateTime::from_timestamp(0, 0)
+            .unwrap()
+            .with_timezone(&FixedOffset::east_opt(0).unwrap())
+            .into(),
+    });
+    let profile = get_ocel_profile(&IndexLinkedOCEL::new(ocel));
+
+    assert_eq!((profile.num_events, profile.num_objects), (4, 7));
+    assert_eq!(profile.events_without_objects, 1);
+    assert_eq!(profile.objects_without_events, 1);
+
+    // Fill rates (the third order event has no price, only the first order has a status)
+    let place_order = &profile.event_types[0];
+    assert_eq!(
+        (place_order.name.as_str(), place_order.count),
+        ("place order", 3)
+    );
+    let price = &place_order.attributes[0];
+    assert_eq!(price.name, "price");
+    assert_eq!(price.value_type.as_deref(), Some("float"));
+    assert!((price.fill_rate - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(price.num_distinct_values, 2);
+    assert_eq!(price.numeric_summary, Some((10.0, 15.0, 20.0)));
+    let status = &profile.object_types[0].attributes[0];
+    assert_eq!(status.name, "status");
+    assert!((status.fill_rate - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(status.top_values, vec![("new".to_string(), 1)]);
+
+    // Min/avg/max per type pair (orders without items have cardinality 0)
+    let cardinalities = |cards: &[RelationshipCardinality]| {
+        cards
+            .iter()
+            .map(|c| {
+                (
+                    c.from_type.clone(),
+                    c.qualifier.clone(),
+                    c.to_type.clone(),
+                    c.min,
+                    c.avg,
+                    c.max,
+                )
+            })
+            .collect_vec()
+    };
+    let s = |s: &str| s.to_string();
+    assert_eq!(
+        cardinalities(&profile.e2o_cardinalities),
+        vec![
+            (s("place order"), s("item"), s("item"), 0, 1.0, 2),
+            (s("place order"), s("order"), s("order"), 1, 1.0, 1),
+        ]
+    );
+    assert_eq!(
+        profile.e2o_cardinalities[0].histogram,
+        vec![(0, 1), (1, 1), (2, 1)]
+    );
+    assert_eq!(
+        cardinalities(&profile.o2o_cardinalities),
+        vec![(s("order"), s("contains"), s("item"), 0, 2.0 / 3.0, 2)]
+    );
+}
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
-use process_mining::OCEL;
 use rayon::{iter::IntoParallelRefIterator, prelude::ParallelIterator};
 use serde::{Deserialize, Serialize};
 
+use crate::preprocessing::linked_ocel::{EventOrObjectIndex, IndexLinkedOCEL};
+
 #[derive(Serialize, Deserialize)]
 pub struct QualifiersForEventType {
     pub qualifier: String,
@@ -14,23 +15,26 @@ pub struct QualifiersForEventType {
 pub type QualifierAndObjectType = (String, String);
 
 pub fn get_qualifiers_for_event_types(
-    ocel: &OCEL,
+    ocel: &IndexLinkedOCEL,
 ) -> HashMap<String, HashMap<String, QualifiersForEventType>> {
     let qualifiers_per_event_type: Vec<(String, HashMap<QualifierAndObjectType, Vec<i32>>)> = ocel
+        .ocel
         .event_types
         .par_iter()
         .map(|et| {
             (
                 et.name.clone(),
-                ocel.events
-                    .iter()
-                    .filter(|ev| ev.event_type == et.name)
-                    .map(|ev| {
-                        ev.relationships
-                            .iter()
-                            .filter_map(|r| {
-                                let obj = ocel.objects.iter().find(|o| o.id == r.object_id);
-                                obj.map(|obj| (r.qualifier.clone(), obj.object_type.clone()))
+                ocel.events_of_type
+                    .get(&et.name)
+                    .into_iter()
+                    .flatten()
+                    .map(|ev_index| {
+                        ocel.get_rels(&EventOrObjectIndex::Event(*ev_index))
+                            .map(|(ob_index, qualifier)| {
+                                (
+                                    qualifier.to_string(),
+                                    ocel.ocel.objects[ob_index.0].object_type.clone(),
+                                )
                             })
                             .fold(HashMap::new(), |mut acc, c| {
                                 *acc.entry(c).or_insert(0) += 1;
@@ -0,0 +1,528 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem::size_of,
+};
+
+use chrono::{DateTime, FixedOffset};
+use process_mining::{
+    ocel::ocel_struct::{
+        OCELAttributeValue, OCELEvent, OCELEventAttribute, OCELObject, OCELObjectAttribute,
+        OCELRelationship, OCELType, OCELTypeAttribute,
+    },
+    OCEL,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::linked_ocel::EventOrObjectIndex;
+
+/// Index of an interned string (e.g., a qualifier)
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InternedIndex(pub u32);
+
+/// Stores each distinct string only once
+///
+/// Strings are referenced by their [InternedIndex]
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    strings: Vec<String>,
+    indices: HashMap<String, InternedIndex>,
+}
+
+impl StringInterner {
+    pub fn intern(&mut self, s: &str) -> InternedIndex {
+        if let Some(index) = self.indices.get(s) {
+            return *index;
+        }
+        let index = InternedIndex(self.strings.len() as u32);
+        self.strings.push(s.to_string());
+        self.indices.insert(s.to_string(), index);
+        index
+    }
+
+    pub fn lookup(&self, s: &str) -> Option<InternedIndex> {
+        self.indices.get(s).copied()
+    }
+
+    pub fn get(&self, index: InternedIndex) -> &str {
+        &self.strings[index.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// All interned strings, in the order of their [InternedIndex]
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.strings.iter().map(|s| s.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        let strings_bytes: usize = self.strings.iter().map(|s| s.capacity()).sum();
+        // Each string is stored twice (once as key of the index map)
+        vec_usage(&self.strings) + hash_map_usage(&self.indices) + 2 * strings_bytes
+    }
+}
+
+/// A relationship from an event/object to another event/object
+///
+/// `reversed` is true if the relationship points from `to` to the owning event/object
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RelEdge {
+    pub to: EventOrObjectIndex,
+    pub reversed: bool,
+    pub qualifier: InternedIndex,
+}
+
+/// Compressed sparse row (CSR) adjacency list
+///
+/// The entries of node `i` are stored contiguously in `entries[offsets[i]..offsets[i+1]]`.
+/// Entries added after construction are kept separately until [CSRAdjacency::compact] is called,
+/// so that appending does not require rebuilding the whole structure.
+#[derive(Debug, Clone)]
+pub struct CSRAdjacency<T> {
+    offsets: Vec<usize>,
+    entries: Vec<T>,
+    appended: HashMap<usize, Vec<T>>,
+}
+
+impl<T> Default for CSRAdjacency<T> {
+    fn default() -> Self {
+        Self {
+            offsets: vec![0],
+            entries: Vec::new(),
+            appended: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Copy + Ord> CSRAdjacency<T> {
+    /// Construct from (node, entry) pairs
+    ///
+    /// Entries of a node are sorted and duplicates are removed
+    pub fn from_pairs(num_nodes: usize, mut pairs: Vec<(usize, T)>) -> Self {
+        pairs.sort_unstable();
+        pairs.dedup();
+        let mut offsets = vec![0; num_nodes + 1];
+        for (node, _) in &pairs {
+            offsets[node + 1] += 1;
+        }
+        for i in 0..num_nodes {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut entries: Vec<T> = pairs.into_iter().map(|(_, entry)| entry).collect();
+        entries.shrink_to_fit();
+        Self {
+            offsets,
+            entries,
+            appended: HashMap::new(),
+        }
+    }
+
+    /// Construct from a contiguous representation (as produced by [CSRAdjacency::offsets] and the entries of all nodes)
+    ///
+    /// Returns `None` if the offsets are not consistent with the number of entries
+    pub fn from_raw_parts(offsets: Vec<usize>, entries: Vec<T>) -> Option<Self> {
+        if offsets.first() != Some(&0)
+            || offsets.last() != Some(&entries.len())
+            || offsets.windows(2).any(|w| w[0] > w[1])
+        {
+            return None;
+        }
+        Some(Self {
+            offsets,
+            entries,
+            appended: HashMap::new(),
+        })
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        (self.offsets.len() - 1).max(
+            self.appended
+                .keys()
+                .max()
+                .map(|n| n + 1)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Offsets of all nodes (including appended entries), i.e., the entries of node `i` are
+    /// at positions `offsets[i]..offsets[i+1]` when iterating all nodes in order
+    pub fn offsets(&self) -> Vec<usize> {
+        let mut offsets = Vec::with_capacity(self.num_nodes() + 1);
+        offsets.push(0);
+        for node in 0..self.num_nodes() {
+            offsets.push(offsets[node] + self.num_entries_of(node));
+        }
+        offsets
+    }
+
+    fn base_slice(&self, node: usize) -> &[T] {
+        if node + 1 < self.offsets.len() {
+            &self.entries[self.offsets[node]..self.offsets[node + 1]]
+        } else {
+            &[]
+        }
+    }
+
+    pub fn get(&self, node: usize) -> impl Iterator<Item = &T> {
+        self.base_slice(node)
+            .iter()
+            .chain(self.appended.get(&node).into_iter().flatten())
+    }
+
+    pub fn num_entries_of(&self, node: usize) -> usize {
+        self.base_slice(node).len()
+            + self
+                .appended
+                .get(&node)
+                .map(|v| v.len())
+                .unwrap_or_default()
+    }
+
+    /// Add an entry for a node (if it is not already present)
+    pub fn push(&mut self, node: usize, entry: T) {
+        if self.get(node).any(|e| e == &entry) {
+            return;
+        }
+        self.appended.entry(node).or_default().push(entry);
+    }
+
+    /// Merge all appended entries into the contiguous representation
+    pub fn compact(&mut self) {
+        if self.appended.is_empty() {
+            return;
+        }
+        let num_nodes = self.num_nodes();
+        let pairs: Vec<(usize, T)> = (0..num_nodes)
+            .flat_map(|node| self.get(node).map(move |e| (node, *e)))
+            .collect();
+        *self = Self::from_pairs(num_nodes, pairs);
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        vec_usage(&self.offsets)
+            + vec_usage(&self.entries)
+            + hash_map_usage(&self.appended)
+            + self
+                .appended
+                .values()
+                .map(|v| v.capacity() * size_of::<T>())
+                .sum::<usize>()
+    }
+}
+
+/// Columnar storage of the attributes of events or objects
+///
+/// The attributes of node `i` are stored at positions `offsets[i]..offsets[i+1]` of the name, value and time columns.
+/// Attribute names are interned.
+/// The time column is only filled for object attributes (event attributes have the timestamp of the event).
+/// New nodes can only be added at the end (as appended events/objects get the next free index).
+#[derive(Debug, Clone)]
+pub struct AttributeColumns {
+    names: StringInterner,
+    offsets: Vec<usize>,
+    name_column: Vec<InternedIndex>,
+    value_column: Vec<OCELAttributeValue>,
+    time_column: Vec<DateTime<FixedOffset>>,
+}
+
+impl Default for AttributeColumns {
+    fn default() -> Self {
+        Self {
+            names: StringInterner::default(),
+            offsets: vec![0],
+            name_column: Vec::new(),
+            value_column: Vec::new(),
+            time_column: Vec::new(),
+        }
+    }
+}
+
+impl AttributeColumns {
+    /// Add the attributes (name, value and, for object attributes, time) of the next node
+    pub fn push_node<N: AsRef<str>>(
+        &mut self,
+        attributes: impl IntoIterator<Item = (N, OCELAttributeValue, Option<DateTime<FixedOffset>>)>,
+    ) {
+        for (name, value, time) in attributes {
+            self.name_column.push(self.names.intern(name.as_ref()));
+            self.value_column.push(value);
+            if let Some(time) = time {
+                self.time_column.push(time);
+            }
+        }
+        self.offsets.push(self.name_column.len());
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn range(&self, node: usize) -> std::ops::Range<usize> {
+        if node + 1 < self.offsets.len() {
+            self.offsets[node]..self.offsets[node + 1]
+        } else {
+            0..0
+        }
+    }
+
+    /// All attributes of a node (in their original order)
+    pub fn get(
+        &self,
+        node: usize,
+    ) -> impl Iterator<Item = (&str, &OCELAttributeValue, Option<&DateTime<FixedOffset>>)> {
+        self.range(node).map(move |i| {
+            (
+                self.names.get(self.name_column[i]),
+                &self.value_column[i],
+                self.time_column.get(i),
+            )
+        })
+    }
+
+    /// All values of an attribute of a node (object attributes can have multiple values)
+    pub fn values<'a>(
+        &'a self,
+        node: usize,
+        name: &str,
+    ) -> impl Iterator<Item = (&'a OCELAttributeValue, Option<&'a DateTime<FixedOffset>>)> + 'a
+    {
+        let name = self.names.lookup(name);
+        self.range(node)
+            .filter(move |i| Some(self.name_column[*i]) == name)
+            .map(move |i| (&self.value_column[i], self.time_column.get(i)))
+    }
+
+    /// The (first) value of an attribute of a node
+    pub fn value(&self, node: usize, name: &str) -> Option<&OCELAttributeValue> {
+        self.values(node, name).next().map(|(value, _)| value)
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.names.memory_usage()
+            + vec_usage(&self.offsets)
+            + vec_usage(&self.name_column)
+            + vec_usage(&self.value_column)
+            + self
+                .value_column
+                .iter()
+                .map(attribute_value_usage)
+                .sum::<usize>()
+            + vec_usage(&self.time_column)
+    }
+}
+
+/// Approximate memory usage of all structures of an [IndexLinkedOCEL](super::linked_ocel::IndexLinkedOCEL) (in bytes)
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OCELMemoryUsage {
+    pub structures: Vec<(String, usize)>,
+    pub total_bytes: usize,
+}
+
+impl OCELMemoryUsage {
+    pub fn new(structures: Vec<(String, usize)>) -> Self {
+        let total_bytes = structures.iter().map(|(_, b)| b).sum();
+        Self {
+            structures,
+            total_bytes,
+        }
+    }
+}
+
+pub fn vec_usage<T>(v: &Vec<T>) -> usize {
+    v.capacity() * size_of::<T>()
+}
+
+pub fn hash_map_usage<K, V>(m: &HashMap<K, V>) -> usize {
+    // One additional control byte per bucket
+    m.capacity() * (size_of::<K>() + size_of::<V>() + 1)
+}
+
+pub fn hash_set_usage<T>(s: &HashSet<T>) -> usize {
+    s.capacity() * (size_of::<T>() + 1)
+}
+
+fn attribute_value_usage(v: &OCELAttributeValue) -> usize {
+    match v {
+        OCELAttributeValue::String(s) => s.capacity(),
+        _ => 0,
+    }
+}
+
+fn relationships_usage(rels: &Vec<OCELRelationship>) -> usize {
+    vec_usage(rels)
+        + rels
+            .iter()
+            .map(|r| r.object_id.capacity() + r.qualifier.capacity())
+            .sum::<usize>()
+}
+
+fn types_usage(types: &Vec<OCELType>) -> usize {
+    vec_usage(types)
+        + types
+            .iter()
+            .map(|t| {
+                t.name.capacity()
+                    + t.attributes.capacity() * size_of::<OCELTypeAttribute>()
+                    + t.attributes
+                        .iter()
+                        .map(|a| a.name.capacity() + a.value_type.capacity())
+                        .sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
+/// Approximate memory usage of the events, objects and types of an OCEL
+pub fn ocel_memory_usage(ocel: &OCEL) -> Vec<(String, usize)> {
+    let events = vec_usage(&ocel.events)
+        + ocel
+            .events
+            .iter()
+            .map(|e: &OCELEvent| {
+                e.id.capacity()
+                    + e.event_type.capacity()
+                    + e.attributes.capacity() * size_of::<OCELEventAttribute>()
+                    + e.attributes
+                        .iter()
+                        .map(|a| a.name.capacity() + attribute_value_usage(&a.value))
+                        .sum::<usize>()
+                    + relationships_usage(&e.relationships)
+            })
+            .sum::<usize>();
+    let objects = vec_usage(&ocel.objects)
+        + ocel
+            .objects
+            .iter()
+            .map(|o: &OCELObject| {
+                o.id.capacity()
+                    + o.object_type.capacity()
+                    + o.attributes.capacity() * size_of::<OCELObjectAttribute>()
+                    + o.attributes
+                        .iter()
+                        .map(|a| a.name.capacity() + attribute_value_usage(&a.value))
+                        .sum::<usize>()
+                    + relationships_usage(&o.relationships)
+            })
+            .sum::<usize>();
+    vec![
+        ("ocel.events".to_string(), events),
+        ("ocel.objects".to_string(), objects),
+        (
+            "ocel.types".to_string(),
+            types_usage(&ocel.event_types) + types_usage(&ocel.object_types),
+        ),
+    ]
+}
+
+#[test]
+fn string_interner_stores_strings_once() {
+    let mut interner = StringInterner::default();
+    let order = interner.intern("order");
+    let item = interner.intern("item");
+    assert_eq!(interner.intern("order"), order);
+    assert_ne!(order, item);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.get(item), "item");
+    assert_eq!(interner.lookup("order"), Some(order));
+    assert_eq!(interner.lookup("missing"), None);
+    assert_eq!(interner.iter().collect::<Vec<_>>(), vec!["order", "item"]);
+}
+
+#[test]
+fn csr_adjacency_sorts_and_deduplicates_entries() {
+    let mut csr = CSRAdjacency::from_pairs(3, vec![(2, 5), (0, 3), (2, 1), (0, 3), (0, 1)]);
+    assert_eq!(csr.num_nodes(), 3);
+    assert_eq!(csr.get(0).copied().collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(csr.get(1).count(), 0);
+    assert_eq!(csr.get(2).copied().collect::<Vec<_>>(), vec![1, 5]);
+    assert_eq!(csr.offsets(), vec![0, 2, 2, 4]);
+
+    // Appended entries (also for new nodes) are visible before compacting
+    csr.push(1, 7);
+    csr.push(0, 3);
+    csr.push(0, 0);
+    csr.push(4, 2);
+    assert_eq!(csr.num_nodes(), 5);
+    assert_eq!(csr.num_entries_of(0), 3);
+    assert_eq!(csr.get(0).copied().collect::<Vec<_>>(), vec![1, 3, 0]);
+    assert_eq!(csr.offsets(), vec![0, 3, 4, 6, 6, 7]);
+
+    csr.compact();
+    assert_eq!(csr.num_nodes(), 5);
+    assert_eq!(csr.get(0).copied().collect::<Vec<_>>(), vec![0, 1, 3]);
+    assert_eq!(csr.get(1).copied().collect::<Vec<_>>(), vec![7]);
+    assert_eq!(csr.get(4).copied().collect::<Vec<_>>(), vec![2]);
+    assert_eq!(csr.offsets(), vec![0, 3, 4, 6, 6, 7]);
+}
+
+#[test]
+fn csr_adjacency_rejects_invalid_raw_parts() {
+    assert!(CSRAdjacency::from_raw_parts(vec![0, 1, 3], vec![1, 2, 3]).is_some());
+    // Does not start at 0
+    assert!(CSRAdjacency::from_raw_parts(vec![1, 3], vec![1, 2, 3]).is_none());
+    // Does not end at the number of entries
+    assert!(CSRAdjacency::from_raw_parts(vec![0, 1, 2], vec![1, 2, 3]).is_none());
+    // Not monotonic
+    assert!(CSRAdjacency::from_raw_parts(vec![0, 2, 1, 3], vec![1, 2, 3]).is_none());
+    assert!(CSRAdjacency::<usize>::from_raw_parts(Vec::new(), Vec::new()).is_none());
+}
+
+#[test]
+fn attribute_columns_store_attributes_per_node() {
+    let t1 = DateTime::from_timestamp(10, 0).unwrap().fixed_offset();
+    let t2 = DateTime::from_timestamp(20, 0).unwrap().fixed_offset();
+    let mut columns = AttributeColumns::default();
+    columns.push_node([
+        ("price", OCELAttributeValue::Integer(5), Some(t2)),
+        (
+            "status",
+            OCELAttributeValue::String("new".to_string()),
+            Some(t1),
+        ),
+        ("price", OCELAttributeValue::Integer(3), Some(t1)),
+    ]);
+    columns.push_node(Vec::<(&str, _, _)>::new());
+    columns.push_node([("price", OCELAttributeValue::Float(1.5), Some(t1))]);
+    assert_eq!(columns.num_nodes(), 3);
+
+    assert_eq!(
+        columns.get(0).collect::<Vec<_>>(),
+        vec![
+            ("price", &OCELAttributeValue::Integer(5), Some(&t2)),
+            (
+                "status",
+                &OCELAttributeValue::String("new".to_string()),
+                Some(&t1)
+            ),
+            ("price", &OCELAttributeValue::Integer(3), Some(&t1)),
+        ]
+    );
+    assert_eq!(
+        columns.values(0, "price").collect::<Vec<_>>(),
+        vec![
+            (&OCELAttributeValue::Integer(5), Some(&t2)),
+            (&OCELAttributeValue::Integer(3), Some(&t1))
+        ]
+    );
+    assert_eq!(
+        columns.value(0, "price"),
+        Some(&OCELAttributeValue::Integer(5))
+    );
+    assert_eq!(columns.value(0, "missing"), None);
+    assert_eq!(columns.get(1).count(), 0);
+    assert_eq!(
+        columns.value(2, "price"),
+        Some(&OCELAttributeValue::Float(1.5))
+    );
+    // Unknown nodes have no attributes
+    assert_eq!(columns.get(3).count(), 0);
+    assert_eq!(columns.value(3, "price"), None);
+}
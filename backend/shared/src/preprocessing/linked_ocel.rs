@@ -4,9 +4,13 @@ use std::{
     time::Instant,
 };
 
+use chrono::{DateTime, FixedOffset};
 use itertools::Itertools;
 use process_mining::{
-    event_log::ocel::ocel_struct::{OCELEvent, OCELObject, OCELRelationship},
+    event_log::ocel::ocel_struct::{
+        OCELAttributeValue, OCELEvent, OCELEventAttribute, OCELObject, OCELObjectAttribute,
+        OCELRelationship, OCELType, OCELTypeAttribute,
+    },
     OCEL,
 };
 use serde::{Deserialize, Serialize};
@@ -16,32 +20,27 @@ use crate::{
     discovery::advanced::EventOrObjectType, ocel_qualifiers::qualifiers::QualifierAndObjectType,
 };
 
+use super::compact::{
+    hash_map_usage, hash_set_usage, ocel_memory_usage, vec_usage, AttributeColumns, CSRAdjacency,
+    OCELMemoryUsage, RelEdge, StringInterner,
+};
+
 pub fn get_object_events_map(
     ocel: &OCEL,
     object_map: &HashMap<String, ObjectIndex>,
-) -> HashMap<ObjectIndex, Vec<EventIndex>> {
-    let mut object_events_map: HashMap<ObjectIndex, Vec<EventIndex>> = ocel
-        .objects
-        .iter()
-        .enumerate()
-        .map(|(index, _)| (ObjectIndex(index), Vec::new()))
-        .collect();
-
+) -> CSRAdjacency<EventIndex> {
+    let mut object_event_pairs = Vec::new();
     for (e_index, e) in ocel.events.iter().enumerate() {
-        let rels = get_event_relationships(e);
-        for r in rels {
-            match object_map
-                .get(&r.object_id)
-                .and_then(|ob_index| object_events_map.get_mut(ob_index))
-            {
-                Some(o_evs) => o_evs.push(EventIndex(e_index)),
+        for r in e.relationships.iter() {
+            match object_map.get(&r.object_id) {
+                Some(ob_index) => object_event_pairs.push((ob_index.0, EventIndex(e_index))),
                 None => {
                     eprintln!("Malformed OCEL: Event {} relates to object ID {}, which does not belong to any object.",e.id,r.object_id)
                 }
             }
         }
     }
-    object_events_map
+    CSRAdjacency::from_pairs(ocel.objects.len(), object_event_pairs)
 }
 
 pub fn get_events_of_type_associated_with_objects(
@@ -59,33 +58,19 @@ pub fn get_events_of_type_associated_with_objects(
             .map(|(e_index, _)| EventIndex(e_index))
             .collect();
     }
-    let mut sorted_object_ids_iter = object_indices.iter().sorted_by(|a, b| {
-        linked_ocel
-            .object_events_map
-            .get(*a)
-            .unwrap()
-            .len()
-            .cmp(&linked_ocel.object_events_map.get(*b).unwrap().len())
-    });
+    let mut sorted_object_ids_iter = object_indices
+        .iter()
+        .sorted_by_key(|o_index| linked_ocel.num_events_of_object(o_index));
 
     let mut intersection: HashSet<EventIndex> = linked_ocel
-        .object_events_map
-        .get(sorted_object_ids_iter.next().unwrap())
-        .unwrap()
-        .iter()
+        .events_of_object(sorted_object_ids_iter.next().unwrap())
         .filter(|e_index| {
             event_types.contains(&linked_ocel.ev_by_index(e_index).unwrap().event_type)
         })
         .copied()
         .collect();
     for other in sorted_object_ids_iter {
-        let other_map: HashSet<EventIndex> = linked_ocel
-            .object_events_map
-            .get(other)
-            .unwrap()
-            .iter()
-            .copied()
-            .collect();
+        let other_map: HashSet<EventIndex> = linked_ocel.events_of_object(other).copied().collect();
         intersection.retain(|ev| other_map.contains(ev))
     }
     intersection.into_iter().collect()
@@ -130,7 +115,7 @@ pub fn get_object_rels_per_type(
 
 #[derive(TS)]
 #[ts(export, export_to = "../../../frontend/src/types/generated/")]
-#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EventIndex(pub usize);
 
 impl Display for EventIndex {
@@ -141,7 +126,7 @@ impl Display for EventIndex {
 
 #[derive(TS)]
 #[ts(export, export_to = "../../../frontend/src/types/generated/")]
-#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ObjectIndex(pub usize);
 impl Display for ObjectIndex {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -149,7 +134,7 @@ impl Display for ObjectIndex {
     }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
 pub enum EventOrObjectIndex {
     Object(ObjectIndex),
     Event(EventIndex),
@@ -171,6 +156,7 @@ impl OCELNode {
     }
 }
 
+/// Reference to an event or object of an [`IndexLinkedOCEL`] (with empty attributes and relationships)
 #[derive(Debug)]
 pub enum OCELNodeRef<'a> {
     Event(&'a OCELEvent),
@@ -187,31 +173,46 @@ impl OCELNodeRef<'_> {
 
 #[derive(Debug, Clone)]
 pub struct IndexLinkedOCEL {
-    pub object_events_map: HashMap<ObjectIndex, Vec<EventIndex>>,
+    // Events associated with an object (indexed by object index); Each event is only listed once (even if it is related through multiple qualifiers)
+    pub object_events_map: CSRAdjacency<EventIndex>,
     pub object_rels_per_type: HashMap<String, HashSet<QualifierAndObjectType>>,
 
     pub events_of_type: HashMap<String, Vec<EventIndex>>,
     pub objects_of_type: HashMap<String, Vec<ObjectIndex>>,
+    // Events and objects (ID, type and time) and their types.
+    // Attributes and relationships are moved into the attribute columns and adjacency arrays when linking;
+    // Use full_event/full_object or to_ocel to get events/objects including them
     pub ocel: OCEL,
     pub event_index_map: HashMap<String, EventIndex>,
     pub object_index_map: HashMap<String, ObjectIndex>,
 
-    // Relations between Objects and Objects/Events; Not symmetric!
-    // String represents relation qualifier
-    pub rels: HashMap<EventOrObjectIndex, Vec<(ObjectIndex, String)>>,
-    // Symmetric relations: Object/Event Index maps to set of associated Object/Event index; boolean flag is true if the relation is reversed and a String for the qualifier
-    pub symmetric_rels: HashMap<EventOrObjectIndex, HashSet<(EventOrObjectIndex, bool, String)>>,
+    // Interned relationship qualifiers
+    pub qualifiers: StringInterner,
+    // Symmetric relations of events/objects (indexed by event/object index); Use get_symmetric_rels to access them
+    pub event_rels: CSRAdjacency<RelEdge>,
+    pub object_rels: CSRAdjacency<RelEdge>,
+    // Attributes of events/objects (indexed by event/object index)
+    pub event_attributes: AttributeColumns,
+    pub object_attributes: AttributeColumns,
 
     pub avg_rels_of_type_per_type: HashMap<EventOrObjectType, f32>,
+    // Total number of relations involving an event/object of a type (used to update avg_rels_of_type_per_type incrementally)
+    pub rel_counts_per_type: HashMap<EventOrObjectType, usize>,
 }
 
 impl IndexLinkedOCEL {
     pub fn new(ocel: OCEL) -> Self {
         link_ocel_info(ocel)
     }
+    /// An event (ID, type and time), with empty attributes and relationships
+    ///
+    /// Use [`IndexLinkedOCEL::full_event`] to get the event including its attributes and relationships.
     pub fn ev_by_index<'a>(&'a self, index: &EventIndex) -> Option<&'a OCELEvent> {
         self.ocel.events.get(index.0)
     }
+    /// An object (ID and type), with empty attributes and relationships
+    ///
+    /// Use [`IndexLinkedOCEL::full_object`] to get the object including its attributes and relationships.
     pub fn ob_by_index<'a>(&'a self, index: &ObjectIndex) -> Option<&'a OCELObject> {
         self.ocel.objects.get(index.0)
     }
@@ -220,11 +221,13 @@ impl IndexLinkedOCEL {
         self.object_index_map.get(ob_id)
     }
 
+    /// An object (ID and type) by its ID, with empty attributes and relationships (see [`IndexLinkedOCEL::ob_by_index`])
     pub fn ob_by_id<'a>(&'a self, ob_id: &String) -> Option<&'a OCELObject> {
         self.index_of_ob(ob_id)
             .and_then(|ob_index| self.ob_by_index(ob_index))
     }
 
+    /// An event (ID, type and time) by its ID, with empty attributes and relationships (see [`IndexLinkedOCEL::ev_by_index`])
     pub fn ev_by_id<'a>(&'a self, ev_id: &String) -> Option<&'a OCELEvent> {
         self.index_of_ev(ev_id)
             .and_then(|ev_index| self.ev_by_index(ev_index))
@@ -234,6 +237,7 @@ impl IndexLinkedOCEL {
         self.event_index_map.get(ev_id)
     }
 
+    /// An event or object, with empty attributes and relationships (see [`IndexLinkedOCEL::ev_by_index`])
     pub fn ob_or_ev_by_index(&self, index: EventOrObjectIndex) -> Option<OCELNodeRef> {
         match index {
             EventOrObjectIndex::Object(ob_index) => {
@@ -244,17 +248,441 @@ impl IndexLinkedOCEL {
             }
         }
     }
+    /// Symmetric relations of an event/object: Associated event/object index,
+    /// a boolean flag which is true if the relation is reversed and the qualifier
+    pub fn get_symmetric_rels(
+        &self,
+        index: &EventOrObjectIndex,
+    ) -> impl Iterator<Item = (EventOrObjectIndex, bool, &str)> {
+        let rels = match index {
+            EventOrObjectIndex::Event(ev_index) => self.event_rels.get(ev_index.0),
+            EventOrObjectIndex::Object(ob_index) => self.object_rels.get(ob_index.0),
+        };
+        rels.map(move |r| (r.to, r.reversed, self.qualifiers.get(r.qualifier)))
+    }
     pub fn get_symmetric_rels_ob(
         &self,
         index: &ObjectIndex,
-    ) -> Option<&HashSet<(EventOrObjectIndex, bool, String)>> {
-        self.symmetric_rels.get(&EventOrObjectIndex::Object(*index))
+    ) -> impl Iterator<Item = (EventOrObjectIndex, bool, &str)> {
+        self.get_symmetric_rels(&EventOrObjectIndex::Object(*index))
     }
     pub fn get_symmetric_rels_ev(
         &self,
         index: &EventIndex,
-    ) -> Option<&HashSet<(EventOrObjectIndex, bool, String)>> {
-        self.symmetric_rels.get(&EventOrObjectIndex::Event(*index))
+    ) -> impl Iterator<Item = (EventOrObjectIndex, bool, &str)> {
+        self.get_symmetric_rels(&EventOrObjectIndex::Event(*index))
+    }
+    pub fn num_symmetric_rels(&self, index: &EventOrObjectIndex) -> usize {
+        match index {
+            EventOrObjectIndex::Event(ev_index) => self.event_rels.num_entries_of(ev_index.0),
+            EventOrObjectIndex::Object(ob_index) => self.object_rels.num_entries_of(ob_index.0),
+        }
+    }
+    /// Relations from an event/object to objects (i.e., E2O or O2O relationships); Not symmetric!
+    ///
+    /// Relations are sorted by object index, and duplicate relationships (same object and qualifier) are only included once.
+    pub fn get_rels(
+        &self,
+        index: &EventOrObjectIndex,
+    ) -> impl Iterator<Item = (ObjectIndex, &str)> {
+        self.get_symmetric_rels(index)
+            .filter_map(|(to, reversed, qualifier)| match to {
+                EventOrObjectIndex::Object(ob_index) if !reversed => Some((ob_index, qualifier)),
+                _ => None,
+            })
+    }
+    /// Events related to an object, sorted by event index
+    ///
+    /// Each event is included once, even if it is related to the object through multiple qualifiers.
+    pub fn events_of_object(&self, index: &ObjectIndex) -> impl Iterator<Item = &EventIndex> {
+        self.object_events_map.get(index.0)
+    }
+    pub fn num_events_of_object(&self, index: &ObjectIndex) -> usize {
+        self.object_events_map.num_entries_of(index.0)
+    }
+
+    /// Attributes of an event (name and value)
+    pub fn ev_attributes(
+        &self,
+        index: &EventIndex,
+    ) -> impl Iterator<Item = (&str, &OCELAttributeValue)> {
+        self.event_attributes
+            .get(index.0)
+            .map(|(name, value, _)| (name, value))
+    }
+
+    /// Value of an event attribute
+    pub fn ev_attribute(&self, index: &EventIndex, name: &str) -> Option<&OCELAttributeValue> {
+        self.event_attributes.value(index.0, name)
+    }
+
+    /// Attributes of an object (name, value and time of the value)
+    ///
+    /// Attributes with multiple values over time are included once per value.
+    pub fn ob_attributes(
+        &self,
+        index: &ObjectIndex,
+    ) -> impl Iterator<Item = (&str, &OCELAttributeValue, &DateTime<FixedOffset>)> {
+        self.object_attributes
+            .get(index.0)
+            .filter_map(|(name, value, time)| time.map(|time| (name, value, time)))
+    }
+
+    /// All values of an object attribute, with the time from which on they are valid
+    pub fn ob_attribute_values<'a>(
+        &'a self,
+        index: &ObjectIndex,
+        name: &str,
+    ) -> impl Iterator<Item = (&'a OCELAttributeValue, &'a DateTime<FixedOffset>)> + 'a {
+        self.object_attributes
+            .values(index.0, name)
+            .filter_map(|(value, time)| time.map(|time| (value, time)))
+    }
+
+    /// First value of an object attribute
+    pub fn ob_attribute(&self, index: &ObjectIndex, name: &str) -> Option<&OCELAttributeValue> {
+        self.object_attributes.value(index.0, name)
+    }
+
+    /// Attribute names and (first) values of an event or object
+    pub fn attributes_of(
+        &self,
+        index: &EventOrObjectIndex,
+    ) -> impl Iterator<Item = (&str, &OCELAttributeValue)> {
+        match index {
+            EventOrObjectIndex::Event(ev_index) => self.event_attributes.get(ev_index.0),
+            EventOrObjectIndex::Object(ob_index) => self.object_attributes.get(ob_index.0),
+        }
+        .map(|(name, value, _)| (name, value))
+    }
+
+    /// Relationships of an event/object, reconstructed from the adjacency arrays (see [IndexLinkedOCEL::get_rels])
+    pub fn relationships_of(&self, index: &EventOrObjectIndex) -> Vec<OCELRelationship> {
+        self.get_rels(index)
+            .map(|(ob_index, qualifier)| OCELRelationship {
+                object_id: self.ocel.objects[ob_index.0].id.clone(),
+                qualifier: qualifier.to_string(),
+            })
+            .collect()
+    }
+
+    /// An event including its attributes and relationships
+    pub fn full_event(&self, index: &EventIndex) -> Option<OCELEvent> {
+        let mut ev = self.ev_by_index(index)?.clone();
+        ev.attributes = self
+            .ev_attributes(index)
+            .map(|(name, value)| OCELEventAttribute {
+                name: name.to_string(),
+                value: value.clone(),
+            })
+            .collect();
+        ev.relationships = self.relationships_of(&EventOrObjectIndex::Event(*index));
+        Some(ev)
+    }
+
+    /// An object including its attributes and relationships
+    pub fn full_object(&self, index: &ObjectIndex) -> Option<OCELObject> {
+        let mut ob = self.ob_by_index(index)?.clone();
+        ob.attributes = self
+            .ob_attributes(index)
+            .map(|(name, value, time)| OCELObjectAttribute {
+                name: name.to_string(),
+                value: value.clone(),
+                time: (*time).into(),
+            })
+            .collect();
+        ob.relationships = self.relationships_of(&EventOrObjectIndex::Object(*index));
+        Some(ob)
+    }
+
+    /// Reconstruct the full OCEL (including attributes and relationships)
+    pub fn to_ocel(&self) -> OCEL {
+        OCEL {
+            event_types: self.ocel.event_types.clone(),
+            object_types: self.ocel.object_types.clone(),
+            events: (0..self.ocel.events.len())
+                .filter_map(|i| self.full_event(&EventIndex(i)))
+                .collect(),
+            objects: (0..self.ocel.objects.len())
+                .filter_map(|i| self.full_object(&ObjectIndex(i)))
+                .collect(),
+        }
+    }
+
+    /// Merge relations added by appending events/objects into the contiguous adjacency arrays
+    pub fn compact(&mut self) {
+        self.object_events_map.compact();
+        self.event_rels.compact();
+        self.object_rels.compact();
+    }
+
+    /// Report the (approximate) memory usage of all structures
+    pub fn memory_usage(&self) -> OCELMemoryUsage {
+        let mut structures = ocel_memory_usage(&self.ocel);
+        structures.extend([
+            (
+                "object_events_map".to_string(),
+                self.object_events_map.memory_usage(),
+            ),
+            ("event_rels".to_string(), self.event_rels.memory_usage()),
+            ("object_rels".to_string(), self.object_rels.memory_usage()),
+            ("qualifiers".to_string(), self.qualifiers.memory_usage()),
+            (
+                "event_attributes".to_string(),
+                self.event_attributes.memory_usage(),
+            ),
+            (
+                "object_attributes".to_string(),
+                self.object_attributes.memory_usage(),
+            ),
+            (
+                "events_of_type".to_string(),
+                hash_map_usage(&self.events_of_type)
+                    + self
+                        .events_of_type
+                        .iter()
+                        .map(|(t, evs)| t.capacity() + vec_usage(evs))
+                        .sum::<usize>(),
+            ),
+            (
+                "objects_of_type".to_string(),
+                hash_map_usage(&self.objects_of_type)
+                    + self
+                        .objects_of_type
+                        .iter()
+                        .map(|(t, obs)| t.capacity() + vec_usage(obs))
+                        .sum::<usize>(),
+            ),
+            (
+                "event_index_map".to_string(),
+                hash_map_usage(&self.event_index_map)
+                    + self
+                        .event_index_map
+                        .keys()
+                        .map(|id| id.capacity())
+                        .sum::<usize>(),
+            ),
+            (
+                "object_index_map".to_string(),
+                hash_map_usage(&self.object_index_map)
+                    + self
+                        .object_index_map
+                        .keys()
+                        .map(|id| id.capacity())
+                        .sum::<usize>(),
+            ),
+            (
+                "object_rels_per_type".to_string(),
+                hash_map_usage(&self.object_rels_per_type)
+                    + self
+                        .object_rels_per_type
+                        .values()
+                        .map(hash_set_usage)
+                        .sum::<usize>(),
+            ),
+            (
+                "rel_counts_per_type".to_string(),
+                hash_map_usage(&self.avg_rels_of_type_per_type)
+                    + hash_map_usage(&self.rel_counts_per_type),
+            ),
+        ]);
+        OCELMemoryUsage::new(structures)
+    }
+
+    /// Append new objects, incrementally updating all indices
+    ///
+    /// Objects with an already present ID are skipped.
+    /// O2O relationships of the new objects are linked (also between the newly appended objects).
+    /// Relationships of already present objects/events are not re-linked,
+    /// so objects should be appended before (or together with) the events referencing them.
+    ///
+    /// Returns the indices of the appended objects
+    pub fn append_objects(&mut self, objects: Vec<OCELObject>) -> Vec<ObjectIndex> {
+        let mut new_indices = Vec::with_capacity(objects.len());
+        let mut new_relationships = Vec::with_capacity(objects.len());
+        for mut o in objects {
+            if self.object_index_map.contains_key(&o.id) {
+                eprintln!("Object with ID {} already exists; Skipping.", o.id);
+                continue;
+            }
+            let o_index = ObjectIndex(self.ocel.objects.len());
+            if !self.objects_of_type.contains_key(&o.object_type) {
+                self.ocel.object_types.push(get_ocel_type(
+                    &o.object_type,
+                    o.attributes.iter().map(|a| (&a.name, &a.value)),
+                ));
+                self.object_rels_per_type
+                    .insert(o.object_type.clone(), HashSet::new());
+            }
+            self.objects_of_type
+                .entry(o.object_type.clone())
+                .or_default()
+                .push(o_index);
+            self.object_index_map.insert(o.id.clone(), o_index);
+            self.object_attributes.push_node(
+                std::mem::take(&mut o.attributes)
+                    .into_iter()
+                    .map(|a| (a.name, a.value, Some(a.time.fixed_offset()))),
+            );
+            new_relationships.push(std::mem::take(&mut o.relationships));
+            self.ocel.objects.push(o);
+            new_indices.push(o_index);
+        }
+
+        let mut changed_types: HashSet<EventOrObjectType> = new_indices
+            .iter()
+            .map(|o_index| {
+                EventOrObjectType::Object(self.ocel.objects[o_index.0].object_type.clone())
+            })
+            .collect();
+        let mut new_rels = Vec::new();
+        for (o_index, relationships) in new_indices.iter().zip(new_relationships) {
+            let o = &self.ocel.objects[o_index.0];
+            for r in relationships {
+                match self.object_index_map.get(&r.object_id) {
+                    Some(rel_ob_index) => new_rels.push((
+                        EventOrObjectIndex::Object(*o_index),
+                        EventOrObjectType::Object(o.object_type.clone()),
+                        *rel_ob_index,
+                        r.qualifier,
+                    )),
+                    None => {
+                        eprintln!("Malformed OCEL: Object {} has relationship to object ID {}, which does not belong to any object",o.id, r.object_id);
+                    }
+                }
+            }
+        }
+        for (from_index, from_type, to_index, qualifier) in new_rels {
+            let to_type = self.ocel.objects[to_index.0].object_type.clone();
+            self.object_rels_per_type
+                .entry(from_type.inner().clone())
+                .or_default()
+                .insert((qualifier.clone(), to_type.clone()));
+            changed_types.insert(EventOrObjectType::Object(to_type.clone()));
+            self.link_relationship(from_index, from_type, to_index, to_type, qualifier);
+        }
+        self.update_avg_rels_of_types(changed_types);
+        new_indices
+    }
+
+    /// Append new events, incrementally updating all indices
+    ///
+    /// Events with an already present ID are skipped.
+    /// E2O relationships to objects which are not (yet) part of the OCEL are ignored.
+    ///
+    /// Returns the indices of the appended events
+    pub fn append_events(&mut self, events: Vec<OCELEvent>) -> Vec<EventIndex> {
+        let mut new_indices = Vec::with_capacity(events.len());
+        let mut changed_types = HashSet::new();
+        for mut e in events {
+            if self.event_index_map.contains_key(&e.id) {
+                eprintln!("Event with ID {} already exists; Skipping.", e.id);
+                continue;
+            }
+            let e_index = EventIndex(self.ocel.events.len());
+            if !self.events_of_type.contains_key(&e.event_type) {
+                self.ocel.event_types.push(get_ocel_type(
+                    &e.event_type,
+                    e.attributes.iter().map(|a| (&a.name, &a.value)),
+                ));
+            }
+            self.events_of_type
+                .entry(e.event_type.clone())
+                .or_default()
+                .push(e_index);
+            self.event_index_map.insert(e.id.clone(), e_index);
+            let mut new_rels = Vec::new();
+            for r in std::mem::take(&mut e.relationships) {
+                match self.object_index_map.get(&r.object_id) {
+                    Some(ob_index) => new_rels.push((*ob_index, r.qualifier)),
+                    None => {
+                        eprintln!("Malformed OCEL: Event {} relates to object ID {}, which does not belong to any object.",e.id,r.object_id)
+                    }
+                }
+            }
+            let e_type = EventOrObjectType::Event(e.event_type.clone());
+            changed_types.insert(e_type.clone());
+            self.event_attributes.push_node(
+                std::mem::take(&mut e.attributes)
+                    .into_iter()
+                    .map(|a| (a.name, a.value, None)),
+            );
+            self.ocel.events.push(e);
+            for (ob_index, qualifier) in new_rels {
+                self.object_events_map.push(ob_index.0, e_index);
+                let to_type = self.ocel.objects[ob_index.0].object_type.clone();
+                changed_types.insert(EventOrObjectType::Object(to_type.clone()));
+                self.link_relationship(
+                    EventOrObjectIndex::Event(e_index),
+                    e_type.clone(),
+                    ob_index,
+                    to_type,
+                    qualifier,
+                );
+            }
+            new_indices.push(e_index);
+        }
+        self.update_avg_rels_of_types(changed_types);
+        new_indices
+    }
+
+    fn link_relationship(
+        &mut self,
+        from_index: EventOrObjectIndex,
+        from_type: EventOrObjectType,
+        to_index: ObjectIndex,
+        to_type: String,
+        qualifier: String,
+    ) {
+        let qualifier = self.qualifiers.intern(&qualifier);
+        let edge = RelEdge {
+            to: EventOrObjectIndex::Object(to_index),
+            reversed: false,
+            qualifier,
+        };
+        match from_index {
+            EventOrObjectIndex::Event(ev_index) => self.event_rels.push(ev_index.0, edge),
+            EventOrObjectIndex::Object(ob_index) => self.object_rels.push(ob_index.0, edge),
+        }
+        self.object_rels.push(
+            to_index.0,
+            RelEdge {
+                to: from_index,
+                reversed: true,
+                qualifier,
+            },
+        );
+        *self.rel_counts_per_type.entry(from_type).or_default() += 1;
+        *self
+            .rel_counts_per_type
+            .entry(EventOrObjectType::Object(to_type))
+            .or_default() += 1;
+    }
+
+    fn update_avg_rels_of_types(&mut self, types: HashSet<EventOrObjectType>) {
+        for t in types {
+            let count = self
+                .rel_counts_per_type
+                .get(&t)
+                .copied()
+                .unwrap_or_default();
+            let n = match &t {
+                EventOrObjectType::Event(et) => self
+                    .events_of_type
+                    .get(et)
+                    .map(|e| e.len())
+                    .unwrap_or_default(),
+                EventOrObjectType::Object(ot) => self
+                    .objects_of_type
+                    .get(ot)
+                    .map(|o| o.len())
+                    .unwrap_or_default(),
+            };
+            if count > 0 && n > 0 {
+                self.avg_rels_of_type_per_type
+                    .insert(t, count as f32 / n as f32);
+            }
+        }
     }
 
     pub fn get_type_of(&self, index: EventOrObjectIndex) -> Option<EventOrObjectType> {
@@ -293,7 +721,7 @@ impl IndexLinkedOCEL {
     // }
 }
 
-pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
+pub fn link_ocel_info(mut ocel: OCEL) -> IndexLinkedOCEL {
     let now = Instant::now();
     let object_map: HashMap<String, &OCELObject> = ocel
         .objects
@@ -347,30 +775,31 @@ pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
         .collect();
     let object_events_map = get_object_events_map(&ocel, &object_index_map);
     let object_rels_per_type = get_object_rels_per_type(&ocel, &object_map);
-    let mut rels: HashMap<EventOrObjectIndex, Vec<(ObjectIndex, String)>> = HashMap::new();
-    let mut symmetric_rels: HashMap<
-        EventOrObjectIndex,
-        HashSet<(EventOrObjectIndex, bool, String)>,
-    > = HashMap::new();
+    let mut qualifiers = StringInterner::default();
+    let mut event_rel_pairs: Vec<(usize, RelEdge)> = Vec::new();
+    let mut object_rel_pairs: Vec<(usize, RelEdge)> = Vec::new();
     let mut types_rel_counts: HashMap<EventOrObjectType, usize> = HashMap::new();
     for (e_index_usize, e) in ocel.events.iter().enumerate() {
         let e_index = EventOrObjectIndex::Event(EventIndex(e_index_usize));
         for r in e.relationships.iter() {
             if let Some(object_index) = object_index_map.get(&r.object_id) {
-                let o2_index = EventOrObjectIndex::Object(*object_index);
-                symmetric_rels.entry(e_index).or_default().insert((
-                    o2_index,
-                    false,
-                    r.qualifier.clone(),
+                let qualifier = qualifiers.intern(&r.qualifier);
+                event_rel_pairs.push((
+                    e_index_usize,
+                    RelEdge {
+                        to: EventOrObjectIndex::Object(*object_index),
+                        reversed: false,
+                        qualifier,
+                    },
                 ));
-                symmetric_rels.entry(o2_index).or_default().insert((
-                    e_index,
-                    true,
-                    r.qualifier.clone(),
+                object_rel_pairs.push((
+                    object_index.0,
+                    RelEdge {
+                        to: e_index,
+                        reversed: true,
+                        qualifier,
+                    },
                 ));
-                rels.entry(e_index)
-                    .or_default()
-                    .push((*object_index, r.qualifier.clone()));
                 *types_rel_counts
                     .entry(EventOrObjectType::Event(e.event_type.clone()))
                     .or_default() += 1;
@@ -386,20 +815,23 @@ pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
         let o_index = EventOrObjectIndex::Object(ObjectIndex(o_index_usize));
         for r in o.relationships.iter() {
             if let Some(object_index) = object_index_map.get(&r.object_id) {
-                let o2_index = EventOrObjectIndex::Object(*object_index);
-                symmetric_rels.entry(o_index).or_default().insert((
-                    o2_index,
-                    false,
-                    r.qualifier.clone(),
+                let qualifier = qualifiers.intern(&r.qualifier);
+                object_rel_pairs.push((
+                    o_index_usize,
+                    RelEdge {
+                        to: EventOrObjectIndex::Object(*object_index),
+                        reversed: false,
+                        qualifier,
+                    },
                 ));
-                symmetric_rels.entry(o2_index).or_default().insert((
-                    o_index,
-                    true,
-                    r.qualifier.clone(),
+                object_rel_pairs.push((
+                    object_index.0,
+                    RelEdge {
+                        to: o_index,
+                        reversed: true,
+                        qualifier,
+                    },
                 ));
-                rels.entry(o_index)
-                    .or_default()
-                    .push((*object_index, r.qualifier.clone()));
                 *types_rel_counts
                     .entry(EventOrObjectType::Object(o.object_type.clone()))
                     .or_default() += 1;
@@ -412,7 +844,7 @@ pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
         }
     }
     let avg_rels_of_type_per_type = types_rel_counts
-        .into_iter()
+        .iter()
         .map(|(t, count)| {
             let n = match &t {
                 EventOrObjectType::Event(et1) => {
@@ -424,9 +856,31 @@ pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
                     .unwrap_or_default(),
             };
 
-            (t, count as f32 / n as f32)
+            (t.clone(), *count as f32 / n as f32)
         })
         .collect();
+    let num_events = ocel.events.len();
+    let num_objects = ocel.objects.len();
+    // Attributes are stored column-wise and relationships are already part of the adjacency arrays,
+    // so both are removed from the events/objects
+    let mut event_attributes = AttributeColumns::default();
+    for e in ocel.events.iter_mut() {
+        event_attributes.push_node(
+            std::mem::take(&mut e.attributes)
+                .into_iter()
+                .map(|a| (a.name, a.value, None)),
+        );
+        e.relationships = Vec::new();
+    }
+    let mut object_attributes = AttributeColumns::default();
+    for o in ocel.objects.iter_mut() {
+        object_attributes.push_node(
+            std::mem::take(&mut o.attributes)
+                .into_iter()
+                .map(|a| (a.name, a.value, Some(a.time.fixed_offset()))),
+        );
+        o.relationships = Vec::new();
+    }
     println!("Linking OCEL took {:?}", now.elapsed());
     IndexLinkedOCEL {
         events_of_type,
@@ -436,8 +890,207 @@ pub fn link_ocel_info(ocel: OCEL) -> IndexLinkedOCEL {
         ocel,
         event_index_map,
         object_index_map,
-        rels,
-        symmetric_rels,
+        qualifiers,
+        event_rels: CSRAdjacency::from_pairs(num_events, event_rel_pairs),
+        object_rels: CSRAdjacency::from_pairs(num_objects, object_rel_pairs),
+        event_attributes,
+        object_attributes,
         avg_rels_of_type_per_type,
+        rel_counts_per_type: types_rel_counts,
+    }
+}
+
+/// Construct a new [OCELType] with the attributes (and their value types) of a first event/object of that type
+fn get_ocel_type<'a>(
+    name: &str,
+    attributes: impl Iterator<Item = (&'a String, &'a OCELAttributeValue)>,
+) -> OCELType {
+    OCELType {
+        name: name.to_string(),
+        attributes: attributes
+            .map(|(attr_name, value)| OCELTypeAttribute {
+                name: attr_name.clone(),
+                value_type: match value {
+                    OCELAttributeValue::Time(_) => "time",
+                    OCELAttributeValue::Integer(_) => "integer",
+                    OCELAttributeValue::Float(_) => "float",
+                    OCELAttributeValue::Boolean(_) => "boolean",
+                    OCELAttributeValue::String(_) | OCELAttributeValue::Null => "string",
+                }
+                .to_string(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn appending_is_consistent_with_linking_at_once() {
+    use crate::{preprocessing::tests::test_ocel, AppendToOCELCounts, AppendToOCELRequest};
+
+    let events = vec![
+        (
+            "e1",
+            "place order",
+            0,
+            vec![("o1", "order"), ("i1", "item")],
+        ),
+        ("e2", "pay order", 10, vec![("o1", "order")]),
+        ("e3", "pick item", 20, vec![("i2", "item"), ("o2", "order")]),
+        (
+            "e4",
+            "pay order",
+            30,
+            vec![("o2", "order"), ("missing", "order")],
+        ),
+    ];
+    let objects = vec![
+        ("o1", "order", vec![("i1", "contains")]),
+        ("i1", "item", vec![]),
+        ("o2", "order", vec![("i2", "contains"), ("i1", "contains")]),
+        ("i2", "item", vec![("o2", "part of")]),
+    ];
+    let full = IndexLinkedOCEL::new(test_ocel(events.clone(), objects.clone()));
+    let mut appended = IndexLinkedOCEL::new(test_ocel(events[..2].to_vec(), objects[..2].to_vec()));
+    let rest = test_ocel(events[2..].to_vec(), objects[1..].to_vec());
+    let counts = AppendToOCELRequest {
+        objects: rest.objects,
+        events: rest.events,
     }
+    .apply(&mut appended);
+    // i1 is already present
+    assert_eq!(
+        counts,
+        AppendToOCELCounts {
+            num_objects: 2,
+            num_events: 2
+        }
+    );
+
+    let sorted_rels = |ocel: &IndexLinkedOCEL, index: EventOrObjectIndex| {
+        ocel.get_symmetric_rels(&index)
+            .map(|(to, reversed, q)| (to, reversed, q.to_string()))
+            .sorted()
+            .collect_vec()
+    };
+    let assert_consistent = |appended: &IndexLinkedOCEL| {
+        assert_eq!(appended.events_of_type, full.events_of_type);
+        assert_eq!(appended.objects_of_type, full.objects_of_type);
+        assert_eq!(appended.event_index_map, full.event_index_map);
+        assert_eq!(appended.object_index_map, full.object_index_map);
+        assert_eq!(appended.object_rels_per_type, full.object_rels_per_type);
+        assert_eq!(appended.rel_counts_per_type, full.rel_counts_per_type);
+        assert_eq!(
+            appended.avg_rels_of_type_per_type,
+            full.avg_rels_of_type_per_type
+        );
+        for i in 0..full.ocel.events.len() {
+            let index = EventOrObjectIndex::Event(EventIndex(i));
+            assert_eq!(sorted_rels(appended, index), sorted_rels(&full, index));
+        }
+        for i in 0..full.ocel.objects.len() {
+            let index = EventOrObjectIndex::Object(ObjectIndex(i));
+            assert_eq!(sorted_rels(appended, index), sorted_rels(&full, index));
+            assert_eq!(
+                appended
+                    .events_of_object(&ObjectIndex(i))
+                    .sorted()
+                    .collect_vec(),
+                full.events_of_object(&ObjectIndex(i)).collect_vec()
+            );
+        }
+    };
+    assert_consistent(&appended);
+    appended.compact();
+    assert_consistent(&appended);
+}
+
+#[test]
+fn linking_moves_attributes_and_relationships_into_compact_structures() {
+    use crate::preprocessing::tests::test_ocel;
+
+    let mut ocel = test_ocel(
+        vec![
+            (
+                "e1",
+                "place order",
+                0,
+                vec![("o1", "order"), ("o1", "order"), ("o1", "paid order")],
+            ),
+            ("e2", "pay order", 10, vec![("o1", "order"), ("i1", "item")]),
+        ],
+        vec![
+            ("o1", "order", vec![("i1", "contains")]),
+            ("i1", "item", vec![]),
+        ],
+    );
+    let time = |secs| {
+        DateTime::from_timestamp(secs, 0)
+            .unwrap()
+            .with_timezone(&FixedOffset::east_opt(0).unwrap())
+    };
+    ocel.events[0].attributes.push(OCELEventAttribute {
+        name: "price".to_string(),
+        value: OCELAttributeValue::Float(9.5),
+    });
+    ocel.objects[0].attributes.extend([
+        OCELObjectAttribute {
+            name: "status".to_string(),
+            value: OCELAttributeValue::String("paid".to_string()),
+            time: time(10).into(),
+        },
+        OCELObjectAttribute {
+            name: "status".to_string(),
+            value: OCELAttributeValue::String("new".to_string()),
+            time: time(0).into(),
+        },
+    ]);
+    let linked = IndexLinkedOCEL::new(ocel.clone());
+
+    assert!(linked
+        .ocel
+        .events
+        .iter()
+        .all(|e| e.attributes.is_empty() && e.relationships.is_empty()));
+    assert!(linked
+        .ocel
+        .objects
+        .iter()
+        .all(|o| o.attributes.is_empty() && o.relationships.is_empty()));
+
+    let (e1, e2) = (EventIndex(0), EventIndex(1));
+    let (o1, i1) = (ObjectIndex(0), ObjectIndex(1));
+    assert_eq!(
+        linked.ev_attribute(&e1, "price"),
+        Some(&OCELAttributeValue::Float(9.5))
+    );
+    assert_eq!(linked.ev_attribute(&e2, "price"), None);
+    assert_eq!(
+        linked.ob_attribute(&o1, "status"),
+        Some(&OCELAttributeValue::String("paid".to_string()))
+    );
+    assert_eq!(linked.ob_attribute_values(&o1, "status").count(), 2);
+
+    // The duplicate relationship is only included once, the same object with another qualifier is kept
+    assert_eq!(
+        linked
+            .get_rels(&EventOrObjectIndex::Event(e1))
+            .collect_vec(),
+        vec![(o1, "order"), (o1, "paid order")]
+    );
+    // Each event is only included once per object
+    assert_eq!(linked.events_of_object(&o1).collect_vec(), vec![&e1, &e2]);
+    assert_eq!(linked.num_events_of_object(&o1), 2);
+    assert_eq!(linked.events_of_object(&i1).collect_vec(), vec![&e2]);
+
+    // The full OCEL can be reconstructed (without duplicate relationships)
+    ocel.events[0].relationships.remove(1);
+    let restored = linked.to_ocel();
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&ocel).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(linked.full_object(&o1).unwrap()).unwrap(),
+        serde_json::to_value(&ocel.objects[0]).unwrap()
+    );
 }
@@ -0,0 +1,913 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, FixedOffset, Offset, TimeZone};
+use process_mining::{
+    ocel::ocel_struct::{OCELAttributeValue, OCELEvent, OCELObject, OCELType, OCELTypeAttribute},
+    OCEL,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::advanced::EventOrObjectType;
+
+use super::{
+    compact::{AttributeColumns, CSRAdjacency, InternedIndex, RelEdge, StringInterner},
+    linked_ocel::{EventIndex, EventOrObjectIndex, IndexLinkedOCEL, ObjectIndex},
+    validation::{
+        validate_and_link_ocel, OCELValidationReport, ValidationAction, ValidationResult,
+    },
+};
+
+/// Magic bytes at the start of every snapshot file
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"OCPQSNAP";
+/// Version of the snapshot format
+///
+/// Must be increased on every change to the layout, so that outdated cached snapshots are ignored
+pub const SNAPSHOT_VERSION: u32 = 1;
+/// File extension of snapshot files
+pub const SNAPSHOT_EXTENSION: &str = "ocpqsnap";
+/// Maximum number of entries for which space is reserved up front when reading a length from a snapshot
+///
+/// Larger collections grow while reading, so that corrupted lengths cannot trigger huge allocations
+const MAX_PREALLOCATED_ENTRIES: usize = 1 << 16;
+/// Environment variable which can be used to override the snapshot cache directory
+pub const SNAPSHOT_CACHE_DIR_ENV: &str = "OCPQ_SNAPSHOT_CACHE_DIR";
+
+/// Directory in which snapshots of imported OCEL files are cached
+///
+/// Uses the `OCPQ_SNAPSHOT_CACHE_DIR` environment variable if set, and `<data dir>/ocpq/snapshots` otherwise
+pub fn get_snapshot_cache_dir() -> Option<PathBuf> {
+    match std::env::var_os(SNAPSHOT_CACHE_DIR_ENV) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs_next::data_dir().map(|p| p.join("ocpq").join("snapshots")),
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Update a 64-bit FNV-1a hash with the given bytes
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Hash of the contents of a file (64-bit FNV-1a)
+///
+/// Used as key for cached snapshots. Unlike [std::hash::DefaultHasher], the result is stable across Rust versions.
+pub fn hash_file(path: impl AsRef<Path>) -> io::Result<u64> {
+    let mut reader = File::open(path)?;
+    let mut buf = vec![0; 1 << 20];
+    let mut hash = FNV_OFFSET;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hash = fnv1a(hash, &buf[..n]);
+    }
+    Ok(hash)
+}
+
+/// Content hash of a source file, stored together with the size and modification time of the file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SourceFileHash {
+    path: PathBuf,
+    size: u64,
+    modified: Duration,
+    hash: u64,
+}
+
+/// Hash of the contents of the source file at `path` (see [hash_file])
+///
+/// Hashes are stored in the `sources` subdirectory of `cache_dir` per path,
+/// so that files are only hashed again if their size or modification time changed.
+pub fn get_source_hash(path: impl AsRef<Path>, cache_dir: Option<&Path>) -> io::Result<u64> {
+    let path = path.as_ref().canonicalize()?;
+    let metadata = std::fs::metadata(&path)?;
+    let size = metadata.len();
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let stored_path = cache_dir.map(|dir| {
+        let path_hash = fnv1a(FNV_OFFSET, path.to_string_lossy().as_bytes());
+        dir.join("sources").join(format!("{path_hash:016x}.json"))
+    });
+    let stored = stored_path
+        .as_ref()
+        .and_then(|p| File::open(p).ok())
+        .and_then(|f| serde_json::from_reader::<_, SourceFileHash>(BufReader::new(f)).ok());
+    if let Some(stored) = stored {
+        if stored.path == path && stored.size == size && stored.modified == modified {
+            return Ok(stored.hash);
+        }
+    }
+    let hash = hash_file(&path)?;
+    if let Some(stored_path) = stored_path {
+        let source_hash = SourceFileHash {
+            path,
+            size,
+            modified,
+            hash,
+        };
+        let res = stored_path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .transpose()
+            .map_err(Error::from)
+            .and_then(|_| {
+                let mut writer = BufWriter::new(File::create(&stored_path)?);
+                serde_json::to_writer(&mut writer, &source_hash)?;
+                Ok(writer.flush()?)
+            });
+        if let Err(e) = res {
+            eprintln!("Could not store hash of source file {stored_path:?}: {e:?}");
+        }
+    }
+    Ok(hash)
+}
+
+/// Path of the cached snapshot for a source OCEL file with the given hash
+///
+/// Snapshots are cached per [ValidationAction], as they contain the (possibly repaired) OCEL
+pub fn get_snapshot_cache_path(source_hash: u64, action: ValidationAction) -> Option<PathBuf> {
+    let action = format!("{action:?}").to_lowercase();
+    get_snapshot_cache_dir()
+        .map(|dir| dir.join(format!("{source_hash:016x}-{action}.{SNAPSHOT_EXTENSION}")))
+}
+
+/// Load an OCEL file using the snapshot cache
+///
+/// If a snapshot for the contents of the file at `path` (and the validation action) exists in the cache directory
+/// (see [get_source_hash]),
+/// it is loaded instead of importing the file.
+/// Otherwise, the OCEL is imported using `import`, validated and linked (see [validate_and_link_ocel]),
+/// and a snapshot of it is written to the cache directory (unless it was rejected).
+/// Invalid or outdated snapshots are ignored (and overwritten).
+pub fn load_ocel_with_snapshot_cache<F>(
+    path: impl AsRef<Path>,
+    import: F,
+    action: ValidationAction,
+) -> Result<ValidationResult, Error>
+where
+    F: FnOnce(&Path) -> Result<OCEL, Error>,
+{
+    let path = path.as_ref();
+    let cache_path = get_source_hash(path, get_snapshot_cache_dir().as_deref())
+        .ok()
+        .and_then(|hash| get_snapshot_cache_path(hash, action));
+    if let Some(cache_path) = &cache_path {
+        if cache_path.is_file() {
+            let now = Instant::now();
+            match load_snapshot(cache_path) {
+                Ok(res) => {
+                    println!("Loaded OCEL snapshot {cache_path:?} in {:?}", now.elapsed());
+                    return Ok(Ok(res));
+                }
+                Err(e) => eprintln!("Ignoring OCEL snapshot {cache_path:?}: {e:?}"),
+            }
+        }
+    }
+    let res = validate_and_link_ocel(import(path)?, action);
+    if let (Some(cache_path), Ok((ocel, report))) = (&cache_path, &res) {
+        let res = cache_path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .transpose()
+            .map_err(Error::from)
+            .and_then(|_| save_snapshot(ocel, report, cache_path));
+        if let Err(e) = res {
+            eprintln!("Could not write OCEL snapshot {cache_path:?}: {e:?}");
+        }
+    }
+    Ok(res)
+}
+
+pub fn save_snapshot(
+    ocel: &IndexLinkedOCEL,
+    report: &OCELValidationReport,
+    path: impl AsRef<Path>,
+) -> Result<(), Error> {
+    // Write to a temporary file first, so that no partially written snapshot is left behind
+    let path = path.as_ref();
+    let tmp_path = path.with_extension(format!("{SNAPSHOT_EXTENSION}.tmp"));
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    write_snapshot(ocel, report, &mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+pub fn load_snapshot(
+    path: impl AsRef<Path>,
+) -> Result<(IndexLinkedOCEL, OCELValidationReport), Error> {
+    read_snapshot(BufReader::new(File::open(path)?))
+}
+
+/// Write a binary snapshot of an [IndexLinkedOCEL] and the report of its validation
+///
+/// The snapshot contains the OCEL as well as all derived indices (adjacency arrays, type indices, ...),
+/// so that loading does not require linking the OCEL again.
+/// Relationships are only stored as adjacency arrays.
+/// All numbers are stored little-endian; Adjacency arrays are stored as contiguous fixed-width arrays.
+/// The validation report is stored as JSON.
+pub fn write_snapshot<W: Write>(
+    ocel: &IndexLinkedOCEL,
+    report: &OCELValidationReport,
+    w: W,
+) -> Result<(), Error> {
+    let mut w = SnapshotWriter(w);
+    w.0.write_all(SNAPSHOT_MAGIC)?;
+    w.write_u32(SNAPSHOT_VERSION)?;
+    w.write_str(&serde_json::to_string(report)?)?;
+
+    // OCEL
+    w.write_types(&ocel.ocel.event_types)?;
+    w.write_types(&ocel.ocel.object_types)?;
+    w.write_len(ocel.ocel.events.len())?;
+    for (i, e) in ocel.ocel.events.iter().enumerate() {
+        w.write_str(&e.id)?;
+        w.write_str(&e.event_type)?;
+        w.write_time(&e.time)?;
+        let attributes: Vec<_> = ocel.ev_attributes(&EventIndex(i)).collect();
+        w.write_len(attributes.len())?;
+        for (name, value) in attributes {
+            w.write_str(name)?;
+            w.write_attribute_value(value)?;
+        }
+    }
+    w.write_len(ocel.ocel.objects.len())?;
+    for (i, o) in ocel.ocel.objects.iter().enumerate() {
+        w.write_str(&o.id)?;
+        w.write_str(&o.object_type)?;
+        let attributes: Vec<_> = ocel.ob_attributes(&ObjectIndex(i)).collect();
+        w.write_len(attributes.len())?;
+        for (name, value, time) in attributes {
+            w.write_str(name)?;
+            w.write_attribute_value(value)?;
+            w.write_time(time)?;
+        }
+    }
+
+    // Derived indices
+    w.write_len(ocel.qualifiers.len())?;
+    for q in ocel.qualifiers.iter() {
+        w.write_str(q)?;
+    }
+    w.write_csr(&ocel.object_events_map, |w, e| w.write_u64(e.0 as u64))?;
+    w.write_csr(&ocel.event_rels, SnapshotWriter::write_rel_edge)?;
+    w.write_csr(&ocel.object_rels, SnapshotWriter::write_rel_edge)?;
+    w.write_indices_of_type(&ocel.events_of_type, |e| e.0)?;
+    w.write_indices_of_type(&ocel.objects_of_type, |o| o.0)?;
+    w.write_len(ocel.object_rels_per_type.len())?;
+    for (object_type, rels) in &ocel.object_rels_per_type {
+        w.write_str(object_type)?;
+        w.write_len(rels.len())?;
+        for (qualifier, other_type) in rels {
+            w.write_str(qualifier)?;
+            w.write_str(other_type)?;
+        }
+    }
+    w.write_len(ocel.rel_counts_per_type.len())?;
+    for (t, count) in &ocel.rel_counts_per_type {
+        w.write_event_or_object_type(t)?;
+        w.write_u64(*count as u64)?;
+        w.write_f32(
+            ocel.avg_rels_of_type_per_type
+                .get(t)
+                .copied()
+                .unwrap_or_default(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Read a binary snapshot written by [write_snapshot]
+///
+/// Fails if the snapshot was written with a different [SNAPSHOT_VERSION],
+/// is truncated, or contains indices referencing unknown events, objects or qualifiers
+pub fn read_snapshot<R: Read>(r: R) -> Result<(IndexLinkedOCEL, OCELValidationReport), Error> {
+    let mut r = SnapshotReader(r);
+    let mut magic = [0; 8];
+    r.0.read_exact(&mut magic)?;
+    if magic != *SNAPSHOT_MAGIC {
+        return Err(anyhow!("Not an OCPQ snapshot"));
+    }
+    let version = r.read_u32()?;
+    if version != SNAPSHOT_VERSION {
+        return Err(anyhow!(
+            "Unsupported snapshot version {version} (expected {SNAPSHOT_VERSION})"
+        ));
+    }
+    let report: OCELValidationReport = serde_json::from_str(&r.read_string()?)?;
+
+    // OCEL
+    let event_types = r.read_types()?;
+    let object_types = r.read_types()?;
+    let num_events = r.read_len()?;
+    let mut events = Vec::with_capacity(capacity(num_events));
+    let mut event_attributes = AttributeColumns::default();
+    for _ in 0..num_events {
+        let id = r.read_string()?;
+        let event_type = r.read_string()?;
+        let time = r.read_time()?.into();
+        let num_attributes = r.read_len()?;
+        let mut attributes = Vec::with_capacity(capacity(num_attributes));
+        for _ in 0..num_attributes {
+            attributes.push((r.read_string()?, r.read_attribute_value()?, None));
+        }
+        event_attributes.push_node(attributes);
+        events.push(OCELEvent {
+            id,
+            event_type,
+            time,
+            attributes: Vec::new(),
+            relationships: Vec::new(),
+        });
+    }
+    let num_objects = r.read_len()?;
+    let mut objects = Vec::with_capacity(capacity(num_objects));
+    let mut object_attributes = AttributeColumns::default();
+    for _ in 0..num_objects {
+        let id = r.read_string()?;
+        let object_type = r.read_string()?;
+        let num_attributes = r.read_len()?;
+        let mut attributes = Vec::with_capacity(capacity(num_attributes));
+        for _ in 0..num_attributes {
+            attributes.push((
+                r.read_string()?,
+                r.read_attribute_value()?,
+                Some(r.read_time()?),
+            ));
+        }
+        object_attributes.push_node(attributes);
+        objects.push(OCELObject {
+            id,
+            object_type,
+            attributes: Vec::new(),
+            relationships: Vec::new(),
+        });
+    }
+    let ocel = OCEL {
+        event_types,
+        object_types,
+        events,
+        objects,
+    };
+
+    // Derived indices
+    let mut qualifiers = StringInterner::default();
+    for _ in 0..r.read_len()? {
+        qualifiers.intern(&r.read_string()?);
+    }
+    let object_events_map = r.read_csr(|r| Ok(EventIndex(r.read_len()?)))?;
+    let event_rels = r.read_csr(SnapshotReader::read_rel_edge)?;
+    let object_rels = r.read_csr(SnapshotReader::read_rel_edge)?;
+    let events_of_type = r.read_indices_of_type(EventIndex)?;
+    let objects_of_type = r.read_indices_of_type(ObjectIndex)?;
+    let num_object_types = r.read_len()?;
+    let mut object_rels_per_type = HashMap::with_capacity(capacity(num_object_types));
+    for _ in 0..num_object_types {
+        let object_type = r.read_string()?;
+        let num_rels = r.read_len()?;
+        let mut rels = HashSet::with_capacity(capacity(num_rels));
+        for _ in 0..num_rels {
+            rels.insert((r.read_string()?, r.read_string()?));
+        }
+        object_rels_per_type.insert(object_type, rels);
+    }
+    let num_types = r.read_len()?;
+    let mut rel_counts_per_type = HashMap::with_capacity(capacity(num_types));
+    let mut avg_rels_of_type_per_type = HashMap::with_capacity(capacity(num_types));
+    for _ in 0..num_types {
+        let t = r.read_event_or_object_type()?;
+        rel_counts_per_type.insert(t.clone(), r.read_len()?);
+        avg_rels_of_type_per_type.insert(t, r.read_f32()?);
+    }
+
+    if object_events_map.num_nodes() != ocel.objects.len()
+        || event_rels.num_nodes() != ocel.events.len()
+        || object_rels.num_nodes() != ocel.objects.len()
+    {
+        return Err(anyhow!("Inconsistent snapshot: Index sizes do not match OCEL"));
+    }
+    let is_valid_edge = |e: &RelEdge| {
+        (e.qualifier.0 as usize) < qualifiers.len()
+            && match e.to {
+                EventOrObjectIndex::Event(ev_index) => ev_index.0 < num_events,
+                EventOrObjectIndex::Object(ob_index) => ob_index.0 < num_objects,
+            }
+    };
+    if (0..num_events).any(|i| !event_rels.get(i).all(is_valid_edge))
+        || (0..num_objects).any(|i| !object_rels.get(i).all(is_valid_edge))
+        || (0..num_objects).any(|i| object_events_map.get(i).any(|e| e.0 >= num_events))
+        || events_of_type.values().flatten().any(|e| e.0 >= num_events)
+        || objects_of_type
+            .values()
+            .flatten()
+            .any(|o| o.0 >= num_objects)
+    {
+        return Err(anyhow!(
+            "Inconsistent snapshot: Index references unknown event, object or qualifier"
+        ));
+    }
+    let event_index_map = ocel
+        .events
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.id.clone(), EventIndex(i)))
+        .collect();
+    let object_index_map = ocel
+        .objects
+        .iter()
+        .enumerate()
+        .map(|(i, o)| (o.id.clone(), ObjectIndex(i)))
+        .collect();
+    let ocel = IndexLinkedOCEL {
+        object_events_map,
+        object_rels_per_type,
+        events_of_type,
+        objects_of_type,
+        ocel,
+        event_index_map,
+        object_index_map,
+        qualifiers,
+        event_rels,
+        object_rels,
+        event_attributes,
+        object_attributes,
+        avg_rels_of_type_per_type,
+        rel_counts_per_type,
+    };
+    Ok((ocel, report))
+}
+
+/// Capacity to reserve for a collection with `len` entries read from a snapshot
+fn capacity(len: usize) -> usize {
+    len.min(MAX_PREALLOCATED_ENTRIES)
+}
+
+const ATTR_TIME: u8 = 0;
+const ATTR_INTEGER: u8 = 1;
+const ATTR_FLOAT: u8 = 2;
+const ATTR_BOOLEAN: u8 = 3;
+const ATTR_STRING: u8 = 4;
+const ATTR_NULL: u8 = 5;
+
+const EDGE_TO_EVENT: u8 = 0b01;
+const EDGE_REVERSED: u8 = 0b10;
+
+struct SnapshotWriter<W: Write>(W);
+
+impl<W: Write> SnapshotWriter<W> {
+    fn write_u8(&mut self, v: u8) -> io::Result<()> {
+        self.0.write_all(&[v])
+    }
+    fn write_u32(&mut self, v: u32) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_i32(&mut self, v: i32) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_u64(&mut self, v: u64) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_i64(&mut self, v: i64) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_f32(&mut self, v: f32) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_f64(&mut self, v: f64) -> io::Result<()> {
+        self.0.write_all(&v.to_le_bytes())
+    }
+    fn write_len(&mut self, len: usize) -> io::Result<()> {
+        self.write_u64(len as u64)
+    }
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.write_len(s.len())?;
+        self.0.write_all(s.as_bytes())
+    }
+    fn write_time<Tz: TimeZone>(&mut self, t: &DateTime<Tz>) -> io::Result<()> {
+        self.write_i64(t.timestamp())?;
+        self.write_u32(t.timestamp_subsec_nanos())?;
+        self.write_i32(t.offset().fix().local_minus_utc())
+    }
+    fn write_attribute_value(&mut self, v: &OCELAttributeValue) -> io::Result<()> {
+        match v {
+            OCELAttributeValue::Time(t) => {
+                self.write_u8(ATTR_TIME)?;
+                self.write_time(t)
+            }
+            OCELAttributeValue::Integer(i) => {
+                self.write_u8(ATTR_INTEGER)?;
+                self.write_i64(*i)
+            }
+            OCELAttributeValue::Float(f) => {
+                self.write_u8(ATTR_FLOAT)?;
+                self.write_f64(*f)
+            }
+            OCELAttributeValue::Boolean(b) => {
+                self.write_u8(ATTR_BOOLEAN)?;
+                self.write_u8(*b as u8)
+            }
+            OCELAttributeValue::String(s) => {
+                self.write_u8(ATTR_STRING)?;
+                self.write_str(s)
+            }
+            OCELAttributeValue::Null => self.write_u8(ATTR_NULL),
+        }
+    }
+    fn write_types(&mut self, types: &[OCELType]) -> io::Result<()> {
+        self.write_len(types.len())?;
+        for t in types {
+            self.write_str(&t.name)?;
+            self.write_len(t.attributes.len())?;
+            for a in &t.attributes {
+                self.write_str(&a.name)?;
+                self.write_str(&a.value_type)?;
+            }
+        }
+        Ok(())
+    }
+    fn write_rel_edge(&mut self, e: &RelEdge) -> io::Result<()> {
+        let (flags, index) = match e.to {
+            EventOrObjectIndex::Event(ev_index) => (EDGE_TO_EVENT, ev_index.0),
+            EventOrObjectIndex::Object(ob_index) => (0, ob_index.0),
+        };
+        self.write_u8(if e.reversed { flags | EDGE_REVERSED } else { flags })?;
+        self.write_u64(index as u64)?;
+        self.write_u32(e.qualifier.0)
+    }
+    fn write_csr<T: Copy + Ord>(
+        &mut self,
+        csr: &CSRAdjacency<T>,
+        write_entry: impl Fn(&mut Self, &T) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let offsets = csr.offsets();
+        self.write_len(offsets.len())?;
+        for o in &offsets {
+            self.write_u64(*o as u64)?;
+        }
+        for node in 0..csr.num_nodes() {
+            for entry in csr.get(node) {
+                write_entry(self, entry)?;
+            }
+        }
+        Ok(())
+    }
+    fn write_indices_of_type<T>(
+        &mut self,
+        indices_of_type: &HashMap<String, Vec<T>>,
+        to_usize: impl Fn(&T) -> usize,
+    ) -> io::Result<()> {
+        self.write_len(indices_of_type.len())?;
+        for (t, indices) in indices_of_type {
+            self.write_str(t)?;
+            self.write_len(indices.len())?;
+            for i in indices {
+                self.write_u64(to_usize(i) as u64)?;
+            }
+        }
+        Ok(())
+    }
+    fn write_event_or_object_type(&mut self, t: &EventOrObjectType) -> io::Result<()> {
+        match t {
+            EventOrObjectType::Event(et) => {
+                self.write_u8(0)?;
+                self.write_str(et)
+            }
+            EventOrObjectType::Object(ot) => {
+                self.write_u8(1)?;
+                self.write_str(ot)
+            }
+        }
+    }
+}
+
+struct SnapshotReader<R: Read>(R);
+
+impl<R: Read> SnapshotReader<R> {
+    fn read_array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf = [0; N];
+        self.0.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+    fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+    fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+    fn read_i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+    fn read_u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+    fn read_i64(&mut self) -> io::Result<i64> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+    fn read_f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+    fn read_f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_le_bytes(self.read_array()?))
+    }
+    fn read_len(&mut self) -> Result<usize, Error> {
+        Ok(usize::try_from(self.read_u64()?)?)
+    }
+    fn read_string(&mut self) -> Result<String, Error> {
+        let len = self.read_len()?;
+        let mut buf = Vec::new();
+        self.0.by_ref().take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return Err(anyhow!("Unexpected end of snapshot"));
+        }
+        Ok(String::from_utf8(buf)?)
+    }
+    fn read_time(&mut self) -> Result<DateTime<FixedOffset>, Error> {
+        let secs = self.read_i64()?;
+        let nanos = self.read_u32()?;
+        let offset = self.read_i32()?;
+        let offset = FixedOffset::east_opt(offset).ok_or_else(|| anyhow!("Invalid time offset"))?;
+        Ok(DateTime::from_timestamp(secs, nanos)
+            .ok_or_else(|| anyhow!("Invalid timestamp"))?
+            .with_timezone(&offset))
+    }
+    fn read_attribute_value(&mut self) -> Result<OCELAttributeValue, Error> {
+        Ok(match self.read_u8()? {
+            ATTR_TIME => OCELAttributeValue::Time(self.read_time()?.into()),
+            ATTR_INTEGER => OCELAttributeValue::Integer(self.read_i64()?),
+            ATTR_FLOAT => OCELAttributeValue::Float(self.read_f64()?),
+            ATTR_BOOLEAN => OCELAttributeValue::Boolean(self.read_u8()? != 0),
+            ATTR_STRING => OCELAttributeValue::String(self.read_string()?),
+            ATTR_NULL => OCELAttributeValue::Null,
+            tag => return Err(anyhow!("Invalid attribute value tag {tag}")),
+        })
+    }
+    fn read_types(&mut self) -> Result<Vec<OCELType>, Error> {
+        let num_types = self.read_len()?;
+        let mut types = Vec::with_capacity(capacity(num_types));
+        for _ in 0..num_types {
+            let name = self.read_string()?;
+            let num_attributes = self.read_len()?;
+            let mut attributes = Vec::with_capacity(capacity(num_attributes));
+            for _ in 0..num_attributes {
+                attributes.push(OCELTypeAttribute {
+                    name: self.read_string()?,
+                    value_type: self.read_string()?,
+                });
+            }
+            types.push(OCELType { name, attributes });
+        }
+        Ok(types)
+    }
+    fn read_rel_edge(&mut self) -> Result<RelEdge, Error> {
+        let flags = self.read_u8()?;
+        let index = self.read_len()?;
+        let qualifier = InternedIndex(self.read_u32()?);
+        let to = if flags & EDGE_TO_EVENT != 0 {
+            EventOrObjectIndex::Event(EventIndex(index))
+        } else {
+            EventOrObjectIndex::Object(ObjectIndex(index))
+        };
+        Ok(RelEdge {
+            to,
+            reversed: flags & EDGE_REVERSED != 0,
+            qualifier,
+        })
+    }
+    fn read_csr<T: Copy + Ord>(
+        &mut self,
+        read_entry: impl Fn(&mut Self) -> Result<T, Error>,
+    ) -> Result<CSRAdjacency<T>, Error> {
+        let num_offsets = self.read_len()?;
+        let mut offsets = Vec::with_capacity(capacity(num_offsets));
+        for _ in 0..num_offsets {
+            offsets.push(self.read_len()?);
+        }
+        let num_entries = offsets.last().copied().unwrap_or_default();
+        let mut entries = Vec::with_capacity(capacity(num_entries));
+        for _ in 0..num_entries {
+            entries.push(read_entry(self)?);
+        }
+        CSRAdjacency::from_raw_parts(offsets, entries)
+            .ok_or_else(|| anyhow!("Inconsistent snapshot: Invalid adjacency offsets"))
+    }
+    fn read_indices_of_type<T>(
+        &mut self,
+        from_usize: impl Fn(usize) -> T,
+    ) -> Result<HashMap<String, Vec<T>>, Error> {
+        let num_types = self.read_len()?;
+        let mut indices_of_type = HashMap::with_capacity(capacity(num_types));
+        for _ in 0..num_types {
+            let t = self.read_string()?;
+            let num_indices = self.read_len()?;
+            let mut indices = Vec::with_capacity(capacity(num_indices));
+            for _ in 0..num_indices {
+                indices.push(from_usize(self.read_len()?));
+            }
+            indices_of_type.insert(t, indices);
+        }
+        Ok(indices_of_type)
+    }
+    fn read_event_or_object_type(&mut self) -> Result<EventOrObjectType, Error> {
+        Ok(match self.read_u8()? {
+            0 => EventOrObjectType::Event(self.read_string()?),
+            1 => EventOrObjectType::Object(self.read_string()?),
+            tag => return Err(anyhow!("Invalid type tag {tag}")),
+        })
+    }
+}
+
+#[cfg(test)]
+fn snapshot_test_ocel() -> IndexLinkedOCEL {
+    use process_mining::ocel::ocel_struct::{OCELEventAttribute, OCELObjectAttribute};
+
+    let mut ocel = super::tests::test_ocel(
+        vec![
+            (
+                "e1",
+                "place order",
+                0,
+                vec![("o1", "order"), ("i1", "item")],
+            ),
+            ("e2", "pay order", 10, vec![("o1", "order")]),
+        ],
+        vec![
+            ("o1", "order", vec![("i1", "contains")]),
+            ("i1", "item", vec![]),
+        ],
+    );
+    ocel.events[0].attributes.push(OCELEventAttribute {
+        name: "price".to_string(),
+        value: OCELAttributeValue::Float(9.5),
+    });
+    ocel.objects[0].attributes.push(OCELObjectAttribute {
+        name: "status".to_string(),
+        value: OCELAttributeValue::String("new".to_string()),
+        time: DateTime::from_timestamp(0, 0)
+            .unwrap()
+            .fixed_offset()
+            .into(),
+    });
+    IndexLinkedOCEL::new(ocel)
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let ocel = snapshot_test_ocel();
+    let report = OCELValidationReport {
+        num_dangling_references: 1,
+        repaired: true,
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    write_snapshot(&ocel, &report, &mut bytes).unwrap();
+    let (read, read_report) = read_snapshot(bytes.as_slice()).unwrap();
+
+    assert_eq!(read_report, report);
+    assert_eq!(
+        serde_json::to_value(read.to_ocel()).unwrap(),
+        serde_json::to_value(ocel.to_ocel()).unwrap()
+    );
+    assert_eq!(read.events_of_type, ocel.events_of_type);
+    assert_eq!(read.objects_of_type, ocel.objects_of_type);
+    assert_eq!(read.event_index_map, ocel.event_index_map);
+    assert_eq!(read.object_index_map, ocel.object_index_map);
+    assert_eq!(read.object_rels_per_type, ocel.object_rels_per_type);
+    assert_eq!(read.rel_counts_per_type, ocel.rel_counts_per_type);
+    assert_eq!(
+        read.avg_rels_of_type_per_type,
+        ocel.avg_rels_of_type_per_type
+    );
+    assert_eq!(
+        read.qualifiers.iter().collect::<Vec<_>>(),
+        ocel.qualifiers.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(read.event_rels.offsets(), ocel.event_rels.offsets());
+    assert_eq!(read.object_rels.offsets(), ocel.object_rels.offsets());
+    assert_eq!(
+        read.object_events_map.offsets(),
+        ocel.object_events_map.offsets()
+    );
+    for i in 0..ocel.ocel.objects.len() {
+        assert!(read.object_rels.get(i).eq(ocel.object_rels.get(i)));
+        assert!(read
+            .object_events_map
+            .get(i)
+            .eq(ocel.object_events_map.get(i)));
+    }
+    for i in 0..ocel.ocel.events.len() {
+        assert!(read.event_rels.get(i).eq(ocel.event_rels.get(i)));
+    }
+}
+
+#[test]
+fn truncated_snapshot_is_rejected() {
+    let mut bytes = Vec::new();
+    write_snapshot(&snapshot_test_ocel(), &Default::default(), &mut bytes).unwrap();
+    for len in 0..bytes.len() {
+        assert!(
+            read_snapshot(&bytes[..len]).is_err(),
+            "Snapshot truncated to {len} bytes was accepted"
+        );
+    }
+}
+
+#[test]
+fn snapshot_with_huge_length_is_rejected() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(SNAPSHOT_MAGIC);
+    bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+    let report = serde_json::to_string(&OCELValidationReport::default()).unwrap();
+    bytes.extend_from_slice(&(report.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(report.as_bytes());
+    // No event or object types
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    // Number of events
+    bytes.extend_from_slice(&(u32::MAX as u64).to_le_bytes());
+    assert!(read_snapshot(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn snapshot_with_invalid_indices_is_rejected() {
+    let ocel = snapshot_test_ocel();
+    let invalid_edges = [
+        RelEdge {
+            to: EventOrObjectIndex::Object(ObjectIndex(ocel.ocel.objects.len())),
+            reversed: false,
+            qualifier: InternedIndex(0),
+        },
+        RelEdge {
+            to: EventOrObjectIndex::Event(EventIndex(ocel.ocel.events.len())),
+            reversed: true,
+            qualifier: InternedIndex(0),
+        },
+        RelEdge {
+            to: EventOrObjectIndex::Object(ObjectIndex(0)),
+            reversed: false,
+            qualifier: InternedIndex(ocel.qualifiers.len() as u32),
+        },
+    ];
+    for edge in invalid_edges {
+        let mut invalid = snapshot_test_ocel();
+        invalid.event_rels.push(0, edge);
+        let mut bytes = Vec::new();
+        write_snapshot(&invalid, &Default::default(), &mut bytes).unwrap();
+        assert!(read_snapshot(bytes.as_slice()).is_err());
+    }
+
+    let mut invalid = snapshot_test_ocel();
+    invalid
+        .object_events_map
+        .push(0, EventIndex(ocel.ocel.events.len()));
+    let mut bytes = Vec::new();
+    write_snapshot(&invalid, &Default::default(), &mut bytes).unwrap();
+    assert!(read_snapshot(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn source_hashes_are_only_recomputed_on_changes() {
+    let dir = std::env::temp_dir().join(format!("ocpq-source-hash-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ocel.json");
+    std::fs::write(&path, "{}").unwrap();
+    let cache_dir = dir.join("cache");
+
+    let hash = get_source_hash(&path, Some(&cache_dir)).unwrap();
+    assert_eq!(hash, hash_file(&path).unwrap());
+    assert_eq!(get_source_hash(&path, None).unwrap(), hash);
+
+    // The stored hash is used as long as path, size and modification time match
+    let stored_path = std::fs::read_dir(cache_dir.join("sources"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let mut stored: SourceFileHash =
+        serde_json::from_reader(File::open(&stored_path).unwrap()).unwrap();
+    stored.hash = 42;
+    serde_json::to_writer(File::create(&stored_path).unwrap(), &stored).unwrap();
+    assert_eq!(get_source_hash(&path, Some(&cache_dir)).unwrap(), 42);
+
+    std::fs::write(&path, "{\"events\": []}").unwrap();
+    let changed_hash = get_source_hash(&path, Some(&cache_dir)).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(changed_hash, fnv1a(FNV_OFFSET, b"{\"events\": []}"));
+    assert_ne!(changed_hash, hash);
+}
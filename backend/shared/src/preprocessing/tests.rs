@@ -78,3 +78,67 @@
 //         }
 //     });
 // }
+
+/// Construct a small OCEL for tests
+///
+/// Events are given as `(id, event type, time in seconds, E2O relationships)`,
+/// objects as `(id, object type, O2O relationships)`, with relationships as `(object id, qualifier)`.
+/// The event/object types (without attributes) are derived from the events and objects.
+#[cfg(test)]
+pub fn test_ocel(
+    events: Vec<(&str, &str, i64, Vec<(&str, &str)>)>,
+    objects: Vec<(&str, &str, Vec<(&str, &str)>)>,
+) -> process_mining::OCEL {
+    use chrono::{DateTime, FixedOffset};
+    use itertools::Itertools;
+    use process_mining::ocel::ocel_struct::{OCELEvent, OCELObject, OCELRelationship, OCELType};
+
+    let rels = |rels: Vec<(&str, &str)>| {
+        rels.into_iter()
+            .map(|(object_id, qualifier)| OCELRelationship {
+                object_id: object_id.to_string(),
+                qualifier: qualifier.to_string(),
+            })
+            .collect_vec()
+    };
+    let ocel_type = |name: &str| OCELType {
+        name: name.to_string(),
+        attributes: Vec::new(),
+    };
+    process_mining::OCEL {
+        event_types: events
+            .iter()
+            .map(|(_, t, _, _)| *t)
+            .unique()
+            .map(ocel_type)
+            .collect(),
+        object_types: objects
+            .iter()
+            .map(|(_, t, _)| *t)
+            .unique()
+            .map(ocel_type)
+            .collect(),
+        events: events
+            .into_iter()
+            .map(|(id, event_type, secs, relationships)| OCELEvent {
+                id: id.to_string(),
+                event_type: event_type.to_string(),
+                time: DateTime::from_timestamp(secs, 0)
+                    .unwrap()
+                    .with_timezone(&FixedOffset::east_opt(0).unwrap())
+                    .into(),
+                attributes: Vec::new(),
+                relationships: rels(relationships),
+            })
+            .collect(),
+        objects: objects
+            .into_iter()
+            .map(|(id, object_type, relationships)| OCELObject {
+                id: id.to_string(),
+                object_type: object_type.to_string(),
+                attributes: Vec::new(),
+                relationships: rels(relationships),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,576 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::DateTime;
+use process_mining::{
+    ocel::ocel_struct::{OCELAttributeValue, OCELType},
+    OCEL,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::linked_ocel::IndexLinkedOCEL;
+
+/// Maximal number of issues listed in a [OCELValidationReport] (all issues are counted)
+const MAX_LISTED_ISSUES: usize = 1_000;
+
+/// What to do if an OCEL has data-quality issues when loading it
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationAction {
+    /// Only report issues and load the OCEL as-is
+    #[default]
+    Keep,
+    /// Report issues and repair them before loading the OCEL
+    Repair,
+    /// Report issues and refuse to load the OCEL
+    Reject,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationOptions {
+    #[serde(default)]
+    pub on_issues: ValidationAction,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ValidationIssue {
+    /// An E2O relationship references an object ID which does not belong to any object
+    #[serde(rename_all = "camelCase")]
+    DanglingE2O {
+        event_id: String,
+        object_id: String,
+        qualifier: String,
+    },
+    /// An O2O relationship references an object ID which does not belong to any object
+    #[serde(rename_all = "camelCase")]
+    DanglingO2O {
+        object_id: String,
+        target_object_id: String,
+        qualifier: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DuplicateEventId { id: String, count: usize },
+    #[serde(rename_all = "camelCase")]
+    DuplicateObjectId { id: String, count: usize },
+    #[serde(rename_all = "camelCase")]
+    UndefinedEventType { event_id: String, event_type: String },
+    #[serde(rename_all = "camelCase")]
+    UndefinedObjectType {
+        object_id: String,
+        object_type: String,
+    },
+    /// An attribute value does not match the value type declared in the event/object type
+    #[serde(rename_all = "camelCase")]
+    AttributeTypeMismatch {
+        id: String,
+        is_event: bool,
+        ocel_type: String,
+        attribute: String,
+        declared_type: String,
+        value: String,
+    },
+    /// The timestamps of the values of an object attribute are not in chronological order
+    #[serde(rename_all = "camelCase")]
+    NonMonotonicAttributeTimestamps { object_id: String, attribute: String },
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OCELValidationReport {
+    /// Listed issues (at most 1000)
+    pub issues: Vec<ValidationIssue>,
+    pub num_dangling_references: usize,
+    pub num_duplicate_ids: usize,
+    pub num_undefined_types: usize,
+    pub num_attribute_type_mismatches: usize,
+    pub num_non_monotonic_timestamps: usize,
+    /// Set if the issues were repaired
+    pub repaired: bool,
+}
+
+impl OCELValidationReport {
+    pub fn num_issues(&self) -> usize {
+        self.num_dangling_references
+            + self.num_duplicate_ids
+            + self.num_undefined_types
+            + self.num_attribute_type_mismatches
+            + self.num_non_monotonic_timestamps
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.num_issues() == 0
+    }
+
+    /// Short summary of the number of issues, e.g., for logging
+    pub fn summary(&self) -> String {
+        format!(
+            "{} data-quality issues ({} dangling references, {} duplicate IDs, {} undefined types, {} attribute type mismatches, {} non-monotonic timestamps)",
+            self.num_issues(),
+            self.num_dangling_references,
+            self.num_duplicate_ids,
+            self.num_undefined_types,
+            self.num_attribute_type_mismatches,
+            self.num_non_monotonic_timestamps
+        )
+    }
+
+    fn add(&mut self, issue: ValidationIssue) {
+        match &issue {
+            ValidationIssue::DanglingE2O { .. } | ValidationIssue::DanglingO2O { .. } => {
+                self.num_dangling_references += 1
+            }
+            ValidationIssue::DuplicateEventId { .. } | ValidationIssue::DuplicateObjectId { .. } => {
+                self.num_duplicate_ids += 1
+            }
+            ValidationIssue::UndefinedEventType { .. }
+            | ValidationIssue::UndefinedObjectType { .. } => self.num_undefined_types += 1,
+            ValidationIssue::AttributeTypeMismatch { .. } => {
+                self.num_attribute_type_mismatches += 1
+            }
+            ValidationIssue::NonMonotonicAttributeTimestamps { .. } => {
+                self.num_non_monotonic_timestamps += 1
+            }
+        }
+        if self.issues.len() < MAX_LISTED_ISSUES {
+            self.issues.push(issue);
+        }
+    }
+}
+
+fn attribute_value_to_string(v: &OCELAttributeValue) -> String {
+    match v {
+        OCELAttributeValue::Time(t) => t.to_rfc3339(),
+        OCELAttributeValue::Integer(i) => i.to_string(),
+        OCELAttributeValue::Float(f) => f.to_string(),
+        OCELAttributeValue::Boolean(b) => b.to_string(),
+        OCELAttributeValue::String(s) => s.clone(),
+        OCELAttributeValue::Null => "null".to_string(),
+    }
+}
+
+/// Check if an attribute value matches a declared value type
+///
+/// Null values match every type and integers are also accepted as floats
+fn value_matches_type(v: &OCELAttributeValue, value_type: &str) -> bool {
+    match v {
+        OCELAttributeValue::Null => true,
+        OCELAttributeValue::Time(_) => value_type == "time",
+        OCELAttributeValue::Integer(_) => value_type == "integer" || value_type == "float",
+        OCELAttributeValue::Float(_) => value_type == "float",
+        OCELAttributeValue::Boolean(_) => value_type == "boolean",
+        OCELAttributeValue::String(_) => value_type == "string",
+    }
+}
+
+/// Convert an attribute value to a declared value type (if possible)
+///
+/// Values which can not be converted are replaced by [OCELAttributeValue::Null]
+fn convert_value_to_type(v: &OCELAttributeValue, value_type: &str) -> OCELAttributeValue {
+    let s = attribute_value_to_string(v);
+    match value_type {
+        "string" => Some(OCELAttributeValue::String(s)),
+        "integer" => match v {
+            OCELAttributeValue::Float(f) if f.fract() == 0.0 => {
+                Some(OCELAttributeValue::Integer(*f as i64))
+            }
+            _ => s.trim().parse().ok().map(OCELAttributeValue::Integer),
+        },
+        "float" => s.trim().parse().ok().map(OCELAttributeValue::Float),
+        "boolean" => s.trim().parse().ok().map(OCELAttributeValue::Boolean),
+        "time" => DateTime::parse_from_rfc3339(s.trim())
+            .ok()
+            .map(|t| OCELAttributeValue::Time(t.into())),
+        _ => None,
+    }
+    .unwrap_or(OCELAttributeValue::Null)
+}
+
+fn declared_attribute_types(types: &[OCELType]) -> HashMap<&str, HashMap<&str, &str>> {
+    types
+        .iter()
+        .map(|t| {
+            (
+                t.name.as_str(),
+                t.attributes
+                    .iter()
+                    .map(|a| (a.name.as_str(), a.value_type.as_str()))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn count_ids<'a>(ids: impl Iterator<Item = &'a String>) -> HashMap<&'a String, usize> {
+    let mut counts: HashMap<&String, usize> = HashMap::new();
+    for id in ids {
+        *counts.entry(id).or_default() += 1;
+    }
+    counts.retain(|_, c| *c > 1);
+    counts
+}
+
+/// Check an OCEL for data-quality issues
+///
+/// Reports dangling E2O/O2O references, duplicate event/object IDs, events/objects with undefined types,
+/// attribute values not matching the declared attribute types and object attribute values with non-monotonic timestamps
+pub fn validate_ocel(ocel: &OCEL) -> OCELValidationReport {
+    let mut report = OCELValidationReport::default();
+    let object_ids: HashSet<&String> = ocel.objects.iter().map(|o| &o.id).collect();
+    let event_types = declared_attribute_types(&ocel.event_types);
+    let object_types = declared_attribute_types(&ocel.object_types);
+
+    let mut duplicate_events = count_ids(ocel.events.iter().map(|e| &e.id))
+        .into_iter()
+        .collect::<Vec<_>>();
+    duplicate_events.sort();
+    for (id, count) in duplicate_events {
+        report.add(ValidationIssue::DuplicateEventId {
+            id: id.clone(),
+            count,
+        });
+    }
+    let mut duplicate_objects = count_ids(ocel.objects.iter().map(|o| &o.id))
+        .into_iter()
+        .collect::<Vec<_>>();
+    duplicate_objects.sort();
+    for (id, count) in duplicate_objects {
+        report.add(ValidationIssue::DuplicateObjectId {
+            id: id.clone(),
+            count,
+        });
+    }
+
+    for e in &ocel.events {
+        for r in &e.relationships {
+            if !object_ids.contains(&r.object_id) {
+                report.add(ValidationIssue::DanglingE2O {
+                    event_id: e.id.clone(),
+                    object_id: r.object_id.clone(),
+                    qualifier: r.qualifier.clone(),
+                });
+            }
+        }
+        let Some(declared) = event_types.get(e.event_type.as_str()) else {
+            report.add(ValidationIssue::UndefinedEventType {
+                event_id: e.id.clone(),
+                event_type: e.event_type.clone(),
+            });
+            continue;
+        };
+        for a in &e.attributes {
+            if let Some(value_type) = declared.get(a.name.as_str()) {
+                if !value_matches_type(&a.value, value_type) {
+                    report.add(ValidationIssue::AttributeTypeMismatch {
+                        id: e.id.clone(),
+                        is_event: true,
+                        ocel_type: e.event_type.clone(),
+                        attribute: a.name.clone(),
+                        declared_type: value_type.to_string(),
+                        value: attribute_value_to_string(&a.value),
+                    });
+                }
+            }
+        }
+    }
+
+    for o in &ocel.objects {
+        for r in &o.relationships {
+            if !object_ids.contains(&r.object_id) {
+                report.add(ValidationIssue::DanglingO2O {
+                    object_id: o.id.clone(),
+                    target_object_id: r.object_id.clone(),
+                    qualifier: r.qualifier.clone(),
+                });
+            }
+        }
+        let mut non_monotonic: Vec<&String> = Vec::new();
+        let mut last_time_of_attr: HashMap<&String, _> = HashMap::new();
+        for a in &o.attributes {
+            if let Some(last_time) = last_time_of_attr.insert(&a.name, a.time) {
+                if last_time > a.time && !non_monotonic.contains(&&a.name) {
+                    non_monotonic.push(&a.name);
+                }
+            }
+        }
+        for attribute in non_monotonic {
+            report.add(ValidationIssue::NonMonotonicAttributeTimestamps {
+                object_id: o.id.clone(),
+                attribute: attribute.clone(),
+            });
+        }
+        let Some(declared) = object_types.get(o.object_type.as_str()) else {
+            report.add(ValidationIssue::UndefinedObjectType {
+                object_id: o.id.clone(),
+                object_type: o.object_type.clone(),
+            });
+            continue;
+        };
+        for a in &o.attributes {
+            if let Some(value_type) = declared.get(a.name.as_str()) {
+                if !value_matches_type(&a.value, value_type) {
+                    report.add(ValidationIssue::AttributeTypeMismatch {
+                        id: o.id.clone(),
+                        is_event: false,
+                        ocel_type: o.object_type.clone(),
+                        attribute: a.name.clone(),
+                        declared_type: value_type.to_string(),
+                        value: attribute_value_to_string(&a.value),
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Repair all data-quality issues reported by [validate_ocel]
+///
+/// - Only the first event/object with a duplicate ID is kept
+/// - Dangling E2O/O2O relationships are removed
+/// - Undefined event/object types are added (without declared attributes)
+/// - Mismatching attribute values are converted to the declared type (or `null`, if that is not possible)
+/// - Object attribute values are (stably) sorted by their timestamp
+pub fn repair_ocel(ocel: &mut OCEL) {
+    let mut seen_event_ids = HashSet::new();
+    ocel.events.retain(|e| seen_event_ids.insert(e.id.clone()));
+    let mut seen_object_ids = HashSet::new();
+    ocel.objects.retain(|o| seen_object_ids.insert(o.id.clone()));
+
+    for e in ocel.events.iter_mut() {
+        e.relationships
+            .retain(|r| seen_object_ids.contains(&r.object_id));
+    }
+    for o in ocel.objects.iter_mut() {
+        o.relationships
+            .retain(|r| seen_object_ids.contains(&r.object_id));
+        o.attributes.sort_by_key(|a| a.time);
+    }
+
+    let mut declared_event_types: HashSet<String> =
+        ocel.event_types.iter().map(|t| t.name.clone()).collect();
+    for e in &ocel.events {
+        if declared_event_types.insert(e.event_type.clone()) {
+            ocel.event_types.push(OCELType {
+                name: e.event_type.clone(),
+                attributes: Vec::new(),
+            });
+        }
+    }
+    let mut declared_object_types: HashSet<String> =
+        ocel.object_types.iter().map(|t| t.name.clone()).collect();
+    for o in &ocel.objects {
+        if declared_object_types.insert(o.object_type.clone()) {
+            ocel.object_types.push(OCELType {
+                name: o.object_type.clone(),
+                attributes: Vec::new(),
+            });
+        }
+    }
+
+    let event_types = declared_attribute_types(&ocel.event_types);
+    for e in ocel.events.iter_mut() {
+        if let Some(declared) = event_types.get(e.event_type.as_str()) {
+            for a in e.attributes.iter_mut() {
+                if let Some(value_type) = declared.get(a.name.as_str()) {
+                    if !value_matches_type(&a.value, value_type) {
+                        a.value = convert_value_to_type(&a.value, value_type);
+                    }
+                }
+            }
+        }
+    }
+    let object_types = declared_attribute_types(&ocel.object_types);
+    for o in ocel.objects.iter_mut() {
+        if let Some(declared) = object_types.get(o.object_type.as_str()) {
+            for a in o.attributes.iter_mut() {
+                if let Some(value_type) = declared.get(a.name.as_str()) {
+                    if !value_matches_type(&a.value, value_type) {
+                        a.value = convert_value_to_type(&a.value, value_type);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Result of [validate_and_link_ocel]: The linked (possibly repaired) OCEL together with the validation report,
+/// or only the report if the OCEL was rejected
+pub type ValidationResult = Result<(IndexLinkedOCEL, OCELValidationReport), OCELValidationReport>;
+
+/// Validate an OCEL, handle found issues according to the given [ValidationAction] and link it
+///
+/// The OCEL is validated (and repaired) before linking, as linking silently drops dangling relationships.
+/// This way, the OCEL is also only linked once.
+/// Found issues are only returned in the report (see [OCELValidationReport::summary]), not logged.
+pub fn validate_and_link_ocel(mut ocel: OCEL, action: ValidationAction) -> ValidationResult {
+    let mut report = validate_ocel(&ocel);
+    if !report.is_valid() {
+        match action {
+            ValidationAction::Keep => {}
+            ValidationAction::Reject => return Err(report),
+            ValidationAction::Repair => {
+                repair_ocel(&mut ocel);
+                report.repaired = true;
+            }
+        }
+    }
+    Ok((IndexLinkedOCEL::new(ocel), report))
+}
+
+#[cfg(test)]
+fn ocel_with_issues() -> OCEL {
+    use chrono::FixedOffset;
+    use process_mining::ocel::ocel_struct::{
+        OCELEventAttribute, OCELObjectAttribute, OCELTypeAttribute,
+    };
+
+    let mut ocel = super::tests::test_ocel(
+        vec![
+            (
+                "e1",
+                "place order",
+                0,
+                vec![("o1", "order"), ("missing", "order")],
+            ),
+            ("e1", "place order", 5, vec![]),
+            ("e2", "pay order", 10, vec![("o1", "order")]),
+        ],
+        vec![
+            ("o1", "order", vec![("gone", "contains")]),
+            ("o2", "ghost", vec![]),
+        ],
+    );
+    ocel.object_types.retain(|t| t.name != "ghost");
+    ocel.event_types
+        .iter_mut()
+        .find(|t| t.name == "pay order")
+        .unwrap()
+        .attributes
+        .push(OCELTypeAttribute {
+            name: "amount".to_string(),
+            value_type: "integer".to_string(),
+        });
+    ocel.events[2].attributes.push(OCELEventAttribute {
+        name: "amount".to_string(),
+        value: OCELAttributeValue::String(" 12".to_string()),
+    });
+    let time = |secs| {
+        DateTime::from_timestamp(secs, 0)
+            .unwrap()
+            .with_timezone(&FixedOffset::east_opt(0).unwrap())
+    };
+    ocel.objects[0].attributes = vec![
+        OCELObjectAttribute {
+            name: "status".to_string(),
+            value: OCELAttributeValue::String("paid".to_string()),
+            time: time(10).into(),
+        },
+        OCELObjectAttribute {
+            name: "status".to_string(),
+            value: OCELAttributeValue::String("new".to_string()),
+            time: time(0).into(),
+        },
+    ];
+    ocel
+}
+
+#[test]
+fn validation_reports_all_issues() {
+    let report = validate_ocel(&ocel_with_issues());
+    assert_eq!(report.num_duplicate_ids, 1);
+    assert_eq!(report.num_dangling_references, 2);
+    assert_eq!(report.num_undefined_types, 1);
+    assert_eq!(report.num_attribute_type_mismatches, 1);
+    assert_eq!(report.num_non_monotonic_timestamps, 1);
+    assert_eq!(report.issues.len(), 6);
+    assert!(report.issues.contains(&ValidationIssue::DanglingE2O {
+        event_id: "e1".to_string(),
+        object_id: "missing".to_string(),
+        qualifier: "order".to_string(),
+    }));
+    assert!(report.issues.contains(&ValidationIssue::DanglingO2O {
+        object_id: "o1".to_string(),
+        target_object_id: "gone".to_string(),
+        qualifier: "contains".to_string(),
+    }));
+    assert!(report.issues.contains(&ValidationIssue::DuplicateEventId {
+        id: "e1".to_string(),
+        count: 2,
+    }));
+    assert!(report
+        .issues
+        .contains(&ValidationIssue::UndefinedObjectType {
+            object_id: "o2".to_string(),
+            object_type: "ghost".to_string(),
+        }));
+    assert!(!report.is_valid());
+    assert!(!report.repaired);
+
+    let valid = super::tests::test_ocel(
+        vec![("e1", "place order", 0, vec![("o1", "order")])],
+        vec![("o1", "order", vec![])],
+    );
+    assert!(validate_ocel(&valid).is_valid());
+}
+
+#[test]
+fn repaired_ocel_is_valid() {
+    let mut ocel = ocel_with_issues();
+    repair_ocel(&mut ocel);
+    assert!(validate_ocel(&ocel).is_valid());
+
+    assert_eq!(ocel.events.len(), 2);
+    // The first event with a duplicate ID is kept
+    assert_eq!(ocel.events[0].relationships.len(), 1);
+    assert_eq!(ocel.events[0].relationships[0].object_id, "o1");
+    assert_eq!(
+        ocel.events[1].attributes[0].value,
+        OCELAttributeValue::Integer(12)
+    );
+    assert!(ocel.objects[0].relationships.is_empty());
+    assert_eq!(
+        ocel.objects[0]
+            .attributes
+            .iter()
+            .map(|a| a.value.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            OCELAttributeValue::String("new".to_string()),
+            OCELAttributeValue::String("paid".to_string())
+        ]
+    );
+    assert!(ocel.object_types.iter().any(|t| t.name == "ghost"));
+}
+
+#[test]
+fn validation_action_is_applied_before_linking() {
+    let report = validate_and_link_ocel(ocel_with_issues(), ValidationAction::Reject).unwrap_err();
+    assert_eq!(report.num_issues(), 6);
+
+    let (ocel, report) =
+        validate_and_link_ocel(ocel_with_issues(), ValidationAction::Keep).unwrap();
+    assert_eq!(report.num_issues(), 6);
+    assert!(!report.repaired);
+    assert_eq!(ocel.ocel.events.len(), 3);
+
+    let (ocel, report) =
+        validate_and_link_ocel(ocel_with_issues(), ValidationAction::Repair).unwrap();
+    assert_eq!(report.num_issues(), 6);
+    assert!(report.repaired);
+    assert_eq!(ocel.ocel.events.len(), 2);
+    let e2 = ocel.index_of_ev(&"e2".to_string()).unwrap();
+    assert_eq!(
+        ocel.ev_attribute(e2, "amount"),
+        Some(&OCELAttributeValue::Integer(12))
+    );
+}
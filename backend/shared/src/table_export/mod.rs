@@ -9,7 +9,10 @@ use rust_xlsxwriter::{
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::{binding_box::EvaluationResultWithCount, preprocessing::linked_ocel::IndexLinkedOCEL};
+use crate::{
+    binding_box::{structs::LabelValue, EvaluationResultWithCount},
+    preprocessing::linked_ocel::IndexLinkedOCEL,
+};
 
 // #[test]
 // fn test(){
@@ -20,6 +23,28 @@ use crate::{binding_box::EvaluationResultWithCount, preprocessing::linked_ocel::
 pub enum CellContent<'a> {
     String(Cow<'a, str>),
     Value(&'a OCELAttributeValue),
+    Label(&'a LabelValue),
+}
+
+/// Attribute type used for formatting a label value cell
+///
+/// Durations are represented as float values (in seconds)
+pub fn label_value_type(val: &LabelValue) -> OCELAttributeType {
+    match val {
+        LabelValue::Int(_) => OCELAttributeType::Integer,
+        LabelValue::Float(_) | LabelValue::Duration(_) => OCELAttributeType::Float,
+        LabelValue::Bool(_) => OCELAttributeType::Boolean,
+        LabelValue::Time(_) => OCELAttributeType::Time,
+        LabelValue::String(_) | LabelValue::List(_) => OCELAttributeType::String,
+        LabelValue::Null => OCELAttributeType::Null,
+    }
+}
+
+fn label_value_to_cell_string(val: &LabelValue) -> String {
+    match val {
+        LabelValue::Duration(d) => d.to_string(),
+        v => v.to_string(),
+    }
 }
 
 impl IntoExcelData for CellContent<'_> {
@@ -40,6 +65,17 @@ impl IntoExcelData for CellContent<'_> {
                 }
                 s => IntoExcelData::write(format!("{}", s), worksheet, row, col),
             },
+            CellContent::Label(val) => match val {
+                LabelValue::Int(i) => IntoExcelData::write(*i, worksheet, row, col),
+                LabelValue::Float(f) | LabelValue::Duration(f) => {
+                    IntoExcelData::write(f.0, worksheet, row, col)
+                }
+                LabelValue::Bool(b) => IntoExcelData::write(*b, worksheet, row, col),
+                LabelValue::Time(date_time) => {
+                    IntoExcelData::write(&date_time.naive_utc(), worksheet, row, col)
+                }
+                v => IntoExcelData::write(label_value_to_cell_string(v), worksheet, row, col),
+            },
         }
     }
 
@@ -79,6 +115,31 @@ impl IntoExcelData for CellContent<'_> {
                     format,
                 ),
             },
+            CellContent::Label(val) => match val {
+                LabelValue::Int(i) => {
+                    IntoExcelData::write_with_format(*i, worksheet, row, col, format)
+                }
+                LabelValue::Float(f) | LabelValue::Duration(f) => {
+                    IntoExcelData::write_with_format(f.0, worksheet, row, col, format)
+                }
+                LabelValue::Bool(b) => {
+                    IntoExcelData::write_with_format(*b, worksheet, row, col, format)
+                }
+                LabelValue::Time(date_time) => IntoExcelData::write_with_format(
+                    &date_time.naive_utc(),
+                    worksheet,
+                    row,
+                    col,
+                    format,
+                ),
+                v => IntoExcelData::write_with_format(
+                    label_value_to_cell_string(v),
+                    worksheet,
+                    row,
+                    col,
+                    format,
+                ),
+            },
         }
     }
 }
@@ -125,6 +186,7 @@ impl From<&CellContent<'_>> for Vec<u8> {
                     v => format!("{}", v).into_bytes(),
                 }
             }
+            CellContent::Label(v) => label_value_to_cell_string(v).into_bytes(),
         }
     }
 }
@@ -297,9 +359,9 @@ pub fn export_bindings_to_table_writer<'a, W: std::io::Write>(
                     .situations
                     .iter()
                     .flat_map(|(b, _)| {
-                        b.get_ev(ev_var, ocel)
-                            .iter()
-                            .flat_map(|e| e.attributes.iter().map(|a| &a.name))
+                        b.get_ev_index(ev_var)
+                            .into_iter()
+                            .flat_map(|e| ocel.ev_attributes(e).map(|(name, _)| name))
                             .collect::<Vec<_>>()
                     })
                     .collect::<HashSet<_>>()
@@ -315,9 +377,9 @@ pub fn export_bindings_to_table_writer<'a, W: std::io::Write>(
                     .situations
                     .iter()
                     .flat_map(|(b, _)| {
-                        b.get_ob(ob_var, ocel)
-                            .iter()
-                            .flat_map(|e| e.attributes.iter().map(|a| &a.name))
+                        b.get_ob_index(ob_var)
+                            .into_iter()
+                            .flat_map(|o| ocel.ob_attributes(o).map(|(name, _, _)| name))
                             .collect::<Vec<_>>()
                     })
                     .collect::<HashSet<_>>()
@@ -357,22 +419,17 @@ pub fn export_bindings_to_table_writer<'a, W: std::io::Write>(
 
         for (b, v) in &bindings.situations {
             for (ob_v, ob_attrs) in ob_vars.iter().zip(&ob_attrs) {
-                if let Some(ob) = b.get_ob(ob_v, ocel) {
+                if let (Some(ob_index), Some(ob)) = (b.get_ob_index(ob_v), b.get_ob(ob_v, ocel)) {
                     if options.include_ids {
                         w.write_cell(&ob.id, CellType::DEFAULT)?;
                     }
                     for attr in ob_attrs {
-                        if let Some(val) = ob
-                            .attributes
-                            .iter()
-                            .filter(|a| &&a.name == attr)
-                            .sorted_by_key(|a| a.time)
+                        if let Some((val, _)) = ocel
+                            .ob_attribute_values(ob_index, attr)
+                            .sorted_by_key(|(_, time)| **time)
                             .next()
                         {
-                            w.write_cell(
-                                CellContent::Value(&val.value),
-                                CellType::ValueType((&val.value).into()),
-                            )?;
+                            w.write_cell(CellContent::Value(val), CellType::ValueType(val.into()))?;
                         } else {
                             w.write_cell("", CellType::DEFAULT)?;
                         }
@@ -387,16 +444,13 @@ pub fn export_bindings_to_table_writer<'a, W: std::io::Write>(
                 }
             }
             for (ev_v, ev_attrs) in ev_vars.iter().zip(&ev_attrs) {
-                if let Some(ev) = b.get_ev(ev_v, ocel) {
+                if let (Some(ev_index), Some(ev)) = (b.get_ev_index(ev_v), b.get_ev(ev_v, ocel)) {
                     if options.include_ids {
                         w.write_cell(&ev.id, CellType::DEFAULT)?;
                     }
                     for attr in ev_attrs {
-                        if let Some(val) = ev.attributes.iter().find(|a| &&a.name == attr) {
-                            w.write_cell(
-                                CellContent::Value(&val.value),
-                                CellType::ValueType((&val.value).into()),
-                            )?;
+                        if let Some(val) = ocel.ev_attribute(ev_index, attr) {
+                            w.write_cell(CellContent::Value(val), CellType::ValueType(val.into()))?;
                         } else {
                             w.write_cell("", CellType::DEFAULT)?;
                         }
@@ -413,8 +467,10 @@ pub fn export_bindings_to_table_writer<'a, W: std::io::Write>(
 
             for label in &options.labels {
                 match b.label_map.get(label) {
-                    // TODO: Also represent label values with correct types
-                    Some(val) => w.write_cell(val.to_string(), CellType::DEFAULT)?,
+                    Some(val) => w.write_cell(
+                        CellContent::Label(val),
+                        CellType::ValueType(label_value_type(val)),
+                    )?,
                     None => w.write_cell("null", CellType::DEFAULT)?,
                 }
             }
@@ -0,0 +1,573 @@
+use std::fmt::Display;
+
+use chrono::DateTime;
+
+use cel_parser::{parse, ArithmeticOp, Atom, Expression, Member, RelationOp, UnaryOp};
+
+use crate::{binding_box::structs::Variable, cel::string_to_var};
+
+use super::DatabaseType;
+
+/// Error while translating a CEL expression to SQL/Cypher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CelTranslationError {
+    /// The expression could not be parsed (or uses syntax outside of the supported subset)
+    Parse(String),
+    /// The function is not supported (or called with unsupported arguments)
+    UnsupportedFunction(String),
+    /// The name is neither an event/object variable nor a child set name
+    UnknownVariable(String),
+}
+
+impl Display for CelTranslationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CelTranslationError::Parse(msg) => write!(f, "Could not parse CEL expression: {msg}"),
+            CelTranslationError::UnsupportedFunction(name) => {
+                write!(f, "Unsupported CEL function '{name}'")
+            }
+            CelTranslationError::UnknownVariable(name) => write!(
+                f,
+                "Unknown CEL variable '{name}' (only event/object variables and child sets are supported)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CelTranslationError {}
+
+/// Translate a CEL expression to an SQL predicate
+///
+/// `child_queries` are the translated child queries with their names (i.e., the child binding sets available in the expression).
+/// Event/object variables refer to the aliases `E{i}`/`O{i}` of the surrounding query.
+pub fn cel_to_sql(
+    cel: &str,
+    database_type: DatabaseType,
+    child_queries: &[(String, String)],
+) -> Result<String, CelTranslationError> {
+    let expr = parse_cel(cel)?;
+    let mut translator = CelTranslator {
+        target: Target::Sql(database_type),
+        child_queries,
+        num_subqueries: 0,
+    };
+    Ok(translator.translate(&expr)?.0)
+}
+
+/// Translate a CEL expression to a Cypher predicate
+///
+/// `event_labels`/`object_labels` are the node labels of all events/objects (used for `numEvents()`/`numObjects()`).
+/// `child_queries` are expressions for the number of bindings of each child with their names (used for `size(...)`).
+/// Event/object variables refer to the aliases `e{i}`/`o{i}` of the surrounding query.
+pub fn cel_to_cypher(
+    cel: &str,
+    event_labels: &[String],
+    object_labels: &[String],
+    child_queries: &[(String, String)],
+) -> Result<String, CelTranslationError> {
+    let expr = parse_cel(cel)?;
+    let mut translator = CelTranslator {
+        target: Target::Cypher {
+            event_labels,
+            object_labels,
+        },
+        child_queries,
+        num_subqueries: 0,
+    };
+    Ok(translator.translate(&expr)?.0)
+}
+
+/// Parse a CEL expression (with the same parser as the CEL interpreter)
+fn parse_cel(cel: &str) -> Result<Expression, CelTranslationError> {
+    parse(cel).map_err(|e| CelTranslationError::Parse(e.to_string()))
+}
+
+/// Parse a CEL duration string (e.g., `1h30m` or `80s`) into seconds
+fn parse_duration(s: &str) -> Option<f64> {
+    let (sign, mut rest) = match s.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, s),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let num: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let (unit, factor) = [
+            ("ms", 1e-3),
+            ("us", 1e-6),
+            ("µs", 1e-6),
+            ("ns", 1e-9),
+            ("h", 3600.0),
+            ("m", 60.0),
+            ("s", 1.0),
+        ]
+        .into_iter()
+        .find(|(unit, _)| rest.starts_with(unit))?;
+        rest = &rest[unit.len()..];
+        seconds += num * factor;
+    }
+    Some(sign * seconds)
+}
+
+//
+// Translation
+//
+
+#[derive(Debug, Clone, Copy)]
+enum Target<'a> {
+    Sql(DatabaseType),
+    Cypher {
+        event_labels: &'a [String],
+        object_labels: &'a [String],
+    },
+}
+
+/// Type of a translated expression (as far as relevant for the translation)
+///
+/// Timestamps are translated to epoch seconds and durations to seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CelType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Timestamp,
+    Duration,
+    /// Attribute value with unknown type
+    Attribute,
+}
+
+struct CelTranslator<'a> {
+    target: Target<'a>,
+    child_queries: &'a [(String, String)],
+    num_subqueries: usize,
+}
+
+impl CelTranslator<'_> {
+    fn translate(&mut self, expr: &Expression) -> Result<(String, CelType), CelTranslationError> {
+        let is_sql = matches!(self.target, Target::Sql(_));
+        Ok(match expr {
+            Expression::Atom(atom) => match atom {
+                Atom::Null => ("NULL".to_string(), CelType::Null),
+                Atom::Bool(true) => ("TRUE".to_string(), CelType::Bool),
+                Atom::Bool(false) => ("FALSE".to_string(), CelType::Bool),
+                Atom::Int(i) => (i.to_string(), CelType::Number),
+                Atom::UInt(u) => (u.to_string(), CelType::Number),
+                Atom::Float(f) => (format!("{f:?}"), CelType::Number),
+                Atom::String(s) => (self.string_literal(s), CelType::String),
+                Atom::Bytes(_) => {
+                    return Err(CelTranslationError::Parse(
+                        "Bytes literals are not supported".to_string(),
+                    ))
+                }
+            },
+            Expression::Ident(name) => {
+                // Variables are only supported as function targets (e.g., `e1.time()`)
+                return Err(CelTranslationError::UnknownVariable(name.to_string()));
+            }
+            Expression::Unary(op, e) => {
+                let (e, t) = self.translate(e)?;
+                match op {
+                    UnaryOp::Not => (format!("(NOT {e})"), CelType::Bool),
+                    UnaryOp::Minus => (format!("(-{e})"), t),
+                    UnaryOp::DoubleNot | UnaryOp::DoubleMinus => (e, t),
+                }
+            }
+            Expression::Ternary(cond, then, otherwise) => {
+                let cond = self.translate(cond)?.0;
+                let (then, t) = self.translate(then)?;
+                let otherwise = self.translate(otherwise)?.0;
+                (
+                    format!("(CASE WHEN {cond} THEN {then} ELSE {otherwise} END)"),
+                    t,
+                )
+            }
+            Expression::Or(left, right) => {
+                let (l, r) = (self.translate(left)?.0, self.translate(right)?.0);
+                (format!("({l} OR {r})"), CelType::Bool)
+            }
+            Expression::And(left, right) => {
+                let (l, r) = (self.translate(left)?.0, self.translate(right)?.0);
+                (format!("({l} AND {r})"), CelType::Bool)
+            }
+            Expression::Relation(left, op, right) => {
+                let (l, lt, r, rt) = self.translate_operands(left, right)?;
+                let symbol = match op {
+                    RelationOp::Equals | RelationOp::NotEquals
+                        if lt == CelType::Null || rt == CelType::Null =>
+                    {
+                        let operand = if lt == CelType::Null { r } else { l };
+                        let negation = if *op == RelationOp::NotEquals {
+                            "NOT "
+                        } else {
+                            ""
+                        };
+                        return Ok((format!("({operand} IS {negation}NULL)"), CelType::Bool));
+                    }
+                    RelationOp::Equals => "=",
+                    RelationOp::NotEquals => "<>",
+                    RelationOp::LessThan => "<",
+                    RelationOp::LessThanEq => "<=",
+                    RelationOp::GreaterThan => ">",
+                    RelationOp::GreaterThanEq => ">=",
+                    RelationOp::In => {
+                        return Err(CelTranslationError::Parse(
+                            "The 'in' operator is not supported".to_string(),
+                        ))
+                    }
+                };
+                (format!("({l} {symbol} {r})"), CelType::Bool)
+            }
+            Expression::Arithmetic(left, op, right) => {
+                let (l, lt, r, rt) = self.translate_operands(left, right)?;
+                if *op == ArithmeticOp::Add && (lt == CelType::String || rt == CelType::String) {
+                    let concat = if is_sql { "||" } else { "+" };
+                    return Ok((format!("({l} {concat} {r})"), CelType::String));
+                }
+                let t = match (op, lt, rt) {
+                    (ArithmeticOp::Subtract, CelType::Timestamp, CelType::Timestamp) => {
+                        CelType::Duration
+                    }
+                    (ArithmeticOp::Add | ArithmeticOp::Subtract, CelType::Timestamp, _)
+                    | (ArithmeticOp::Add, _, CelType::Timestamp) => CelType::Timestamp,
+                    (_, CelType::Duration, _) | (_, _, CelType::Duration) => CelType::Duration,
+                    (_, CelType::Attribute, _) | (_, _, CelType::Attribute) => CelType::Attribute,
+                    _ => CelType::Number,
+                };
+                let symbol = match op {
+                    ArithmeticOp::Add => "+",
+                    ArithmeticOp::Subtract => "-",
+                    ArithmeticOp::Multiply => "*",
+                    ArithmeticOp::Divide => "/",
+                    ArithmeticOp::Modulus => "%",
+                };
+                (format!("({l} {symbol} {r})"), t)
+            }
+            Expression::FunctionCall(function, target, args) => match &**function {
+                Expression::Ident(function) => {
+                    self.translate_call(target.as_deref(), function, args)?
+                }
+                _ => {
+                    return Err(CelTranslationError::Parse(
+                        "Only named functions can be called".to_string(),
+                    ))
+                }
+            },
+            Expression::Member(_, member) => {
+                let msg = match &**member {
+                    Member::Attribute(name) => format!("Field access ('.{name}') is not supported"),
+                    Member::Index(_) => "Indexing is not supported".to_string(),
+                    Member::Fields(_) => "Message construction is not supported".to_string(),
+                };
+                return Err(CelTranslationError::Parse(msg));
+            }
+            Expression::List(_) | Expression::Map(_) => {
+                return Err(CelTranslationError::Parse(
+                    "Lists and maps are not supported".to_string(),
+                ))
+            }
+        })
+    }
+
+    /// Translate both operands of a binary operator
+    ///
+    /// Attribute values compared to/combined with timestamps are interpreted as timestamps.
+    fn translate_operands(
+        &mut self,
+        left: &Expression,
+        right: &Expression,
+    ) -> Result<(String, CelType, String, CelType), CelTranslationError> {
+        let (mut l, lt) = self.translate(left)?;
+        let (mut r, rt) = self.translate(right)?;
+        if lt == CelType::Timestamp && rt == CelType::Attribute {
+            r = self.epoch_seconds(&r);
+        }
+        if rt == CelType::Timestamp && lt == CelType::Attribute {
+            l = self.epoch_seconds(&l);
+        }
+        Ok((l, lt, r, rt))
+    }
+
+    fn translate_call(
+        &mut self,
+        target: Option<&Expression>,
+        function: &str,
+        args: &[Expression],
+    ) -> Result<(String, CelType), CelTranslationError> {
+        use Expression::{Atom as A, Ident};
+        let unsupported = || CelTranslationError::UnsupportedFunction(function.to_string());
+        match (function, target, args) {
+            ("attr", Some(Ident(var)), [A(Atom::String(attr))]) => {
+                let alias = self.variable_alias(var)?;
+                let column = match self.target {
+                    Target::Sql(_) => format!("{alias}.\"{}\"", attr.replace('"', "\"\"")),
+                    Target::Cypher { .. } => format!("{alias}.`{}`", attr.replace('`', "``")),
+                };
+                Ok((column, CelType::Attribute))
+            }
+            ("time", Some(Ident(var)), []) => {
+                if !matches!(string_to_var(var), Some(Variable::Event(_))) {
+                    return Err(unsupported());
+                }
+                let alias = self.variable_alias(var)?;
+                let time = match self.target {
+                    Target::Sql(_) => self.epoch_seconds(&format!("{alias}.ocel_time")),
+                    Target::Cypher { .. } => format!("({alias}.time.epochMillis / 1000.0)"),
+                };
+                Ok((time, CelType::Timestamp))
+            }
+            ("numEvents" | "numObjects", None, []) => {
+                let is_events = function == "numEvents";
+                let count = match self.target {
+                    Target::Sql(_) => format!(
+                        "(SELECT COUNT(*) FROM \"{}\")",
+                        if is_events { "event" } else { "object" }
+                    ),
+                    Target::Cypher {
+                        event_labels,
+                        object_labels,
+                    } => {
+                        let labels = if is_events {
+                            event_labels
+                        } else {
+                            object_labels
+                        };
+                        if labels.is_empty() {
+                            "0".to_string()
+                        } else {
+                            let labels = labels
+                                .iter()
+                                .map(|l| format!("`{}`", l.replace('`', "``")))
+                                .collect::<Vec<_>>()
+                                .join("|");
+                            format!("COUNT {{ MATCH (n:{labels}) }}")
+                        }
+                    }
+                };
+                Ok((count, CelType::Number))
+            }
+            ("size", Some(Ident(child_name)), []) | ("size", None, [Ident(child_name)]) => {
+                let child_query = self
+                    .child_queries
+                    .iter()
+                    .find(|(_, name)| name.as_str() == child_name.as_str())
+                    .map(|(query, _)| query.clone())
+                    .ok_or_else(|| CelTranslationError::UnknownVariable(child_name.to_string()))?;
+                let size = match self.target {
+                    Target::Sql(_) => {
+                        let alias = format!("subqCEL_{}", self.num_subqueries);
+                        self.num_subqueries += 1;
+                        format!(
+                            "COALESCE((SELECT COUNT(DISTINCT {alias}.cnt_key) FROM ({child_query}) AS {alias}), 0)"
+                        )
+                    }
+                    Target::Cypher { .. } => child_query,
+                };
+                Ok((size, CelType::Number))
+            }
+            ("duration", None, [A(Atom::String(s))]) => {
+                let seconds = parse_duration(s)
+                    .ok_or_else(|| CelTranslationError::Parse(format!("Invalid duration '{s}'")))?;
+                Ok((seconds.to_string(), CelType::Duration))
+            }
+            ("timestamp", None, [A(Atom::String(s))]) => {
+                let time = DateTime::parse_from_rfc3339(s).map_err(|e| {
+                    CelTranslationError::Parse(format!("Invalid timestamp '{s}': {e}"))
+                })?;
+                let seconds = time.timestamp_millis() as f64 / 1000.0;
+                Ok((seconds.to_string(), CelType::Timestamp))
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// Alias of an event/object variable (e.g., `e1`) in the translated query
+    fn variable_alias(&self, name: &str) -> Result<String, CelTranslationError> {
+        let var = string_to_var(name)
+            .ok_or_else(|| CelTranslationError::UnknownVariable(name.to_string()))?;
+        Ok(match (self.target, var) {
+            (Target::Sql(_), Variable::Event(v)) => format!("E{}", v.0),
+            (Target::Sql(_), Variable::Object(v)) => format!("O{}", v.0),
+            (Target::Cypher { .. }, Variable::Event(v)) => format!("e{}", v.0),
+            (Target::Cypher { .. }, Variable::Object(v)) => format!("o{}", v.0),
+        })
+    }
+
+    /// Convert a timestamp value to epoch seconds
+    fn epoch_seconds(&self, value: &str) -> String {
+        match self.target {
+            // strftime returns text, which would not compare correctly with numbers
+            Target::Sql(DatabaseType::SQLite) => {
+                format!("CAST(strftime('%s', {value}) AS INTEGER)")
+            }
+            Target::Sql(DatabaseType::DuckDB) => format!("EPOCH(CAST({value} AS TIMESTAMP))"),
+            Target::Sql(DatabaseType::Postgres) => {
+                format!("EXTRACT(EPOCH FROM CAST({value} AS TIMESTAMPTZ))")
+            }
+            Target::Cypher { .. } => format!("(datetime({value}).epochMillis / 1000.0)"),
+        }
+    }
+
+    fn string_literal(&self, s: &str) -> String {
+        match self.target {
+            Target::Sql(_) => format!("'{}'", s.replace('\'', "''")),
+            Target::Cypher { .. } => {
+                format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cel_to_sql() {
+    let children = vec![("SELECT 1 AS cnt_key".to_string(), "A".to_string())];
+    assert_eq!(
+        cel_to_sql(
+            "e1.attr('price') >= 100 && size(A) < 3",
+            DatabaseType::SQLite,
+            &children
+        )
+        .unwrap(),
+        "((E0.\"price\" >= 100) AND (COALESCE((SELECT COUNT(DISTINCT subqCEL_0.cnt_key) FROM (SELECT 1 AS cnt_key) AS subqCEL_0), 0) < 3))"
+    );
+    assert_eq!(
+        cel_to_cypher("e2.time() - e1.time() <= duration('1h')", &[], &[], &[]).unwrap(),
+        "(((e1.time.epochMillis / 1000.0) - (e0.time.epochMillis / 1000.0)) <= 3600)"
+    );
+    assert_eq!(
+        cel_to_sql(
+            "o1.attrAt('price', e1.time()) > 1",
+            DatabaseType::SQLite,
+            &[]
+        ),
+        Err(CelTranslationError::UnsupportedFunction(
+            "attrAt".to_string()
+        ))
+    );
+    assert_eq!(
+        cel_to_sql("size(B) > 1", DatabaseType::SQLite, &children),
+        Err(CelTranslationError::UnknownVariable("B".to_string()))
+    );
+}
+
+#[test]
+fn test_cel_operators() {
+    let sql = |cel: &str| cel_to_sql(cel, DatabaseType::SQLite, &[]).unwrap();
+    let cypher = |cel: &str| cel_to_cypher(cel, &[], &[], &[]).unwrap();
+    assert_eq!(sql("e1.attr('status') == null"), "(E0.\"status\" IS NULL)");
+    assert_eq!(
+        cypher("e1.attr('status') != null"),
+        "(e0.`status` IS NOT NULL)"
+    );
+    assert_eq!(sql("!(o1.attr('price') > 10)"), "(NOT (O0.\"price\" > 10))");
+    assert_eq!(
+        sql("o1.attr('price') % 2 == 0 ? 'even' : 'odd'"),
+        "(CASE WHEN ((O0.\"price\" % 2) = 0) THEN 'even' ELSE 'odd' END)"
+    );
+    assert_eq!(sql("o1.attr('price') * 1.5"), "(O0.\"price\" * 1.5)");
+    assert_eq!(sql("o1.attr('name') + '!'"), "(O0.\"name\" || '!')");
+    assert_eq!(cypher("o1.attr('name') + '!'"), "(o0.`name` + '!')");
+    assert_eq!(
+        sql("o1.attr('name') == \"it's\""),
+        "(O0.\"name\" = 'it''s')"
+    );
+    assert_eq!(
+        cypher("o1.attr('name') == \"it's\""),
+        "(o0.`name` = 'it\\'s')"
+    );
+}
+
+#[test]
+fn test_cel_sizes_and_counts() {
+    let children = vec![("COUNT { MATCH (e1) }".to_string(), "A".to_string())];
+    let labels = vec!["payorder".to_string(), "placeorder".to_string()];
+    assert_eq!(
+        cel_to_cypher("size(A) >= 1 || A.size() == 0", &[], &[], &children).unwrap(),
+        "((COUNT { MATCH (e1) } >= 1) OR (COUNT { MATCH (e1) } = 0))"
+    );
+    assert_eq!(
+        cel_to_cypher("numEvents() > 2 && numObjects() == 0", &labels, &[], &[]).unwrap(),
+        "((COUNT { MATCH (n:`payorder`|`placeorder`) } > 2) AND (0 = 0))"
+    );
+    assert_eq!(
+        cel_to_sql("numEvents() > 2", DatabaseType::SQLite, &[]).unwrap(),
+        "((SELECT COUNT(*) FROM \"event\") > 2)"
+    );
+}
+
+#[test]
+fn test_cel_timestamps() {
+    assert_eq!(
+        cel_to_sql(
+            "e1.time() >= timestamp('2023-01-01T00:00:00Z')",
+            DatabaseType::SQLite,
+            &[]
+        )
+        .unwrap(),
+        "(CAST(strftime('%s', E0.ocel_time) AS INTEGER) >= 1672531200)"
+    );
+    assert_eq!(
+        cel_to_sql(
+            "e1.time() >= timestamp('2023-01-01T00:00:00Z')",
+            DatabaseType::Postgres,
+            &[]
+        )
+        .unwrap(),
+        "(EXTRACT(EPOCH FROM CAST(E0.ocel_time AS TIMESTAMPTZ)) >= 1672531200)"
+    );
+    // Attribute values compared with timestamps are interpreted as timestamps
+    assert_eq!(
+        cel_to_sql("o1.attr('deadline') < e1.time()", DatabaseType::DuckDB, &[]).unwrap(),
+        "(EPOCH(CAST(O0.\"deadline\" AS TIMESTAMP)) < EPOCH(CAST(E0.ocel_time AS TIMESTAMP)))"
+    );
+    assert_eq!(
+        cel_to_sql(
+            "e2.time() - e1.time() > duration('1h30m')",
+            DatabaseType::SQLite,
+            &[]
+        )
+        .unwrap(),
+        "((CAST(strftime('%s', E1.ocel_time) AS INTEGER) - CAST(strftime('%s', E0.ocel_time) AS INTEGER)) > 5400)"
+    );
+}
+
+#[test]
+fn test_cel_unsupported() {
+    let sql = |cel: &str| cel_to_sql(cel, DatabaseType::SQLite, &[]);
+    assert!(matches!(
+        sql("e1.price > 1"),
+        Err(CelTranslationError::Parse(_))
+    ));
+    assert!(matches!(
+        sql("o1.attr('tags')[0] == 'a'"),
+        Err(CelTranslationError::Parse(_))
+    ));
+    assert!(matches!(
+        sql("o1.attr('price') in [1, 2]"),
+        Err(CelTranslationError::Parse(_))
+    ));
+    assert!(matches!(
+        sql("e1.attr('price') >"),
+        Err(CelTranslationError::Parse(_))
+    ));
+    assert_eq!(
+        sql("matches(o1.attr('name'), 'a.*')"),
+        Err(CelTranslationError::UnsupportedFunction(
+            "matches".to_string()
+        ))
+    );
+    assert_eq!(
+        sql("o1.time() > 0"),
+        Err(CelTranslationError::UnsupportedFunction("time".to_string()))
+    );
+    assert_eq!(
+        sql("A > 1"),
+        Err(CelTranslationError::UnknownVariable("A".to_string()))
+    );
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::binding_box::{
+    structs::{BindingBoxTreeNode, Constraint, Filter, SizeFilter},
+    BindingBoxTree,
+};
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranslationIssueKind {
+    /// The construct is not translated (or translated incorrectly)
+    Unsupported,
+    /// The construct is translated, but with slightly different semantics
+    Approximated,
+    /// An event/object type is not mapped to a table/label, so no query can be generated
+    UnknownType,
+}
+
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationIssue {
+    /// Index of the node in the tree
+    pub node_index: usize,
+    pub kind: TranslationIssueKind,
+    /// Name of the filter/constraint/variable, e.g., `O2E` or `ev_0`
+    pub construct: String,
+    pub reason: String,
+}
+
+impl TranslationIssue {
+    pub(crate) fn new(
+        node_index: usize,
+        kind: TranslationIssueKind,
+        construct: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        TranslationIssue {
+            node_index,
+            kind,
+            construct: construct.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// A translated query which is not semantically equivalent to the native evaluation of the tree
+#[derive(Debug, Clone)]
+pub struct TranslationError {
+    /// The (approximate) translated query
+    ///
+    /// Empty, if no query could be generated (see [`TranslationIssueKind::UnknownType`])
+    pub query: String,
+    pub issues: Vec<TranslationIssue>,
+}
+
+impl Display for TranslationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Translated query is not equivalent to the native evaluation:"
+        )?;
+        for issue in &self.issues {
+            write!(
+                f,
+                "\n- Node {} ({:?}): {}: {}",
+                issue.node_index, issue.kind, issue.construct, issue.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TranslationError {}
+
+/// Whether a query can be generated despite the given issues
+pub(crate) fn can_generate_query(issues: &[TranslationIssue]) -> bool {
+    issues
+        .iter()
+        .all(|i| i.kind != TranslationIssueKind::UnknownType)
+}
+
+/// Translated query together with all detected issues (empty if the query is equivalent)
+#[derive(TS)]
+#[ts(export, export_to = "../../../frontend/src/types/generated/")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedQuery {
+    pub query: String,
+    pub issues: Vec<TranslationIssue>,
+}
+
+impl From<Result<String, TranslationError>> for TranslatedQuery {
+    fn from(res: Result<String, TranslationError>) -> Self {
+        match res {
+            Ok(query) => TranslatedQuery {
+                query,
+                issues: Vec::new(),
+            },
+            Err(e) => TranslatedQuery {
+                query: e.query,
+                issues: e.issues,
+            },
+        }
+    }
+}
+
+/// Collect the issues of the variables of the tree, i.e., event/object types which are not mapped to a table/label
+///
+/// Only nodes reachable from the root are checked.
+/// Issues of the other constructs (filters, constraints, ...) are recorded during the translation.
+pub fn check_variables(
+    tree: &BindingBoxTree,
+    event_tables: &HashMap<String, String>,
+    object_tables: &HashMap<String, String>,
+) -> Vec<TranslationIssue> {
+    let mut issues = Vec::new();
+    let mut stack = if tree.nodes.is_empty() {
+        Vec::new()
+    } else {
+        vec![0]
+    };
+    while let Some(index) = stack.pop() {
+        let (bbox, children) = tree.nodes[index].clone().to_box();
+        let mut issue = |kind: TranslationIssueKind, construct: String, reason: &str| {
+            issues.push(TranslationIssue::new(index, kind, construct, reason))
+        };
+        for (ev_var, types) in &bbox.new_event_vars {
+            check_var_types(ev_var.to_string(), types, event_tables, &mut issue);
+        }
+        for (ob_var, types) in &bbox.new_object_vars {
+            check_var_types(ob_var.to_string(), types, object_tables, &mut issue);
+        }
+        stack.extend(children);
+    }
+    issues
+}
+
+fn check_var_types(
+    var_name: String,
+    types: &HashSet<String>,
+    tables: &HashMap<String, String>,
+    issue: &mut impl FnMut(TranslationIssueKind, String, &str),
+) {
+    if types.len() > 1 {
+        issue(
+            TranslationIssueKind::Approximated,
+            var_name.clone(),
+            "Variables with multiple types are only matched against one of their types",
+        );
+    }
+    for t in types {
+        if !tables.contains_key(t) {
+            issue(
+                TranslationIssueKind::UnknownType,
+                format!("{var_name} ({t})"),
+                "Type is not mapped to a table/label",
+            );
+        }
+    }
+}
+
+pub(crate) fn gate_name(node: &BindingBoxTreeNode) -> &'static str {
+    match node {
+        BindingBoxTreeNode::Box(_, _) => "Box",
+        BindingBoxTreeNode::OR(_, _) => "OR",
+        BindingBoxTreeNode::AND(_, _) => "AND",
+        BindingBoxTreeNode::NOT(_) => "NOT",
+    }
+}
+
+pub(crate) fn filter_name(filter: &Filter) -> &'static str {
+    match filter {
+        Filter::O2E { .. } => "O2E",
+        Filter::O2O { .. } => "O2O",
+        Filter::TimeBetweenEvents { .. } => "TimeBetweenEvents",
+        Filter::NotEqual { .. } => "NotEqual",
+        Filter::EventAttributeValueFilter { .. } => "EventAttributeValueFilter",
+        Filter::ObjectAttributeValueFilter { .. } => "ObjectAttributeValueFilter",
+        Filter::BasicFilterCEL { .. } => "BasicFilterCEL",
+        Filter::BindingCEL { .. } => "BindingCEL",
+    }
+}
+
+pub(crate) fn size_filter_name(size_filter: &SizeFilter) -> &'static str {
+    match size_filter {
+        SizeFilter::NumChilds { .. } => "NumChilds",
+        SizeFilter::BindingSetEqual { .. } => "BindingSetEqual",
+        SizeFilter::BindingSetProjectionEqual { .. } => "BindingSetProjectionEqual",
+        SizeFilter::NumChildsProj { .. } => "NumChildsProj",
+        SizeFilter::AdvancedCEL { .. } => "AdvancedCEL",
+    }
+}
+
+pub(crate) fn constraint_name(constraint: &Constraint) -> String {
+    match constraint {
+        Constraint::Filter { filter } => format!("Constraint {}", filter_name(filter)),
+        Constraint::SizeFilter { filter } => format!("Constraint {}", size_filter_name(filter)),
+        Constraint::SAT { .. } => "SAT".to_string(),
+        Constraint::ANY { .. } => "ANY".to_string(),
+        Constraint::NOT { .. } => "NOT".to_string(),
+        Constraint::OR { .. } => "OR".to_string(),
+        Constraint::AND { .. } => "AND".to_string(),
+    }
+}
@@ -1,15 +1,24 @@
 use std::{
-    collections::{ HashMap, HashSet}
+    collections::{ BTreeSet, HashMap, HashSet}
 };
-use crate::binding_box::{structs::{Constraint, Filter, ObjectValueFilterTimepoint, SizeFilter, ValueFilter}, BindingBoxTree};
+use crate::binding_box::{structs::{BindingBoxTreeNode, Constraint, Filter, ObjectValueFilterTimepoint, SizeFilter, ValueFilter}, BindingBoxTree};
 use crate::binding_box::structs::NewEventVariables;
 use crate::binding_box::structs::NewObjectVariables;
 use crate::binding_box::structs::ObjectVariable;
 use crate::binding_box::structs::EventVariable;
 use crate::binding_box::structs::Qualifier;
+use crate::binding_box::structs::Variable;
 use ts_rs::TS;
 use serde::{Deserialize, Serialize};
 
+pub mod sql_execution;
+pub mod issues;
+pub mod cel_translation;
+pub mod neo4j_export;
+
+use cel_translation::{cel_to_cypher, cel_to_sql};
+use issues::{can_generate_query, check_variables, constraint_name, filter_name, gate_name, size_filter_name, TranslationError, TranslationIssue, TranslationIssueKind};
+
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,11 +36,18 @@ pub enum DatabaseType {
 
 SQLite,
 
-DuckDB
+DuckDB,
+
+/// OCEL 2.0 relational schema (same tables as the OCEL 2.0 SQLite format) imported into PostgreSQL
+Postgres
 
 }
 
 
+// Prefix of the result columns listing the unsatisfied bindings of a child (followed by the child name)
+pub const UNSATISFIED_CHILDREN_COLUMN_PREFIX: &str = "unsatisfied_";
+
+
 #[derive(Clone)]
 pub struct SqlParts{
     node: InterMediateNode,
@@ -43,20 +59,60 @@ pub struct SqlParts{
     event_tables: HashMap<String,String>,
     object_tables: HashMap<String,String>,
     used_keys: HashSet<String>,
-    database_type: DatabaseType 
+    database_type: DatabaseType,
+    // Constructs which are not (exactly) translated
+    issues: Vec<TranslationIssue>
 }
 
 
 
 // Implementation of the General translate to SQL function
+// Returns an error (still containing the query if possible) if the query is not equivalent to the native evaluation
 pub fn translate_to_sql_shared(
     tree: BindingBoxTree,
     database: DatabaseType
-)-> String{
+)-> Result<String, TranslationError>{
+
+    let (event_tables, object_tables) = match database {
+
+            DatabaseType::SQLite => {
+                (map_to_event_tables_sqllite(), map_to_object_tables_sqllite()) // args can be done later, for now hardcoded for Management
+            }
+
+            DatabaseType::DuckDB => {
+                (map_to_event_tables_duckdb(), map_to_object_tables_duckdb())
+            }
+
+            // Same table names as the OCEL 2.0 SQLite export
+            DatabaseType::Postgres => {
+                (map_to_event_tables_sqllite(), map_to_object_tables_sqllite())
+            }
+
+        };
+
+    return translate_to_sql_with_tables(tree, database, event_tables, object_tables);
+}
+
+
+// Translate to SQL with the given mapping of event/object types to table names (without the event_/object_ prefix)
+// The mappings also need to contain "object" (for the event_object and object_object tables)
+pub fn translate_to_sql_with_tables(
+    tree: BindingBoxTree,
+    database: DatabaseType,
+    event_tables: HashMap<String, String>,
+    object_tables: HashMap<String, String>
+)-> Result<String, TranslationError>{
+
+    // Step 0: Check that all types are mapped to tables (all other issues are recorded during the translation)
+    let mut issues = check_variables(&tree, &event_tables, &object_tables);
+
+    if !can_generate_query(&issues){
+        return Err(TranslationError { query: String::new(), issues });
+    }
 
     
     //Step 1:  Extract Intermediate Representation
-    let inter = convert_to_intermediate(tree);
+    let inter = convert_to_intermediate(tree, &mut issues);
 
    
    
@@ -69,44 +125,35 @@ pub fn translate_to_sql_shared(
         join_clauses: vec![],
         where_clauses: vec![],
         child_sql: vec![],
-        event_tables: HashMap::new(),
-        object_tables: HashMap::new(),
+        event_tables,
+        object_tables,
         used_keys: HashSet::new(),
         database_type: database ,
+        issues,
     };
-
-    match sql_parts.database_type {
-
-            DatabaseType::SQLite => {
-                sql_parts.event_tables = map_to_event_tables_sqllite(); // args can be done later, for now hardcoded for Management
-
-                sql_parts.object_tables = map_to_object_tables_sqllite();
-            }
-
-            DatabaseType::DuckDB => {
-                sql_parts.event_tables = map_to_event_tables_duckdb();
-                sql_parts.object_tables = map_to_object_tables_duckdb();
-            }
-
-        }
    
    
     // Step 2: Translate the Intermediate Representation to SQL
    
-    let result = translate_to_sql_from_intermediate(sql_parts);
-    
+    let result = translate_to_sql_from_intermediate(&mut sql_parts);
     
+    let issues = sql_parts.issues;
+    if !issues.is_empty(){
+        return Err(TranslationError { query: result, issues });
+    }
     
-    return result;
+    return Ok(result);
 }
 
 
+// Gate nodes are recorded as issues (and converted to binding boxes)
 pub fn convert_to_intermediate(
-    tree: BindingBoxTree
+    tree: BindingBoxTree,
+    issues: &mut Vec<TranslationIssue>
 ) -> InterMediateNode {
 
     // Recursive approach for each binding box, start with the root node
-    let intermediate = bindingbox_to_intermediate(&tree, 0);
+    let intermediate = bindingbox_to_intermediate(&tree, 0, issues);
 
     
     return intermediate;
@@ -115,6 +162,8 @@ pub fn convert_to_intermediate(
 
 #[derive(Clone)]
 pub struct InterMediateNode{
+    // Index of the node in the tree
+    pub index: usize,
     pub event_vars: NewEventVariables,
     pub object_vars: NewObjectVariables,
     pub relations: Vec<Relation>, // O2O, E2O, TBE Basics have to be included
@@ -147,11 +196,16 @@ pub enum Relation{
 
 pub fn bindingbox_to_intermediate(
     tree: &BindingBoxTree,
-    index: usize
+    index: usize,
+    issues: &mut Vec<TranslationIssue>
 ) -> InterMediateNode{
 
     let node = &tree.nodes[index];
 
+    if !matches!(node, BindingBoxTreeNode::Box(_, _)) {
+        issues.push(TranslationIssue::new(index, TranslationIssueKind::Unsupported, gate_name(node), "Gate nodes (OR/AND/NOT) are not translated"));
+    }
+
     let (binding_box, child_indices) = node.clone().to_box();
 
 
@@ -172,7 +226,7 @@ pub fn bindingbox_to_intermediate(
 
     // Iterate over all BindingBoxes in tree
     for child_index in child_indices{
-        let child_node = bindingbox_to_intermediate(tree, child_index);
+        let child_node = bindingbox_to_intermediate(tree, child_index, issues);
         
         // Extract label names from edge_names
         let edge_name = tree
@@ -185,6 +239,7 @@ pub fn bindingbox_to_intermediate(
     }
 
     let result = InterMediateNode {
+            index,
             event_vars,
             object_vars,
             relations,
@@ -269,17 +324,9 @@ pub fn extract_constraints(
             }
 
 
-            Constraint::SizeFilter { filter } =>{
-                match filter {
-                    SizeFilter::NumChilds { child_name: _, min: _, max: _ } =>{
-                        result.push(constraint.clone());
-                    }
-
-                    _ => {
-                // Ignore the other constraints
-                }
-
-                }
+            // Unsupported size filters are recorded as issues by the translation
+            Constraint::SizeFilter { filter: _ } =>{
+                result.push(constraint.clone());
             }
 
 
@@ -336,11 +383,20 @@ pub fn extract_filters(
                 result.push(filter.clone());
             }
 
+            Filter::BasicFilterCEL { cel:_ } =>{
+                result.push(filter.clone());
+            }
 
-            _=>{
+            // Basic relations (see extract_basic_relations)
+            Filter::O2E { .. } | Filter::O2O { .. } | Filter::TimeBetweenEvents { .. } =>{
 
             }
 
+            // Unsupported filters are recorded as issues by the translation
+            _=>{
+                result.push(filter.clone());
+            }
+
 
         }
     }
@@ -586,24 +642,27 @@ pub fn map_to_object_tables_duckdb(
 
 // Function which translates Intermediate to SQL
 pub fn translate_to_sql_from_intermediate(
-     mut sql_parts: SqlParts
+     sql_parts: &mut SqlParts
 ) -> String {
 
 
-    sql_parts.select_fields = construct_select_fields(&sql_parts);
+    sql_parts.select_fields = construct_select_fields(sql_parts);
     
     
-    sql_parts.base_from = construct_from_clauses(&mut sql_parts);
+    sql_parts.base_from = construct_from_clauses(sql_parts);
     
-    (sql_parts.join_clauses, sql_parts.where_clauses) = construct_basic_operations(&mut sql_parts);
+    (sql_parts.join_clauses, sql_parts.where_clauses) = construct_basic_operations(sql_parts);
 
-    let childs = construct_childstrings(&sql_parts);
+    let childs = construct_childstrings(sql_parts);
     sql_parts.child_sql = childs;
     
 
-    let filter_clauses = construct_filter_non_basic(&mut sql_parts);
+    let filter_clauses = construct_filter_non_basic(sql_parts);
     sql_parts.where_clauses.extend(filter_clauses);    
 
+    let cel_clauses = construct_cel_filters(sql_parts);
+    sql_parts.where_clauses.extend(cel_clauses);
+
     for (obj_var, _types) in &sql_parts.node.object_vars {
             sql_parts.where_clauses.push(format!("O{}.ocel_changed_field IS NULL", obj_var.0));
 
@@ -611,7 +670,7 @@ pub fn translate_to_sql_from_intermediate(
 
 
         let result = construct_result(
-        &mut sql_parts,
+        sql_parts,
     );
 
     return result;
@@ -646,6 +705,19 @@ pub fn construct_result(
             ",\nCASE WHEN {} THEN 1 ELSE 0 END AS satisfied \n",
             child_constraint_string
         ));
+
+        // Keys of the unsatisfied bindings of each child (comma-separated), to see why a binding is violated
+        for (j, (child_sql, child_label)) in sql_parts.child_sql.iter().enumerate() {
+            let alias = format!("subqU_{}_{}", j, child_label.trim());
+            result.push_str(&format!(
+                ",\n(SELECT {} FROM ({}) AS {alias} WHERE {alias}.satisfied = 0) AS \"{}{}\"\n",
+                map_string_agg(sql_parts, &format!("{alias}.cnt_key")),
+                child_sql,
+                UNSATISFIED_CHILDREN_COLUMN_PREFIX,
+                child_label.trim(),
+                alias = alias
+            ));
+        }
     }
 
 
@@ -769,7 +841,10 @@ pub fn construct_from_clauses(sql_parts: &mut SqlParts) -> Vec<String> {
 
     for relation in &sql_parts.node.relations {
         match relation {
-            Relation::E2O { event, object, qualifier: _ } => {
+            Relation::E2O { event, object, qualifier } => {
+                if qualifier.is_some() {
+                    sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Approximated, "O2E", "Qualifiers are ignored"));
+                }
                 let event_alias = format!("E{}", event.0);
                 let object_alias = format!("O{}", object.0);
                 let mut event_object_alias = format!("E2O{}", counter);
@@ -905,7 +980,10 @@ pub fn construct_from_clauses(sql_parts: &mut SqlParts) -> Vec<String> {
                 }
             }
 
-            Relation::O2O { object_1, object_2, qualifier: _ } => {
+            Relation::O2O { object_1, object_2, qualifier } => {
+                if qualifier.is_some() {
+                    sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Approximated, "O2O", "Qualifiers are ignored"));
+                }
                 let object1_alias = format!("O{}", object_1.0);
                 let object2_alias = format!("O{}", object_2.0);
                 let mut object_object_alias = format!("O2O{}", counter);
@@ -1110,22 +1188,7 @@ pub fn construct_basic_operations(
                 min_seconds,
                 max_seconds,
             } => {
-                where_clauses.push(format!("{left_event} <= {right_event}",
-                 left_event = map_timestamp_event(sql_parts, from_event.0),
-                 right_event = map_timestamp_event(sql_parts, to_event.0)));
-                if let Some(min) = min_seconds {
-                    where_clauses.push(format!("{time_left} - {time_right} >= {min}", 
-                    time_left = map_timestamp_event(sql_parts, to_event.0),
-                    time_right = map_timestamp_event(sql_parts, from_event.0)));
-                }
-                if let Some(max) = max_seconds {
-                    where_clauses.push(format!("{time_left} - {time_right} <= {max}",
-                     time_left = map_timestamp_event(sql_parts, to_event.0),
-                     time_right = map_timestamp_event(sql_parts, from_event.0)));
-                }
-           
-           
-           
+                where_clauses.extend(map_time_between_events(sql_parts, from_event.0, to_event.0, min_seconds, max_seconds));
             }
         
             _ => {}
@@ -1141,7 +1204,8 @@ pub fn construct_basic_operations(
 
 
 
-pub fn construct_childstrings(sql_parts: &SqlParts) -> Vec<(String, String)> {
+// Issues of the children are added to the issues of the parent
+pub fn construct_childstrings(sql_parts: &mut SqlParts) -> Vec<(String, String)> {
     let mut result = Vec::new();
 
     for (inter_node, node_label) in &sql_parts.node.children {
@@ -1156,6 +1220,7 @@ pub fn construct_childstrings(sql_parts: &SqlParts) -> Vec<(String, String)> {
             object_tables: sql_parts.object_tables.clone(),
             used_keys: sql_parts.used_keys.clone(),
             database_type: sql_parts.database_type,
+            issues: vec![],
         };
 
 
@@ -1163,6 +1228,7 @@ pub fn construct_childstrings(sql_parts: &SqlParts) -> Vec<(String, String)> {
 
         let child_sql = translate_to_sql_from_child(&mut child_sql_parts);
         result.push((child_sql, node_label.clone()));
+        sql_parts.issues.extend(child_sql_parts.issues);
     }
 
     return result;
@@ -1269,7 +1335,17 @@ pub fn construct_child_constraints(
             }
 
 
+            Constraint::SizeFilter { filter: SizeFilter::AdvancedCEL { cel } } => {
+                match cel_to_sql(cel, sql_parts.database_type, &sql_parts.child_sql) {
+                    Ok(clause) => result_string.push(clause),
+                    Err(e) => sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), e.to_string())),
+                }
+            }
+
             Constraint::SizeFilter { filter } => {
+                if !matches!(filter, SizeFilter::NumChilds { .. }) {
+                    sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), "Size filter constraint is not translated"));
+                }
                 if let SizeFilter::NumChilds { child_name, min, max } = filter {
                     for (j, (child_sql, child_label)) in sql_parts.child_sql.iter().enumerate() {
                     if child_label == child_name {
@@ -1293,6 +1369,12 @@ pub fn construct_child_constraints(
                 }
             
             Constraint::Filter { filter } => {
+                match filter {
+                    Filter::O2E { qualifier: Some(_), .. } | Filter::O2O { qualifier: Some(_), .. } => {
+                        sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Approximated, constraint_name(constraint), "Qualifiers are ignored"));
+                    }
+                    _ => {}
+                }
                 match filter {
                     Filter::O2E { object, event, .. } => {
                         let base_alias = format!("E2O{}{}", event.0, object.0);
@@ -1334,25 +1416,20 @@ pub fn construct_child_constraints(
 
                     Filter::TimeBetweenEvents { from_event, to_event, min_seconds, max_seconds } =>{
 
-                        result_string.push(format!("{left_event} <= {right_event}",
-                        left_event = map_timestamp_event(sql_parts, from_event.0),
-                        right_event = map_timestamp_event(sql_parts, to_event.0)));
-                        if let Some(min) = min_seconds {
-                            result_string.push(format!("{time_left} - {time_right} >= {min}", 
-                            time_left = map_timestamp_event(sql_parts, to_event.0),
-                            time_right = map_timestamp_event(sql_parts, from_event.0)));
-                        }
-                        if let Some(max) = max_seconds {
-                            result_string.push(format!("{time_left} - {time_right} <= {max}",
-                            time_left = map_timestamp_event(sql_parts, to_event.0),
-                            time_right = map_timestamp_event(sql_parts, from_event.0)));
-                        }
-
+                        result_string.extend(map_time_between_events(sql_parts, from_event.0, to_event.0, min_seconds, max_seconds));
 
+                    }
 
+                    Filter::BasicFilterCEL { cel } =>{
+                        match cel_to_sql(cel, sql_parts.database_type, &[]) {
+                            Ok(clause) => result_string.push(clause),
+                            Err(e) => sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), e.to_string())),
+                        }
                     }
 
-                    _ => {}
+                    _ => {
+                        sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), "Filter constraint is not translated"));
+                    }
                 }
             }
         }
@@ -1374,9 +1451,36 @@ pub fn translate_to_sql_from_child(
     sql_parts.base_from = construct_from_clauses(sql_parts);
     (sql_parts.join_clauses, sql_parts.where_clauses) = construct_basic_operations(sql_parts);
     
-    let childs = construct_childstrings(&sql_parts);
+    let childs = construct_childstrings(sql_parts);
     sql_parts.child_sql = childs;
 
+    // Attribute and size filters are only translated in the root node (see construct_filter_non_basic)
+    for filter in &sql_parts.node.filter {
+        match filter {
+            Filter::BasicFilterCEL { .. } => {}
+            Filter::EventAttributeValueFilter { .. } | Filter::ObjectAttributeValueFilter { .. } => {
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Attribute filters are only translated in the root node"));
+            }
+            _ => {
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Filter is not translated"));
+            }
+        }
+    }
+    for sizefilter in &sql_parts.node.sizefilter {
+        match sizefilter {
+            SizeFilter::AdvancedCEL { .. } => {}
+            SizeFilter::NumChilds { .. } => {
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, size_filter_name(sizefilter), "Size filters are only translated in the root node"));
+            }
+            _ => {
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, size_filter_name(sizefilter), "Size filter is not translated"));
+            }
+        }
+    }
+
+    let cel_clauses = construct_cel_filters(sql_parts);
+    sql_parts.where_clauses.extend(cel_clauses);
+
     let constraint_expr = construct_child_constraints(sql_parts);
 
     let sub_condition = if constraint_expr.trim().is_empty() {
@@ -1393,13 +1497,8 @@ pub fn translate_to_sql_from_child(
             if key_parts.is_empty() {
                 _tmp.push("1 AS cnt_key".to_string());
             } else {
-                // Concatenate IDs with a delimiter; works for SQLite and Postgres
-                let delim = "'|'";
-                let concat = key_parts.iter()
-                    .map(|c| format!("COALESCE(CAST({} AS TEXT),'')", c))
-                    .collect::<Vec<_>>()
-                    .join(&format!(" || {} || ", delim));
-                _tmp.push(format!("({}) AS cnt_key", concat));
+                // Concatenate IDs with a delimiter
+                _tmp.push(format!("({}) AS cnt_key", map_concat_key(&sql_parts, &key_parts)));
             }
             _tmp
         };
@@ -1485,9 +1584,11 @@ pub fn construct_filter_non_basic(
                 }
             }
 
+            // Translated by construct_cel_filters
+            SizeFilter::AdvancedCEL { .. } => {}
 
             _ =>{
-
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, size_filter_name(sizefilter), "Size filter is not translated"));
             }
 
         }
@@ -1574,28 +1675,28 @@ pub fn construct_filter_non_basic(
                             .map(|v| format!("'{}'", v.replace('\'', "''")))
                             .collect::<Vec<_>>()
                             .join(", ");
-                        format!("{}.{} IN ({})", object_alias, attr, values)
+                        format!("{} IN ({})", map_attribute_column(sql_parts, &object_alias, attr), values)
                     },
                     ValueFilter::Boolean { is_true } => {
-                        format!("{}.{} = {}", object_alias,attr,is_true)
+                        format!("{} = {}", map_attribute_column(sql_parts, &object_alias, attr),is_true)
                     },
                     ValueFilter::Integer { min, max } => {
                         let mut parts = vec![];
                         if let Some(min) = min {
-                            parts.push(format!("{}.{} >= {}", temp_alias,attr, min));
+                            parts.push(format!("{} >= {}", map_attribute_column(sql_parts, &temp_alias, attr), min));
                         }
                         if let Some(max) = max {
-                            parts.push(format!("{}.{} <= {}", temp_alias,attr,max));
+                            parts.push(format!("{} <= {}", map_attribute_column(sql_parts, &temp_alias, attr),max));
                         }
                         parts.join(" AND ")
                     },
                     ValueFilter::Float { min, max } => {
                         let mut parts = vec![];
                         if let Some(min) = min {
-                            parts.push(format!("{}.{} >= {}", temp_alias,attr,min));
+                            parts.push(format!("{} >= {}", map_attribute_column(sql_parts, &temp_alias, attr),min));
                         }
                         if let Some(max) = max {
-                            parts.push(format!("{}.{} <= {}",temp_alias,attr, max));
+                            parts.push(format!("{} <= {}", map_attribute_column(sql_parts, &temp_alias, attr), max));
                         }
                         parts.join(" AND ")
                     },
@@ -1606,13 +1707,13 @@ pub fn construct_filter_non_basic(
                         if let Some(from) = from {
                             parts.push(format!("{time_left} >= {time_right}",
                             
-                            time_left = map_timestamp(sql_parts, format!("{}.{}", object_alias, attr)),
+                            time_left = map_timestamp(sql_parts, map_attribute_column(sql_parts, &object_alias, attr)),
                             time_right = map_timestamp(sql_parts, from.to_string())
                             ));
                         }
                         if let Some(to) = to {
                             parts.push(format!("{time_left} <= {time_right}",
-                            time_left = map_timestamp(sql_parts, format!("{}.{}", object_alias, attr)),
+                            time_left = map_timestamp(sql_parts, map_attribute_column(sql_parts, &object_alias, attr)),
                             time_right = map_timestamp(sql_parts, to.to_string())));
                         }
                         parts.join(" AND ")
@@ -1636,6 +1737,7 @@ pub fn construct_filter_non_basic(
                 // need for used_keys here?    
                 let clause = match at_time {
                     ObjectValueFilterTimepoint::Sometime => {
+                        sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Object attribute filters at time 'Sometime' are not translated correctly"));
                         let condition = value_sql;
                         format!(
                             "(EXISTS  SELECT 1\n FROM {otype} AS OA\n WHERE OA.ocel_id = {oid} AND {cond})",
@@ -1682,8 +1784,11 @@ pub fn construct_filter_non_basic(
                 result.push(clause);
             }
 
+            // Translated by construct_cel_filters
+            Filter::BasicFilterCEL { .. } => {}
+
             _ => {
-                
+                sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Filter is not translated"));
             }
 
 
@@ -1698,6 +1803,38 @@ pub fn construct_filter_non_basic(
 
 
 
+// CEL filters (BasicFilterCEL) and CEL size filters (AdvancedCEL), for root and child nodes
+// Unsupported expressions are skipped here and recorded as issues
+pub fn construct_cel_filters(
+    sql_parts: &mut SqlParts
+) -> Vec<String> {
+
+    let mut result = Vec::new();
+
+    for filter in &sql_parts.node.filter{
+        if let Filter::BasicFilterCEL { cel } = filter {
+            // Child sets are not available when filtering bindings
+            match cel_to_sql(cel, sql_parts.database_type, &[]) {
+                Ok(clause) => result.push(clause),
+                Err(e) => sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), e.to_string())),
+            }
+        }
+    }
+
+    for sizefilter in &sql_parts.node.sizefilter{
+        if let SizeFilter::AdvancedCEL { cel } = sizefilter {
+            match cel_to_sql(cel, sql_parts.database_type, &sql_parts.child_sql) {
+                Ok(clause) => result.push(clause),
+                Err(e) => sql_parts.issues.push(TranslationIssue::new(sql_parts.node.index, TranslationIssueKind::Unsupported, size_filter_name(sizefilter), e.to_string())),
+            }
+        }
+    }
+
+    return result;
+}
+
+
+
 pub fn map_objecttables(
     sql_parts: &SqlParts,
     object_type: &str
@@ -1718,6 +1855,12 @@ pub fn map_objecttables(
         }
 
 
+        // Case Postgres (quoted, as table names are case-sensitive)
+        DatabaseType::Postgres =>{
+            return format!("\"object_{}\"", sql_parts.object_tables[object_type]);
+        }
+
+
     }
 
 
@@ -1744,6 +1887,12 @@ pub fn map_eventttables(
         }
 
 
+        // Case Postgres (quoted, as table names are case-sensitive)
+        DatabaseType::Postgres =>{
+            return format!("\"event_{}\"", sql_parts.event_tables[event_type]);
+        }
+
+
     }
 
 
@@ -1768,6 +1917,11 @@ pub fn map_timestamp_event(
             return format!("EPOCH(E{}.ocel_time)", event_count);
         }
 
+
+        DatabaseType::Postgres => {
+            return format!("EXTRACT(EPOCH FROM CAST(E{}.ocel_time AS TIMESTAMPTZ))", event_count);
+        }
+
     }
 
 
@@ -1789,6 +1943,133 @@ pub fn map_timestamp(
              return format!("EPOCH({})", alias);
         }
 
+
+        DatabaseType::Postgres =>{
+             return format!("EXTRACT(EPOCH FROM CAST({} AS TIMESTAMPTZ))", alias);
+        }
+
+    }
+
+}
+
+
+// Conditions for the time between two events, same as the native evaluation (no order is implied if no minimum is given)
+pub fn map_time_between_events(
+    sql_parts: &SqlParts,
+    from_event: usize,
+    to_event: usize,
+    min_seconds: &Option<f64>,
+    max_seconds: &Option<f64>
+) -> Vec<String> {
+
+    let mut result = Vec::new();
+
+    match sql_parts.database_type{
+
+        DatabaseType::SQLite | DatabaseType::DuckDB =>{
+            if let Some(min) = min_seconds {
+                result.push(format!("{time_left} - {time_right} >= {min}",
+                time_left = map_timestamp_event(sql_parts, to_event),
+                time_right = map_timestamp_event(sql_parts, from_event)));
+            }
+            if let Some(max) = max_seconds {
+                result.push(format!("{time_left} - {time_right} <= {max}",
+                time_left = map_timestamp_event(sql_parts, to_event),
+                time_right = map_timestamp_event(sql_parts, from_event)));
+            }
+        }
+
+
+        // Subtracting timestamps yields an interval in Postgres
+        DatabaseType::Postgres =>{
+            let from_time = format!("CAST(E{from_event}.ocel_time AS TIMESTAMPTZ)");
+            let to_time = format!("CAST(E{to_event}.ocel_time AS TIMESTAMPTZ)");
+            if let Some(min) = min_seconds {
+                result.push(format!("{to_time} - {from_time} >= make_interval(secs => {min})"));
+            }
+            if let Some(max) = max_seconds {
+                result.push(format!("{to_time} - {from_time} <= make_interval(secs => {max})"));
+            }
+        }
+
+    }
+
+    return result;
+}
+
+
+// Concatenation of the given columns (e.g., IDs) into one string key, separated by '|'
+pub fn map_concat_key(
+    sql_parts: &SqlParts,
+    columns: &[String]
+) -> String {
+
+    let parts = columns.iter()
+        .map(|c| format!("COALESCE(CAST({} AS TEXT),'')", c))
+        .collect::<Vec<_>>();
+
+    match sql_parts.database_type{
+
+        DatabaseType::SQLite | DatabaseType::DuckDB =>{
+            return parts.join(" || '|' || ");
+        }
+
+
+        DatabaseType::Postgres =>{
+            return format!("CONCAT_WS('|', {})", parts.join(", "));
+        }
+
+    }
+
+}
+
+
+// Aggregation of the given (string) expression over all rows into one comma-separated string
+pub fn map_string_agg(
+    sql_parts: &SqlParts,
+    expr: &str
+) -> String {
+
+    match sql_parts.database_type{
+
+        DatabaseType::SQLite =>{
+            return format!("GROUP_CONCAT({}, ',')", expr);
+        }
+
+
+        DatabaseType::DuckDB =>{
+            return format!("STRING_AGG({}, ',')", expr);
+        }
+
+
+        // STRING_AGG only accepts text in Postgres
+        DatabaseType::Postgres =>{
+            return format!("STRING_AGG(CAST({} AS TEXT), ',')", expr);
+        }
+
+    }
+
+}
+
+
+// Column of an object attribute (Postgres folds unquoted identifiers to lower case)
+pub fn map_attribute_column(
+    sql_parts: &SqlParts,
+    alias: &str,
+    attribute_name: &str
+) -> String {
+
+    match sql_parts.database_type{
+
+        DatabaseType::SQLite | DatabaseType::DuckDB =>{
+            return format!("{}.{}", alias, attribute_name);
+        }
+
+
+        DatabaseType::Postgres =>{
+            return format!("{}.\"{}\"", alias, attribute_name);
+        }
+
     }
 
 }
@@ -1813,12 +2094,12 @@ for query in query_names {
 
 let tree_path = base_path.join(query).join("ocpq-tree.json");
 
-for db_type in [DatabaseType::SQLite, DatabaseType::DuckDB]
+for db_type in [DatabaseType::SQLite, DatabaseType::DuckDB, DatabaseType::Postgres]
 { 
 
 let tree = serde_json::from_reader(File::open(&tree_path).unwrap()).unwrap();
 
-let sql = translate_to_sql_shared(tree, db_type);
+let sql = issues::TranslatedQuery::from(translate_to_sql_shared(tree, db_type)).query;
 
 let sql_export_path = base_path.join(query).join(format!("auto-sql-{db_type:?}.txt"));
 
@@ -1837,30 +2118,141 @@ write!(sql_export_file,"{sql}").unwrap();
 
 
 
+// Executing the generated queries on Postgres is tested in sql_execution (differential_postgres_evaluation, opt-in with OCPQ_TEST_POSTGRES_URL)
+#[test]
+fn translate_to_postgres() {
+    use crate::binding_box::BindingBox;
+
+    // Orders with a 'pay order' event at most one hour after a 'place order' event
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(ObjectVariable(0), vec!["orders".to_string()].into_iter().collect());
+    root.new_event_vars.insert(EventVariable(0), vec!["place order".to_string()].into_iter().collect());
+    root.filters.push(Filter::O2E { object: ObjectVariable(0), event: EventVariable(0), qualifier: None, filter_label: None });
+    root.constraints.push(Constraint::SAT { child_names: vec!["A".to_string()] });
+    let mut child = BindingBox::default();
+    child.new_event_vars.insert(EventVariable(1), vec!["pay order".to_string()].into_iter().collect());
+    child.filters.push(Filter::O2E { object: ObjectVariable(0), event: EventVariable(1), qualifier: None, filter_label: None });
+    child.filters.push(Filter::TimeBetweenEvents { from_event: EventVariable(0), to_event: EventVariable(1), min_seconds: None, max_seconds: Some(3600.0) });
+    let tree = BindingBoxTree {
+        nodes: vec![BindingBoxTreeNode::Box(root, vec![1]), BindingBoxTreeNode::Box(child, vec![])],
+        edge_names: vec![((0, 1), "A".to_string())].into_iter().collect(),
+    };
+
+    let query = issues::TranslatedQuery::from(translate_to_sql_shared(tree, DatabaseType::Postgres)).query;
+    // Timestamps are cast, so that the interval arithmetic also works for timestamps stored as text
+    assert!(query.contains("CAST(E1.ocel_time AS TIMESTAMPTZ) - CAST(E0.ocel_time AS TIMESTAMPTZ) <= make_interval(secs => 3600)"));
+    assert!(!query.contains("strftime"));
+    assert!(!query.contains("EPOCH(E"));
+    // Child bindings are counted by their IDs, the keys of unsatisfied child bindings are aggregated into one string
+    assert!(query.contains("CONCAT_WS('|', COALESCE(CAST(E1.ocel_id AS TEXT),''))"));
+    assert!(query.contains("STRING_AGG(CAST(subqU_0_A.cnt_key AS TEXT), ',')"));
+    assert!(query.contains("AS \"unsatisfied_A\""));
+    // Quoted table names, as Postgres folds unquoted identifiers to lower case
+    assert!(query.contains("\"object_Orders\""));
+    assert!(query.contains("\"event_PayOrder\""));
+}
+
+
+#[test]
+fn translation_records_issues() {
+    use crate::binding_box::BindingBox;
+
+    // Orders with a 'pay order' event (with qualifier) at most one hour after a 'place order' event of another order
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(ObjectVariable(0), vec!["orders".to_string()].into_iter().collect());
+    root.new_object_vars.insert(ObjectVariable(1), vec!["orders".to_string()].into_iter().collect());
+    root.new_event_vars.insert(EventVariable(0), vec!["place order".to_string()].into_iter().collect());
+    root.filters.push(Filter::O2E { object: ObjectVariable(1), event: EventVariable(0), qualifier: None, filter_label: None });
+    root.filters.push(Filter::NotEqual { var_1: Variable::Object(ObjectVariable(0)), var_2: Variable::Object(ObjectVariable(1)) });
+    root.constraints.push(Constraint::SAT { child_names: vec!["A".to_string()] });
+    let mut child = BindingBox::default();
+    child.new_event_vars.insert(EventVariable(1), vec!["pay order".to_string()].into_iter().collect());
+    child.filters.push(Filter::O2E { object: ObjectVariable(0), event: EventVariable(1), qualifier: Some("order".to_string()), filter_label: None });
+    child.filters.push(Filter::TimeBetweenEvents { from_event: EventVariable(0), to_event: EventVariable(1), min_seconds: None, max_seconds: Some(3600.0) });
+    let tree = BindingBoxTree {
+        nodes: vec![BindingBoxTreeNode::Box(root, vec![1]), BindingBoxTreeNode::Box(child, vec![])],
+        edge_names: vec![((0, 1), "A".to_string())].into_iter().collect(),
+    };
+
+    let summary = |res: Result<String, TranslationError>| -> (String, Vec<(usize, TranslationIssueKind, String)>) {
+        let translated = issues::TranslatedQuery::from(res);
+        let mut issues: Vec<_> = translated.issues.into_iter().map(|i| (i.node_index, i.kind, i.construct)).collect();
+        issues.sort_by_key(|(node_index, _, _)| *node_index);
+        (translated.query, issues)
+    };
+    let expected_issues = vec![
+        (0, TranslationIssueKind::Unsupported, "NotEqual".to_string()),
+        (1, TranslationIssueKind::Approximated, "O2E".to_string()),
+    ];
+
+    let (sql, sql_issues) = summary(translate_to_sql_shared(tree.clone(), DatabaseType::SQLite));
+    assert_eq!(sql_issues, expected_issues);
+    // Same as the native evaluation: No order of the events without a minimum time
+    assert!(!sql.contains("E0.ocel_time) <= strftime"));
+    assert!(sql.contains("<= 3600"));
+
+    let (cypher, cypher_issues) = summary(translate_to_cypher_shared(tree.clone()));
+    // Qualifiers are matched on the `qualifier` property of relationships
+    assert_eq!(cypher_issues, expected_issues[..1]);
+    assert!(cypher.contains("[:E2O {qualifier: 'order'}]->(o0"));
+    assert!(cypher.contains("(e1.time.epochMillis - e0.time.epochMillis) / 1000.0 <= 3600"));
+
+    // Types which are not mapped prevent generating a query
+    let mut unknown_tree = tree;
+    if let BindingBoxTreeNode::Box(child, _) = &mut unknown_tree.nodes[1] {
+        child.new_event_vars.insert(EventVariable(1), vec!["unknown".to_string()].into_iter().collect());
+    }
+    let (sql, sql_issues) = summary(translate_to_sql_shared(unknown_tree, DatabaseType::SQLite));
+    assert!(sql.is_empty());
+    assert_eq!(sql_issues, vec![(1, TranslationIssueKind::UnknownType, "ev_1 (unknown)".to_string())]);
+}
+
+
 // Cypher Translation 
 
 
 pub struct CypherParts{
     node:  InterMediateNode,
     match_clauses: Vec<String>,
+    // Number of bindings of each child (by child name)
     child_queries: Vec<(String,String)>,
+    child_sets: HashMap<String, CypherChildSet>,
     where_clauses: Vec<String>,
     return_clauses: Vec<String>,
     used_alias: HashSet<String>,
     event_tables: HashMap<String,String>,
     object_tables: HashMap<String,String>,
-    alias_type: HashMap<String,String>
+    alias_type: HashMap<String,String>,
+    // Constructs which are not (exactly) translated
+    issues: Vec<TranslationIssue>
 }
 
 
 
 
+// Returns an error (still containing the query if possible) if the query is not equivalent to the native evaluation
 pub fn translate_to_cypher_shared(
-   tree: BindingBoxTree ) -> String{
+   tree: BindingBoxTree ) -> Result<String, TranslationError>{
     
+    let mut event_tables = HashMap::new();
+    let mut object_tables = HashMap::new();
+
+    get_event_table_cypher(&mut event_tables);
+
+    get_object_table_cypher(&mut object_tables);
+
+    add_type_labels_cypher(&tree, &mut event_tables, &mut object_tables);
+
+    // Check that all types are mapped to labels (all other issues are recorded during the translation)
+    let mut issues = check_variables(&tree, &event_tables, &object_tables);
+
+    if !can_generate_query(&issues){
+        return Err(TranslationError { query: String::new(), issues });
+    }
+
     // Convert to Intermediate
 
-    let inter = convert_to_intermediate(tree);
+    let inter = convert_to_intermediate(tree, &mut issues);
 
 
 
@@ -1868,23 +2260,24 @@ pub fn translate_to_cypher_shared(
     node: inter,    
     match_clauses: vec![],
     child_queries: vec![],
+    child_sets: HashMap::new(),
     where_clauses: vec![],
     return_clauses: vec![],
     used_alias: HashSet::new(),
-    event_tables: HashMap::new(),
-    object_tables: HashMap::new(),
-    alias_type: HashMap::new()
+    event_tables,
+    object_tables,
+    alias_type: HashMap::new(),
+    issues
     };
 
-
-    get_event_table_cypher(&mut cypher_parts);
-
-    get_object_table_cypher(&mut cypher_parts);
-
     let result = convert_to_cypher_from_inter(&mut cypher_parts);
 
+    let issues = cypher_parts.issues;
+    if !issues.is_empty(){
+        return Err(TranslationError { query: result, issues });
+    }
 
-    return result;
+    return Ok(result);
 
 
 
@@ -1898,16 +2291,19 @@ pub fn convert_to_cypher_from_inter(
     construct_match_clauses(cypher_parts);
     
     
-    if(!cypher_parts.node.filter.is_empty()){
     construct_childstrings_cypher(cypher_parts);
 
     
     construct_filter_clauses(cypher_parts);
     
-    }
-    
     
     construct_return_clauses(cypher_parts);
+
+    // Satisfaction of the constraints (as for SQL)
+    if !cypher_parts.node.constraints.is_empty() {
+        let satisfied = construct_constraint_condition_cypher(cypher_parts);
+        cypher_parts.return_clauses.push(format!("CASE WHEN {satisfied} THEN 1 ELSE 0 END AS satisfied"));
+    }
     
     
 
@@ -1928,29 +2324,30 @@ pub fn construct_match_clauses(
     for relation in &cypher_parts.node.relations{
         match relation{
 
-            Relation::E2O { event, object, qualifier:_ } =>{
+            Relation::E2O { event, object, qualifier } =>{
 
                 let event_alias = format!("e{}", event.0);
                 let object_alias = format!("o{}", object.0);
 
-                let event_object_alias = "E2O".to_string();
+                let event_object_alias = relationship_cypher("E2O", qualifier);
 
                 let event_type = get_event_type(cypher_parts.node.clone(), event.0);
                 let object_type = get_object_type(cypher_parts.node.clone(), object.0);
 
 
-                let mut mapped_event_type = &cypher_parts.event_tables[&event_type.clone()];
-                let mut mapped_object_type = &cypher_parts.object_tables[&object_type.clone()];
-
-
-
-                if(mapped_event_type == "no type found event"){
-                    mapped_event_type = &cypher_parts.event_tables[&event_type.clone()];
-                }
-                
-                if(mapped_object_type == "no type found object"){
-                    mapped_object_type = &cypher_parts.object_tables[&object_type.clone()];
-                }
+                // Variables of parent nodes are not part of this node (use the label from the parent)
+                let mapped_event_type = cypher_parts
+                    .event_tables
+                    .get(&event_type)
+                    .or_else(|| cypher_parts.alias_type.get(&event_alias))
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string());
+                let mapped_object_type = cypher_parts
+                    .object_tables
+                    .get(&object_type)
+                    .or_else(|| cypher_parts.alias_type.get(&object_alias))
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string());
 
 
                 cypher_parts.used_alias.insert(event_alias.clone());
@@ -1960,8 +2357,9 @@ pub fn construct_match_clauses(
                 cypher_parts.alias_type.insert(event_alias.clone(),mapped_event_type.to_string());
                 cypher_parts.alias_type.insert(object_alias.clone(),mapped_object_type.to_string());
 
-                cypher_parts.match_clauses.push(format!("({event_alias}:{mapped_event_type})-[:{event_object_alias}]->({object_alias}:{mapped_object_type})", 
-                
+                cypher_parts.match_clauses.push(format!("({event_alias}:{})-[{event_object_alias}]->({object_alias}:{})", 
+                quote_label_cypher(&mapped_event_type),
+                quote_label_cypher(&mapped_object_type)
             ));
 
 
@@ -1969,12 +2367,12 @@ pub fn construct_match_clauses(
             }
 
 
-            Relation::O2O { object_1, object_2, qualifier:_ } => {
+            Relation::O2O { object_1, object_2, qualifier } => {
 
                 let object1_alias = format!("o{}", object_1.0);
                 let object2_alias = format!("o{}", object_2.0);
 
-                let object_object_alias = "O2O".to_string();
+                let object_object_alias = relationship_cypher("O2O", qualifier);
 
                 
 
@@ -2018,7 +2416,9 @@ pub fn construct_match_clauses(
                 cypher_parts.alias_type.insert(object2_alias.clone(), mapped_object2_type.clone());
 
                 cypher_parts.match_clauses.push(format!(
-                    "({object1_alias}:{mapped_object1_type})-[:{object_object_alias}]->({object2_alias}:{mapped_object2_type})"
+                    "({object1_alias}:{})-[{object_object_alias}]->({object2_alias}:{})",
+                    quote_label_cypher(&mapped_object1_type),
+                    quote_label_cypher(&mapped_object2_type)
                 ));
 
 
@@ -2042,7 +2442,7 @@ pub fn construct_match_clauses(
             let key = format!("o{}", obj_var.0);
              if !cypher_parts.used_alias.contains(&key){
                 let type1 = &cypher_parts.object_tables[&object_type.clone()];
-                cypher_parts.match_clauses.push(format!("({}:{})", key, &cypher_parts.object_tables[&object_type.clone()] ));
+                cypher_parts.match_clauses.push(format!("({}:{})", key, quote_label_cypher(type1) ));
                 cypher_parts.used_alias.insert(key.clone());
                 cypher_parts.alias_type.insert(key, type1.to_string());
              }
@@ -2056,7 +2456,7 @@ pub fn construct_match_clauses(
             let key = format!("e{}", event_var.0);
             if !cypher_parts.used_alias.contains(&key){
             let type1 = &cypher_parts.event_tables[&event_type.clone()];
-            cypher_parts.match_clauses.push(format!("({}:{})",key, &cypher_parts.event_tables[&event_type.clone()]  ));
+            cypher_parts.match_clauses.push(format!("({}:{})",key, quote_label_cypher(type1) ));
             cypher_parts.used_alias.insert(key.clone());
             cypher_parts.alias_type.insert(key, type1.to_string());
             }
@@ -2068,76 +2468,121 @@ pub fn construct_match_clauses(
 }
 
 
+// Also used for the labels of the Neo4j export (see neo4j_export)
 pub fn get_event_table_cypher(
-    cypher_parts: &mut CypherParts
+    event_tables: &mut HashMap<String,String>
 ){
-    cypher_parts.event_tables.insert("confirm order".to_string(), "confirmorder".to_string());
-    cypher_parts.event_tables.insert("create package".to_string(), "createpackage".to_string());
-    cypher_parts.event_tables.insert("failed delivery".to_string(), "faileddelivery".to_string());
-    cypher_parts.event_tables.insert("item out of stock".to_string(), "itemoutofstock".to_string());
-    cypher_parts.event_tables.insert("package delivered".to_string(), "packagedelivered".to_string());
-    cypher_parts.event_tables.insert("pay order".to_string(), "payorder".to_string());
-    cypher_parts.event_tables.insert("payment reminder".to_string(), "paymentreminder".to_string());
-    cypher_parts.event_tables.insert("pick item".to_string(), "pickitem".to_string());
-    cypher_parts.event_tables.insert("place order".to_string(), "placeorder".to_string());
-    cypher_parts.event_tables.insert("reorder item".to_string(), "reorderitem".to_string());
-    cypher_parts.event_tables.insert("send package".to_string(), "sendpackage".to_string());
-
-
-
-
-    cypher_parts.event_tables.insert("A_Accepted".to_string(), "A_Accepted".to_string());
-    cypher_parts.event_tables.insert("A_Cancelled".to_string(), "A_Cancelled".to_string());
-    cypher_parts.event_tables.insert("A_Complete".to_string(), "A_Complete".to_string());
-    cypher_parts.event_tables.insert("A_Concept".to_string(), "A_Concept".to_string());
-    cypher_parts.event_tables.insert("A_Create Application".to_string(), "A_Create Application".to_string());
-    cypher_parts.event_tables.insert("A_Denied".to_string(), "A_Denied".to_string());
-    cypher_parts.event_tables.insert("A_Incomplete".to_string(), "A_Incomplete".to_string());
-    cypher_parts.event_tables.insert("A_Pending".to_string(), "A_Pending".to_string());
-    cypher_parts.event_tables.insert("A_Submitted".to_string(), "A_Submitted".to_string());
-    cypher_parts.event_tables.insert("A_Validating".to_string(), "A_Validating".to_string());
-
-    cypher_parts.event_tables.insert("O_Accepted".to_string(), "O_Accepted".to_string());
-    cypher_parts.event_tables.insert("O_Cancelled".to_string(), "O_Cancelled".to_string());
-    cypher_parts.event_tables.insert("O_Create Offer".to_string(), "O_Create Offer".to_string());
-    cypher_parts.event_tables.insert("O_Created".to_string(), "O_Created".to_string());
-    cypher_parts.event_tables.insert("O_Refused".to_string(), "O_Refused".to_string());
-    cypher_parts.event_tables.insert("O_Returned".to_string(), "O_Returned".to_string());
-    cypher_parts.event_tables.insert("O_Sent (mail and online)".to_string(), "O_Sent (mail and online)".to_string());
-    cypher_parts.event_tables.insert("O_Sent (online only)".to_string(), "O_Sent (online only)".to_string());
-
-    cypher_parts.event_tables.insert("W_Assess potential fraud".to_string(), "W_Assess potential fraud".to_string());
-    cypher_parts.event_tables.insert("W_Call after offers".to_string(), "W_Call after offers".to_string());
-    cypher_parts.event_tables.insert("W_Call incomplete files".to_string(), "W_Call incomplete files".to_string());
-    cypher_parts.event_tables.insert("W_Complete application".to_string(), "W_Complete application".to_string());
-    cypher_parts.event_tables.insert("W_Handle leads".to_string(), "W_Handle leads".to_string());
-    cypher_parts.event_tables.insert("W_Personal Loan collection".to_string(), "W_Personal Loan collection".to_string());
-    cypher_parts.event_tables.insert("W_Shorten completion".to_string(), "W_Shorten completion".to_string());
-    cypher_parts.event_tables.insert("W_Validate application".to_string(), "W_Validate application".to_string());
+    event_tables.insert("confirm order".to_string(), "confirmorder".to_string());
+    event_tables.insert("create package".to_string(), "createpackage".to_string());
+    event_tables.insert("failed delivery".to_string(), "faileddelivery".to_string());
+    event_tables.insert("item out of stock".to_string(), "itemoutofstock".to_string());
+    event_tables.insert("package delivered".to_string(), "packagedelivered".to_string());
+    event_tables.insert("pay order".to_string(), "payorder".to_string());
+    event_tables.insert("payment reminder".to_string(), "paymentreminder".to_string());
+    event_tables.insert("pick item".to_string(), "pickitem".to_string());
+    event_tables.insert("place order".to_string(), "placeorder".to_string());
+    event_tables.insert("reorder item".to_string(), "reorderitem".to_string());
+    event_tables.insert("send package".to_string(), "sendpackage".to_string());
+
+
+
+
+    event_tables.insert("A_Accepted".to_string(), "A_Accepted".to_string());
+    event_tables.insert("A_Cancelled".to_string(), "A_Cancelled".to_string());
+    event_tables.insert("A_Complete".to_string(), "A_Complete".to_string());
+    event_tables.insert("A_Concept".to_string(), "A_Concept".to_string());
+    event_tables.insert("A_Create Application".to_string(), "A_Create Application".to_string());
+    event_tables.insert("A_Denied".to_string(), "A_Denied".to_string());
+    event_tables.insert("A_Incomplete".to_string(), "A_Incomplete".to_string());
+    event_tables.insert("A_Pending".to_string(), "A_Pending".to_string());
+    event_tables.insert("A_Submitted".to_string(), "A_Submitted".to_string());
+    event_tables.insert("A_Validating".to_string(), "A_Validating".to_string());
+
+    event_tables.insert("O_Accepted".to_string(), "O_Accepted".to_string());
+    event_tables.insert("O_Cancelled".to_string(), "O_Cancelled".to_string());
+    event_tables.insert("O_Create Offer".to_string(), "O_Create Offer".to_string());
+    event_tables.insert("O_Created".to_string(), "O_Created".to_string());
+    event_tables.insert("O_Refused".to_string(), "O_Refused".to_string());
+    event_tables.insert("O_Returned".to_string(), "O_Returned".to_string());
+    event_tables.insert("O_Sent (mail and online)".to_string(), "O_Sent (mail and online)".to_string());
+    event_tables.insert("O_Sent (online only)".to_string(), "O_Sent (online only)".to_string());
+
+    event_tables.insert("W_Assess potential fraud".to_string(), "W_Assess potential fraud".to_string());
+    event_tables.insert("W_Call after offers".to_string(), "W_Call after offers".to_string());
+    event_tables.insert("W_Call incomplete files".to_string(), "W_Call incomplete files".to_string());
+    event_tables.insert("W_Complete application".to_string(), "W_Complete application".to_string());
+    event_tables.insert("W_Handle leads".to_string(), "W_Handle leads".to_string());
+    event_tables.insert("W_Personal Loan collection".to_string(), "W_Personal Loan collection".to_string());
+    event_tables.insert("W_Shorten completion".to_string(), "W_Shorten completion".to_string());
+    event_tables.insert("W_Validate application".to_string(), "W_Validate application".to_string());
 
 
 }
 
 
 
+// Also used for the labels of the Neo4j export (see neo4j_export)
 pub fn get_object_table_cypher(
-    cypher_parts: &mut CypherParts
+    object_tables: &mut HashMap<String,String>
 ){
-    cypher_parts.object_tables.insert("customers".to_string(), "customers".to_string());
-    cypher_parts.object_tables.insert("employees".to_string(), "employees".to_string());
-    cypher_parts.object_tables.insert("items".to_string(), "items".to_string());
-    cypher_parts.object_tables.insert("orders".to_string(), "orders".to_string());
-    cypher_parts.object_tables.insert("packages".to_string(), "packages".to_string());
-    cypher_parts.object_tables.insert("products".to_string(), "products".to_string());
+    object_tables.insert("customers".to_string(), "customers".to_string());
+    object_tables.insert("employees".to_string(), "employees".to_string());
+    object_tables.insert("items".to_string(), "items".to_string());
+    object_tables.insert("orders".to_string(), "orders".to_string());
+    object_tables.insert("packages".to_string(), "packages".to_string());
+    object_tables.insert("products".to_string(), "products".to_string());
+
+
+
+    object_tables.insert("Application".to_string(), "Application".to_string());
+    object_tables.insert("Case_R".to_string(), "Case_R".to_string());
+    object_tables.insert("Offer".to_string(), "Offer".to_string());
+    object_tables.insert("Workflow".to_string(), "Workflow".to_string());
+
 
+}
 
 
-    cypher_parts.object_tables.insert("Application".to_string(), "Application".to_string());
-    cypher_parts.object_tables.insert("Case_R".to_string(), "Case_R".to_string());
-    cypher_parts.object_tables.insert("Offer".to_string(), "Offer".to_string());
-    cypher_parts.object_tables.insert("Workflow".to_string(), "Workflow".to_string());
+// Add the types of the tree which are not mapped to a label, with their type name as label (as in the Neo4j export, see neo4j_export)
+pub fn add_type_labels_cypher(
+    tree: &BindingBoxTree,
+    event_tables: &mut HashMap<String,String>,
+    object_tables: &mut HashMap<String,String>
+){
+    for node in &tree.nodes {
+        let (binding_box, _) = node.clone().to_box();
+        for event_type in binding_box.new_event_vars.values().flatten() {
+            event_tables.entry(event_type.clone()).or_insert_with(|| event_type.clone());
+        }
+        for object_type in binding_box.new_object_vars.values().flatten() {
+            object_tables.entry(object_type.clone()).or_insert_with(|| object_type.clone());
+        }
+    }
+}
+
+
+// Label in a pattern, quoted if it is not a plain identifier (e.g., type names with spaces)
+pub fn quote_label_cypher(
+    label: &str
+) -> String {
+    let is_identifier = label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        return label.to_string();
+    }
+    return format!("`{}`", label.replace('`', "``"));
+}
 
 
+// Relationship pattern (without brackets) of the given type, restricted to the qualifier (stored as `qualifier` property, see neo4j_export)
+pub fn relationship_cypher(
+    relationship_type: &str,
+    qualifier: &Option<String>
+) -> String {
+    match qualifier {
+        Some(q) => format!(":{relationship_type} {{qualifier: '{}'}}", q.replace('\\', "\\\\").replace('\'', "\\'")),
+        None => format!(":{relationship_type}"),
+    }
 }
 
 
@@ -2174,8 +2619,8 @@ pub fn construct_result_cypher(
         result.push_str(&format!("WHERE {}\n", cypher_parts.where_clauses.join(" AND ")));
     }
 
-    //  RETURN 
-    result.push_str(&format!("RETURN {}", cypher_parts.return_clauses.join(",")));
+    //  RETURN (MATCH clauses can yield the same binding multiple times, e.g., for E2O relationships with different qualifiers)
+    result.push_str(&format!("RETURN DISTINCT {}", cypher_parts.return_clauses.join(",")));
     return result;
 }
 
@@ -2183,138 +2628,459 @@ pub fn construct_result_cypher(
 pub fn construct_filter_clauses(
     cypher_parts: &mut CypherParts
 ) {
-    for (i, sizefilter) in cypher_parts.node.sizefilter.iter().enumerate() {
-        match sizefilter {
-            SizeFilter::NumChilds { child_name, min, max } => {
-                for (j, (child_cypher, child_label)) in cypher_parts.child_queries.iter().enumerate() {
-                    if child_label == child_name {
 
-                        let clause = match (min, max) {
-                            (Some(min), Some(max)) => format!("BETWEEN {min} AND {max}"),
-                            (Some(min), None) => format!(">= {min}"),
-                            (None, Some(max)) => format!("<= {max}"),
-                            (None, None) => continue,
-                        };
+    // Size filters remove bindings (in contrast to size constraints)
+    for sizefilter in &cypher_parts.node.sizefilter {
+        match construct_size_condition_cypher(cypher_parts, sizefilter) {
+            Ok(clause) => cypher_parts.where_clauses.push(clause),
+            Err(reason) => cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, size_filter_name(sizefilter), reason)),
+        }
+    }
 
-                        cypher_parts.where_clauses.push(format!("COUNT {{{child_cypher}}} {clause}"));
 
+    for filter in &cypher_parts.node.relations{
+        match filter{
+            Relation::TimeBetweenEvents { from_event, to_event, min_seconds, max_seconds } =>{
+
+                let clauses = map_time_between_events_cypher(from_event, to_event, min_seconds, max_seconds);
+                cypher_parts.where_clauses.extend(clauses);
+
+            }
+
+
+            _ =>{
 
-                    }
-                }
             }
-            _ => { }
+
         }
     }
 
 
-    for filter in &cypher_parts.node.relations{
-        match filter{
-            Relation::TimeBetweenEvents { from_event, to_event, min_seconds, max_seconds } =>{
+    // CEL filters (unsupported expressions are recorded as issues)
+    let (event_labels, object_labels) = get_labels_cypher(cypher_parts);
 
-                let alias_eventto = format!("e{}", to_event.0);
-                let alias_eventfrom = format!("e{}", from_event.0);
+    for filter in &cypher_parts.node.filter{
+        match filter {
+            Filter::BasicFilterCEL { cel } => {
+                match cel_to_cypher(cel, &event_labels, &object_labels, &[]) {
+                    Ok(clause) => cypher_parts.where_clauses.push(clause),
+                    Err(e) => cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), e.to_string())),
+                }
+            }
 
-                if let Some(min) = min_seconds {
-                cypher_parts.where_clauses.push(format!(
-                    "{alias_eventto}.time >= {alias_eventfrom}.time + INTERVAL('{min} SECONDS')",
-                ));
+            Filter::EventAttributeValueFilter { .. } | Filter::ObjectAttributeValueFilter { .. } => {
+                cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Attribute filters are not translated"));
             }
 
-            if let Some(max) = max_seconds {
-                cypher_parts.where_clauses.push(format!(
-                    "{alias_eventto}.time <= {alias_eventfrom}.time + INTERVAL('{max} SECONDS')"
-                ));
+            _ => {
+                cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, filter_name(filter), "Filter is not translated"));
             }
+        }
+    }
+
+}
 
 
-            cypher_parts.where_clauses.push(format!(
-                    "{alias_eventto}.time >= {alias_eventfrom}.time + INTERVAL('0 SECONDS')",
-                ));
+// All event and object labels (sorted), e.g., for numEvents() in CEL expressions
+pub fn get_labels_cypher(
+    cypher_parts: &CypherParts
+) -> (Vec<String>, Vec<String>) {
+
+    let event_labels: BTreeSet<String> = cypher_parts.event_tables.values().cloned().collect();
+    let object_labels: BTreeSet<String> = cypher_parts.object_tables.values().cloned().collect();
+
+    return (event_labels.into_iter().collect(), object_labels.into_iter().collect());
+}
+
+
+// Time between two events, same as the native evaluation (no order is implied if no minimum is given)
+pub fn map_time_between_events_cypher(
+    from_event: &EventVariable,
+    to_event: &EventVariable,
+    min_seconds: &Option<f64>,
+    max_seconds: &Option<f64>
+) -> Vec<String> {
+
+    let mut result = Vec::new();
+
+    let duration = format!("(e{}.time.epochMillis - e{}.time.epochMillis) / 1000.0", to_event.0, from_event.0);
+
+    if let Some(min) = min_seconds {
+        result.push(format!("{duration} >= {min}"));
+    }
+
+    if let Some(max) = max_seconds {
+        result.push(format!("{duration} <= {max}"));
+    }
+
+    return result;
+}
+
+
+// Condition of a size filter/size constraint, the reason as error if not supported
+pub fn construct_size_condition_cypher(
+    cypher_parts: &CypherParts,
+    sizefilter: &SizeFilter
+) -> Result<String, String> {
+
+    let bounds = |count: String, min: &Option<usize>, max: &Option<usize>| -> String {
+        match (min, max) {
+            (Some(min), Some(max)) => format!("{count} >= {min} AND {count} <= {max}"),
+            (Some(min), None) => format!("{count} >= {min}"),
+            (None, Some(max)) => format!("{count} <= {max}"),
+            (None, None) => "true".to_string(),
+        }
+    };
 
+    match sizefilter {
 
+        // Unknown child sets do not satisfy size filters (as in the native evaluation)
+        SizeFilter::NumChilds { child_name, min, max } => {
+            let clause = match cypher_parts.child_sets.get(child_name) {
+                Some(child_set) => bounds(child_set.count(), min, max),
+                None => "false".to_string(),
+            };
+            return Ok(format!("({clause})"));
+        }
+
+        SizeFilter::NumChildsProj { child_name, var_name, min, max } => {
+            let clause = match cypher_parts.child_sets.get(child_name) {
+                Some(child_set) => {
+                    let alias = match var_name {
+                        Variable::Event(ev) => format!("e{}", ev.0),
+                        Variable::Object(ob) => format!("o{}", ob.0),
+                    };
+                    bounds(child_set.count_projection(&alias), min, max)
+                }
+                None => "false".to_string(),
+            };
+            return Ok(format!("({clause})"));
+        }
+
+        SizeFilter::AdvancedCEL { cel } => {
+            let (event_labels, object_labels) = get_labels_cypher(cypher_parts);
+            return cel_to_cypher(cel, &event_labels, &object_labels, &cypher_parts.child_queries).map_err(|e| e.to_string());
+        }
+
+        _ => {
+            return Err("Size filter is not translated".to_string());
+        }
+    }
+}
+
+
+// Condition under which a binding of the node satisfies all its constraints
+pub fn construct_constraint_condition_cypher(
+    cypher_parts: &mut CypherParts
+) -> String {
+
+    let mut result = Vec::new();
+
+    // Child sets which are not present (e.g., a constraint naming a child which does not exist) are replaced by `missing`,
+    // which is chosen per constraint to match the native evaluation (e.g., violated for SAT, but satisfied for AND)
+    let all_satisfied = |name: &String, missing: &str| -> String {
+        cypher_parts.child_sets.get(name).map(|c| c.all_satisfied()).unwrap_or_else(|| missing.to_string())
+    };
+    let any_satisfied = |name: &String, missing: &str| -> String {
+        cypher_parts.child_sets.get(name).map(|c| c.any_satisfied()).unwrap_or_else(|| missing.to_string())
+    };
+    let join = |parts: Vec<String>, operator: &str, empty: &str| -> String {
+        if parts.is_empty() {
+            empty.to_string()
+        } else {
+            format!("({})", parts.join(&format!(" {operator} ")))
+        }
+    };
+
+    for constraint in &cypher_parts.node.constraints {
+        match constraint {
 
+            // For-all semantics: All bindings of all child sets are satisfied
+            Constraint::SAT { child_names } => {
+                let parts = child_names.iter().map(|name| all_satisfied(name, "false")).collect();
+                result.push(join(parts, "AND", "true"));
             }
 
+            // Same as SAT, but missing child sets count as satisfied
+            Constraint::AND { child_names } => {
+                let parts = child_names.iter().map(|name| all_satisfied(name, "true")).collect();
+                result.push(join(parts, "AND", "true"));
+            }
 
-            _ =>{
+            // Exists semantics: Each child set contains a satisfied binding
+            Constraint::ANY { child_names } => {
+                let parts = child_names.iter().map(|name| any_satisfied(name, "false")).collect();
+                result.push(join(parts, "AND", "true"));
+            }
+
+            // Violated if each child set contains a satisfied binding
+            Constraint::NOT { child_names } => {
+                let parts = child_names.iter().map(|name| any_satisfied(name, "true")).collect();
+                result.push(format!("NOT {}", join(parts, "AND", "true")));
+            }
+
+            // All bindings of at least one child set are satisfied
+            Constraint::OR { child_names } => {
+                let parts = child_names.iter().map(|name| all_satisfied(name, "true")).collect();
+                result.push(join(parts, "OR", "false"));
+            }
+
+            Constraint::SizeFilter { filter } => {
+                match construct_size_condition_cypher(cypher_parts, filter) {
+                    Ok(clause) => result.push(clause),
+                    Err(reason) => cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), reason)),
+                }
+            }
+
+            Constraint::Filter { filter } => {
+                match filter {
+                    Filter::O2E { object, event, qualifier, .. } => {
+                        result.push(format!("EXISTS {{ MATCH (e{})-[{}]->(o{}) }}", event.0, relationship_cypher("E2O", qualifier), object.0));
+                    }
+
+                    Filter::O2O { object, other_object, qualifier, .. } => {
+                        result.push(format!("EXISTS {{ MATCH (o{})-[{}]->(o{}) }}", object.0, relationship_cypher("O2O", qualifier), other_object.0));
+                    }
+
+                    Filter::TimeBetweenEvents { from_event, to_event, min_seconds, max_seconds } => {
+                        result.extend(map_time_between_events_cypher(from_event, to_event, min_seconds, max_seconds));
+                    }
+
+                    Filter::BasicFilterCEL { cel } => {
+                        let (event_labels, object_labels) = get_labels_cypher(cypher_parts);
+                        match cel_to_cypher(cel, &event_labels, &object_labels, &[]) {
+                            Ok(clause) => result.push(clause),
+                            Err(e) => cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), e.to_string())),
+                        }
+                    }
 
+                    _ => {
+                        cypher_parts.issues.push(TranslationIssue::new(cypher_parts.node.index, TranslationIssueKind::Unsupported, constraint_name(constraint), "Filter constraint is not translated"));
+                    }
+                }
             }
+        }
+    }
+
+    return join(result, "AND", "true");
+}
+
+
+// Binding set of a child node
+//
+// A binding of the child extends the binding of the parent (the variables of the parent are bound in the surrounding query)
+#[derive(Clone)]
+pub struct CypherChildSet{
+    match_clauses: Vec<String>,
+    where_clauses: Vec<String>,
+    // Condition under which a child binding is satisfied
+    satisfied: String,
+    // Aliases of the variables introduced by the child
+    variables: Vec<String>,
+}
 
+impl CypherChildSet {
+
+    fn subquery(&self, additional_condition: Option<&str>, return_clause: Option<&str>) -> String {
+        let mut result = String::new();
+        for m in &self.match_clauses {
+            result.push_str(&format!("MATCH {m} "));
+        }
+        let mut conditions = self.where_clauses.clone();
+        if let Some(condition) = additional_condition {
+            conditions.push(condition.to_string());
         }
+        if !conditions.is_empty() {
+            result.push_str(&format!("WHERE {} ", conditions.join(" AND ")));
+        }
+        if let Some(return_clause) = return_clause {
+            result.push_str(&format!("RETURN {return_clause} "));
+        }
+        return result;
     }
 
+    // Condition without any MATCH clauses (i.e., the child binding is the parent binding)
+    fn condition(&self, additional_condition: Option<&str>) -> String {
+        let mut conditions = self.where_clauses.clone();
+        if let Some(condition) = additional_condition {
+            conditions.push(condition.to_string());
+        }
+        if conditions.is_empty() {
+            return "true".to_string();
+        }
+        return format!("({})", conditions.join(" AND "));
+    }
+
+    fn exists(&self, additional_condition: Option<&str>) -> String {
+        if self.match_clauses.is_empty() {
+            return self.condition(additional_condition);
+        }
+        return format!("EXISTS {{ {}}}", self.subquery(additional_condition, None));
+    }
+
+    // Number of distinct child bindings
+    pub fn count(&self) -> String {
+        if self.variables.is_empty() {
+            return format!("(CASE WHEN {} THEN 1 ELSE 0 END)", self.exists(None));
+        }
+        return format!("COUNT {{ {}}}", self.subquery(None, Some(&format!("DISTINCT {}", self.variables.join(", ")))));
+    }
 
+    // Number of distinct values of the given variable in the child bindings
+    pub fn count_projection(&self, alias: &str) -> String {
+        if !self.variables.iter().any(|v| v == alias) {
+            // Variable of the parent: Same value in all child bindings
+            return format!("(CASE WHEN {} THEN 1 ELSE 0 END)", self.exists(None));
+        }
+        return format!("COUNT {{ {}}}", self.subquery(None, Some(&format!("DISTINCT {alias}"))));
+    }
 
+    pub fn any_satisfied(&self) -> String {
+        return self.exists(Some(&self.satisfied));
+    }
 
+    pub fn all_satisfied(&self) -> String {
+        return format!("NOT {}", self.exists(Some(&format!("NOT {}", self.satisfied))));
+    }
 }
 
+
+// Issues of the children are added to the issues of the parent
 pub fn construct_childstrings_cypher(
     cypher_parts: &mut CypherParts
 ){
 
     for (inter_node, node_label) in &cypher_parts.node.children {
-        let mut child_cypheer_parts = CypherParts {
+        let mut child_cypher_parts = CypherParts {
             node: inter_node.clone(),
             match_clauses: vec![],
             child_queries: vec![],
+            child_sets: HashMap::new(),
             return_clauses: vec![],
             where_clauses: vec![],
             event_tables: cypher_parts.event_tables.clone(),
             object_tables: cypher_parts.object_tables.clone(),
             used_alias: cypher_parts.used_alias.clone(),
-            alias_type: cypher_parts.alias_type.clone()
+            alias_type: cypher_parts.alias_type.clone(),
+            issues: vec![]
         };
-    
-    
-        let child_cypher = translate_to_cypher_from_child(&mut child_cypheer_parts);
-        cypher_parts.child_queries.push((child_cypher, node_label.clone()));
-    
+
+
+        let child_set = translate_to_cypher_from_child(&mut child_cypher_parts);
+        cypher_parts.child_queries.push((child_set.count(), node_label.clone()));
+        cypher_parts.child_sets.insert(node_label.clone(), child_set);
+        cypher_parts.issues.extend(child_cypher_parts.issues);
+
     }
-    
+
 
     }
 
 
+// Translate a child node (recursively including its children) into its binding set
 pub fn translate_to_cypher_from_child(
     cypher_parts: &mut CypherParts
-) -> String {
+) -> CypherChildSet {
+
+    let parent_aliases = cypher_parts.used_alias.clone();
+
     construct_match_clauses(cypher_parts);
 
-    
-    
+    construct_childstrings_cypher(cypher_parts);
+
     construct_filter_clauses(cypher_parts);
-    
-    
 
-    let result = construct_result_child_cypher(cypher_parts);
+    let satisfied = construct_constraint_condition_cypher(cypher_parts);
 
-    return result;
-
-}
+    let mut variables: Vec<String> = cypher_parts.used_alias.difference(&parent_aliases).cloned().collect();
+    variables.sort();
 
+    return CypherChildSet {
+        match_clauses: cypher_parts.match_clauses.clone(),
+        where_clauses: cypher_parts.where_clauses.clone(),
+        satisfied,
+        variables,
+    };
 
-pub fn construct_result_child_cypher(
-    cypher_parts: &mut CypherParts
-) -> String {
-    let mut result = String::new();
+}
 
-    //  MATCH
-    for m in &cypher_parts.match_clauses {
-        result.push_str(&format!("MATCH {m}\n"));
-    }
 
 
-    //  WHERE 
-    if !cypher_parts.where_clauses.is_empty() {
-        result.push_str(&format!("WHERE {}\n", cypher_parts.where_clauses.join(" AND ")));
+#[test]
+fn translate_constraints_to_cypher() {
+    use crate::binding_box::BindingBox;
+
+    // Orders with their 'pay order' (A) and 'confirm order' (B) events
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(ObjectVariable(0), vec!["orders".to_string()].into_iter().collect());
+    root.size_filters.push(SizeFilter::NumChildsProj { child_name: "B".to_string(), var_name: Variable::Object(ObjectVariable(0)), min: Some(1), max: None });
+    for constraint in [
+        Constraint::SAT { child_names: vec!["A".to_string()] },
+        Constraint::ANY { child_names: vec!["B".to_string()] },
+        Constraint::NOT { child_names: vec!["B".to_string()] },
+        Constraint::OR { child_names: vec!["A".to_string(), "B".to_string()] },
+        // C is not a child
+        Constraint::AND { child_names: vec!["A".to_string(), "C".to_string()] },
+        Constraint::SizeFilter { filter: SizeFilter::NumChildsProj { child_name: "A".to_string(), var_name: Variable::Event(EventVariable(1)), min: Some(1), max: Some(2) } },
+    ] {
+        root.constraints.push(constraint);
     }
+    let child = |event: usize, event_type: &str| {
+        let mut child = BindingBox::default();
+        child.new_event_vars.insert(EventVariable(event), vec![event_type.to_string()].into_iter().collect());
+        child.filters.push(Filter::O2E { object: ObjectVariable(0), event: EventVariable(event), qualifier: None, filter_label: None });
+        child
+    };
+    let tree = BindingBoxTree {
+        nodes: vec![
+            BindingBoxTreeNode::Box(root, vec![1, 2]),
+            BindingBoxTreeNode::Box(child(1, "pay order"), vec![]),
+            BindingBoxTreeNode::Box(child(2, "confirm order"), vec![]),
+        ],
+        edge_names: vec![((0, 1), "A".to_string()), ((0, 2), "B".to_string())].into_iter().collect(),
+    };
 
-    //  RETURN 
-    
-    if(!cypher_parts.return_clauses.is_empty()){
-    result.push_str(&format!("RETURN {}", cypher_parts.return_clauses.join(",")));
-    }
+    let translated = issues::TranslatedQuery::from(translate_to_cypher_shared(tree));
+    assert!(translated.issues.is_empty());
+    let query = translated.query;
+
+    let a = "MATCH (e1:payorder)-[:E2O]->(o0:orders) ";
+    let b = "MATCH (e2:confirmorder)-[:E2O]->(o0:orders) ";
+    let all_a = format!("NOT EXISTS {{ {a}WHERE NOT true }}");
+    let all_b = format!("NOT EXISTS {{ {b}WHERE NOT true }}");
+    let any_b = format!("EXISTS {{ {b}WHERE true }}");
+    let count_a = format!("COUNT {{ {a}RETURN DISTINCT e1 }}");
+
+    assert!(query.starts_with("MATCH (o0:orders)\n"));
+    // Size filter on a variable of the parent (which has the same value in all child bindings)
+    assert!(query.contains(&format!("WHERE ((CASE WHEN EXISTS {{ {b}}} THEN 1 ELSE 0 END) >= 1)\n")));
+    let expected_satisfied = [
+        format!("({all_a})"),
+        format!("({any_b})"),
+        format!("NOT ({any_b})"),
+        format!("({all_a} OR {all_b})"),
+        format!("({all_a} AND true)"),
+        format!("({count_a} >= 1 AND {count_a} <= 2)"),
+    ]
+    .join(" AND ");
+    assert!(query.ends_with(&format!("RETURN DISTINCT o0.id,CASE WHEN ({expected_satisfied}) THEN 1 ELSE 0 END AS satisfied")));
+}
 
-    return result;
 
+#[test]
+fn translate_unmapped_types_to_cypher() {
+    use crate::binding_box::BindingBox;
+
+    // Types which are not mapped are labeled with their type name (as in the Neo4j export)
+    let mut root = BindingBox::default();
+    root.new_object_vars.insert(ObjectVariable(0), vec!["custom objects".to_string()].into_iter().collect());
+    root.new_object_vars.insert(ObjectVariable(1), vec!["Items".to_string()].into_iter().collect());
+    root.new_event_vars.insert(EventVariable(0), vec!["custom event".to_string()].into_iter().collect());
+    root.filters.push(Filter::O2E { object: ObjectVariable(0), event: EventVariable(0), qualifier: None, filter_label: None });
+    let tree = BindingBoxTree { nodes: vec![BindingBoxTreeNode::Box(root, vec![])], edge_names: HashMap::new() };
+
+    let translated = issues::TranslatedQuery::from(translate_to_cypher_shared(tree));
+    assert!(translated.issues.is_empty());
+    assert!(translated.query.contains("(e0:`custom event`)-[:E2O]->(o0:`custom objects`)"));
+    assert!(translated.query.contains("(o1:Items)"));
 }
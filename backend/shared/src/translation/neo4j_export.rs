@@ -0,0 +1,372 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Error;
+use process_mining::ocel::ocel_struct::{OCELAttributeValue, OCELType};
+use serde::{Deserialize, Serialize};
+
+use crate::preprocessing::linked_ocel::{
+    EventIndex, EventOrObjectIndex, IndexLinkedOCEL, ObjectIndex,
+};
+
+use super::{get_event_table_cypher, get_object_table_cypher};
+
+/// ID space of event nodes (events and objects can share IDs)
+const EVENT_ID_SPACE: &str = "Event";
+/// ID space of object nodes
+const OBJECT_ID_SPACE: &str = "Object";
+
+/// CSV files written by [`export_ocel_to_neo4j_csv`]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Neo4jImportFiles {
+    /// One file per event/object type
+    pub node_files: Vec<PathBuf>,
+    /// E2O and O2O relationships
+    pub relationship_files: Vec<PathBuf>,
+}
+
+impl Neo4jImportFiles {
+    /// Arguments for `neo4j-admin database import full` to import all files
+    pub fn import_args(&self) -> Vec<String> {
+        self.node_files
+            .iter()
+            .map(|f| format!("--nodes={}", f.display()))
+            .chain(
+                self.relationship_files
+                    .iter()
+                    .map(|f| format!("--relationships={}", f.display())),
+            )
+            .collect()
+    }
+}
+
+/// Neo4j type of an OCEL attribute column (by the value type declared for the event/object type)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    String,
+    Long,
+    Double,
+    Boolean,
+    DateTime,
+}
+
+impl ColumnType {
+    fn from_value_type(value_type: &str) -> Self {
+        match value_type {
+            "integer" => ColumnType::Long,
+            "float" => ColumnType::Double,
+            "boolean" => ColumnType::Boolean,
+            "time" => ColumnType::DateTime,
+            _ => ColumnType::String,
+        }
+    }
+
+    fn header_suffix(&self) -> &'static str {
+        match self {
+            ColumnType::String => "string",
+            ColumnType::Long => "long",
+            ColumnType::Double => "double",
+            ColumnType::Boolean => "boolean",
+            ColumnType::DateTime => "datetime",
+        }
+    }
+
+    /// CSV field of a value (empty, i.e., no property, for null values or values not matching the column type)
+    fn format(&self, value: &OCELAttributeValue) -> String {
+        match (self, value) {
+            (_, OCELAttributeValue::Null) => String::new(),
+            (ColumnType::String, v) => v.to_string(),
+            (ColumnType::Long, OCELAttributeValue::Integer(i)) => i.to_string(),
+            (ColumnType::Double, OCELAttributeValue::Integer(i)) => i.to_string(),
+            (ColumnType::Double, OCELAttributeValue::Float(f)) => f.to_string(),
+            (ColumnType::Boolean, OCELAttributeValue::Boolean(b)) => b.to_string(),
+            (ColumnType::DateTime, OCELAttributeValue::Time(t)) => t.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Attribute columns of an event/object type
+///
+/// Attributes named `id` or `time` are skipped, as these properties are used for the event/object ID and event timestamp.
+fn attribute_columns(ocel_type: &OCELType) -> Vec<(String, ColumnType)> {
+    ocel_type
+        .attributes
+        .iter()
+        .filter(|a| a.name != "id" && a.name != "time")
+        .map(|a| (a.name.clone(), ColumnType::from_value_type(&a.value_type)))
+        .collect()
+}
+
+fn attribute_header(columns: &[(String, ColumnType)]) -> impl Iterator<Item = String> + '_ {
+    columns
+        .iter()
+        .map(|(name, t)| format!("{}:{}", name, t.header_suffix()))
+}
+
+/// Node label of an event/object type (the type name, if it is not mapped for the Cypher translation)
+fn get_label(labels: &HashMap<String, String>, type_name: &str) -> String {
+    labels
+        .get(type_name)
+        .cloned()
+        .unwrap_or_else(|| type_name.to_string())
+}
+
+/// File name part of an event/object type
+fn file_name_part(type_name: &str) -> String {
+    type_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn csv_writer(path: &Path) -> Result<csv::Writer<BufWriter<File>>, Error> {
+    Ok(csv::WriterBuilder::new().from_writer(BufWriter::new(File::create(path)?)))
+}
+
+/// Export an OCEL as CSV files for the Neo4j bulk import (`neo4j-admin database import`)
+///
+/// The resulting graph is the one expected by the Cypher translation:
+/// Events and objects are nodes labeled with their (mapped) type, with the properties `id`, `time` (events only) and their attributes.
+/// E2O relationships point from events to objects, O2O relationships from objects to objects; both have a `qualifier` property.
+///
+/// Objects have the first value of each attribute (as the `attr` CEL function).
+/// Relationships to unknown objects are skipped (they are not part of the linked OCEL).
+pub fn export_ocel_to_neo4j_csv<P: AsRef<Path>>(
+    ocel: &IndexLinkedOCEL,
+    dir: P,
+) -> Result<Neo4jImportFiles, Error> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let mut event_labels = HashMap::new();
+    get_event_table_cypher(&mut event_labels);
+    let mut object_labels = HashMap::new();
+    get_object_table_cypher(&mut object_labels);
+
+    let mut node_files = Vec::new();
+
+    for (i, event_type) in ocel.ocel.event_types.iter().enumerate() {
+        let path = dir.join(format!(
+            "events_{}_{}.csv",
+            i,
+            file_name_part(&event_type.name)
+        ));
+        let columns = attribute_columns(event_type);
+        let label = get_label(&event_labels, &event_type.name);
+        let mut writer = csv_writer(&path)?;
+        writer.write_record(
+            [
+                format!("id:ID({EVENT_ID_SPACE})"),
+                "time:datetime".to_string(),
+            ]
+            .into_iter()
+            .chain(attribute_header(&columns))
+            .chain([":LABEL".to_string()]),
+        )?;
+        for (index, ev) in ocel
+            .events_of_type
+            .get(&event_type.name)
+            .into_iter()
+            .flatten()
+            .filter_map(|index| Some((index, ocel.ev_by_index(index)?)))
+        {
+            let attributes = columns.iter().map(|(name, t)| {
+                ocel.ev_attribute(index, name)
+                    .map(|value| t.format(value))
+                    .unwrap_or_default()
+            });
+            writer.write_record(
+                [ev.id.clone(), ev.time.to_rfc3339()]
+                    .into_iter()
+                    .chain(attributes)
+                    .chain([label.clone()]),
+            )?;
+        }
+        writer.flush()?;
+        node_files.push(path);
+    }
+
+    for (i, object_type) in ocel.ocel.object_types.iter().enumerate() {
+        let path = dir.join(format!(
+            "objects_{}_{}.csv",
+            i,
+            file_name_part(&object_type.name)
+        ));
+        let columns = attribute_columns(object_type);
+        let label = get_label(&object_labels, &object_type.name);
+        let mut writer = csv_writer(&path)?;
+        writer.write_record(
+            [format!("id:ID({OBJECT_ID_SPACE})")]
+                .into_iter()
+                .chain(attribute_header(&columns))
+                .chain([":LABEL".to_string()]),
+        )?;
+        for (index, ob) in ocel
+            .objects_of_type
+            .get(&object_type.name)
+            .into_iter()
+            .flatten()
+            .filter_map(|index| Some((index, ocel.ob_by_index(index)?)))
+        {
+            let attributes = columns.iter().map(|(name, t)| {
+                ocel.ob_attribute(index, name)
+                    .map(|value| t.format(value))
+                    .unwrap_or_default()
+            });
+            writer.write_record(
+                [ob.id.clone()]
+                    .into_iter()
+                    .chain(attributes)
+                    .chain([label.clone()]),
+            )?;
+        }
+        writer.flush()?;
+        node_files.push(path);
+    }
+
+    let e2o_path = dir.join("e2o.csv");
+    let mut writer = csv_writer(&e2o_path)?;
+    writer.write_record([
+        format!(":START_ID({EVENT_ID_SPACE})"),
+        format!(":END_ID({OBJECT_ID_SPACE})"),
+        "qualifier".to_string(),
+        ":TYPE".to_string(),
+    ])?;
+    for (i, ev) in ocel.ocel.events.iter().enumerate() {
+        for (ob_index, qualifier) in ocel.get_rels(&EventOrObjectIndex::Event(EventIndex(i))) {
+            let ob = &ocel.ocel.objects[ob_index.0];
+            writer.write_record([ev.id.as_str(), &ob.id, qualifier, "E2O"])?;
+        }
+    }
+    writer.flush()?;
+
+    let o2o_path = dir.join("o2o.csv");
+    let mut writer = csv_writer(&o2o_path)?;
+    writer.write_record([
+        format!(":START_ID({OBJECT_ID_SPACE})"),
+        format!(":END_ID({OBJECT_ID_SPACE})"),
+        "qualifier".to_string(),
+        ":TYPE".to_string(),
+    ])?;
+    for (i, ob) in ocel.ocel.objects.iter().enumerate() {
+        for (ob_index, qualifier) in ocel.get_rels(&EventOrObjectIndex::Object(ObjectIndex(i))) {
+            let other = &ocel.ocel.objects[ob_index.0];
+            writer.write_record([ob.id.as_str(), &other.id, qualifier, "O2O"])?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(Neo4jImportFiles {
+        node_files,
+        relationship_files: vec![e2o_path, o2o_path],
+    })
+}
+
+#[test]
+fn export_writes_csv_files() {
+    use chrono::{DateTime, FixedOffset};
+    use process_mining::ocel::ocel_struct::{
+        OCELEventAttribute, OCELObjectAttribute, OCELTypeAttribute,
+    };
+
+    let mut ocel = crate::preprocessing::tests::test_ocel(
+        vec![
+            ("e1", "place order", 0, vec![("o1", "order")]),
+            ("e2", "custom event", 60, vec![("o1", "order")]),
+        ],
+        vec![
+            ("o1", "orders", vec![("i1", "contains")]),
+            ("i1", "items", vec![]),
+        ],
+    );
+    ocel.event_types[0].attributes.push(OCELTypeAttribute {
+        name: "price".to_string(),
+        value_type: "float".to_string(),
+    });
+    ocel.events[0].attributes.push(OCELEventAttribute {
+        name: "price".to_string(),
+        value: OCELAttributeValue::Float(12.5),
+    });
+    ocel.object_types[0].attributes.push(OCELTypeAttribute {
+        name: "status".to_string(),
+        value_type: "string".to_string(),
+    });
+    ocel.objects[0].attributes.push(OCELObjectAttribute {
+        name: "status".to_string(),
+        value: OCELAttributeValue::String("new".to_string()),
+        time: DateTime::from_timestamp(0, 0)
+            .unwrap()
+            .with_timezone(&FixedOffset::east_opt(0).unwrap())
+            .into(),
+    });
+    let ocel = IndexLinkedOCEL::new(ocel);
+
+    let dir = std::env::temp_dir().join(format!("ocpq-neo4j-export-test-{}", std::process::id()));
+    let files = export_ocel_to_neo4j_csv(&ocel, &dir).unwrap();
+    let read = |name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .unwrap()
+            .lines()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Mapped types use the label of the Cypher translation, all others their type name
+    assert_eq!(
+        read("events_0_place_order.csv"),
+        vec![
+            "id:ID(Event),time:datetime,price:double,:LABEL",
+            "e1,1970-01-01T00:00:00+00:00,12.5,placeorder",
+        ]
+    );
+    assert_eq!(
+        read("events_1_custom_event.csv"),
+        vec![
+            "id:ID(Event),time:datetime,:LABEL",
+            "e2,1970-01-01T00:01:00+00:00,custom event",
+        ]
+    );
+    assert_eq!(
+        read("objects_0_orders.csv"),
+        vec!["id:ID(Object),status:string,:LABEL", "o1,new,orders"]
+    );
+    assert_eq!(
+        read("objects_1_items.csv"),
+        vec!["id:ID(Object),:LABEL", "i1,items"]
+    );
+    assert_eq!(
+        read("e2o.csv"),
+        vec![
+            ":START_ID(Event),:END_ID(Object),qualifier,:TYPE",
+            "e1,o1,order,E2O",
+            "e2,o1,order,E2O",
+        ]
+    );
+    assert_eq!(
+        read("o2o.csv"),
+        vec![
+            ":START_ID(Object),:END_ID(Object),qualifier,:TYPE",
+            "o1,i1,contains,O2O",
+        ]
+    );
+    assert_eq!(
+        files.import_args(),
+        vec![
+            format!("--nodes={}", dir.join("events_0_place_order.csv").display()),
+            format!(
+                "--nodes={}",
+                dir.join("events_1_custom_event.csv").display()
+            ),
+            format!("--nodes={}", dir.join("objects_0_orders.csv").display()),
+            format!("--nodes={}", dir.join("objects_1_items.csv").display()),
+            format!("--relationships={}", dir.join("e2o.csv").display()),
+            format!("--relationships={}", dir.join("o2o.csv").display()),
+        ]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -3,8 +3,15 @@ use std::{
     io::BufReader,
 };
 
-use axum::{extract::State, http::StatusCode, Json};
-use ocpq_shared::{preprocessing::linked_ocel::IndexLinkedOCEL, OCELInfo};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use ocpq_shared::{
+    preprocessing::{snapshot::load_ocel_with_snapshot_cache, validation::ValidationOptions},
+    IncludeIDsOptions, OCELInfo,
+};
 use serde::{Deserialize, Serialize};
 
 use process_mining::{
@@ -13,7 +20,7 @@ use process_mining::{
     ocel::xml_ocel_import::{import_ocel_xml_file_with, OCELImportOptions},
 };
 
-use crate::AppState;
+use crate::{sessions::OCELHandle, AppState};
 
 #[derive(Deserialize, Serialize)]
 pub struct LoadOcel {
@@ -45,20 +52,34 @@ pub async fn get_available_ocels() -> (StatusCode, Json<Option<Vec<String>>>) {
 
 pub async fn load_ocel_file_req(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
+    Query(validation): Query<ValidationOptions>,
     Json(payload): Json<LoadOcel>,
 ) -> (StatusCode, Json<Option<OCELInfo>>) {
-    match load_ocel_file_to_state(&payload.name, &state) {
+    match load_ocel_file_to_state(&payload.name, &handle, ids.include_ids, validation, &state) {
         Some(ocel_info) => (StatusCode::OK, Json(Some(ocel_info))),
         None => (StatusCode::BAD_REQUEST, Json(None)),
     }
 }
 
-pub fn load_ocel_file_to_state(name: &str, state: &AppState) -> Option<OCELInfo> {
-    match load_ocel_file(name) {
-        Ok(ocel) => {
-            let ocel_info: OCELInfo = (&ocel).into();
-            let mut x = state.ocel.write().unwrap();
-            *x = Some(IndexLinkedOCEL::new(ocel));
+/// Load an OCEL file into the session referenced by the handle
+///
+/// The returned info only includes all event and object IDs if `include_ids` is set.
+/// Returns `None` if the OCEL could not be imported or was rejected by the validation.
+pub fn load_ocel_file_to_state(
+    name: &str,
+    handle: &OCELHandle,
+    include_ids: bool,
+    validation: ValidationOptions,
+    state: &AppState,
+) -> Option<OCELInfo> {
+    let path = format!("{DATA_PATH}{name}");
+    match load_ocel_with_snapshot_cache(path, |_| Ok(load_ocel_file(name)?), validation.on_issues) {
+        Ok(res) => {
+            let ocel = state.store_validation_result(handle, res)?;
+            let ocel_info = OCELInfo::from_ocel(&ocel.ocel, include_ids);
+            state.insert_session(handle, name, ocel);
             Some(ocel_info)
         }
         Err(e) => {
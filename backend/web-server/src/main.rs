@@ -1,6 +1,6 @@
 use axum::{
     body::Bytes,
-    extract::{DefaultBodyLimit, Path, State},
+    extract::{DefaultBodyLimit, Path, Query, State},
     http::StatusCode,
     routing::{get, post},
     Json, Router,
@@ -17,18 +17,25 @@ use std::{
 
 use ocpq_shared::{
     binding_box::{
-        evaluate_box_tree, filter_ocel_box_tree, BindingBoxTree, CheckWithBoxTreeRequest, EvaluateBoxTreeResult, ExportFormat, FilterExportWithBoxTreeRequest
-    }, discovery::{
-        auto_discover_constraints_with_options, AutoDiscoverConstraintsRequest,
-        AutoDiscoverConstraintsResponse,
+        compare::{compare_box_tree, BoxTreeComparison}, evaluate_box_tree, filter_ocel_box_tree, BindingBoxTree, CheckWithBoxTreeRequest, EvaluateBoxTreeResult, ExportFormat, FilterExportWithBoxTreeRequest
+    }, cel::custom_functions::load_default_cel_plugins, discovery::{
+        auto_discover_constraints_with_options,
+        drift::{discover_constraint_drift, DriftDiscoveryRequest, DriftDiscoveryResponse},
+        labeled_discovery::{
+            discover_constraints_from_labels, LabeledDiscoveryRequest, LabeledDiscoveryResponse,
+        },
+        AutoDiscoverConstraintsRequest, AutoDiscoverConstraintsResponse,
     }, get_event_info, get_object_info, hpc_backend::{
         get_job_status, login_on_hpc, start_port_forwarding, submit_hpc_job, Client,
         ConnectionConfig, JobStatus, OCPQJobOptions,
-    }, ocel_graph::{get_ocel_graph, OCELGraph, OCELGraphOptions}, ocel_qualifiers::qualifiers::{
+    }, ocel_graph::{get_ocel_graph, OCELGraph, OCELGraphOptions}, ocel_profile::{get_ocel_profile, OCELProfile}, ocel_qualifiers::qualifiers::{
         get_qualifiers_for_event_types, QualifierAndObjectType, QualifiersForEventType,
-    }, preprocessing::{linked_ocel::IndexLinkedOCEL, preprocess::link_ocel_info}, table_export::{export_bindings_to_writer, TableExportOptions}, translation::{
-        translate_to_cypher_shared, translate_to_sql_shared, TranslationToSQL
-    }, EventWithIndex, IndexOrID, OCELInfo, ObjectWithIndex
+    }, preprocessing::{
+        linked_ocel::IndexLinkedOCEL,
+        validation::{validate_and_link_ocel, OCELValidationReport, ValidationOptions},
+    }, table_export::{export_bindings_to_writer, TableExportOptions}, translation::{
+        issues::TranslatedQuery, translate_to_cypher_shared, translate_to_sql_shared, TranslationToSQL
+    }, id_search::{search_ids, SearchIDsRequest, SearchIDsResponse}, AppendToOCELRequest, EventWithIndex, IncludeIDsOptions, IndexOrID, OCELInfo, ObjectWithIndex
 };
 use process_mining::{
     event_log::ocel::ocel_struct::OCEL,
@@ -36,19 +43,31 @@ use process_mining::{
     import_ocel_sqlite_from_slice, import_ocel_xml_slice,
     ocel::ocel_struct::{OCELEvent, OCELObject},
 };
+use serde::Deserialize;
 use tower_http::cors::CorsLayer;
 
-use crate::load_ocel::{
-    get_available_ocels, load_ocel_file_req, load_ocel_file_to_state, DEFAULT_OCEL_FILE,
+use crate::{
+    load_ocel::{
+        get_available_ocels, load_ocel_file_req, load_ocel_file_to_state, DEFAULT_OCEL_FILE,
+    },
+    sessions::{
+        get_session_memory_usage, get_session_validation_report, list_sessions, select_session,
+        unload_session, OCELHandle, OCELSession,
+    },
 };
 pub mod load_ocel;
+pub mod sessions;
 
 #[derive(Clone, Default)]
 pub struct AppState {
-    ocel: Arc<RwLock<Option<IndexLinkedOCEL>>>,
+    /// Loaded OCELs by session name
+    sessions: Arc<RwLock<HashMap<String, OCELSession>>>,
+    /// Session used for requests without an OCEL handle
+    selected_session: Arc<RwLock<Option<String>>>,
+    /// Validation report of the last OCEL loaded (or rejected) per session name
+    validation_reports: Arc<RwLock<HashMap<String, OCELValidationReport>>>,
     client: Arc<RwLock<Option<Client>>>,
     jobs: Arc<RwLock<Vec<(String, u16, JoinHandle<()>)>>>,
-    eval_res: Arc<RwLock<Option<EvaluateBoxTreeResult>>>,
 }
 
 #[tokio::main]
@@ -61,12 +80,20 @@ async fn main() {
     // .allow_headers([CONTENT_TYPE])
     // .allow_origin(tower_http::cors::Any);
 
-    load_ocel_file_to_state(DEFAULT_OCEL_FILE, &state);
+    load_ocel_file_to_state(
+        DEFAULT_OCEL_FILE,
+        &OCELHandle::default(),
+        false,
+        ValidationOptions::default(),
+        &state,
+    );
+    load_default_cel_plugins();
 
     // build our application with a single route
     let app = Router::new()
         .route("/ocel/load", post(load_ocel_file_req))
         .route("/ocel/info", get(get_loaded_ocel_info))
+        .route("/ocel/profile", get(get_ocel_profile_req))
         .route(
             "/ocel/upload-json",
             post(upload_ocel_json).layer(DefaultBodyLimit::disable()),
@@ -80,6 +107,13 @@ async fn main() {
             post(upload_ocel_sqlite).layer(DefaultBodyLimit::disable()),
         )
         .route("/ocel/available", get(get_available_ocels))
+        .route("/ocel/sessions", get(list_sessions))
+        .route("/ocel/sessions/select", post(select_session))
+        .route("/ocel/sessions/unload", post(unload_session))
+        .route(
+            "/ocel/append",
+            post(append_to_ocel).layer(DefaultBodyLimit::disable()),
+        )
         .route(
             "/ocel/event-qualifiers",
             get(get_qualifiers_for_event_types_handler),
@@ -88,8 +122,11 @@ async fn main() {
             "/ocel/object-qualifiers",
             get(get_qualifers_for_object_types),
         )
+        .route("/ocel/memory-usage", get(get_session_memory_usage))
+        .route("/ocel/validation-report", get(get_session_validation_report))
         .route("/ocel/graph", post(ocel_graph_req))
         .route("/ocel/check-constraints-box", post(check_with_box_tree_req))
+        .route("/ocel/compare-box-tree", post(compare_box_tree_req))
         .route(
             "/ocel/export-filter-box",
             post(filter_export_with_box_tree_req),
@@ -98,6 +135,11 @@ async fn main() {
             "/ocel/discover-constraints",
             post(auto_discover_constraints_handler),
         )
+        .route("/ocel/discover-drift", post(discover_constraint_drift_handler))
+        .route(
+            "/ocel/discover-labeled-constraints",
+            post(discover_constraints_from_labels_handler),
+        )
         .route(
             "/ocel/export-bindings",
             post(export_bindings_table).layer(DefaultBodyLimit::disable()),
@@ -106,6 +148,7 @@ async fn main() {
         .route("/ocel/object/:object_id", get(get_object_info_req))
         .route("/ocel/get-event", post(get_event_req))
         .route("/ocel/get-object", post(get_object_req))
+        .route("/ocel/search-ids", post(search_ids_req))
         .route("/hpc/login", post(login_to_hpc_web))
         .route("/hpc/start", post(start_hpc_job_web))
         .route("/hpc/job-status/:job_id", get(get_hpc_job_status_web))
@@ -123,68 +166,131 @@ async fn main() {
 
 async fn get_loaded_ocel_info(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
 ) -> (StatusCode, Json<Option<OCELInfo>>) {
-    match with_ocel_from_state(&State(state), |ocel| (&ocel.ocel).into()) {
+    match with_ocel_from_state(&State(state), &handle, |ocel| {
+        OCELInfo::from_ocel(&ocel.ocel, ids.include_ids)
+    }) {
         Some(ocel_info) => (StatusCode::OK, Json(Some(ocel_info))),
         None => (StatusCode::NOT_FOUND, Json(None)),
     }
 }
 
+async fn get_ocel_profile_req(
+    State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+) -> (StatusCode, Json<Option<OCELProfile>>) {
+    match with_ocel_from_state(&State(state), &handle, get_ocel_profile) {
+        Some(profile) => (StatusCode::OK, Json(Some(profile))),
+        None => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
+
 async fn upload_ocel_xml<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
+    Query(validation): Query<ValidationOptions>,
     ocel_bytes: Bytes,
-) -> (StatusCode, Json<OCELInfo>) {
+) -> (StatusCode, Json<Option<OCELInfo>>) {
     let ocel = import_ocel_xml_slice(&ocel_bytes);
-    let mut x = state.ocel.write().unwrap();
-    let ocel_info: OCELInfo = (&ocel).into();
-    *x = Some(IndexLinkedOCEL::new(ocel));
-
-    (StatusCode::OK, Json(ocel_info))
+    let Some(ocel) =
+        state.store_validation_result(&handle, validate_and_link_ocel(ocel, validation.on_issues))
+    else {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(None));
+    };
+    let ocel_info = OCELInfo::from_ocel(&ocel.ocel, ids.include_ids);
+    state.insert_session(&handle, "upload.xml", ocel);
+
+    (StatusCode::OK, Json(Some(ocel_info)))
 }
 
 async fn upload_ocel_sqlite<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
+    Query(validation): Query<ValidationOptions>,
     ocel_bytes: Bytes,
-) -> (StatusCode, Json<OCELInfo>) {
+) -> (StatusCode, Json<Option<OCELInfo>>) {
     let ocel = import_ocel_sqlite_from_slice(&ocel_bytes).unwrap();
-    let mut x: std::sync::RwLockWriteGuard<'_, Option<IndexLinkedOCEL>> =
-        state.ocel.write().unwrap();
-    let ocel_info: OCELInfo = (&ocel).into();
-    *x = Some(IndexLinkedOCEL::new(ocel));
-
-    (StatusCode::OK, Json(ocel_info))
+    let Some(ocel) =
+        state.store_validation_result(&handle, validate_and_link_ocel(ocel, validation.on_issues))
+    else {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(None));
+    };
+    let ocel_info = OCELInfo::from_ocel(&ocel.ocel, ids.include_ids);
+    state.insert_session(&handle, "upload.sqlite", ocel);
+
+    (StatusCode::OK, Json(Some(ocel_info)))
 }
 
 async fn upload_ocel_json<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
+    Query(validation): Query<ValidationOptions>,
     ocel_bytes: Bytes,
-) -> (StatusCode, Json<OCELInfo>) {
+) -> (StatusCode, Json<Option<OCELInfo>>) {
     let ocel: OCEL = serde_json::from_slice(&ocel_bytes).unwrap();
-    let mut x = state.ocel.write().unwrap();
-    let ocel_info: OCELInfo = (&ocel).into();
-    *x = Some(IndexLinkedOCEL::new(ocel));
-    (StatusCode::OK, Json(ocel_info))
+    let Some(ocel) =
+        state.store_validation_result(&handle, validate_and_link_ocel(ocel, validation.on_issues))
+    else {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(None));
+    };
+    let ocel_info = OCELInfo::from_ocel(&ocel.ocel, ids.include_ids);
+    state.insert_session(&handle, "upload.json", ocel);
+    (StatusCode::OK, Json(Some(ocel_info)))
 }
 
-pub fn with_ocel_from_state<T, F>(State(state): &State<AppState>, f: F) -> Option<T>
+async fn append_to_ocel(
+    State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Query(ids): Query<IncludeIDsOptions>,
+    Json(req): Json<AppendToOCELRequest>,
+) -> (StatusCode, Json<Option<OCELInfo>>) {
+    match state.get_session(&handle) {
+        Some(session) => {
+            let mut ocel = session.ocel.write().unwrap();
+            let counts = req.apply(&mut ocel);
+            println!(
+                "Appended {} objects and {} events to OCEL",
+                counts.num_objects, counts.num_events
+            );
+            (
+                StatusCode::OK,
+                Json(Some(OCELInfo::from_ocel(&ocel.ocel, ids.include_ids))),
+            )
+        }
+        None => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
+
+pub fn with_ocel_from_state<T, F>(
+    State(state): &State<AppState>,
+    handle: &OCELHandle,
+    f: F,
+) -> Option<T>
 where
     F: FnOnce(&IndexLinkedOCEL) -> T,
 {
-    let read_guard = state.ocel.read().ok()?;
-    let ocel_ref = read_guard.as_ref()?;
-    Some(f(ocel_ref))
+    let session = state.get_session(handle)?;
+    let read_guard = session.ocel.read().ok()?;
+    Some(f(&read_guard))
 }
 
 pub async fn get_qualifiers_for_event_types_handler<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
 ) -> (
     StatusCode,
     Json<Option<HashMap<String, HashMap<String, QualifiersForEventType>>>>,
 ) {
     match with_ocel_from_state(
         &State(state),
+        &handle,
         |ocel| -> HashMap<String, HashMap<String, QualifiersForEventType>> {
-            get_qualifiers_for_event_types(&ocel.ocel)
+            get_qualifiers_for_event_types(ocel)
         },
     ) {
         Some(x) => (StatusCode::OK, Json(Some(x))),
@@ -194,12 +300,13 @@ pub async fn get_qualifiers_for_event_types_handler<'a>(
 
 pub async fn get_qualifers_for_object_types<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
 ) -> (
     StatusCode,
     Json<Option<HashMap<String, HashSet<QualifierAndObjectType>>>>,
 ) {
-    let qualifier_and_type = with_ocel_from_state(&State(state), |ocel| {
-        link_ocel_info(&ocel.ocel).object_rels_per_type.clone()
+    let qualifier_and_type = with_ocel_from_state(&State(state), &handle, |ocel| {
+        ocel.object_rels_per_type.clone()
     });
     match qualifier_and_type {
         Some(x) => (StatusCode::OK, Json(Some(x))),
@@ -209,9 +316,12 @@ pub async fn get_qualifers_for_object_types<'a>(
 
 pub async fn ocel_graph_req<'a>(
     State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(options): Json<OCELGraphOptions>,
 ) -> (StatusCode, Json<Option<OCELGraph>>) {
-    let graph = with_ocel_from_state(&State(state), |ocel| get_ocel_graph(ocel, options));
+    let graph = with_ocel_from_state(&State(state), &handle, |ocel| {
+        get_ocel_graph(ocel, options)
+    });
     match graph.flatten() {
         Some(x) => (StatusCode::OK, Json(Some(x))),
         None => (StatusCode::BAD_REQUEST, Json(None)),
@@ -220,25 +330,64 @@ pub async fn ocel_graph_req<'a>(
 
 pub async fn check_with_box_tree_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(req): Json<CheckWithBoxTreeRequest>,
 ) -> (StatusCode, Json<Option<EvaluateBoxTreeResult>>) {
-    let ocel_guard = state.ocel.read().unwrap();
-    let ocel = ocel_guard.as_ref();
-    if let Some(ocel) = ocel {
-        let res = evaluate_box_tree(req.tree, ocel, req.measure_performance.unwrap_or(false));
+    if let Some(session) = state.get_session(&handle) {
+        let ocel = session.ocel.read().unwrap();
+        let res = evaluate_box_tree(
+            req.tree,
+            &ocel,
+            req.measure_performance.unwrap_or(false),
+            req.include_ids.unwrap_or(false),
+        );
         let res_to_ret = res.clone_first_few();
-        let mut new_eval_res_state = state.eval_res.write().unwrap();
+        let mut new_eval_res_state = session.eval_res.write().unwrap();
         *new_eval_res_state = Some(res);
         return (StatusCode::OK, Json(Some(res_to_ret)));
     }
     (StatusCode::INTERNAL_SERVER_ERROR, Json(None))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareBoxTreeRequest {
+    pub tree: BindingBoxTree,
+    /// Session name of the OCEL to compare against
+    pub other_ocel: String,
+}
+
+/// Evaluate a tree on the OCEL referenced by the handle and the `other_ocel` of the request and compare the results
+pub async fn compare_box_tree_req(
+    state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Json(req): Json<CompareBoxTreeRequest>,
+) -> (StatusCode, Json<Option<BoxTreeComparison>>) {
+    let other_handle = OCELHandle {
+        ocel: Some(req.other_ocel),
+    };
+    match (state.get_session(&handle), state.get_session(&other_handle)) {
+        (Some(session_a), Some(session_b)) => {
+            let ocel_a = session_a.ocel.read().unwrap();
+            let res = if Arc::ptr_eq(&session_a.ocel, &session_b.ocel) {
+                // Do not acquire the same lock twice
+                compare_box_tree(&req.tree, &ocel_a, &ocel_a)
+            } else {
+                let ocel_b = session_b.ocel.read().unwrap();
+                compare_box_tree(&req.tree, &ocel_a, &ocel_b)
+            };
+            (StatusCode::OK, Json(Some(res)))
+        }
+        _ => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
+
 pub async fn filter_export_with_box_tree_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(req): Json<FilterExportWithBoxTreeRequest>,
 ) -> (StatusCode, Bytes) {
-    with_ocel_from_state(&state, |ocel| {
+    with_ocel_from_state(&state, &handle, |ocel| {
         let res = filter_ocel_box_tree(req.tree, ocel).unwrap();
         let bytes = match req.export_format {
             ExportFormat::XML => {
@@ -263,23 +412,46 @@ pub async fn filter_export_with_box_tree_req<'a>(
 
 pub async fn auto_discover_constraints_handler<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(req): Json<AutoDiscoverConstraintsRequest>,
 ) -> Json<Option<AutoDiscoverConstraintsResponse>> {
-    Json(with_ocel_from_state(&state, |ocel| {
+    Json(with_ocel_from_state(&state, &handle, |ocel| {
         auto_discover_constraints_with_options(ocel, req)
     }))
 }
 
+pub async fn discover_constraint_drift_handler(
+    state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Json(req): Json<DriftDiscoveryRequest>,
+) -> Json<Option<DriftDiscoveryResponse>> {
+    Json(with_ocel_from_state(&state, &handle, |ocel| {
+        discover_constraint_drift(ocel, req)
+    }))
+}
+
+pub async fn discover_constraints_from_labels_handler(
+    state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Json(req): Json<LabeledDiscoveryRequest>,
+) -> Json<Option<LabeledDiscoveryResponse>> {
+    Json(with_ocel_from_state(&state, &handle, |ocel| {
+        discover_constraints_from_labels(ocel, req)
+    }))
+}
+
 pub async fn export_bindings_table(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json((node_index, table_options)): Json<(usize, TableExportOptions)>,
 ) -> (StatusCode, Bytes) {
-    if let Some(ocel) = state.ocel.read().unwrap().as_ref() {
-        if let Some(eval_res) = state.eval_res.read().unwrap().as_ref() {
+    if let Some(session) = state.get_session(&handle) {
+        let ocel = session.ocel.read().unwrap();
+        if let Some(eval_res) = session.eval_res.read().unwrap().as_ref() {
             if let Some(node_eval_res) = eval_res.evaluation_results.get(node_index) {
                 let inner = Vec::new();
                 let mut w: Cursor<Vec<u8>> = Cursor::new(inner);
-                export_bindings_to_writer(ocel, &node_eval_res, &mut w, &table_options).unwrap();
+                export_bindings_to_writer(&ocel, &node_eval_res, &mut w, &table_options).unwrap();
                 let b = Bytes::from(w.into_inner());
                 return (StatusCode::OK, b);
             }
@@ -290,33 +462,56 @@ pub async fn export_bindings_table(
 
 pub async fn get_event_info_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Path(event_id): Path<String>,
 ) -> Json<Option<OCELEvent>> {
-    Json(with_ocel_from_state(&state, |ocel| ocel.ev_by_id(&event_id).cloned()).unwrap_or_default())
+    Json(
+        with_ocel_from_state(&state, &handle, |ocel| {
+            ocel.index_of_ev(&event_id).and_then(|ev_index| ocel.full_event(ev_index))
+        })
+        .flatten(),
+    )
 }
 pub async fn get_object_info_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Path(object_id): Path<String>,
 ) -> Json<Option<OCELObject>> {
     Json(
-        with_ocel_from_state(&state, |ocel| ocel.ob_by_id(&object_id).cloned()).unwrap_or_default(),
+        with_ocel_from_state(&state, &handle, |ocel| {
+            ocel.index_of_ob(&object_id).and_then(|ob_index| ocel.full_object(ob_index))
+        })
+        .flatten(),
     )
 }
 
 async fn get_event_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(req): Json<IndexOrID>,
 ) -> Json<Option<EventWithIndex>> {
-    let res = with_ocel_from_state(&state, |ocel| get_event_info(ocel, req)).flatten();
+    let res = with_ocel_from_state(&state, &handle, |ocel| get_event_info(ocel, req)).flatten();
 
     Json(res)
 }
 
+async fn search_ids_req(
+    state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
+    Json(req): Json<SearchIDsRequest>,
+) -> (StatusCode, Json<Option<SearchIDsResponse>>) {
+    match with_ocel_from_state(&state, &handle, |ocel| search_ids(ocel, &req)) {
+        Some(res) => (StatusCode::OK, Json(Some(res))),
+        None => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
+
 async fn get_object_req<'a>(
     state: State<AppState>,
+    Query(handle): Query<OCELHandle>,
     Json(req): Json<IndexOrID>,
 ) -> Json<Option<ObjectWithIndex>> {
-    let res = with_ocel_from_state(&state, |ocel| get_object_info(ocel, req)).flatten();
+    let res = with_ocel_from_state(&state, &handle, |ocel| get_object_info(ocel, req)).flatten();
 
     Json(res)
 }
@@ -375,18 +570,18 @@ async fn get_hpc_job_status_web(
 
 async fn translate_to_sql(
     Json(req): Json<TranslationToSQL>
-)-> Result<Json<String>, (StatusCode, String)>{
+)-> Result<Json<TranslatedQuery>, (StatusCode, String)>{
     let res = translate_to_sql_shared(req.tree, req.database_type);
 
-    Ok(Json(res))
+    Ok(Json(res.into()))
 }
 
 
 async fn translate_to_cypher(
     Json(tree): Json<BindingBoxTree>
-) -> Result<Json<String>, (StatusCode, String)>{
+) -> Result<Json<TranslatedQuery>, (StatusCode, String)>{
     let res = translate_to_cypher_shared(tree);
 
-    Ok(Json(res))
+    Ok(Json(res.into()))
 }
 
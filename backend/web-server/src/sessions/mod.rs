@@ -0,0 +1,203 @@
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use itertools::Itertools;
+use ocpq_shared::{
+    binding_box::EvaluateBoxTreeResult,
+    preprocessing::{
+        compact::OCELMemoryUsage,
+        linked_ocel::IndexLinkedOCEL,
+        validation::{OCELValidationReport, ValidationResult},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// Name of the session used if no OCEL handle is given and no other session is selected
+pub const DEFAULT_SESSION_NAME: &str = "default";
+
+/// Optional OCEL handle (i.e., session name) passed as `?ocel=<name>` query parameter to all `/ocel/*` endpoints
+///
+/// If no handle is given, the currently selected session is used.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OCELHandle {
+    pub ocel: Option<String>,
+}
+
+/// A loaded OCEL, together with the last evaluation result on it
+#[derive(Clone)]
+pub struct OCELSession {
+    /// Name of the file (or upload) the OCEL was loaded from
+    pub source: String,
+    pub ocel: Arc<RwLock<IndexLinkedOCEL>>,
+    pub eval_res: Arc<RwLock<Option<EvaluateBoxTreeResult>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OCELSessionInfo {
+    pub name: String,
+    pub source: String,
+    pub selected: bool,
+    pub num_events: usize,
+    pub num_objects: usize,
+    pub memory_usage: OCELMemoryUsage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionRequest {
+    pub name: String,
+}
+
+impl AppState {
+    fn session_name(&self, handle: &OCELHandle) -> String {
+        handle
+            .ocel
+            .clone()
+            .or_else(|| self.selected_session.read().unwrap().clone())
+            .unwrap_or_else(|| DEFAULT_SESSION_NAME.to_string())
+    }
+
+    /// Get the session referenced by the handle (or the selected session, if the handle is empty)
+    pub fn get_session(&self, handle: &OCELHandle) -> Option<OCELSession> {
+        let name = self.session_name(handle);
+        self.sessions.read().unwrap().get(&name).cloned()
+    }
+
+    /// Insert an OCEL as the session referenced by the handle, replacing any OCEL previously loaded in that session
+    ///
+    /// The new session is selected if no session was selected before.
+    /// Returns the name of the session.
+    pub fn insert_session(
+        &self,
+        handle: &OCELHandle,
+        source: impl Into<String>,
+        ocel: IndexLinkedOCEL,
+    ) -> String {
+        let name = self.session_name(handle);
+        let session = OCELSession {
+            source: source.into(),
+            ocel: Arc::new(RwLock::new(ocel)),
+            eval_res: Arc::new(RwLock::new(None)),
+        };
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(name.clone(), session);
+        let mut selected = self.selected_session.write().unwrap();
+        if selected.is_none() {
+            *selected = Some(name.clone());
+        }
+        name
+    }
+
+    /// Store the validation result of an OCEL to be loaded into the session referenced by the handle
+    ///
+    /// The validation report is kept for the session, also if the OCEL is rejected.
+    /// Returns the (possibly repaired) OCEL, or `None` if it was rejected.
+    pub fn store_validation_result(
+        &self,
+        handle: &OCELHandle,
+        res: ValidationResult,
+    ) -> Option<IndexLinkedOCEL> {
+        let name = self.session_name(handle);
+        let (Ok((_, report)) | Err(report)) = &res;
+        if !report.is_valid() {
+            eprintln!("OCEL for session {name} has {}", report.summary());
+        }
+        let (ocel, report) = match res {
+            Ok((ocel, report)) => (Some(ocel), report),
+            Err(report) => {
+                eprintln!("Rejected OCEL for session {name} because of data-quality issues");
+                (None, report)
+            }
+        };
+        self.validation_reports
+            .write()
+            .unwrap()
+            .insert(name, report);
+        ocel
+    }
+}
+
+pub async fn list_sessions(State(state): State<AppState>) -> Json<Vec<OCELSessionInfo>> {
+    let selected = state.selected_session.read().unwrap().clone();
+    let sessions = state.sessions.read().unwrap();
+    Json(
+        sessions
+            .iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(name, session)| {
+                let ocel = session.ocel.read().unwrap();
+                OCELSessionInfo {
+                    name: name.clone(),
+                    source: session.source.clone(),
+                    selected: selected.as_ref() == Some(name),
+                    num_events: ocel.ocel.events.len(),
+                    num_objects: ocel.ocel.objects.len(),
+                    memory_usage: ocel.memory_usage(),
+                }
+            })
+            .collect(),
+    )
+}
+
+pub async fn select_session(
+    State(state): State<AppState>,
+    Json(req): Json<SessionRequest>,
+) -> StatusCode {
+    if !state.sessions.read().unwrap().contains_key(&req.name) {
+        return StatusCode::NOT_FOUND;
+    }
+    *state.selected_session.write().unwrap() = Some(req.name);
+    StatusCode::OK
+}
+
+/// Unload a session
+///
+/// If the unloaded session was selected, another loaded session (if any) is selected instead
+pub async fn unload_session(
+    State(state): State<AppState>,
+    Json(req): Json<SessionRequest>,
+) -> StatusCode {
+    let mut sessions = state.sessions.write().unwrap();
+    if sessions.remove(&req.name).is_none() {
+        return StatusCode::NOT_FOUND;
+    }
+    state.validation_reports.write().unwrap().remove(&req.name);
+    let mut selected = state.selected_session.write().unwrap();
+    if selected.as_ref() == Some(&req.name) {
+        *selected = sessions.keys().min().cloned();
+    }
+    StatusCode::OK
+}
+
+pub async fn get_session_memory_usage(
+    State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+) -> (StatusCode, Json<Option<OCELMemoryUsage>>) {
+    match state.get_session(&handle) {
+        Some(session) => (
+            StatusCode::OK,
+            Json(Some(session.ocel.read().unwrap().memory_usage())),
+        ),
+        None => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
+
+/// Get the validation report of the last OCEL loaded (or rejected) for the session referenced by the handle
+pub async fn get_session_validation_report(
+    State(state): State<AppState>,
+    Query(handle): Query<OCELHandle>,
+) -> (StatusCode, Json<Option<OCELValidationReport>>) {
+    let name = state.session_name(&handle);
+    match state.validation_reports.read().unwrap().get(&name) {
+        Some(report) => (StatusCode::OK, Json(Some(report.clone()))),
+        None => (StatusCode::NOT_FOUND, Json(None)),
+    }
+}
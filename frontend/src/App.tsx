@@ -9,8 +9,9 @@ import { createContext, ReactNode, useContext, useEffect, useMemo, useRef, useSt
 import toast from "react-hot-toast";
 import { Outlet, useLocation, useNavigate } from "react-router-dom";
 import "./App.css";
-import { BackendProvider, BackendProviderContext, ErrorBackendContext, getAPIServerBackendProvider } from "./BackendProviderContext";
+import { BackendProvider, BackendProviderContext, ErrorBackendContext, getAPIServerBackendProvider, OcelSessionContext } from "./BackendProviderContext";
 import MenuLink from "./components/MenuLink";
+import OcelSessionChooser from "./components/OcelSessionChooser";
 import Spinner from "./components/Spinner";
 import { Button } from "./components/ui/button";
 import { type OCELInfo } from "./types/ocel";
@@ -44,6 +45,7 @@ function App() {
   const [loading, setLoading] = useState(false);
   const [step, setStep] = useState<number>();
   const ownBackend = useContext(BackendProviderContext);
+  const { session: ocelSession } = useContext(OcelSessionContext);
   const connectionFormRef = useRef<{ getConfig: () => ConnectionConfig }>(null);
   const [hpcOptions, setHpcOptions] = useState<OCPQJobOptions>({
     cpus: 4,
@@ -71,7 +73,7 @@ function App() {
       return ownBackend;
     } else {
       return {
-        ...getAPIServerBackendProvider("http://localhost:" + hpcOptions.port),
+        ...getAPIServerBackendProvider("http://localhost:" + hpcOptions.port, ocelSession),
         ["hpc/login"]: ownBackend["hpc/login"],
         ["hpc/start"]: ownBackend["hpc/start"],
         ["hpc/job-status"]: ownBackend["hpc/job-status"],
@@ -79,7 +81,7 @@ function App() {
 
       } satisfies BackendProvider
     }
-  }, [backendMode])
+  }, [backendMode, ownBackend, ocelSession])
   return <BackendProviderContext.Provider value={innerBackend}>
     <InnerApp>
       <AlertDialog
@@ -376,6 +378,7 @@ function InnerApp({ children }: { children?: ReactNode }) {
           <div className="flex flex-col gap-2 mt-4">
             {backendAvailable && <span className="text-green-700 font-semibold bg-green-200 w-fit mx-auto p-1 rounded">Backend online</span>}
             {!backendAvailable && <span className="text-red-700 font-semibold bg-red-200 w-fit mx-auto p-1 rounded">Backend offline</span>}
+            {backendAvailable && backend["ocel/sessions"] !== undefined && <OcelSessionChooser />}
             {ocelInfo != null && (
               <span className="flex flex-col items-center mx-auto text-xl">
                 <span className=" font-semibold text-green-700">
@@ -2,6 +2,10 @@ import { createContext } from "react";
 import type {
   DiscoverConstraintsRequest,
   DiscoverConstraintsResponse,
+  DiscoverDriftRequest,
+  DiscoverDriftResponse,
+  DiscoverLabeledConstraintsRequest,
+  DiscoverLabeledConstraintsResponse,
 } from "./routes/visual-editor/helper/types";
 import type { BindingBoxTree } from "./types/generated/BindingBoxTree";
 import type { EvaluateBoxTreeResult } from "./types/generated/EvaluateBoxTreeResult";
@@ -11,6 +15,7 @@ import type {
   OCELEvent,
   OCELInfo,
   OCELObject,
+  OCELSessionInfo,
   ObjectTypeQualifiers,
 } from "./types/ocel";
 import { EvaluationResultWithCount } from "./types/generated/EvaluationResultWithCount";
@@ -19,12 +24,16 @@ import { z } from "zod";
 import { ConnectionConfig, JobStatus } from "./types/hpc-backend";
 import { OCPQJobOptions } from "./types/generated/OCPQJobOptions";
 import { DatabaseType } from "./types/generated/DatabaseType";
+import { TranslatedQuery } from "./types/generated/TranslatedQuery";
+import type { SearchIDsRequest } from "./types/generated/SearchIDsRequest";
+import type { SearchIDsResponse } from "./types/generated/SearchIDsResponse";
 export type BackendProvider = {
   "ocel/info": () => Promise<OCELInfo|undefined>;
   "ocel/upload"?: (file: File) => Promise<OCELInfo>;
   "ocel/available"?: () => Promise<string[]>;
   "ocel/load"?: (name: string) => Promise<OCELInfo>;
   "ocel/picker"?: () => Promise<OCELInfo>;
+  "ocel/sessions"?: () => Promise<OCELSessionInfo[]>;
   "ocel/check-constraints-box": (
     tree: BindingBoxTree,
     measurePerformance?: boolean,
@@ -38,6 +47,12 @@ export type BackendProvider = {
   "ocel/discover-constraints": (
     autoDiscoveryOptions: DiscoverConstraintsRequest,
   ) => Promise<DiscoverConstraintsResponse>;
+  "ocel/discover-drift": (
+    driftOptions: DiscoverDriftRequest,
+  ) => Promise<DiscoverDriftResponse>;
+  "ocel/discover-labeled-constraints": (
+    labeledOptions: DiscoverLabeledConstraintsRequest,
+  ) => Promise<DiscoverLabeledConstraintsResponse>;
   "ocel/export-bindings": (
     nodeIndex: number,
     options: TableExportOptions,
@@ -52,6 +67,7 @@ export type BackendProvider = {
   "ocel/get-event": (
     specifier: { id: string } | { index: number },
   ) => Promise<{ index: number; event: OCELEvent }>;
+  "ocel/search-ids": (req: SearchIDsRequest) => Promise<SearchIDsResponse>;
   "hpc/login": (connectionConfig: ConnectionConfig) => Promise<void>,
   "hpc/start": (jobOptions: OCPQJobOptions) => Promise<string>,
   "hpc/job-status": (jobID: string) => Promise<JobStatus>,
@@ -59,10 +75,10 @@ export type BackendProvider = {
   "translate-to-sql": (
     tree: BindingBoxTree,
     database: DatabaseType)
-    => Promise<string>,
+    => Promise<TranslatedQuery>,
     "translate-to-cypher": (
     tree: BindingBoxTree,)
-    => Promise<string>,
+    => Promise<TranslatedQuery>,
 };
 
 export async function warnForNoBackendProvider<T>(): Promise<T> {
@@ -79,10 +95,13 @@ export const ErrorBackendContext: BackendProvider = {
   "ocel/event-qualifiers": warnForNoBackendProvider,
   "ocel/object-qualifiers": warnForNoBackendProvider,
   "ocel/discover-constraints": warnForNoBackendProvider,
+  "ocel/discover-drift": warnForNoBackendProvider,
+  "ocel/discover-labeled-constraints": warnForNoBackendProvider,
   "ocel/export-bindings": warnForNoBackendProvider,
   "ocel/graph": warnForNoBackendProvider,
   "ocel/get-event": warnForNoBackendProvider,
   "ocel/get-object": warnForNoBackendProvider,
+  "ocel/search-ids": warnForNoBackendProvider,
   "hpc/login": warnForNoBackendProvider,
   "hpc/start": warnForNoBackendProvider,
   "hpc/job-status": warnForNoBackendProvider,
@@ -93,12 +112,22 @@ export const ErrorBackendContext: BackendProvider = {
 
 export const BackendProviderContext = createContext<BackendProvider>(ErrorBackendContext);
 
+/** Name of the OCEL session used for all OCEL requests (`undefined` uses the session selected in the backend) */
+export const OcelSessionContext = createContext<{
+  session: string | undefined;
+  setSession: (session: string | undefined) => void;
+}>({ session: undefined, setSession: () => {} });
+
 export const DEFAULT_BACKEND_URL = "http://localhost:3000";
 
-export function getAPIServerBackendProvider(localBackendURL: string):  BackendProvider {
+export function getAPIServerBackendProvider(localBackendURL: string, ocelSession?: string):  BackendProvider {
+  const ocelURL = (path: string) =>
+    localBackendURL +
+    path +
+    (ocelSession !== undefined ? `?ocel=${encodeURIComponent(ocelSession)}` : "");
   return {
   "ocel/info": async () => {
-    const res = await fetch(localBackendURL + "/ocel/info", {
+    const res = await fetch(ocelURL("/ocel/info"), {
       method: "get",
       headers: {},
     });
@@ -112,6 +141,14 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
       })
     ).json();
   },
+  "ocel/sessions": async () => {
+    return await (
+      await fetch(localBackendURL + "/ocel/sessions", {
+        method: "get",
+        headers: {},
+      })
+    ).json();
+  },
   "ocel/upload": async (ocelFile) => {
     const type = ocelFile.name.endsWith(".json")
       ? "json"
@@ -119,7 +156,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
       ? "xml"
       : "sqlite";
     return await (
-      await fetch(localBackendURL + `/ocel/upload-${type}`, {
+      await fetch(ocelURL(`/ocel/upload-${type}`), {
         method: "post",
         body: ocelFile,
         headers: {},
@@ -128,7 +165,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/load": async (name) => {
     return await (
-      await fetch(localBackendURL + "/ocel/load", {
+      await fetch(ocelURL("/ocel/load"), {
         method: "post",
         body: JSON.stringify({ name }),
         headers: { "Content-Type": "application/json" },
@@ -137,7 +174,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/check-constraints-box": async (tree, measurePerformance) => {
     return await (
-      await fetch(localBackendURL + "/ocel/check-constraints-box", {
+      await fetch(ocelURL("/ocel/check-constraints-box"), {
         method: "post",
         body: JSON.stringify({ tree, measurePerformance }),
         headers: { "Content-Type": "application/json" },
@@ -146,7 +183,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/export-filter-box": async (tree, exportFormat) => {
     return await (
-      await fetch(localBackendURL + "/ocel/export-filter-box", {
+      await fetch(ocelURL("/ocel/export-filter-box"), {
         method: "post",
         body: JSON.stringify({ tree, exportFormat }),
         headers: { "Content-Type": "application/json" },
@@ -155,7 +192,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/event-qualifiers": async () => {
     return await (
-      await fetch(localBackendURL + "/ocel/event-qualifiers", {
+      await fetch(ocelURL("/ocel/event-qualifiers"), {
         method: "get",
         headers: {},
       })
@@ -163,14 +200,14 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/object-qualifiers": async () => {
     return await (
-      await fetch(localBackendURL + "/ocel/object-qualifiers", {
+      await fetch(ocelURL("/ocel/object-qualifiers"), {
         method: "get",
         headers: {},
       })
     ).json();
   },
   "ocel/export-bindings": async (nodeId, options) => {
-    const res = await fetch(localBackendURL + "/ocel/export-bindings", {
+    const res = await fetch(ocelURL("/ocel/export-bindings"), {
       method: "post",
       headers: { "Content-Type": "application/json" },
       body: JSON.stringify([nodeId, options]),
@@ -183,15 +220,33 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
   },
   "ocel/discover-constraints": async (autoDiscoveryOptions) => {
     return await (
-      await fetch(localBackendURL + "/ocel/discover-constraints", {
+      await fetch(ocelURL("/ocel/discover-constraints"), {
         method: "post",
         headers: { "Content-Type": "application/json" },
         body: JSON.stringify(autoDiscoveryOptions),
       })
     ).json();
   },
+  "ocel/discover-drift": async (driftOptions) => {
+    return await (
+      await fetch(ocelURL("/ocel/discover-drift"), {
+        method: "post",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(driftOptions),
+      })
+    ).json();
+  },
+  "ocel/discover-labeled-constraints": async (labeledOptions) => {
+    return await (
+      await fetch(ocelURL("/ocel/discover-labeled-constraints"), {
+        method: "post",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(labeledOptions),
+      })
+    ).json();
+  },
   "ocel/graph": async (options) => {
-    const res = await fetch(localBackendURL + "/ocel/graph", {
+    const res = await fetch(ocelURL("/ocel/graph"), {
       method: "post",
       headers: { "Content-Type": "application/json" },
       body: JSON.stringify(options),
@@ -204,7 +259,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
     }
   },
   "ocel/get-event": async (specifier) => {
-    const res = await fetch(localBackendURL + "/ocel/get-event", {
+    const res = await fetch(ocelURL("/ocel/get-event"), {
       method: "post",
       headers: { "Content-Type": "application/json" },
       body: JSON.stringify(specifier),
@@ -217,7 +272,7 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
     }
   },
   "ocel/get-object": async (specifier) => {
-    const res = await fetch(localBackendURL + "/ocel/get-object", {
+    const res = await fetch(ocelURL("/ocel/get-object"), {
       method: "post",
       headers: { "Content-Type": "application/json" },
       body: JSON.stringify(specifier),
@@ -229,6 +284,18 @@ export function getAPIServerBackendProvider(localBackendURL: string):  BackendPr
       throw new Error(res.statusText);
     }
   },
+  "ocel/search-ids": async (req) => {
+    const res = await fetch(ocelURL("/ocel/search-ids"), {
+      method: "post",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(req),
+    });
+    if (res.ok) {
+      return await res.json();
+    } else {
+      throw new Error(res.statusText);
+    }
+  },
   "hpc/login": async (connectionConfig) => {
     const res = await fetch(localBackendURL + "/hpc/login", {
         method: "post",
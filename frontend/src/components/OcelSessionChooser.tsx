@@ -0,0 +1,52 @@
+import { useContext, useEffect, useState } from "react";
+import {
+  BackendProviderContext,
+  OcelSessionContext,
+} from "../BackendProviderContext";
+import type { OCELSessionInfo } from "../types/ocel";
+import { Input } from "./ui/input";
+import { Label } from "./ui/label";
+
+/**
+ * Choose the OCEL session used for all requests to the backend
+ *
+ * OCEL loaded or uploaded afterwards are loaded into the chosen session.
+ */
+export default function OcelSessionChooser() {
+  const backend = useContext(BackendProviderContext);
+  const { session, setSession } = useContext(OcelSessionContext);
+  const [sessions, setSessions] = useState<OCELSessionInfo[]>([]);
+  useEffect(() => {
+    if (backend["ocel/sessions"] !== undefined) {
+      backend["ocel/sessions"]()
+        .then(setSessions)
+        .catch((e) => {
+          console.error(e);
+          setSessions([]);
+        });
+    }
+  }, [backend]);
+
+  return (
+    <div className="flex flex-col gap-1 text-left">
+      <Label htmlFor="ocel-session">Session</Label>
+      <Input
+        id="ocel-session"
+        list="ocel-session-names"
+        placeholder={sessions.find((s) => s.selected)?.name ?? "default"}
+        value={session ?? ""}
+        onChange={(ev) => {
+          const value = ev.currentTarget.value.trim();
+          setSession(value === "" ? undefined : value);
+        }}
+      />
+      <datalist id="ocel-session-names">
+        {sessions.map((s) => (
+          <option key={s.name} value={s.name}>
+            {s.source} ({s.numEvents} Events, {s.numObjects} Objects)
+          </option>
+        ))}
+      </datalist>
+    </div>
+  );
+}
@@ -12,7 +12,7 @@ import { Checkbox } from "../ui/checkbox";
 import { LabelValue } from "@/types/generated/LabelValue";
 import { LabelLabel } from "@/routes/visual-editor/helper/box/LabelFunctionChooser";
 
-function getLabelValuePrimitive(l: LabelValue | null) {
+function getLabelValuePrimitive(l: LabelValue | null): string {
   if (l == null) {
     return "null";
   }
@@ -28,14 +28,23 @@ function getLabelValuePrimitive(l: LabelValue | null) {
   if (l.type === "int") {
     return String(l.value);
   }
+  if (l.type === "time") {
+    return l.value;
+  }
+  if (l.type === "duration") {
+    return `${l.value}s`;
+  }
+  if (l.type === "list") {
+    return `[${l.value.map((v) => getLabelValuePrimitive(v)).join(", ")}]`;
+  }
   return "null";
 }
 type BindingInfo = EvaluationRes["situations"][number];
 
 export function columnsForBinding(
   binding: Binding,
-  objectIds: string[],
-  eventIds: string[],
+  objectIds: Record<number, string>,
+  eventIds: Record<number, string>,
   showElementInfo: (
     elInfo:
       | { req: { id: string } | { index: number }; type: "object" | "event" }
@@ -139,11 +148,24 @@ export function columnsForBinding(
                   : undefined;
               return (
                 <div className="flex items-center gap-x-2 w-[7.66rem]">
-                  {v === undefined && (
+                  {r === null && (
                     <div className="h-4 w-full flex items-center gap-x-2">
                       <Checkbox disabled title="Satisfied" />
                     </div>
                   )}
+                  {r !== null && v === undefined && (
+                    <div
+                      className="h-4 w-full flex items-center gap-x-2 pr-1 text-xs"
+                      title={JSON.stringify(r)}
+                    >
+                      <Checkbox disabled checked title="Violated" />
+                      {r === "BindingCELFailed"
+                        ? "CEL error"
+                        : typeof r === "string"
+                        ? r
+                        : Object.keys(r)[0]}
+                    </div>
+                  )}
                   {v !== undefined && (
                     <div className="h-4 w-full flex items-center gap-x-2 pr-1">
                       <Checkbox disabled checked title="Violated" />
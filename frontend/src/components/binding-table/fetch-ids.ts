@@ -0,0 +1,50 @@
+import type { BackendProvider } from "@/BackendProviderContext";
+import type { IDKind } from "@/types/generated/IDKind";
+
+/** Maximal number of IDs returned by one `ocel/search-ids` request (see backend) */
+const MAX_IDS_PER_REQUEST = 10_000;
+/** Indices closer than this are fetched with one request (including the IDs in between) */
+const MAX_INDEX_GAP = 1_000;
+
+/**
+ * Fetch the IDs of the events/objects with the given indices using the `ocel/search-ids` endpoint
+ *
+ * Nearby indices are grouped into index ranges, so that only few requests are needed.
+ */
+export async function fetchIDsByIndex(
+  backend: BackendProvider,
+  kind: IDKind,
+  indices: Iterable<number>,
+): Promise<Record<number, string>> {
+  const ranges: [number, number][] = [];
+  for (const index of [...new Set(indices)].sort((a, b) => a - b)) {
+    const last = ranges[ranges.length - 1];
+    if (
+      last !== undefined &&
+      index - last[1] < MAX_INDEX_GAP &&
+      index - last[0] < MAX_IDS_PER_REQUEST
+    ) {
+      last[1] = index + 1;
+    } else {
+      ranges.push([index, index + 1]);
+    }
+  }
+  const ids: Record<number, string> = {};
+  await Promise.all(
+    ranges.map(async ([fromIndex, toIndex]) => {
+      const res = await backend["ocel/search-ids"]({
+        kind,
+        prefix: null,
+        ocelType: null,
+        fromIndex,
+        toIndex,
+        offset: 0,
+        limit: toIndex - fromIndex,
+      });
+      for (const { index, id } of res.ids) {
+        ids[index] = id;
+      }
+    }),
+  );
+  return ids;
+}
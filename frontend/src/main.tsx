@@ -1,18 +1,32 @@
 import "./index.css";
-import React from "react";
+import React, { useMemo, useState } from "react";
 import ReactDOM from "react-dom/client";
 import { Toaster } from "react-hot-toast";
 import {
   BackendProviderContext,
   DEFAULT_BACKEND_URL,
   getAPIServerBackendProvider,
+  OcelSessionContext,
 } from "./BackendProviderContext.ts";
 import { MainRouterProvider } from "./router.tsx";
 
+function Root() {
+  const [session, setSession] = useState<string>();
+  const backend = useMemo(
+    () => getAPIServerBackendProvider(DEFAULT_BACKEND_URL, session),
+    [session],
+  );
+  return (
+    <OcelSessionContext.Provider value={{ session, setSession }}>
+      <BackendProviderContext.Provider value={backend}>
+        <MainRouterProvider />
+      </BackendProviderContext.Provider>
+    </OcelSessionContext.Provider>
+  );
+}
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
   <React.StrictMode>
-    <BackendProviderContext.Provider value={getAPIServerBackendProvider(DEFAULT_BACKEND_URL)}>
-      <MainRouterProvider />
-    </BackendProviderContext.Provider>
+    <Root />
   </React.StrictMode>,
 );
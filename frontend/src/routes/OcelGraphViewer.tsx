@@ -337,9 +337,34 @@ function GraphOptions({
     maxDistance: 2,
     relsSizeIgnoreThreshold: 10,
     rootIsObject: initialGrapOptions?.type !== "event",
-    root: initialGrapOptions?.id ?? ocelInfo.object_ids[0],
+    root: initialGrapOptions?.id ?? "",
     spanningTree: false,
   });
+  const [idSuggestions, setIdSuggestions] = useState<string[]>([]);
+  const defaultRootSet = useRef(initialGrapOptions?.id !== undefined);
+
+  useEffect(() => {
+    void backend["ocel/search-ids"]({
+      kind: options.rootIsObject ? "object" : "event",
+      prefix: options.root,
+      ocelType: null,
+      fromIndex: null,
+      toIndex: null,
+      offset: 0,
+      limit: 100,
+    })
+      .then((res) => {
+        setIdSuggestions(res.ids.map(({ id }) => id));
+        // Default to the first ID as root
+        if (!defaultRootSet.current && res.ids.length > 0) {
+          defaultRootSet.current = true;
+          setOptions((o) =>
+            o.root === "" ? { ...o, root: res.ids[0].id } : o,
+          );
+        }
+      })
+      .catch(() => setIdSuggestions([]));
+  }, [options.root, options.rootIsObject, ocelInfo]);
 
   useEffect(() => {
     if (
@@ -359,18 +384,13 @@ function GraphOptions({
       <div className="flex flex-col gap-y-2 mb-4">
         <div className="flex gap-x-1 items-center">
           <Label className="w-[12ch]">Root ID</Label>
-          <datalist id="object-ids">
-            {ocelInfo.object_ids.slice(0, 100).map((id) => (
-              <option key={id} value={id} />
-            ))}
-          </datalist>
-          <datalist id="event-ids">
-            {ocelInfo.event_ids.slice(0, 100).map((id) => (
+          <datalist id="root-ids">
+            {idSuggestions.map((id) => (
               <option key={id} value={id} />
             ))}
           </datalist>
           <Input
-            list={options.rootIsObject ? "object-ids" : "event-ids"}
+            list="root-ids"
             className="max-w-[24ch]"
             placeholder="Root Object/Event ID"
             type="text"
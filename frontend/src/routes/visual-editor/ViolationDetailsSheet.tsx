@@ -1,6 +1,7 @@
 import { BackendProviderContext } from "@/BackendProviderContext";
 import AlertHelper from "@/components/AlertHelper";
 import { columnsForBinding } from "@/components/binding-table/columns";
+import { fetchIDsByIndex } from "@/components/binding-table/fetch-ids";
 import type PaginatedBindingTable from "@/components/binding-table/PaginatedBindingTable";
 import Spinner from "@/components/Spinner";
 import { Button } from "@/components/ui/button";
@@ -15,7 +16,7 @@ import {
 import { Switch } from "@/components/ui/switch";
 import type { BindingBoxTreeNode } from "@/types/generated/BindingBoxTreeNode";
 import { TableExportOptions } from "@/types/generated/TableExportOptions";
-import { Suspense, lazy, memo, useContext, useMemo, useState } from "react";
+import { Suspense, lazy, memo, useContext, useEffect, useMemo, useState } from "react";
 import toast from "react-hot-toast";
 import { TbTableExport } from "react-icons/tb";
 import type { EvaluationRes, EvaluationResPerNodes } from "./helper/types";
@@ -68,6 +69,48 @@ const ViolationDetailsSheet = memo(function ViolationDetailsSheet({
     );
   }, [appliedCutoff, violationsPerNode, nodeID]);
 
+  // IDs are not included in the evaluation result by default, so only the IDs of the shown bindings are fetched
+  const [fetchedIds, setFetchedIds] = useState<{
+    objectIds: Record<number, string>;
+    eventIds: Record<number, string>;
+  }>({ objectIds: {}, eventIds: {} });
+  useEffect(() => {
+    if (
+      violationResPerNodes.objectIds.length > 0 ||
+      violationResPerNodes.eventIds.length > 0
+    ) {
+      return;
+    }
+    let cancelled = false;
+    void Promise.all([
+      fetchIDsByIndex(
+        backend,
+        "object",
+        items.flatMap(([b]) => Object.values(b.objectMap)),
+      ),
+      fetchIDsByIndex(
+        backend,
+        "event",
+        items.flatMap(([b]) => Object.values(b.eventMap)),
+      ),
+    ])
+      .then(([objectIds, eventIds]) => {
+        if (!cancelled) {
+          setFetchedIds({ objectIds, eventIds });
+        }
+      })
+      .catch((e) => {
+        console.error(e);
+        toast.error("Failed to load event/object IDs");
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [items, backend, violationResPerNodes]);
+  const idsIncluded =
+    violationResPerNodes.objectIds.length > 0 ||
+    violationResPerNodes.eventIds.length > 0;
+
   const numBindings = violationsPerNode?.evalRes[nodeID].situationCount ?? 0;
   const numViolations =
     violationsPerNode?.evalRes[nodeID].situationViolatedCount ?? 0;
@@ -76,8 +119,8 @@ const ViolationDetailsSheet = memo(function ViolationDetailsSheet({
     if (items.length >= 1) {
       return columnsForBinding(
         items[0][0],
-        violationResPerNodes.objectIds,
-        violationResPerNodes.eventIds,
+        idsIncluded ? violationResPerNodes.objectIds : fetchedIds.objectIds,
+        idsIncluded ? violationResPerNodes.eventIds : fetchedIds.eventIds,
         showElementInfo,
         node,
         hasConstraints,
@@ -85,7 +128,7 @@ const ViolationDetailsSheet = memo(function ViolationDetailsSheet({
     } else {
       return [];
     }
-  }, [violationResPerNodes, node]);
+  }, [violationResPerNodes, node, fetchedIds, idsIncluded]);
 
   return (
     <Sheet
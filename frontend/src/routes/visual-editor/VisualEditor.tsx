@@ -75,8 +75,29 @@ import {
   type GateNodeData,
 } from "./helper/types";
 import { DatabaseType } from "@/types/generated/DatabaseType";
+import type { TranslationIssue } from "@/types/generated/TranslationIssue";
 import { error } from "console";
 
+function showTranslationIssues(language: string, issues: TranslationIssue[]) {
+  if (issues.length === 0) {
+    return;
+  }
+  toast.error(
+    <div>
+      {language} query is not equivalent to the constraint:
+      <ul className="list-disc pl-4 text-xs">
+        {issues.map((issue, i) => (
+          <li key={i}>
+            {issue.kind} <span className="font-mono">{issue.construct}</span>{" "}
+            (node {issue.nodeIndex}): {issue.reason}
+          </li>
+        ))}
+      </ul>
+    </div>,
+    { position: "bottom-center", duration: 10000 },
+  );
+}
+
 function isEditorElementTarget(el: HTMLElement | EventTarget | null) {
   return (
     el === document.body ||
@@ -845,16 +866,16 @@ export default function VisualEditor(props: VisualEditorProps) {
           </Button>
           <Button
                  variant="outline"
-                 title="Translate to SQL"
+                 title="Translate to SQL (Shift: DuckDB, Alt: PostgreSQL)"
                  className="bg-white relative"
                  onClick={(ev) => {
                   const subTrees = evaluateConstraints(
                     instance.getNodes(),
                     instance.getEdges(),
                   );
-                  const database_used: DatabaseType = ev.shiftKey ? "DuckDB" : "SQLite";
+                  const database_used: DatabaseType = ev.altKey ? "Postgres" : ev.shiftKey ? "DuckDB" : "SQLite";
                   for (const tree of subTrees){
-                  backend["translate-to-sql"](tree.tree, database_used).then((x)=> {console.log(x)}).catch((x)=> {console.error(x)})
+                  backend["translate-to-sql"](tree.tree, database_used).then((x)=> {console.log(x.query); showTranslationIssues("SQL", x.issues); }).catch((x)=> {console.error(x); toast.error("Failed to translate to SQL", { position: "bottom-center" }); })
                   }
                   }}
                 >
@@ -877,8 +898,14 @@ export default function VisualEditor(props: VisualEditorProps) {
 
                       for (const tree of subTrees) {
                         backend["translate-to-cypher"](tree.tree)
-                          .then((x) => { console.log(x); })
-                          .catch((x) => { console.error(x); });
+                          .then((x) => {
+                            console.log(x.query);
+                            showTranslationIssues("Cypher", x.issues);
+                          })
+                          .catch((x) => {
+                            console.error(x);
+                            toast.error("Failed to translate to Cypher", { position: "bottom-center" });
+                          });
                       }
                     }}
                   >
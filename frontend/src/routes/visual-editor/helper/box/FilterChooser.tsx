@@ -221,6 +221,10 @@ export default function FilterChooser({
                       label: "BasicCEL: Basic CEL Script",
                       value: "BasicFilterCEL",
                     },
+                    {
+                      label: "BindCEL: Bind Variable from CEL Script",
+                      value: "BindingCEL",
+                    },
                     ...(alertState.type !== "filter" ||
                     alertState.mode !== "edit" ||
                     alertState.wasSizeFilter
@@ -313,6 +317,15 @@ export default function FilterChooser({
                           cel: "true",
                         },
                       });
+                    } else if (val === "BindingCEL") {
+                      setAlertState({
+                        ...alertState,
+                        value: {
+                          type: "BindingCEL",
+                          variable: { Object: 0 },
+                          cel: "[]",
+                        },
+                      });
                     } else if (val === "NumChilds") {
                       setAlertState({
                         ...alertState,
@@ -529,6 +542,7 @@ export default function FilterChooser({
                           "TimeBetweenEvents",
                           "NotEqual",
                           "BasicFilterCEL",
+                          "BindingCEL",
                           "ObjectAttributeValueFilter",
                           "EventAttributeValueFilter",
                         ].includes(alertState.value.type)
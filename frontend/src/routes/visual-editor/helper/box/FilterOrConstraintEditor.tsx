@@ -190,6 +190,46 @@ export default function FilterOrConstraintEditor<
           </Suspense>
         </>
       );
+    case "BindingCEL":
+      return (
+        <>
+          <ObjectOrEventVarSelector
+            objectVars={availableObjectVars}
+            eventVars={availableEventVars}
+            value={
+              "Event" in value.variable
+                ? { type: "event", value: value.variable.Event }
+                : { type: "object", value: value.variable.Object }
+            }
+            onChange={(v) => {
+              if (v !== undefined) {
+                value.variable =
+                  v.type === "event" ? { Event: v.value } : { Object: v.value };
+                updateValue({ ...value });
+              }
+            }}
+          />
+          <Suspense
+            fallback={
+              <div>
+                Loading editor... <Spinner />
+              </div>
+            }
+          >
+            <CELEditor
+              key="binding"
+              cel={value.cel}
+              onChange={(newCel) => {
+                value.cel = newCel ?? "[]";
+                updateValue({ ...value });
+              }}
+              availableEventVars={availableEventVars}
+              availableObjectVars={availableObjectVars}
+              nodeID={nodeID}
+            />
+          </Suspense>
+        </>
+      );
     case "AdvancedCEL":
       return (
         <>
@@ -860,6 +900,22 @@ export function FilterOrConstraintDisplay<
           </pre>
         </div>
       );
+    case "BindingCEL":
+      return (
+        <div className="flex items-center gap-x-1 font-normal text-sm">
+          <EvOrObVarName varName={value.variable} /> ∈
+          <pre
+            className={clsx(
+              "text-[0.5rem] overflow-ellipsis overflow-hidden leading-tight font-semibold",
+              !(compact ?? false) && " break-all whitespace-normal",
+              compact === true && "whitespace-nowrap max-w-[5rem]",
+            )}
+            title={value.cel}
+          >
+            {value.cel}
+          </pre>
+        </div>
+      );
     case "NumChilds":
       return (
         <div className="flex items-center gap-x-1 font-normal text-sm whitespace-nowrap">
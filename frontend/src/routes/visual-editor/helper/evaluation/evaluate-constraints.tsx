@@ -382,6 +382,8 @@ function predicateToLaTeX<T extends Filter | SizeFilter | Constraint>(
       return String.raw`\mathrm{BasicCEL}(\mintinline{js}|${value.cel}|)`;
     case "AdvancedCEL":
       return String.raw`\mathrm{AdvCEL}(\mintinline{js}|${value.cel}|)`;
+    case "BindingCEL":
+      return String.raw`\mathrm{BindCEL}(\mintinline{js}|${value.cel}|)`;
     case "NotEqual":
     default:
       console.log({ value });
@@ -39,11 +39,53 @@ export type DiscoverConstraintsRequest = {
   eventuallyFollowsConstraints?: {
     objectTypes: string[];
     coverFraction: number;
+    maxO2oPathLength?: number;
   };
   orConstraints?: {
     objectTypes: string[];
     eventTypes: string[];
     coverFraction: number;
+    countCoverMultipliers?: number[];
+  };
+  combinationConstraints?: {
+    objectTypes: string[];
+    eventTypes: string[];
+    coverFraction: number;
+    maxCandidates?: number;
+    search?: {
+      maxSubtrees?: number;
+      maxDepth?: number;
+      beamWidth?: number;
+      minCoverage?: number;
+      maxCoverage?: number;
+      minCoverageGain?: number;
+    };
+  };
+  attributeConstraints?: {
+    objectTypes: string[];
+    eventTypes: string[];
+    coverFraction: number;
+    conditionedCountConstraints?: boolean;
+  };
+  negativeConstraints?: {
+    objectTypes: string[];
+    eventTypes: string[];
+    coverFraction: number;
+  };
+  ranking?: {
+    rankBy?: "none" | "support" | "confidence" | "coverage";
+    deduplicate?: boolean;
+    maxRedundancy?: number;
+    minConfidence?: number | null;
+    maxConstraints?: number | null;
+  };
+  sampling?: {
+    minNumInstances?: number;
+    sampleFraction?: number;
+    seed?: number;
+    strategy?: "random" | "stratifiedByTime";
+    numTimeStrata?: number;
+    minNumObjectEvents?: number;
   };
 };
 
@@ -51,10 +93,80 @@ export type DiscoverConstraintsRequestWrapper = DiscoverConstraintsRequest & {
   countConstraints: { enabled: boolean };
   eventuallyFollowsConstraints: { enabled: boolean };
   orConstraints: { enabled: boolean };
+  combinationConstraints: { enabled: boolean };
+  attributeConstraints: { enabled: boolean };
+  negativeConstraints: { enabled: boolean };
+};
+
+export type DiscoveredConstraintMetrics = {
+  support: number;
+  numSituations: number;
+  confidence: number;
+  violationRate: number;
+  coverage: number;
+  redundancy: number;
+  bindingsSkipped: boolean;
 };
 
 export type DiscoverConstraintsResponse = {
-  constraints: [string, BindingBoxTree][];
+  constraints: [string, BindingBoxTree, DiscoveredConstraintMetrics | null][];
+};
+
+export type DiscoverDriftRequest = {
+  countConstraints?: DiscoverConstraintsRequest["countConstraints"];
+  eventuallyFollowsConstraints?: DiscoverConstraintsRequest["eventuallyFollowsConstraints"];
+  windowing?:
+    | { type: "fixed"; numWindows: number }
+    | { type: "adaptive"; numWindows: number };
+  minInstancesPerWindow?: number;
+  minRelativeChange?: number;
+  sampling?: DiscoverConstraintsRequest["sampling"];
+};
+
+export type DriftTimeWindow = { from: string; to: string; numEvents: number };
+
+export type DriftingConstraint = {
+  name: string;
+  kind: "count" | "eventuallyFollows";
+  numInstances: number[];
+  bounds: ({ min: number | null; max: number | null } | null)[];
+  changeWindows: number[];
+  latestTree: BindingBoxTree | null;
+};
+
+export type DiscoverDriftResponse = {
+  windows: DriftTimeWindow[];
+  constraints: DriftingConstraint[];
+};
+
+export type DiscoverLabeledConstraintsRequest = {
+  goodIds: string[];
+  badIds: string[];
+  coverFraction?: number;
+  countConstraints?: boolean;
+  eventuallyFollowsConstraints?: boolean;
+  attributeConstraints?: boolean;
+  orConstraints?: boolean;
+  maxCombinationCandidates?: number;
+  maxResults?: number;
+  sampling?: DiscoverConstraintsRequest["sampling"];
+};
+
+export type LabeledConstraintCandidate = {
+  name: string;
+  tree: BindingBoxTree;
+  precision: number;
+  recall: number;
+  f1Score: number;
+  truePositives: number;
+  falsePositives: number;
+  falseNegatives: number;
+  trueNegatives: number;
+};
+
+export type DiscoverLabeledConstraintsResponse = {
+  candidates: LabeledConstraintCandidate[];
+  unknownIds: string[];
 };
 
 export type ConstraintInfo = { name: string; description: string };
@@ -97,6 +97,40 @@ export default function AutoDiscoveryButton({
             coverFraction: 0.85,
             enabled: true,
           },
+          combinationConstraints: {
+            objectTypes:
+              ocelInfo.object_types.length > 0
+                ? [ocelInfo.object_types[0].name]
+                : [],
+            eventTypes: [],
+            coverFraction: 0.5,
+            enabled: false,
+          },
+          attributeConstraints: {
+            objectTypes:
+              ocelInfo.object_types.length > 0
+                ? [ocelInfo.object_types[0].name]
+                : [],
+            eventTypes:
+              ocelInfo.event_types.length > 0
+                ? [ocelInfo.event_types[0].name]
+                : [],
+            coverFraction: 0.95,
+            conditionedCountConstraints: false,
+            enabled: false,
+          },
+          negativeConstraints: {
+            objectTypes:
+              ocelInfo.object_types.length > 0
+                ? [ocelInfo.object_types[0].name]
+                : [],
+            eventTypes:
+              ocelInfo.event_types.length > 0
+                ? [ocelInfo.event_types[0].name]
+                : [],
+            coverFraction: 0.95,
+            enabled: false,
+          },
         } satisfies DiscoverConstraintsRequestWrapper as DiscoverConstraintsRequestWrapper
       }
       content={({ data, setData }) => {
@@ -514,6 +548,468 @@ export default function AutoDiscoveryButton({
                   </div>
                 </AccordionContent>
               </AccordionItem>
+
+              <AccordionItem value="combinationConstraints">
+                <AccordionTrigger>
+                  <h3 className="text-lg text-gray-900 flex gap-x-2 items-center">
+                    Combined AND/OR Constraints
+                    <Switch
+                      className="scale-125 ml-2"
+                      checked={data.combinationConstraints.enabled}
+                      onClick={(ev) => {
+                        ev.preventDefault();
+                        const newData = { ...data };
+                        newData.combinationConstraints.enabled =
+                          !newData.combinationConstraints.enabled;
+
+                        if (newData.combinationConstraints.enabled) {
+                          const d =
+                            ev.currentTarget.parentElement?.parentElement;
+                          if (d !== null && d?.dataset.state === "closed") {
+                            d.click();
+                          }
+                        }
+                        setData(newData);
+                      }}
+                    />
+                  </h3>
+                </AccordionTrigger>
+                <AccordionContent>
+                  <div
+                    className={clsx(
+                      "ml-2 pl-2 border-l-2",
+                      !data.combinationConstraints.enabled && "text-gray-400",
+                    )}
+                  >
+                    <Label>Cover Fraction</Label>
+                    <Input
+                      disabled={!data.combinationConstraints.enabled}
+                      type="number"
+                      min={0.0}
+                      step={0.05}
+                      max={1.0}
+                      value={data.combinationConstraints.coverFraction}
+                      onChange={(ev) => {
+                        setData({
+                          ...data,
+                          combinationConstraints: {
+                            ...data.combinationConstraints,
+                            coverFraction: ev.currentTarget.valueAsNumber,
+                          },
+                        });
+                      }}
+                    />
+                    <Label className="mt-3 mb-1 block">Object Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.combinationConstraints.objectTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.combinationConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.combinationConstraints.objectTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.combinationConstraints.enabled}
+                      options={ocelInfo.object_types
+                        .filter(
+                          (ot) =>
+                            !data.combinationConstraints.objectTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          combinationConstraints: {
+                            ...data.combinationConstraints,
+                            objectTypes: [
+                              ...data.combinationConstraints.objectTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add object type..."}
+                      value={""}
+                    />
+                    <Label className="mt-3 mb-1 block">Event Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.combinationConstraints.eventTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.combinationConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.combinationConstraints.eventTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.combinationConstraints.enabled}
+                      options={ocelInfo.event_types
+                        .filter(
+                          (ot) =>
+                            !data.combinationConstraints.eventTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          combinationConstraints: {
+                            ...data.combinationConstraints,
+                            eventTypes: [
+                              ...data.combinationConstraints.eventTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add event type..."}
+                      value={""}
+                    />
+                  </div>
+                </AccordionContent>
+              </AccordionItem>
+
+              <AccordionItem value="attributeConstraints">
+                <AccordionTrigger>
+                  <h3 className="text-lg text-gray-900 flex gap-x-2 items-center">
+                    Attribute Constraints
+                    <Switch
+                      className="scale-125 ml-2"
+                      checked={data.attributeConstraints.enabled}
+                      onClick={(ev) => {
+                        ev.preventDefault();
+                        const newData = { ...data };
+                        newData.attributeConstraints.enabled =
+                          !newData.attributeConstraints.enabled;
+
+                        if (newData.attributeConstraints.enabled) {
+                          const d =
+                            ev.currentTarget.parentElement?.parentElement;
+                          if (d !== null && d?.dataset.state === "closed") {
+                            d.click();
+                          }
+                        }
+                        setData(newData);
+                      }}
+                    />
+                  </h3>
+                </AccordionTrigger>
+                <AccordionContent>
+                  <div
+                    className={clsx(
+                      "ml-2 pl-2 border-l-2",
+                      !data.attributeConstraints.enabled && "text-gray-400",
+                    )}
+                  >
+                    <Label>Cover Fraction</Label>
+                    <Input
+                      disabled={!data.attributeConstraints.enabled}
+                      type="number"
+                      min={0.0}
+                      step={0.05}
+                      max={1.0}
+                      value={data.attributeConstraints.coverFraction}
+                      onChange={(ev) => {
+                        setData({
+                          ...data,
+                          attributeConstraints: {
+                            ...data.attributeConstraints,
+                            coverFraction: ev.currentTarget.valueAsNumber,
+                          },
+                        });
+                      }}
+                    />
+                    <Label className="mt-3 flex gap-x-2 items-center">
+                      <Switch
+                        disabled={!data.attributeConstraints.enabled}
+                        checked={
+                          data.attributeConstraints.conditionedCountConstraints
+                        }
+                        onCheckedChange={(checked) => {
+                          setData({
+                            ...data,
+                            attributeConstraints: {
+                              ...data.attributeConstraints,
+                              conditionedCountConstraints: checked,
+                            },
+                          });
+                        }}
+                      />
+                      Attribute-Conditioned Count Constraints
+                    </Label>
+                    <Label className="mt-3 mb-1 block">Object Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.attributeConstraints.objectTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.attributeConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.attributeConstraints.objectTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.attributeConstraints.enabled}
+                      options={ocelInfo.object_types
+                        .filter(
+                          (ot) =>
+                            !data.attributeConstraints.objectTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          attributeConstraints: {
+                            ...data.attributeConstraints,
+                            objectTypes: [
+                              ...data.attributeConstraints.objectTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add object type..."}
+                      value={""}
+                    />
+                    <Label className="mt-3 mb-1 block">Event Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.attributeConstraints.eventTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.attributeConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.attributeConstraints.eventTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.attributeConstraints.enabled}
+                      options={ocelInfo.event_types
+                        .filter(
+                          (ot) =>
+                            !data.attributeConstraints.eventTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          attributeConstraints: {
+                            ...data.attributeConstraints,
+                            eventTypes: [
+                              ...data.attributeConstraints.eventTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add event type..."}
+                      value={""}
+                    />
+                  </div>
+                </AccordionContent>
+              </AccordionItem>
+
+              <AccordionItem value="negativeConstraints">
+                <AccordionTrigger>
+                  <h3 className="text-lg text-gray-900 flex gap-x-2 items-center">
+                    Negative Constraints
+                    <Switch
+                      className="scale-125 ml-2"
+                      checked={data.negativeConstraints.enabled}
+                      onClick={(ev) => {
+                        ev.preventDefault();
+                        const newData = { ...data };
+                        newData.negativeConstraints.enabled =
+                          !newData.negativeConstraints.enabled;
+
+                        if (newData.negativeConstraints.enabled) {
+                          const d =
+                            ev.currentTarget.parentElement?.parentElement;
+                          if (d !== null && d?.dataset.state === "closed") {
+                            d.click();
+                          }
+                        }
+                        setData(newData);
+                      }}
+                    />
+                  </h3>
+                </AccordionTrigger>
+                <AccordionContent>
+                  <div
+                    className={clsx(
+                      "ml-2 pl-2 border-l-2",
+                      !data.negativeConstraints.enabled && "text-gray-400",
+                    )}
+                  >
+                    <Label>Cover Fraction</Label>
+                    <Input
+                      disabled={!data.negativeConstraints.enabled}
+                      type="number"
+                      min={0.0}
+                      step={0.05}
+                      max={1.0}
+                      value={data.negativeConstraints.coverFraction}
+                      onChange={(ev) => {
+                        setData({
+                          ...data,
+                          negativeConstraints: {
+                            ...data.negativeConstraints,
+                            coverFraction: ev.currentTarget.valueAsNumber,
+                          },
+                        });
+                      }}
+                    />
+                    <Label className="mt-3 mb-1 block">Object Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.negativeConstraints.objectTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.negativeConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.negativeConstraints.objectTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.negativeConstraints.enabled}
+                      options={ocelInfo.object_types
+                        .filter(
+                          (ot) =>
+                            !data.negativeConstraints.objectTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          negativeConstraints: {
+                            ...data.negativeConstraints,
+                            objectTypes: [
+                              ...data.negativeConstraints.objectTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add object type..."}
+                      value={""}
+                    />
+                    <Label className="mt-3 mb-1 block">Event Types</Label>
+                    <ul className="flex flex-col mb-1 list-disc ml-6 text-base">
+                      {data.negativeConstraints.eventTypes.map((ot, i) => (
+                        <li key={i}>
+                          <div className="flex gap-x-2 items-center">
+                            {ot}
+                            <button
+                              disabled={!data.negativeConstraints.enabled}
+                              className="enabled:hover:text-red-500"
+                              onClick={() => {
+                                const newData = { ...data };
+                                data.negativeConstraints.eventTypes.splice(i, 1);
+                                setData(newData);
+                              }}
+                            >
+                              <LuDelete className="w-4 h-4" />
+                            </button>
+                          </div>
+                        </li>
+                      ))}
+                    </ul>
+                    <Combobox
+                      disabled={!data.negativeConstraints.enabled}
+                      options={ocelInfo.event_types
+                        .filter(
+                          (ot) =>
+                            !data.negativeConstraints.eventTypes.includes(ot.name),
+                        )
+                        .map((ot) => ({
+                          value: ot.name,
+                          label: ot.name,
+                        }))}
+                      onChange={(value) => {
+                        setData({
+                          ...data,
+                          negativeConstraints: {
+                            ...data.negativeConstraints,
+                            eventTypes: [
+                              ...data.negativeConstraints.eventTypes,
+                              value,
+                            ],
+                          },
+                        });
+                      }}
+                      name={"Add event type..."}
+                      value={""}
+                    />
+                  </div>
+                </AccordionContent>
+              </AccordionItem>
             </Accordion>
           </div>
         );
@@ -523,11 +1019,15 @@ export default function AutoDiscoveryButton({
         ev.preventDefault();
         const reqData: DiscoverConstraintsRequest = {
           ...data,
+          ranking: { rankBy: "confidence", deduplicate: true },
         };
         for (const k of [
           "countConstraints",
           "eventuallyFollowsConstraints",
           "orConstraints",
+          "combinationConstraints",
+          "attributeConstraints",
+          "negativeConstraints",
         ] as const) {
           if (!data[k].enabled) {
             reqData[k] = undefined;
@@ -540,10 +1040,21 @@ export default function AutoDiscoveryButton({
               const updatedConstraints = [...constraints];
 
               let index = constraints.length;
-              for (const [name, newConstraint] of json.constraints) {
+              for (const [name, newConstraint, metrics] of json.constraints) {
                 updatedConstraints.push({
                   name,
-                  description: "Automatically Discovered",
+                  description:
+                    metrics === null
+                      ? "Automatically Discovered"
+                      : `Automatically Discovered (support: ${
+                          metrics.support
+                        }, confidence: ${(metrics.confidence * 100).toFixed(
+                          1,
+                        )}%${
+                          metrics.bindingsSkipped
+                            ? ", some bindings skipped"
+                            : ""
+                        })`,
                 });
                 const [ns, es] = bindingBoxTreeToNodes(
                   newConstraint,
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AttributeProfile = { name: string, 
+/**
+ * Value type as declared in the OCEL type (if declared)
+ */
+valueType: string | null, 
+/**
+ * Fraction of events/objects of the type with a (non-null) value for this attribute
+ */
+fillRate: number, numDistinctValues: number, 
+/**
+ * Most frequent values and their number of occurrences
+ */
+topValues: Array<[string, number]>, 
+/**
+ * Minimum, average and maximum of integer or float values (if any)
+ */
+numericSummary: [number, number, number] | null, };
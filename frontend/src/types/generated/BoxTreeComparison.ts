@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NodeComparison } from "./NodeComparison";
+import type { SchemaDifference } from "./SchemaDifference";
+
+export type BoxTreeComparison = { nodes: Array<NodeComparison>, 
+/**
+ * Schema differences, where differences involving types used in the tree come first
+ */
+schemaDifferences: Array<SchemaDifference>, bindingsSkippedA: boolean, bindingsSkippedB: boolean, };
@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DatabaseType = "SQLite" | "DuckDB";
\ No newline at end of file
+export type DatabaseType = "SQLite" | "DuckDB" | "Postgres";
\ No newline at end of file
@@ -1,4 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EvaluationResultWithCount } from "./EvaluationResultWithCount";
 
-export type EvaluateBoxTreeResult = { evaluationResults: Array<EvaluationResultWithCount>, objectIds: Array<string>, eventIds: Array<string>, bindingsSkipped: boolean, };
\ No newline at end of file
+export type EvaluateBoxTreeResult = { evaluationResults: Array<EvaluationResultWithCount>, 
+/**
+ * All object IDs, to resolve the object indices of bindings (empty, unless requested)
+ */
+objectIds: Array<string>, 
+/**
+ * All event IDs, to resolve the event indices of bindings (empty, unless requested)
+ */
+eventIds: Array<string>, bindingsSkipped: boolean, };
@@ -42,4 +42,5 @@ export type Filter =
       at_time: ObjectValueFilterTimepoint;
       value_filter: ValueFilter;
     }
-  | { type: "BasicFilterCEL"; cel: string };
+  | { type: "BasicFilterCEL"; cel: string }
+  | { type: "BindingCEL"; variable: Variable; cel: string };
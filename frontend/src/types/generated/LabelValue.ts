@@ -5,4 +5,7 @@ export type LabelValue =
   | { type: "int"; value: bigint }
   | { type: "float"; value: number }
   | { type: "bool"; value: boolean }
+  | { type: "time"; value: string }
+  | { type: "duration"; value: number }
+  | { type: "list"; value: Array<LabelValue> }
   | { type: "null" };
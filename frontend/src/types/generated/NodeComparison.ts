@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NodeEvaluationCounts } from "./NodeEvaluationCounts";
+
+export type NodeComparison = { nodeIndex: number, a: NodeEvaluationCounts, b: NodeEvaluationCounts, 
+/**
+ * Violation rate on OCEL B minus violation rate on OCEL A (`None` if one of them is undefined)
+ */
+violationRateDelta: number | null, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RelationshipCardinality } from "./RelationshipCardinality";
+import type { TypeProfile } from "./TypeProfile";
+
+export type OCELProfile = { numEvents: number, numObjects: number, eventTypes: Array<TypeProfile>, objectTypes: Array<TypeProfile>, 
+/**
+ * Cardinalities of E2O relationships, per (event type, qualifier, object type)
+ */
+e2oCardinalities: Array<RelationshipCardinality>, 
+/**
+ * Cardinalities of O2O relationships, per (object type, qualifier, object type)
+ */
+o2oCardinalities: Array<RelationshipCardinality>, eventsWithoutObjects: number, objectsWithoutEvents: number, };
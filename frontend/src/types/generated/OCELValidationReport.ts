@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ValidationIssue } from "./ValidationIssue";
+
+export type OCELValidationReport = { 
+/**
+ * Listed issues (at most 1000)
+ */
+issues: Array<ValidationIssue>, numDanglingReferences: number, numDuplicateIds: number, numUndefinedTypes: number, numAttributeTypeMismatches: number, numNonMonotonicTimestamps: number, 
+/**
+ * Set if the issues were repaired
+ */
+repaired: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Number of related objects per source event/object for one (source type, qualifier, target type) combination
+ *
+ * Sources without any such relationship are counted with cardinality 0
+ */
+export type RelationshipCardinality = { fromType: string, qualifier: string, toType: string, min: number, avg: number, max: number, 
+/**
+ * (Cardinality, Number of source events/objects with this cardinality), sorted by cardinality
+ */
+histogram: Array<[number, number]>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComparedOCEL } from "./ComparedOCEL";
+
+/**
+ * A difference between the event/object types or attributes of two OCELs
+ *
+ * `used_in_tree` is set if the (event/object) type is bound by a variable of the compared tree,
+ * i.e., the difference may explain missing matches
+ */
+export type SchemaDifference = { "type": "eventTypeMissing", eventType: string, missingIn: ComparedOCEL, usedInTree: boolean, } | { "type": "objectTypeMissing", objectType: string, missingIn: ComparedOCEL, usedInTree: boolean, } | { "type": "attributeMissing", ocelType: string, isEventType: boolean, attribute: string, missingIn: ComparedOCEL, usedInTree: boolean, } | { "type": "attributeValueTypeDiffers", ocelType: string, isEventType: boolean, attribute: string, valueTypeA: string, valueTypeB: string, usedInTree: boolean, };
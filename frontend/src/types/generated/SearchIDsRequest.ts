@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IDKind } from "./IDKind";
+
+/**
+ * Search for event or object IDs
+ *
+ * All filters are optional and combined conjunctively.
+ * Matching IDs are returned in index order, paged by `offset` and `limit`.
+ */
+export type SearchIDsRequest = { kind: IDKind, 
+/**
+ * Only include IDs starting with this prefix
+ */
+prefix: string | null, 
+/**
+ * Only include events/objects of this type
+ */
+ocelType: string | null, 
+/**
+ * Only include events/objects with an index of at least `from_index`
+ */
+fromIndex: number | null, 
+/**
+ * Only include events/objects with an index less than `to_index`
+ */
+toIndex: number | null, offset: number, 
+/**
+ * Maximal number of returned IDs (capped at 10000)
+ */
+limit: number, };
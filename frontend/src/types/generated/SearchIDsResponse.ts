@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IDWithIndex } from "./IDWithIndex";
+
+export type SearchIDsResponse = { ids: Array<IDWithIndex>, 
+/**
+ * Total number of matching IDs (across all pages)
+ */
+total: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TranslationIssue } from "./TranslationIssue";
+
+/**
+ * Translated query together with all detected issues (empty if the query is equivalent)
+ */
+export type TranslatedQuery = { query: string, issues: Array<TranslationIssue>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TranslationIssueKind } from "./TranslationIssueKind";
+
+export type TranslationIssue = { 
+/**
+ * Index of the node in the tree
+ */
+nodeIndex: number, kind: TranslationIssueKind, 
+/**
+ * Name of the filter/constraint/variable, e.g., `O2E` or `ev_0`
+ */
+construct: string, reason: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeProfile } from "./AttributeProfile";
+
+export type TypeProfile = { name: string, count: number, 
+/**
+ * Earliest and latest event (of this type, or involving an object of this type)
+ */
+timeRange: [string, string] | null, attributes: Array<AttributeProfile>, };
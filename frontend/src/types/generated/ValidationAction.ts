@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What to do if an OCEL has data-quality issues when loading it
+ */
+export type ValidationAction = "keep" | "repair" | "reject";
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ValidationIssue = { "type": "danglingE2O", eventId: string, objectId: string, qualifier: string, } | { "type": "danglingO2O", objectId: string, targetObjectId: string, qualifier: string, } | { "type": "duplicateEventId", id: string, count: number, } | { "type": "duplicateObjectId", id: string, count: number, } | { "type": "undefinedEventType", eventId: string, eventType: string, } | { "type": "undefinedObjectType", objectId: string, objectType: string, } | { "type": "attributeTypeMismatch", id: string, isEvent: boolean, ocelType: string, attribute: string, declaredType: string, value: string, } | { "type": "nonMonotonicAttributeTimestamps", objectId: string, attribute: string, };
@@ -9,4 +9,7 @@ export type ViolationReason =
   | "BothChildrenOfANDUnsatisfied"
   | "ChildrenOfNOTSatisfied"
   | "ChildNotSatisfied"
-  | { ConstraintNotSatisfied: number };
+  | { ConstraintNotSatisfied: number }
+  | "UnknownChildSet"
+  | "BindingCELFailed"
+  | "LabelAlreadyDefined";
@@ -1,3 +1,4 @@
+import type { OCELMemoryUsage } from "./generated/OCELMemoryUsage";
 export type OCELTypeAttribute = { name: string; type: string };
 export type OCELType = { name: string; attributes: OCELTypeAttribute[] };
 export type OCELInfo = {
@@ -8,6 +9,15 @@ export type OCELInfo = {
   object_ids: string[];
   event_ids: string[];
 };
+/** An OCEL loaded in a named session of the web server */
+export type OCELSessionInfo = {
+  name: string;
+  source: string;
+  selected: boolean;
+  numEvents: number;
+  numObjects: number;
+  memoryUsage: OCELMemoryUsage;
+};
 export type OCELAttributeValue = string | number | boolean | null;
 export type OCELObjectAttribute = {
   name: string;
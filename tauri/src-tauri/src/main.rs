@@ -10,21 +10,25 @@ use std::{
 
 use ocpq_shared::{
     binding_box::{
-        evaluate_box_tree, filter_ocel_box_tree, BindingBoxTree, CheckWithBoxTreeRequest, EvaluateBoxTreeResult, ExportFormat, FilterExportWithBoxTreeRequest
-    }, discovery::{
-        auto_discover_constraints_with_options, AutoDiscoverConstraintsRequest,
-        AutoDiscoverConstraintsResponse,
+        compare::{compare_box_tree, BoxTreeComparison}, evaluate_box_tree, filter_ocel_box_tree, BindingBoxTree, CheckWithBoxTreeRequest, EvaluateBoxTreeResult, ExportFormat, FilterExportWithBoxTreeRequest
+    }, cel::custom_functions::load_default_cel_plugins, discovery::{
+        auto_discover_constraints_with_options,
+        drift::{discover_constraint_drift, DriftDiscoveryRequest, DriftDiscoveryResponse},
+        labeled_discovery::{
+            discover_constraints_from_labels, LabeledDiscoveryRequest, LabeledDiscoveryResponse,
+        },
+        AutoDiscoverConstraintsRequest, AutoDiscoverConstraintsResponse,
     }, get_event_info, get_object_info, hpc_backend::{
         get_job_status, login_on_hpc, start_port_forwarding, submit_hpc_job, Client,
         ConnectionConfig, JobStatus, OCPQJobOptions,
-    }, ocel_graph::{get_ocel_graph, OCELGraph, OCELGraphOptions}, ocel_qualifiers::qualifiers::{get_qualifiers_for_event_types, QualifiersForEventType}, preprocessing::linked_ocel::{link_ocel_info, IndexLinkedOCEL}, table_export::{export_bindings_to_writer, TableExportFormat, TableExportOptions}, translation::{
-        translate_to_sql_shared, DatabaseType, translate_to_cypher_shared
-    }, EventWithIndex, IndexOrID, OCELInfo, ObjectWithIndex,
+    }, ocel_graph::{get_ocel_graph, OCELGraph, OCELGraphOptions}, ocel_profile::{get_ocel_profile, OCELProfile}, ocel_qualifiers::qualifiers::{get_qualifiers_for_event_types, QualifiersForEventType}, preprocessing::{compact::OCELMemoryUsage, linked_ocel::IndexLinkedOCEL, snapshot::load_ocel_with_snapshot_cache, validation::{OCELValidationReport, ValidationAction}}, table_export::{export_bindings_to_writer, TableExportFormat, TableExportOptions}, translation::{
+        translate_to_sql_shared, DatabaseType, translate_to_cypher_shared, issues::TranslatedQuery
+    }, id_search::{search_ids, SearchIDsRequest, SearchIDsResponse}, EventWithIndex, IndexOrID, OCELInfo, ObjectWithIndex,
 
 };
 use process_mining::{
     export_ocel_json_path, export_ocel_sqlite_to_path, export_ocel_xml_path,
-    import_ocel_json_from_path, import_ocel_sqlite_from_path, import_ocel_xml_file,
+    import_ocel_json_from_path, import_ocel_sqlite_from_path, import_ocel_xml_file, OCEL,
 };
 use tauri::{
     async_runtime::{JoinHandle, RwLock}, AppHandle, State
@@ -37,40 +41,90 @@ pub struct AppState {
     client: Arc<RwLock<Option<Client>>>,
     jobs: Arc<RwLock<Vec<(String, u16, JoinHandle<()>)>>>,
     eval_res: Arc<RwLock<Option<EvaluateBoxTreeResult>>>,
+    /// Validation report of the last imported (or rejected) OCEL
+    validation_report: Arc<RwLock<Option<OCELValidationReport>>>,
+}
+
+fn import_ocel_from_path(path: &std::path::Path) -> Result<OCEL, anyhow::Error> {
+    Ok(match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => import_ocel_json_from_path(path)?,
+        Some("xml") => import_ocel_xml_file(path),
+        _ => import_ocel_sqlite_from_path(path)?,
+    })
 }
 
 #[tauri::command(async)]
-async fn import_ocel(path: &str, state: tauri::State<'_, AppState>) -> Result<OCELInfo, String> {
-    let ocel = match path.ends_with(".json") {
-        true => import_ocel_json_from_path(path).map_err(|e| format!("{:?}", e))?,
-        false => match path.ends_with(".xml") {
-            true => import_ocel_xml_file(path),
-            false => import_ocel_sqlite_from_path(path).map_err(|e| format!("{:?}", e))?,
-        },
+async fn import_ocel(
+    path: &str,
+    include_ids: Option<bool>,
+    on_issues: Option<ValidationAction>,
+    state: tauri::State<'_, AppState>,
+) -> Result<OCELInfo, String> {
+    let res = load_ocel_with_snapshot_cache(path, import_ocel_from_path, on_issues.unwrap_or_default())
+        .map_err(|e| format!("{:?}", e))?;
+    let ocel = match res {
+        Ok((ocel, report)) => {
+            *state.validation_report.write().await = Some(report);
+            ocel
+        }
+        Err(report) => {
+            let num_issues = report.num_issues();
+            *state.validation_report.write().await = Some(report);
+            return Err(format!(
+                "OCEL rejected because of {num_issues} data-quality issues"
+            ));
+        }
     };
-    let ocel_info: OCELInfo = (&ocel).into();
+    let ocel_info = OCELInfo::from_ocel(&ocel.ocel, include_ids.unwrap_or(false));
     let mut state_guard = state.ocel.write().await;
-    *state_guard = Some(link_ocel_info(ocel));
+    *state_guard = Some(ocel);
     Ok(ocel_info)
 }
 
 #[tauri::command(async)]
 async fn get_current_ocel_info(
+    include_ids: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Option<OCELInfo>, String> {
     let res: Result<Option<OCELInfo>, String> = match state.ocel.read().await.as_ref() {
-        Some(ocel) => Ok(Some((&ocel.ocel).into())),
+        Some(ocel) => Ok(Some(OCELInfo::from_ocel(
+            &ocel.ocel,
+            include_ids.unwrap_or(false),
+        ))),
         None => Ok(None),
     };
     res
 }
 
+#[tauri::command(async)]
+async fn get_current_ocel_profile(state: State<'_, AppState>) -> Result<OCELProfile, String> {
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(get_ocel_profile(ocel)),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
+
+#[tauri::command(async)]
+async fn get_ocel_memory_usage(state: State<'_, AppState>) -> Result<OCELMemoryUsage, String> {
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(ocel.memory_usage()),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
+
+#[tauri::command(async)]
+async fn get_ocel_validation_report(
+    state: State<'_, AppState>,
+) -> Result<Option<OCELValidationReport>, String> {
+    Ok(state.validation_report.read().await.clone())
+}
+
 #[tauri::command(async)]
 async fn get_event_qualifiers(
     state: State<'_, AppState>,
 ) -> Result<HashMap<String, HashMap<String, QualifiersForEventType>>, String> {
     match state.ocel.read().await.as_ref() {
-        Some(ocel) => Ok(get_qualifiers_for_event_types(&ocel.ocel)),
+        Some(ocel) => Ok(get_qualifiers_for_event_types(ocel)),
         None => Err("No OCEL loaded".to_string()),
     }
 }
@@ -92,7 +146,12 @@ async fn check_with_box_tree(
 ) -> Result<EvaluateBoxTreeResult, String> {
     match state.ocel.read().await.as_ref() {
         Some(ocel) => {
-            let res = evaluate_box_tree(req.tree, ocel, req.measure_performance.unwrap_or(false));
+            let res = evaluate_box_tree(
+                req.tree,
+                ocel,
+                req.measure_performance.unwrap_or(false),
+                req.include_ids.unwrap_or(false),
+            );
             let res_to_ret: EvaluateBoxTreeResult = res.clone_first_few();
             *state.eval_res.write().await = Some(res);
             return Ok(res_to_ret);
@@ -101,6 +160,37 @@ async fn check_with_box_tree(
     }
 }
 
+#[tauri::command(async)]
+async fn search_ocel_ids(
+    req: SearchIDsRequest,
+    state: State<'_, AppState>,
+) -> Result<SearchIDsResponse, String> {
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(search_ids(ocel, &req)),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
+
+/// Compare the evaluation of a tree on the loaded OCEL (A) with its evaluation on the OCEL file at `path` (B)
+#[tauri::command(async)]
+async fn compare_box_tree_with_ocel_file(
+    tree: BindingBoxTree,
+    path: &str,
+    state: State<'_, AppState>,
+) -> Result<BoxTreeComparison, String> {
+    // Data-quality issues of the other OCEL are kept as-is (so it is never rejected)
+    let (other_ocel, _) =
+        load_ocel_with_snapshot_cache(path, import_ocel_from_path, ValidationAction::Keep)
+            .map_err(|e| format!("{:?}", e))?
+            .map_err(|report| {
+                format!("OCEL rejected because of {} data-quality issues", report.num_issues())
+            })?;
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(compare_box_tree(&tree, ocel, &other_ocel)),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
+
 #[tauri::command(async)]
 async fn export_filter_box(
     req: FilterExportWithBoxTreeRequest,
@@ -156,6 +246,28 @@ async fn auto_discover_constraints(
         None => Err("No OCEL loaded".to_string()),
     }
 }
+
+#[tauri::command(async)]
+async fn discover_constraint_drift_tauri(
+    options: DriftDiscoveryRequest,
+    state: State<'_, AppState>,
+) -> Result<DriftDiscoveryResponse, String> {
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(discover_constraint_drift(ocel, options)),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
+
+#[tauri::command(async)]
+async fn discover_constraints_from_labels_tauri(
+    options: LabeledDiscoveryRequest,
+    state: State<'_, AppState>,
+) -> Result<LabeledDiscoveryResponse, String> {
+    match state.ocel.read().await.as_ref() {
+        Some(ocel) => Ok(discover_constraints_from_labels(ocel, options)),
+        None => Err("No OCEL loaded".to_string()),
+    }
+}
 #[tauri::command(async)]
 async fn export_bindings_table(
     node_index: usize,
@@ -293,21 +405,22 @@ async fn get_hpc_job_status_tauri(
 async  fn translate_to_sql_tauri(
     tree: BindingBoxTree,
     database: DatabaseType
-)-> Result<String, String>{
+)-> Result<TranslatedQuery, String>{
     let res = translate_to_sql_shared(tree, database);
-    Ok(res)
+    Ok(res.into())
 }
 
 
 #[tauri::command(async)]
 async  fn translate_to_cypher_tauri(
     tree: BindingBoxTree,
-)-> Result<String, String>{
+)-> Result<TranslatedQuery, String>{
     let res = translate_to_cypher_shared(tree);
-    Ok(res)
+    Ok(res.into())
 }
 
 fn main() {
+    load_default_cel_plugins();
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
@@ -315,15 +428,22 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             import_ocel,
             get_current_ocel_info,
+            get_ocel_memory_usage,
+            get_current_ocel_profile,
+            get_ocel_validation_report,
             get_event_qualifiers,
             get_object_qualifiers,
             export_filter_box,
             check_with_box_tree,
+            compare_box_tree_with_ocel_file,
             auto_discover_constraints,
+            discover_constraint_drift_tauri,
+            discover_constraints_from_labels_tauri,
             export_bindings_table,
             ocel_graph,
             get_event,
             get_object,
+            search_ocel_ids,
             login_to_hpc_tauri,
             start_hpc_job_tauri,
             get_hpc_job_status_tauri,
@@ -13,7 +13,7 @@ import type {
   OCELInfo,
   ObjectTypeQualifiers,
 } from "$/types/ocel";
-import type { DiscoverConstraintsResponse } from "$/routes/visual-editor/helper/types";
+import type { DiscoverConstraintsResponse, DiscoverDriftResponse, DiscoverLabeledConstraintsResponse } from "$/routes/visual-editor/helper/types";
 import { BindingBoxTree } from "$/types/generated/BindingBoxTree";
 import { OCPQJobOptions } from "$/types/generated/OCPQJobOptions";
 import { ConnectionConfig, JobStatus } from "$/types/hpc-backend";
@@ -21,6 +21,7 @@ import { save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
 import * as dialog from "@tauri-apps/plugin-dialog"
 import { DatabaseType } from "$/types/generated/DatabaseType";
+import { TranslatedQuery } from "$/types/generated/TranslatedQuery";
 
 const tauriBackend: BackendProvider = {
   "ocel/info": async () => {
@@ -54,6 +55,18 @@ const tauriBackend: BackendProvider = {
       { options }
     );
   },
+  "ocel/discover-drift": async (options) => {
+    return await invoke<DiscoverDriftResponse>(
+      "discover_constraint_drift_tauri",
+      { options }
+    );
+  },
+  "ocel/discover-labeled-constraints": async (options) => {
+    return await invoke<DiscoverLabeledConstraintsResponse>(
+      "discover_constraints_from_labels_tauri",
+      { options }
+    );
+  },
   "ocel/export-bindings": async (nodeIndex, options) => {
     const res: undefined = await invoke("export_bindings_table", { nodeIndex, options });
     return undefined;
@@ -67,6 +80,9 @@ const tauriBackend: BackendProvider = {
   "ocel/get-object": async (req) => {
     return await invoke("get_object", { req });
   },
+  "ocel/search-ids": async (req) => {
+    return await invoke("search_ocel_ids", { req });
+  },
   "ocel/export-filter-box": async (tree: BindingBoxTree, format: "XML" | "JSON" | "SQLITE") => {
     const res: undefined = await invoke("export_filter_box", { req: { tree, exportFormat: format } });
     //  const blob = new Blob([res],{type: format === "JSON" ? 
@@ -89,10 +105,10 @@ const tauriBackend: BackendProvider = {
       await writeFile(filePath, new Uint8Array(await blob.arrayBuffer()));
     }
   },
-  "translate-to-sql": async (tree:BindingBoxTree, database: DatabaseType): Promise<string> =>{
+  "translate-to-sql": async (tree:BindingBoxTree, database: DatabaseType): Promise<TranslatedQuery> =>{
     return await invoke("translate_to_sql_tauri",{tree, database});
   },
-  "translate-to-cypher": async (tree:BindingBoxTree): Promise<string> =>{
+  "translate-to-cypher": async (tree:BindingBoxTree): Promise<TranslatedQuery> =>{
     return await invoke("translate_to_cypher_tauri",{tree});
   }
 };